@@ -0,0 +1,156 @@
+use jni::{
+    errors::Result,
+    objects::{JObject, JString, JValue, JValueOwned},
+};
+
+/// A Rust type standing for a Java type in the bindings generated by [`class`](crate::class).
+///
+/// Implemented for the primitives, `()` as `void`, [`JObject`], [`JString`] and every bound
+/// class.
+pub trait JavaType<'local>: Sized {
+    /// The field descriptor of the type, like `I` or `Ljava/lang/String;`.
+    const DESCRIPTOR: &'static str;
+
+    fn as_jvalue(&self) -> JValue<'local, '_>;
+
+    fn from_jvalue(value: JValueOwned<'local>) -> Result<Self>;
+}
+
+/// Joins the descriptors of parameters and of a return type into a method descriptor.
+pub fn method_descriptor(params: &[&str], ret: &str) -> String {
+    let mut descriptor = String::from("(");
+    for param in params {
+        descriptor.push_str(param);
+    }
+    descriptor.push(')');
+    descriptor.push_str(ret);
+    descriptor
+}
+
+macro_rules! primitive {
+    ($ty:ty, $descriptor:literal, $variant:ident, $getter:ident) => {
+        impl<'local> JavaType<'local> for $ty {
+            const DESCRIPTOR: &'static str = $descriptor;
+
+            fn as_jvalue(&self) -> JValue<'local, '_> {
+                JValue::$variant((*self).into())
+            }
+
+            fn from_jvalue(value: JValueOwned<'local>) -> Result<Self> {
+                value.$getter()
+            }
+        }
+    };
+}
+
+primitive!(bool, "Z", Bool, z);
+primitive!(i8, "B", Byte, b);
+primitive!(u16, "C", Char, c);
+primitive!(i16, "S", Short, s);
+primitive!(i32, "I", Int, i);
+primitive!(i64, "J", Long, j);
+primitive!(f32, "F", Float, f);
+primitive!(f64, "D", Double, d);
+
+impl<'local> JavaType<'local> for () {
+    const DESCRIPTOR: &'static str = "V";
+
+    fn as_jvalue(&self) -> JValue<'local, '_> {
+        JValue::Void
+    }
+
+    fn from_jvalue(value: JValueOwned<'local>) -> Result<Self> {
+        value.v()
+    }
+}
+
+impl<'local> JavaType<'local> for JObject<'local> {
+    const DESCRIPTOR: &'static str = "Ljava/lang/Object;";
+
+    fn as_jvalue(&self) -> JValue<'local, '_> {
+        JValue::Object(self)
+    }
+
+    fn from_jvalue(value: JValueOwned<'local>) -> Result<Self> {
+        value.l()
+    }
+}
+
+impl<'local> JavaType<'local> for JString<'local> {
+    const DESCRIPTOR: &'static str = "Ljava/lang/String;";
+
+    fn as_jvalue(&self) -> JValue<'local, '_> {
+        JValue::Object(self)
+    }
+
+    fn from_jvalue(value: JValueOwned<'local>) -> Result<Self> {
+        value.l().map(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use jni::objects::JString;
+
+    use crate::{JavaType, method_descriptor};
+
+    #[crate::class("net/minecraft/core/BlockPos")]
+    trait BlockPos {
+        fn new(x: i32, y: i32, z: i32);
+        fn getX(&self) -> i32;
+        fn offset(&self, x: i32, y: i32, z: i32) -> Self;
+        fn distManhattan(&self, other: Vec3i) -> i32;
+        #[mcje(name = "of")]
+        fn of_long(packed: i64) -> Self;
+        #[mcje(field)]
+        fn ZERO() -> Self;
+    }
+
+    #[crate::class("net/minecraft/core/Vec3i")]
+    trait Vec3i {
+        #[mcje(constructor)]
+        fn at(x: i32, y: i32, z: i32) -> Self;
+        fn toShortString(&self) -> JString;
+        #[mcje(field)]
+        fn x(&self) -> i32;
+    }
+
+    #[test]
+    fn test_primitives() {
+        assert_eq!(<bool as JavaType>::DESCRIPTOR, "Z");
+        assert_eq!(<u16 as JavaType>::DESCRIPTOR, "C");
+        assert_eq!(<() as JavaType>::DESCRIPTOR, "V");
+        assert_eq!(
+            method_descriptor(&["I", "Ljava/lang/String;", "[D"], "V"),
+            "(ILjava/lang/String;[D)V"
+        );
+    }
+
+    #[test]
+    fn test_class_descriptors() {
+        assert_eq!(BlockPos::CLASS, "net/minecraft/core/BlockPos");
+        assert_eq!(
+            <BlockPos as JavaType>::DESCRIPTOR,
+            "Lnet/minecraft/core/BlockPos;"
+        );
+        assert_eq!(
+            BlockPos::descriptors(),
+            [
+                ("<init>", "(III)V".into()),
+                ("getX", "()I".into()),
+                ("offset", "(III)Lnet/minecraft/core/BlockPos;".into()),
+                ("distManhattan", "(Lnet/minecraft/core/Vec3i;)I".into()),
+                ("of", "(J)Lnet/minecraft/core/BlockPos;".into()),
+                ("ZERO", "Lnet/minecraft/core/BlockPos;".into()),
+            ]
+        );
+        assert_eq!(
+            Vec3i::descriptors(),
+            [
+                ("<init>", "(III)V".into()),
+                ("toShortString", "()Ljava/lang/String;".into()),
+                ("x", "I".into()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,205 @@
+use std::fmt::{self, Display};
+
+use jni::{
+    JNIEnv,
+    errors::{Error, StartJvmError},
+    objects::{JObject, JString, JValue},
+};
+
+pub type Result<T, E = McjeError> = std::result::Result<T, E>;
+
+#[derive(Debug)]
+pub enum McjeError {
+    /// An exception thrown by Java code, cleared from the thread once captured.
+    Java(JavaException),
+    /// A JNI failure without a Java exception, like a value of an unexpected type.
+    Jni(Error),
+    Start(StartJvmError),
+}
+
+/// A Java exception, described while it was still reachable.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JavaException {
+    /// The binary name of the exception class, like `java.lang.IllegalStateException`.
+    pub class: String,
+    pub message: Option<String>,
+    /// As printed by `Throwable.printStackTrace`, with the causes.
+    pub stack_trace: String,
+}
+
+/// Captures the pending Java exception of a failed JNI call.
+pub trait Catch<T> {
+    fn catch(self, env: &mut JNIEnv) -> Result<T>;
+}
+
+impl McjeError {
+    /// Wraps a JNI error, taking the pending exception of the thread if there is one.
+    pub fn from_jni(env: &mut JNIEnv, err: Error) -> Self {
+        match JavaException::take(env) {
+            Ok(Some(exception)) => Self::Java(exception),
+            Ok(None) => Self::Jni(err),
+            Err(_) => {
+                // describing the exception threw again, leave it at the original error
+                let _ = env.exception_clear();
+                Self::Jni(err)
+            }
+        }
+    }
+}
+
+impl JavaException {
+    /// Takes and clears the pending exception of the thread.
+    ///
+    /// Uses raw JNI calls rather than bindings, whose errors would be captured with this.
+    pub fn take(env: &mut JNIEnv) -> jni::errors::Result<Option<Self>> {
+        if !env.exception_check()? {
+            return Ok(None);
+        }
+        let throwable = env.exception_occurred()?;
+        env.exception_clear()?;
+
+        let class = env.get_object_class(&throwable)?;
+        let class = env
+            .call_method(class, "getName", "()Ljava/lang/String;", &[])?
+            .l()?;
+        let message = env
+            .call_method(&throwable, "getMessage", "()Ljava/lang/String;", &[])?
+            .l()?;
+
+        let writer = env.new_object("java/io/StringWriter", "()V", &[])?;
+        let print_writer = env.new_object(
+            "java/io/PrintWriter",
+            "(Ljava/io/Writer;)V",
+            &[JValue::Object(&writer)],
+        )?;
+        env.call_method(
+            &throwable,
+            "printStackTrace",
+            "(Ljava/io/PrintWriter;)V",
+            &[JValue::Object(&print_writer)],
+        )?;
+        let stack_trace = env
+            .call_method(&writer, "toString", "()Ljava/lang/String;", &[])?
+            .l()?;
+
+        Ok(Some(Self {
+            class: string(env, class)?.unwrap_or_default(),
+            message: string(env, message)?,
+            stack_trace: string(env, stack_trace)?.unwrap_or_default(),
+        }))
+    }
+}
+
+fn string(env: &mut JNIEnv, object: JObject) -> jni::errors::Result<Option<String>> {
+    if object.is_null() {
+        return Ok(None);
+    }
+    let string = JString::from(object);
+    Ok(Some(env.get_string(&string)?.into()))
+}
+
+impl<T> Catch<T> for jni::errors::Result<T> {
+    fn catch(self, env: &mut JNIEnv) -> Result<T> {
+        self.map_err(|err| McjeError::from_jni(env, err))
+    }
+}
+
+impl Display for McjeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Java(exception) => write!(f, "{exception}"),
+            Self::Jni(err) => write!(f, "{err}"),
+            Self::Start(err) => write!(f, "failed to start the JVM: {err}"),
+        }
+    }
+}
+
+impl Display for JavaException {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.stack_trace.is_empty() {
+            true => match &self.message {
+                Some(message) => write!(f, "{}: {message}", self.class),
+                None => write!(f, "{}", self.class),
+            },
+            // the stack trace starts with the class and message
+            false => write!(f, "{}", self.stack_trace.trim_end()),
+        }
+    }
+}
+
+impl std::error::Error for McjeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Java(_) => None,
+            Self::Jni(err) => Some(err),
+            Self::Start(err) => Some(err),
+        }
+    }
+}
+
+impl From<Error> for McjeError {
+    /// Wraps the error as is, see [`Catch`] to capture the exception of
+    /// [`Error::JavaException`].
+    fn from(err: Error) -> Self {
+        Self::Jni(err)
+    }
+}
+
+impl From<StartJvmError> for McjeError {
+    fn from(err: StartJvmError) -> Self {
+        Self::Start(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use jni::{JNIEnv, objects::JString};
+
+    use crate::{McjeError, jvm};
+
+    #[crate::class("java/lang/Integer")]
+    trait Integer {
+        fn parseInt(s: JString) -> i32;
+    }
+
+    #[mcje::test]
+    fn test_exception(env: &mut JNIEnv<'_>) {
+        let number = env.new_string("42").unwrap();
+        assert_eq!(Integer::parseInt(env, &number).unwrap(), 42);
+
+        let not_number = env.new_string("x").unwrap();
+        let Err(McjeError::Java(exception)) = Integer::parseInt(env, &not_number) else {
+            panic!("expected a Java exception");
+        };
+        assert_eq!(exception.class, "java.lang.NumberFormatException");
+        assert_eq!(
+            exception.message.as_deref(),
+            Some("For input string: \"x\"")
+        );
+        assert!(
+            exception
+                .stack_trace
+                .contains("java.lang.Integer.parseInt(")
+        );
+        assert!(!env.exception_check().unwrap());
+    }
+
+    #[mcje::test]
+    fn test_shared_jvm(env: &mut JNIEnv<'_>) {
+        let threads: Vec<_> = (0..4)
+            .map(|_| {
+                std::thread::spawn(|| {
+                    let jvm = jvm().unwrap();
+                    let mut env = jvm.attach_current_thread().unwrap();
+                    let number = env.new_string("7").unwrap();
+                    assert_eq!(Integer::parseInt(&mut env, &number).unwrap(), 7);
+                    jvm as *const _ as usize
+                })
+            })
+            .collect();
+        for thread in threads {
+            assert_eq!(thread.join().unwrap(), jvm().unwrap() as *const _ as usize);
+        }
+        assert!(!env.exception_check().unwrap());
+    }
+}
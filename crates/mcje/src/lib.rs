@@ -1,31 +1,66 @@
+use std::sync::{Mutex, OnceLock, PoisonError};
+
 use jni::{
     InitArgsBuilder, JNIEnv, JavaVM,
     objects::{JObject, JValueGen},
 };
 
+// lets the code generated by `mcje_macros` refer to `::mcje` inside this crate too
+extern crate self as mcje;
+
+mod class;
+mod error;
+
+pub use class::{JavaType, method_descriptor};
+pub use error::{Catch, JavaException, McjeError, Result};
+pub use jni;
 pub use mcje_macros::*;
 
-pub async fn init() -> JavaVM {
-    let classpath = env!("MCJE_JVM_CLASSPATH");
+/// Id of the game version the JVM runs, selected by the version features.
+pub const VERSION: &str = env!("MCJE_VERSION");
+
+static JVM: OnceLock<JavaVM> = OnceLock::new();
+
+/// The JVM of the process, started and bootstrapped on first use.
+///
+/// A process can only create one JVM, so every thread shares this one, attaching itself with
+/// [`JavaVM::attach_current_thread`]. If starting fails, later calls fail too.
+pub fn jvm() -> Result<&'static JavaVM> {
+    static START: Mutex<()> = Mutex::new(());
+
+    if let Some(jvm) = JVM.get() {
+        return Ok(jvm);
+    }
+    // held while starting so that threads racing to the first use don't both create one
+    let _start = START.lock().unwrap_or_else(PoisonError::into_inner);
+    if let Some(jvm) = JVM.get() {
+        return Ok(jvm);
+    }
 
+    let classpath = env!("MCJE_JVM_CLASSPATH");
     let jvm_args = InitArgsBuilder::new()
         .option(format!("-Djava.class.path={classpath}"))
         .build()
-        .unwrap();
+        .expect("the classpath has no nul character");
+    let jvm = JavaVM::new(jvm_args)?;
+    {
+        let mut env = jvm.attach_current_thread()?;
+        try_bootstrap(&mut env)?;
+    }
 
-    JavaVM::new(jvm_args).unwrap()
+    Ok(JVM.get_or_init(|| jvm))
 }
 
-pub fn bootstrap(env: &mut JNIEnv) {
+/// Loads the game version and registries, done by [`jvm`] already.
+pub fn try_bootstrap(env: &mut JNIEnv) -> Result<()> {
     let detected_version_built_in = env
         .get_static_field(
             "net/minecraft/DetectedVersion",
             "BUILT_IN",
             "Lnet/minecraft/WorldVersion;",
         )
-        .unwrap()
-        .l()
-        .unwrap();
+        .and_then(JValueGen::l)
+        .catch(env)?;
 
     env.call_static_method(
         "net/minecraft/SharedConstants",
@@ -33,46 +68,57 @@ pub fn bootstrap(env: &mut JNIEnv) {
         "(Lnet/minecraft/WorldVersion;)V",
         &[JValueGen::Object(&detected_version_built_in)],
     )
-    .unwrap();
+    .catch(env)?;
 
     env.call_static_method("net/minecraft/server/Bootstrap", "bootStrap", "()V", &[])
-        .unwrap();
+        .catch(env)?;
+    Ok(())
 }
 
-pub fn get_registry<'a>(env: &mut JNIEnv<'a>, name: &str, jtype: &str) -> JObject<'a> {
+/// Like [`try_bootstrap`], panicking with the Java stack trace on failure.
+pub fn bootstrap(env: &mut JNIEnv) {
+    try_bootstrap(env).unwrap_or_else(|err| panic!("{err}"));
+}
+
+/// A static field of `BuiltInRegistries`, like `BLOCK` of type `DefaultedRegistry`.
+pub fn try_get_registry<'a>(env: &mut JNIEnv<'a>, name: &str, jtype: &str) -> Result<JObject<'a>> {
     let built_in_registries = env
         .find_class("net/minecraft/core/registries/BuiltInRegistries")
-        .unwrap();
+        .catch(env)?;
 
     env.get_static_field(
         built_in_registries,
         name,
         format!("Lnet/minecraft/core/{jtype};"),
     )
-    .unwrap()
-    .l()
-    .unwrap()
+    .and_then(JValueGen::l)
+    .catch(env)
 }
 
-pub fn iterate<'a>(
+/// Like [`try_get_registry`], panicking with the Java stack trace on failure.
+pub fn get_registry<'a>(env: &mut JNIEnv<'a>, name: &str, jtype: &str) -> JObject<'a> {
+    try_get_registry(env, name, jtype).unwrap_or_else(|err| panic!("{err}"))
+}
+
+/// Calls `cb` with the index and value of each element of a Java `Iterable`, stopping at the
+/// first error.
+pub fn try_iterate<'a>(
     obj: &JObject<'a>,
     env: &'a mut JNIEnv,
-    mut cb: impl FnMut(usize, JObject<'a>, &mut JNIEnv),
-) {
+    mut cb: impl FnMut(usize, JObject<'a>, &mut JNIEnv) -> Result<()>,
+) -> Result<()> {
     let iterator = env
         .call_method(obj, "iterator", "()Ljava/util/Iterator;", &[])
-        .unwrap()
-        .l()
-        .unwrap();
+        .and_then(JValueGen::l)
+        .catch(env)?;
 
     let mut i = 0;
     loop {
         // call hasNext()
         let has_next = env
             .call_method(&iterator, "hasNext", "()Z", &[])
-            .unwrap()
-            .z()
-            .unwrap();
+            .and_then(JValueGen::z)
+            .catch(env)?;
         if !has_next {
             break;
         }
@@ -80,12 +126,25 @@ pub fn iterate<'a>(
         // call next()
         let element = env
             .call_method(&iterator, "next", "()Ljava/lang/Object;", &[])
-            .unwrap()
-            .l()
-            .unwrap();
+            .and_then(JValueGen::l)
+            .catch(env)?;
 
-        cb(i, element, env);
+        cb(i, element, env)?;
 
         i += 1;
     }
+    Ok(())
+}
+
+/// Like [`try_iterate`], panicking with the Java stack trace on failure.
+pub fn iterate<'a>(
+    obj: &JObject<'a>,
+    env: &'a mut JNIEnv,
+    mut cb: impl FnMut(usize, JObject<'a>, &mut JNIEnv),
+) {
+    try_iterate(obj, env, |i, element, env| {
+        cb(i, element, env);
+        Ok(())
+    })
+    .unwrap_or_else(|err| panic!("{err}"));
 }
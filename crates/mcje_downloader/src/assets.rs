@@ -0,0 +1,355 @@
+use std::{
+    collections::BTreeMap,
+    io,
+    path::{Component, Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use tokio::fs;
+use url::Url;
+
+use crate::{CacheError, DownloadCache, DownloadManager, DownloadRequest, DownloadSource};
+
+const RESOURCES_URL: &str = "https://resources.download.minecraft.net/";
+
+/// The `assetIndex` of a version manifest.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetIndexInfo {
+    pub id: String,
+    pub sha1: String,
+    pub size: u64,
+    /// The size of every object of the index together.
+    pub total_size: u64,
+    pub url: Url,
+}
+
+/// The hashed objects of a version, like sounds and languages, by their name.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct AssetIndex {
+    /// Whether objects must also be copied to `assets/virtual/<id>`, used before 1.7.3.
+    #[serde(rename = "virtual", default, skip_serializing_if = "is_false")]
+    pub is_virtual: bool,
+    /// Whether objects must also be copied to `resources` in the game directory, used before
+    /// 1.6.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub map_to_resources: bool,
+    pub objects: BTreeMap<String, AssetObject>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct AssetObject {
+    pub hash: String,
+    pub size: u64,
+}
+
+/// Assets in the launcher layout, `indexes/<id>.json` and `objects/<xx>/<hash>` under a root.
+#[derive(Debug, Clone)]
+pub struct AssetStore {
+    root: PathBuf,
+    base_url: Url,
+    objects: DownloadCache,
+}
+
+fn is_false(value: &bool) -> bool {
+    !value
+}
+
+impl DownloadSource for AssetIndexInfo {
+    fn url(&self) -> &Url {
+        &self.url
+    }
+
+    fn sha1(&self) -> &str {
+        &self.sha1
+    }
+
+    fn size(&self) -> u64 {
+        self.size
+    }
+}
+
+impl AssetObject {
+    /// The URL of the object under a server like `https://resources.download.minecraft.net/`.
+    pub fn url(&self, base_url: &Url) -> Url {
+        let prefix = self.hash.get(..2).unwrap_or(&self.hash);
+        base_url
+            .join(&format!("{prefix}/{}", self.hash))
+            .expect("object hashes are valid url paths")
+    }
+}
+
+impl AssetIndex {
+    /// The total size of the objects, counting objects shared by several names once.
+    pub fn total_size(&self) -> u64 {
+        let mut seen = BTreeMap::new();
+        for object in self.objects.values() {
+            seen.insert(&object.hash, object.size);
+        }
+        seen.values().sum()
+    }
+}
+
+impl AssetStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        let root = root.into();
+        Self {
+            objects: DownloadCache::new(root.join("objects")),
+            root,
+            base_url: Url::parse(RESOURCES_URL).unwrap(),
+        }
+    }
+
+    /// Downloads objects from another server, e.g. a mirror or a test server.
+    pub fn with_base_url(mut self, base_url: Url) -> Self {
+        self.base_url = base_url;
+        self
+    }
+
+    /// Fails on missing objects instead of downloading them.
+    pub fn with_offline(mut self, offline: bool) -> Self {
+        self.objects = self.objects.with_offline(offline);
+        self
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn base_url(&self) -> &Url {
+        &self.base_url
+    }
+
+    /// Where the objects are stored, to download them with another [`DownloadManager`].
+    pub fn objects(&self) -> &DownloadCache {
+        &self.objects
+    }
+
+    pub fn index_path(&self, id: &str) -> PathBuf {
+        self.root.join("indexes").join(format!("{id}.json"))
+    }
+
+    pub fn object_path(&self, object: &AssetObject) -> PathBuf {
+        self.objects.path_of(&object.hash)
+    }
+
+    /// Where [`AssetIndex::is_virtual`] indexes are materialized.
+    pub fn virtual_dir(&self, id: &str) -> PathBuf {
+        self.root.join("virtual").join(id)
+    }
+
+    /// Downloads the index to `indexes/<id>.json`, reusing it if already there and valid.
+    pub async fn fetch_index(&self, info: &AssetIndexInfo) -> Result<AssetIndex, CacheError> {
+        let path = self.index_path(&info.id);
+        self.objects.get_at(info, &path).await?;
+        let bytes = fs::read(path).await?;
+        let index = serde_json::from_slice(&bytes)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        Ok(index)
+    }
+
+    pub fn object_request(&self, object: &AssetObject) -> DownloadRequest {
+        DownloadRequest {
+            url: object.url(&self.base_url),
+            sha1: object.hash.clone(),
+            size: object.size,
+        }
+    }
+
+    /// Downloads and verifies one object, returning its path.
+    pub async fn fetch_object(&self, object: &AssetObject) -> Result<PathBuf, CacheError> {
+        self.objects.get(&self.object_request(object)).await
+    }
+
+    /// Downloads every object of the index that isn't already stored, several at once.
+    pub async fn fetch_objects(&self, index: &AssetIndex) -> Result<(), CacheError> {
+        let requests = index
+            .objects
+            .values()
+            .map(|object| self.object_request(object));
+        DownloadManager::new(self.objects.clone())
+            .download(requests)
+            .await?;
+        Ok(())
+    }
+
+    /// Copies the objects to their names under `dir`, the layout of virtual and
+    /// `map_to_resources` indexes.
+    pub async fn materialize(&self, index: &AssetIndex, dir: &Path) -> Result<(), CacheError> {
+        for (name, object) in &index.objects {
+            let relative = Path::new(name);
+            if !relative
+                .components()
+                .all(|component| matches!(component, Component::Normal(_)))
+            {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("asset name {name:?} escapes the target directory"),
+                )
+                .into());
+            }
+
+            let target = dir.join(relative);
+            if fs::metadata(&target)
+                .await
+                .is_ok_and(|metadata| metadata.len() == object.size)
+            {
+                continue;
+            }
+
+            let source = self.fetch_object(object).await?;
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent).await?;
+            }
+            fs::copy(source, target).await?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, path::PathBuf};
+
+    use sha1::{Digest, Sha1};
+
+    use crate::{
+        AssetIndex, AssetIndexInfo, AssetObject, AssetStore, CacheError,
+        cache::hex,
+        test_server::{Response, TestServer},
+    };
+
+    const SOUND: &[u8] = b"OggS fake sound";
+    const LANG: &[u8] = br#"{"menu.singleplayer": "Singleplayer"}"#;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "mcje_downloader_assets_{name}_{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn object(body: &[u8]) -> AssetObject {
+        AssetObject {
+            hash: hex(&Sha1::digest(body)),
+            size: body.len() as u64,
+        }
+    }
+
+    fn index(is_virtual: bool) -> AssetIndex {
+        AssetIndex {
+            is_virtual,
+            map_to_resources: false,
+            objects: BTreeMap::from([
+                (
+                    "minecraft/sounds/ambient/cave/cave1.ogg".into(),
+                    object(SOUND),
+                ),
+                ("minecraft/lang/en_us.json".into(), object(LANG)),
+                ("minecraft/sounds/copy.ogg".into(), object(SOUND)),
+            ]),
+        }
+    }
+
+    /// Serves the index at `/indexes/<id>.json` and the objects under `/objects/`.
+    async fn server(index_json: String) -> TestServer {
+        TestServer::start(move |request| {
+            let objects = [SOUND, LANG];
+            if request.path.starts_with("/indexes/") {
+                return Response::file(request, index_json.as_bytes());
+            }
+            objects
+                .into_iter()
+                .find(|body| request.path.ends_with(&object(body).hash))
+                .map_or_else(
+                    || Response::status(404),
+                    |body| Response::file(request, body),
+                )
+        })
+        .await
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let index: AssetIndex = serde_json::from_str(
+            r#"{"virtual": true, "objects": {"icons/icon_16x16.png": {"hash": "bdf48ef6b5d0d23bbb02e17d04865216179f510a", "size": 3665}}}"#,
+        )
+        .unwrap();
+        assert!(index.is_virtual && !index.map_to_resources);
+
+        let object = &index.objects["icons/icon_16x16.png"];
+        assert_eq!(
+            object
+                .url(&"https://resources.download.minecraft.net/".parse().unwrap())
+                .as_str(),
+            "https://resources.download.minecraft.net/bd/bdf48ef6b5d0d23bbb02e17d04865216179f510a"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fetch() {
+        let index_json = serde_json::to_string(&index(false)).unwrap();
+        let server = server(index_json.clone()).await;
+        let store = AssetStore::new(temp_dir("fetch")).with_base_url(server.url("/objects/"));
+
+        let info = AssetIndexInfo {
+            id: "27".into(),
+            sha1: hex(&Sha1::digest(index_json.as_bytes())),
+            size: index_json.len() as u64,
+            total_size: (SOUND.len() + LANG.len()) as u64,
+            url: server.url("/indexes/27.json"),
+        };
+        let index = store.fetch_index(&info).await.unwrap();
+        assert_eq!(index, self::index(false));
+        assert_eq!(index.total_size(), info.total_size);
+        assert_eq!(
+            std::fs::read_to_string(store.index_path("27")).unwrap(),
+            index_json
+        );
+        assert!(!store.objects().path_of(&info.sha1).exists());
+
+        store.fetch_objects(&index).await.unwrap();
+        let sound = &index.objects["minecraft/sounds/copy.ogg"];
+        assert_eq!(std::fs::read(store.object_path(sound)).unwrap(), SOUND);
+        // one request for the index and one per distinct object
+        assert_eq!(server.requests().len(), 3);
+
+        let offline = AssetStore::new(store.root()).with_offline(true);
+        assert_eq!(offline.fetch_index(&info).await.unwrap(), index);
+        offline.fetch_objects(&index).await.unwrap();
+        assert_eq!(server.requests().len(), 3);
+
+        std::fs::remove_dir_all(store.root()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_materialize() {
+        let server = server(String::new()).await;
+        let store = AssetStore::new(temp_dir("virtual")).with_base_url(server.url("/objects/"));
+        let index = index(true);
+
+        let dir = store.virtual_dir("legacy");
+        store.materialize(&index, &dir).await.unwrap();
+        assert_eq!(
+            std::fs::read(dir.join("minecraft/sounds/ambient/cave/cave1.ogg")).unwrap(),
+            SOUND
+        );
+        assert_eq!(
+            std::fs::read(dir.join("minecraft/lang/en_us.json")).unwrap(),
+            LANG
+        );
+
+        let mut escaping = index.clone();
+        escaping
+            .objects
+            .insert("../outside.ogg".into(), object(SOUND));
+        assert!(matches!(
+            store.materialize(&escaping, &dir).await,
+            Err(CacheError::Io(_))
+        ));
+
+        std::fs::remove_dir_all(store.root()).unwrap();
+    }
+}
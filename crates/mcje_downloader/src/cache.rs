@@ -0,0 +1,544 @@
+use std::{
+    fmt::{self, Display},
+    io::{self, SeekFrom},
+    path::{Path, PathBuf},
+};
+
+use reqwest::{StatusCode, header};
+use sha1::{Digest, Sha1};
+use tokio::{
+    fs::{self, File, OpenOptions},
+    io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt},
+};
+use url::Url;
+
+use crate::{DownloadInfo, LibraryDownload, LoggingFile};
+
+/// A file that can be downloaded and checked against its expected hash and size.
+pub trait DownloadSource {
+    fn url(&self) -> &Url;
+
+    /// The lowercase hex SHA-1 of the content.
+    fn sha1(&self) -> &str;
+
+    fn size(&self) -> u64;
+}
+
+/// Downloaded files stored by SHA-1 under a directory, verified before being used.
+///
+/// Interrupted downloads are kept next to their final location and resumed with a `Range`
+/// request.
+#[derive(Debug, Clone)]
+pub struct DownloadCache {
+    root: PathBuf,
+    client: reqwest::Client,
+    offline: bool,
+}
+
+#[derive(Debug)]
+pub enum CacheError {
+    Io(io::Error),
+    Http(reqwest::Error),
+    /// A file missing from the cache while in offline mode.
+    Offline {
+        url: Url,
+        sha1: String,
+    },
+    SizeMismatch {
+        url: Url,
+        expected: u64,
+        actual: u64,
+    },
+    HashMismatch {
+        url: Url,
+        expected: String,
+        actual: String,
+    },
+    /// Downloads cancelled through a [`Canceller`](crate::Canceller).
+    Cancelled,
+}
+
+impl CacheError {
+    /// Whether trying again may succeed, as with dropped connections and server errors.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            Self::Http(err) => err.status().is_none_or(|status| {
+                status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS
+            }),
+            Self::SizeMismatch { .. } | Self::HashMismatch { .. } => true,
+            Self::Io(_) | Self::Offline { .. } | Self::Cancelled => false,
+        }
+    }
+}
+
+impl DownloadCache {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            client: reqwest::Client::new(),
+            offline: false,
+        }
+    }
+
+    /// Fails on cache misses instead of downloading.
+    pub fn with_offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+
+    pub fn with_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn is_offline(&self) -> bool {
+        self.offline
+    }
+
+    /// Where a file with the given hash is stored, whether it's cached or not.
+    pub fn path_of(&self, sha1: &str) -> PathBuf {
+        let prefix = sha1.get(..2).unwrap_or(sha1);
+        self.root.join(prefix).join(sha1)
+    }
+
+    /// Returns the path of the cached file, downloading it first if missing or corrupt.
+    pub async fn get(&self, source: &impl DownloadSource) -> Result<PathBuf, CacheError> {
+        self.get_with_progress(source, &mut |_| {}).await
+    }
+
+    /// Like [`Self::get`], reporting how many bytes of the file are there as it downloads.
+    pub(crate) async fn get_with_progress(
+        &self,
+        source: &impl DownloadSource,
+        progress: &mut (dyn FnMut(u64) + Send),
+    ) -> Result<PathBuf, CacheError> {
+        let path = self.path_of(source.sha1());
+        self.get_at_with_progress(source, &path, progress).await?;
+        Ok(path)
+    }
+
+    /// Like [`Self::get`], storing the file at `path` instead of under its hash, for files with a
+    /// fixed place in a layout.
+    pub async fn get_at(
+        &self,
+        source: &impl DownloadSource,
+        path: &Path,
+    ) -> Result<(), CacheError> {
+        self.get_at_with_progress(source, path, &mut |_| {}).await
+    }
+
+    async fn get_at_with_progress(
+        &self,
+        source: &impl DownloadSource,
+        path: &Path,
+        progress: &mut (dyn FnMut(u64) + Send),
+    ) -> Result<(), CacheError> {
+        if cached_at(path, source).await? {
+            progress(source.size());
+            return Ok(());
+        }
+
+        if self.offline {
+            return Err(CacheError::Offline {
+                url: source.url().clone(),
+                sha1: source.sha1().to_owned(),
+            });
+        }
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+
+        let partial = partial_path(path);
+        self.download(source, &partial, progress).await?;
+
+        if let Err(err) = verify(&partial, source).await? {
+            fs::remove_file(&partial).await?;
+            return Err(err);
+        }
+
+        fs::rename(&partial, path).await?;
+        Ok(())
+    }
+
+    /// Returns the path of the file if it's cached, deleting it if it's corrupt.
+    pub(crate) async fn cached(
+        &self,
+        source: &impl DownloadSource,
+    ) -> Result<Option<PathBuf>, CacheError> {
+        let path = self.path_of(source.sha1());
+        Ok(cached_at(&path, source).await?.then_some(path))
+    }
+
+    /// Returns the content of the cached file, downloading it first if needed.
+    pub async fn get_bytes(&self, source: &impl DownloadSource) -> Result<Vec<u8>, CacheError> {
+        let path = self.get(source).await?;
+        Ok(fs::read(path).await?)
+    }
+
+    /// Downloads into `partial`, continuing from what it already holds.
+    async fn download(
+        &self,
+        source: &impl DownloadSource,
+        partial: &Path,
+        progress: &mut (dyn FnMut(u64) + Send),
+    ) -> Result<(), CacheError> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(partial)
+            .await?;
+
+        let mut offset = file.metadata().await?.len();
+        if offset >= source.size() {
+            file.set_len(0).await?;
+            offset = 0;
+        }
+
+        let mut request = self.client.get(source.url().clone());
+        if offset > 0 {
+            request = request.header(header::RANGE, format!("bytes={offset}-"));
+        }
+
+        let mut response = request.send().await?;
+        match response.status() {
+            StatusCode::PARTIAL_CONTENT => {}
+            StatusCode::RANGE_NOT_SATISFIABLE => {
+                drop(file);
+                fs::remove_file(partial).await?;
+                return Box::pin(self.download(source, partial, progress)).await;
+            }
+            _ => {
+                response = response.error_for_status()?;
+                if offset > 0 {
+                    file.set_len(0).await?;
+                    file.seek(SeekFrom::Start(0)).await?;
+                    offset = 0;
+                }
+            }
+        }
+        progress(offset);
+
+        // flush even when the transfer fails so a resumed download starts after what was written
+        let result: Result<(), CacheError> = async {
+            while let Some(chunk) = response.chunk().await? {
+                file.write_all(&chunk).await?;
+                offset += chunk.len() as u64;
+                progress(offset);
+            }
+            Ok(())
+        }
+        .await;
+        file.flush().await?;
+
+        result
+    }
+}
+
+/// Where the file at `path` is downloaded before being verified, next to it with `.part`
+/// appended so that files differing only by extension don't share it.
+fn partial_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_owned();
+    name.push(".part");
+    path.with_file_name(name)
+}
+
+/// Whether the file at `path` is there and valid, deleting it if it's corrupt.
+async fn cached_at(path: &Path, source: &impl DownloadSource) -> Result<bool, CacheError> {
+    if fs::try_exists(path).await? {
+        if verify(path, source).await?.is_ok() {
+            return Ok(true);
+        }
+        fs::remove_file(path).await?;
+    }
+    Ok(false)
+}
+
+/// Checks the size then the hash of a file, the outer result being for I/O errors.
+async fn verify(path: &Path, source: &impl DownloadSource) -> io::Result<Result<(), CacheError>> {
+    let mut file = File::open(path).await?;
+    let size = file.metadata().await?.len();
+    if size != source.size() {
+        return Ok(Err(CacheError::SizeMismatch {
+            url: source.url().clone(),
+            expected: source.size(),
+            actual: size,
+        }));
+    }
+
+    let mut hasher = Sha1::new();
+    let mut buf = vec![0; 64 * 1024];
+    loop {
+        let read = file.read(&mut buf).await?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buf[..read]);
+    }
+
+    let actual = hex(&hasher.finalize());
+    if !actual.eq_ignore_ascii_case(source.sha1()) {
+        return Ok(Err(CacheError::HashMismatch {
+            url: source.url().clone(),
+            expected: source.sha1().to_owned(),
+            actual,
+        }));
+    }
+
+    Ok(Ok(()))
+}
+
+pub(crate) fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+impl DownloadSource for DownloadInfo {
+    fn url(&self) -> &Url {
+        &self.url
+    }
+
+    fn sha1(&self) -> &str {
+        &self.sha1
+    }
+
+    fn size(&self) -> u64 {
+        self.size
+    }
+}
+
+impl DownloadSource for LibraryDownload {
+    fn url(&self) -> &Url {
+        &self.url
+    }
+
+    fn sha1(&self) -> &str {
+        &self.sha1
+    }
+
+    fn size(&self) -> u64 {
+        self.size
+    }
+}
+
+impl DownloadSource for LoggingFile {
+    fn url(&self) -> &Url {
+        &self.url
+    }
+
+    fn sha1(&self) -> &str {
+        &self.sha1
+    }
+
+    fn size(&self) -> u64 {
+        self.size
+    }
+}
+
+impl Display for CacheError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "{err}"),
+            Self::Http(err) => write!(f, "{err}"),
+            Self::Offline { url, sha1 } => {
+                write!(f, "{url} ({sha1}) is not cached and downloads are disabled")
+            }
+            Self::SizeMismatch {
+                url,
+                expected,
+                actual,
+            } => write!(f, "{url} has {actual} bytes instead of {expected}"),
+            Self::HashMismatch {
+                url,
+                expected,
+                actual,
+            } => write!(f, "{url} has sha1 {actual} instead of {expected}"),
+            Self::Cancelled => write!(f, "downloads were cancelled"),
+        }
+    }
+}
+
+impl std::error::Error for CacheError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            Self::Http(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for CacheError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<reqwest::Error> for CacheError {
+    fn from(err: reqwest::Error) -> Self {
+        Self::Http(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use sha1::{Digest, Sha1};
+    use url::Url;
+
+    use crate::{
+        CacheError, DownloadCache, DownloadInfo,
+        cache::{hex, partial_path},
+        test_server::{Response, TestServer},
+    };
+
+    const BODY: &[u8] = &[42; 100_000];
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("mcje_downloader_{name}_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn info(url: Url, body: &[u8]) -> DownloadInfo {
+        DownloadInfo {
+            sha1: hex(&Sha1::digest(body)),
+            size: body.len() as u64,
+            url,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_download_and_hit() {
+        let server = TestServer::start(|request| Response::file(request, BODY)).await;
+        let cache = DownloadCache::new(temp_dir("hit"));
+        let info = info(server.url("/client.jar"), BODY);
+
+        let path = cache.get(&info).await.unwrap();
+        assert_eq!(path, cache.path_of(&info.sha1));
+        assert_eq!(std::fs::read(&path).unwrap(), BODY);
+
+        assert_eq!(cache.get_bytes(&info).await.unwrap(), BODY);
+        assert_eq!(server.requests().len(), 1);
+
+        std::fs::write(&path, b"corrupt").unwrap();
+        assert_eq!(cache.get_bytes(&info).await.unwrap(), BODY);
+        assert_eq!(server.requests().len(), 2);
+
+        std::fs::remove_dir_all(cache.root()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_resume() {
+        let server = TestServer::start(|request| {
+            let response = Response::file(request, BODY);
+            if request.range_start.is_none() {
+                response.truncated(30_000)
+            } else {
+                response
+            }
+        })
+        .await;
+        let cache = DownloadCache::new(temp_dir("resume"));
+        let info = info(server.url("/client.jar"), BODY);
+
+        assert!(cache.get(&info).await.is_err());
+        let partial = std::fs::metadata(partial_path(&cache.path_of(&info.sha1)))
+            .unwrap()
+            .len();
+        assert!(partial <= 30_000);
+
+        assert_eq!(cache.get_bytes(&info).await.unwrap(), BODY);
+        let ranges = server
+            .requests()
+            .into_iter()
+            .map(|request| request.range_start)
+            .collect::<Vec<_>>();
+        assert_eq!(ranges, [None, Some(partial).filter(|&len| len > 0)]);
+
+        std::fs::remove_dir_all(cache.root()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_get_at() {
+        let server = TestServer::start(|request| {
+            let response = Response::file(request, BODY);
+            if request.range_start.is_none() {
+                response.truncated(30_000)
+            } else {
+                response
+            }
+        })
+        .await;
+        let cache = DownloadCache::new(temp_dir("get_at"));
+        let info = info(server.url("/1.21.4.json"), BODY);
+        let path = cache.root().join("indexes/1.21.4.json");
+
+        assert!(cache.get_at(&info, &path).await.is_err());
+        assert!(cache.root().join("indexes/1.21.4.json.part").exists());
+        assert!(!cache.root().join("indexes/1.21.part").exists());
+
+        cache.get_at(&info, &path).await.unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), BODY);
+        assert!(!partial_path(&path).exists());
+
+        std::fs::remove_dir_all(cache.root()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_verification() {
+        let server = TestServer::start(|request| Response::file(request, BODY)).await;
+        let cache = DownloadCache::new(temp_dir("verify"));
+
+        let mut wrong_hash = info(server.url("/client.jar"), BODY);
+        wrong_hash.sha1 = hex(&Sha1::digest(b"something else"));
+        assert!(matches!(
+            cache.get(&wrong_hash).await,
+            Err(CacheError::HashMismatch { .. })
+        ));
+
+        let mut wrong_size = info(server.url("/client.jar"), BODY);
+        wrong_size.size += 1;
+        assert!(matches!(
+            cache.get(&wrong_size).await,
+            Err(CacheError::SizeMismatch { .. })
+        ));
+
+        let missing = info(server.url("/missing.jar"), BODY);
+        let server = TestServer::start(|_| Response::status(404)).await;
+        let missing = DownloadInfo {
+            url: server.url("/missing.jar"),
+            ..missing
+        };
+        assert!(matches!(
+            cache.get(&missing).await,
+            Err(CacheError::Http(_))
+        ));
+
+        std::fs::remove_dir_all(cache.root()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_offline() {
+        let server = TestServer::start(|request| Response::file(request, BODY)).await;
+        let dir = temp_dir("offline");
+        let info = info(server.url("/client.jar"), BODY);
+
+        let offline = DownloadCache::new(&dir).with_offline(true);
+        let err = offline.get(&info).await.unwrap_err();
+        assert!(matches!(err, CacheError::Offline { .. }));
+        assert!(err.to_string().contains("is not cached"));
+        assert!(server.requests().is_empty());
+
+        DownloadCache::new(&dir).get(&info).await.unwrap();
+        assert_eq!(offline.get_bytes(&info).await.unwrap(), BODY);
+        assert_eq!(server.requests().len(), 1);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
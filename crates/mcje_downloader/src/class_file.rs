@@ -0,0 +1,463 @@
+//! Just enough of the class file format to rename what a class refers to.
+
+use std::{borrow::Cow, collections::HashMap};
+
+pub(crate) type ClassResult<T> = Result<T, &'static str>;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) enum Constant {
+    /// Index 0 and the slot after longs and doubles.
+    Empty,
+    /// Modified UTF-8, kept as bytes since string literals aren't renamed.
+    Utf8(Vec<u8>),
+    Integer(u32),
+    Float(u32),
+    Long(u64),
+    Double(u64),
+    Class(u16),
+    String(u16),
+    FieldRef(u16, u16),
+    MethodRef(u16, u16),
+    InterfaceMethodRef(u16, u16),
+    NameAndType(u16, u16),
+    MethodHandle(u8, u16),
+    MethodType(u16),
+    Dynamic(u16, u16),
+    InvokeDynamic(u16, u16),
+    Module(u16),
+    Package(u16),
+}
+
+/// A constant pool that only grows, so the indices in the bytecode stay valid.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ConstantPool {
+    constants: Vec<Constant>,
+    indices: HashMap<Constant, u16>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Attribute {
+    pub name: u16,
+    pub info: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Member {
+    pub access: u16,
+    pub name: u16,
+    pub descriptor: u16,
+    pub attributes: Vec<Attribute>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ClassFile {
+    pub minor: u16,
+    pub major: u16,
+    pub pool: ConstantPool,
+    pub access: u16,
+    pub this_class: u16,
+    /// 0 for `java/lang/Object`.
+    pub super_class: u16,
+    pub interfaces: Vec<u16>,
+    pub fields: Vec<Member>,
+    pub methods: Vec<Member>,
+    pub attributes: Vec<Attribute>,
+}
+
+pub(crate) struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes }
+    }
+
+    pub fn bytes(&mut self, len: usize) -> ClassResult<&'a [u8]> {
+        if self.bytes.len() < len {
+            return Err("unexpected end of class file");
+        }
+        let (bytes, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(bytes)
+    }
+
+    pub fn u8(&mut self) -> ClassResult<u8> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    pub fn u16(&mut self) -> ClassResult<u16> {
+        Ok(u16::from_be_bytes(self.bytes(2)?.try_into().unwrap()))
+    }
+
+    pub fn u32(&mut self) -> ClassResult<u32> {
+        Ok(u32::from_be_bytes(self.bytes(4)?.try_into().unwrap()))
+    }
+
+    pub fn u64(&mut self) -> ClassResult<u64> {
+        Ok(u64::from_be_bytes(self.bytes(8)?.try_into().unwrap()))
+    }
+
+    pub fn attributes(&mut self) -> ClassResult<Vec<Attribute>> {
+        (0..self.u16()?)
+            .map(|_| {
+                let name = self.u16()?;
+                let len = self.u32()? as usize;
+                Ok(Attribute {
+                    name,
+                    info: self.bytes(len)?.to_vec(),
+                })
+            })
+            .collect()
+    }
+
+    fn member(&mut self) -> ClassResult<Member> {
+        Ok(Member {
+            access: self.u16()?,
+            name: self.u16()?,
+            descriptor: self.u16()?,
+            attributes: self.attributes()?,
+        })
+    }
+}
+
+pub(crate) fn write_u16(out: &mut Vec<u8>, value: u16) {
+    out.extend_from_slice(&value.to_be_bytes());
+}
+
+pub(crate) fn write_attributes(out: &mut Vec<u8>, attributes: &[Attribute]) {
+    write_u16(out, attributes.len() as u16);
+    for attribute in attributes {
+        write_u16(out, attribute.name);
+        out.extend_from_slice(&(attribute.info.len() as u32).to_be_bytes());
+        out.extend_from_slice(&attribute.info);
+    }
+}
+
+impl ConstantPool {
+    pub fn new() -> Self {
+        Self {
+            constants: vec![Constant::Empty],
+            indices: HashMap::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.constants.len()
+    }
+
+    pub fn get(&self, index: u16) -> ClassResult<&Constant> {
+        self.constants
+            .get(index as usize)
+            .ok_or("constant pool index out of bounds")
+    }
+
+    pub fn set(&mut self, index: u16, constant: Constant) {
+        let old = std::mem::replace(&mut self.constants[index as usize], constant.clone());
+        if self.indices.get(&old) == Some(&index) {
+            self.indices.remove(&old);
+        }
+        self.indices.entry(constant).or_insert(index);
+    }
+
+    /// Adds a constant, reusing an equal one if there's already one.
+    pub fn add(&mut self, constant: Constant) -> ClassResult<u16> {
+        if let Some(&index) = self.indices.get(&constant) {
+            return Ok(index);
+        }
+        let index = u16::try_from(self.constants.len()).map_err(|_| "constant pool too large")?;
+        let wide = matches!(constant, Constant::Long(_) | Constant::Double(_));
+        self.indices.insert(constant.clone(), index);
+        self.constants.push(constant);
+        if wide {
+            self.constants.push(Constant::Empty);
+        }
+        Ok(index)
+    }
+
+    pub fn add_utf8(&mut self, value: &str) -> ClassResult<u16> {
+        self.add(Constant::Utf8(encode_modified_utf8(value)))
+    }
+
+    /// The string of a UTF-8 constant, lossily for the few encodings Rust and Java disagree on.
+    pub fn utf8(&self, index: u16) -> ClassResult<Cow<'_, str>> {
+        match self.get(index)? {
+            Constant::Utf8(bytes) => Ok(String::from_utf8_lossy(bytes)),
+            _ => Err("expected a UTF-8 constant"),
+        }
+    }
+
+    /// The name of a class constant.
+    pub fn class_name(&self, index: u16) -> ClassResult<Cow<'_, str>> {
+        match self.get(index)? {
+            Constant::Class(name) => self.utf8(*name),
+            _ => Err("expected a class constant"),
+        }
+    }
+
+    pub fn name_and_type(&self, index: u16) -> ClassResult<(Cow<'_, str>, Cow<'_, str>)> {
+        match self.get(index)? {
+            Constant::NameAndType(name, descriptor) => {
+                Ok((self.utf8(*name)?, self.utf8(*descriptor)?))
+            }
+            _ => Err("expected a name and type constant"),
+        }
+    }
+
+    fn read(reader: &mut Reader) -> ClassResult<Self> {
+        let count = reader.u16()?;
+        let mut pool = Self::new();
+        while pool.constants.len() < count as usize {
+            let constant = match reader.u8()? {
+                1 => {
+                    let len = reader.u16()? as usize;
+                    Constant::Utf8(reader.bytes(len)?.to_vec())
+                }
+                3 => Constant::Integer(reader.u32()?),
+                4 => Constant::Float(reader.u32()?),
+                5 => Constant::Long(reader.u64()?),
+                6 => Constant::Double(reader.u64()?),
+                7 => Constant::Class(reader.u16()?),
+                8 => Constant::String(reader.u16()?),
+                9 => Constant::FieldRef(reader.u16()?, reader.u16()?),
+                10 => Constant::MethodRef(reader.u16()?, reader.u16()?),
+                11 => Constant::InterfaceMethodRef(reader.u16()?, reader.u16()?),
+                12 => Constant::NameAndType(reader.u16()?, reader.u16()?),
+                15 => Constant::MethodHandle(reader.u8()?, reader.u16()?),
+                16 => Constant::MethodType(reader.u16()?),
+                17 => Constant::Dynamic(reader.u16()?, reader.u16()?),
+                18 => Constant::InvokeDynamic(reader.u16()?, reader.u16()?),
+                19 => Constant::Module(reader.u16()?),
+                20 => Constant::Package(reader.u16()?),
+                _ => return Err("unknown constant pool tag"),
+            };
+            // duplicates in the input keep their first index
+            let index = pool.constants.len() as u16;
+            pool.indices.entry(constant.clone()).or_insert(index);
+            let wide = matches!(constant, Constant::Long(_) | Constant::Double(_));
+            pool.constants.push(constant);
+            if wide {
+                pool.constants.push(Constant::Empty);
+            }
+        }
+        Ok(pool)
+    }
+
+    fn write(&self, out: &mut Vec<u8>) {
+        write_u16(out, self.constants.len() as u16);
+        for constant in &self.constants {
+            match constant {
+                Constant::Empty => {}
+                Constant::Utf8(bytes) => {
+                    out.push(1);
+                    write_u16(out, bytes.len() as u16);
+                    out.extend_from_slice(bytes);
+                }
+                Constant::Integer(value) => {
+                    out.push(3);
+                    out.extend_from_slice(&value.to_be_bytes());
+                }
+                Constant::Float(value) => {
+                    out.push(4);
+                    out.extend_from_slice(&value.to_be_bytes());
+                }
+                Constant::Long(value) => {
+                    out.push(5);
+                    out.extend_from_slice(&value.to_be_bytes());
+                }
+                Constant::Double(value) => {
+                    out.push(6);
+                    out.extend_from_slice(&value.to_be_bytes());
+                }
+                Constant::Class(name) => {
+                    out.push(7);
+                    write_u16(out, *name);
+                }
+                Constant::String(value) => {
+                    out.push(8);
+                    write_u16(out, *value);
+                }
+                Constant::FieldRef(class, nat) => write_pair(out, 9, *class, *nat),
+                Constant::MethodRef(class, nat) => write_pair(out, 10, *class, *nat),
+                Constant::InterfaceMethodRef(class, nat) => write_pair(out, 11, *class, *nat),
+                Constant::NameAndType(name, descriptor) => write_pair(out, 12, *name, *descriptor),
+                Constant::MethodHandle(kind, reference) => {
+                    out.push(15);
+                    out.push(*kind);
+                    write_u16(out, *reference);
+                }
+                Constant::MethodType(descriptor) => {
+                    out.push(16);
+                    write_u16(out, *descriptor);
+                }
+                Constant::Dynamic(bootstrap, nat) => write_pair(out, 17, *bootstrap, *nat),
+                Constant::InvokeDynamic(bootstrap, nat) => write_pair(out, 18, *bootstrap, *nat),
+                Constant::Module(name) => {
+                    out.push(19);
+                    write_u16(out, *name);
+                }
+                Constant::Package(name) => {
+                    out.push(20);
+                    write_u16(out, *name);
+                }
+            }
+        }
+    }
+}
+
+fn write_pair(out: &mut Vec<u8>, tag: u8, first: u16, second: u16) {
+    out.push(tag);
+    write_u16(out, first);
+    write_u16(out, second);
+}
+
+/// Encodes like Java's `DataOutput::writeUTF`, with nulls and supplementary characters in
+/// several bytes.
+fn encode_modified_utf8(value: &str) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(value.len());
+    for c in value.chars() {
+        let mut units = [0; 2];
+        for &unit in c.encode_utf16(&mut units).iter() {
+            match unit {
+                1..=0x7f => bytes.push(unit as u8),
+                0 | 0x80..=0x7ff => {
+                    bytes.push(0xc0 | (unit >> 6) as u8);
+                    bytes.push(0x80 | (unit & 0x3f) as u8);
+                }
+                _ => {
+                    bytes.push(0xe0 | (unit >> 12) as u8);
+                    bytes.push(0x80 | ((unit >> 6) & 0x3f) as u8);
+                    bytes.push(0x80 | (unit & 0x3f) as u8);
+                }
+            }
+        }
+    }
+    bytes
+}
+
+impl ClassFile {
+    pub fn parse(bytes: &[u8]) -> ClassResult<Self> {
+        let mut reader = Reader::new(bytes);
+        if reader.u32()? != 0xcafebabe {
+            return Err("not a class file");
+        }
+        let minor = reader.u16()?;
+        let major = reader.u16()?;
+        let pool = ConstantPool::read(&mut reader)?;
+        let access = reader.u16()?;
+        let this_class = reader.u16()?;
+        let super_class = reader.u16()?;
+        let interfaces = (0..reader.u16()?)
+            .map(|_| reader.u16())
+            .collect::<ClassResult<_>>()?;
+        let fields = (0..reader.u16()?)
+            .map(|_| reader.member())
+            .collect::<ClassResult<_>>()?;
+        let methods = (0..reader.u16()?)
+            .map(|_| reader.member())
+            .collect::<ClassResult<_>>()?;
+        let attributes = reader.attributes()?;
+
+        Ok(Self {
+            minor,
+            major,
+            pool,
+            access,
+            this_class,
+            super_class,
+            interfaces,
+            fields,
+            methods,
+            attributes,
+        })
+    }
+
+    pub fn write(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(&0xcafebabe_u32.to_be_bytes());
+        write_u16(&mut out, self.minor);
+        write_u16(&mut out, self.major);
+        self.pool.write(&mut out);
+        write_u16(&mut out, self.access);
+        write_u16(&mut out, self.this_class);
+        write_u16(&mut out, self.super_class);
+        write_u16(&mut out, self.interfaces.len() as u16);
+        for &interface in &self.interfaces {
+            write_u16(&mut out, interface);
+        }
+        for members in [&self.fields, &self.methods] {
+            write_u16(&mut out, members.len() as u16);
+            for member in members {
+                write_u16(&mut out, member.access);
+                write_u16(&mut out, member.name);
+                write_u16(&mut out, member.descriptor);
+                write_attributes(&mut out, &member.attributes);
+            }
+        }
+        write_attributes(&mut out, &self.attributes);
+        out
+    }
+
+    pub fn name(&self) -> ClassResult<Cow<'_, str>> {
+        self.pool.class_name(self.this_class)
+    }
+
+    pub fn super_name(&self) -> ClassResult<Option<Cow<'_, str>>> {
+        match self.super_class {
+            0 => Ok(None),
+            index => self.pool.class_name(index).map(Some),
+        }
+    }
+
+    pub fn interface_names(&self) -> ClassResult<Vec<Cow<'_, str>>> {
+        self.interfaces
+            .iter()
+            .map(|&index| self.pool.class_name(index))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::class_file::{ClassFile, Constant, ConstantPool, encode_modified_utf8};
+
+    #[test]
+    fn test_round_trip() {
+        let mut pool = ConstantPool::new();
+        let name = pool.add_utf8("a").unwrap();
+        let this_class = pool.add(Constant::Class(name)).unwrap();
+        pool.add(Constant::Long(42)).unwrap();
+        let after_long = pool.add_utf8("b").unwrap();
+        assert_eq!(after_long, 5);
+        assert_eq!(pool.add_utf8("a").unwrap(), name);
+
+        let class = ClassFile {
+            minor: 0,
+            major: 65,
+            pool,
+            access: 0x21,
+            this_class,
+            super_class: 0,
+            interfaces: Vec::new(),
+            fields: Vec::new(),
+            methods: Vec::new(),
+            attributes: Vec::new(),
+        };
+        let parsed = ClassFile::parse(&class.write()).unwrap();
+        assert_eq!(parsed, class);
+        assert_eq!(parsed.name().unwrap(), "a");
+
+        assert_eq!(
+            ClassFile::parse(b"\xca\xfe"),
+            Err("unexpected end of class file")
+        );
+    }
+
+    #[test]
+    fn test_modified_utf8() {
+        assert_eq!(encode_modified_utf8("a/b"), b"a/b");
+        assert_eq!(encode_modified_utf8("\0"), [0xc0, 0x80]);
+        assert_eq!(encode_modified_utf8("é"), "é".as_bytes());
+        assert_eq!(encode_modified_utf8("😀").len(), 6);
+    }
+}
@@ -0,0 +1,446 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    env::JoinPathsError,
+    ffi::{OsStr, OsString},
+    fs::{self, File},
+    io::{self, BufReader},
+    path::Path,
+    slice,
+};
+
+use zip::ZipArchive;
+
+use crate::{
+    Argument, ArgumentValue, LibraryDownload, LibraryExtractInstructions, LoggingConfig, OsName,
+    OsRule, Rule, RuleAction, VersionManifest,
+};
+
+/// What the rules of a version manifest are checked against, with the values of the `${name}`
+/// placeholders of its arguments.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LaunchEnvironment {
+    pub os: OsName,
+    /// The architecture, `x86` being the 32-bit one as in rules.
+    pub arch: String,
+    /// Launcher features such as `is_demo_user`, missing ones being off.
+    pub features: BTreeMap<String, bool>,
+    /// Values like `auth_player_name` or `natives_directory`.
+    pub variables: HashMap<String, String>,
+}
+
+impl LaunchEnvironment {
+    /// The OS and architecture this runs on, without features or variables.
+    pub fn current() -> Self {
+        Self::new(OsName::current(), std::env::consts::ARCH)
+    }
+
+    pub fn new(os: OsName, arch: impl Into<String>) -> Self {
+        Self {
+            os,
+            arch: arch.into(),
+            features: BTreeMap::new(),
+            variables: HashMap::new(),
+        }
+    }
+
+    pub fn with_feature(mut self, name: impl Into<String>, enabled: bool) -> Self {
+        self.features.insert(name.into(), enabled);
+        self
+    }
+
+    pub fn with_variable(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.variables.insert(name.into(), value.into());
+        self
+    }
+
+    /// Whether rules allow something: no rules allow it, otherwise the last matching rule
+    /// decides and it's disallowed if none matches.
+    pub fn allows(&self, rules: &[Rule]) -> bool {
+        rules.is_empty()
+            || rules
+                .iter()
+                .rev()
+                .find(|rule| rule.matches(self))
+                .is_some_and(|rule| rule.action == RuleAction::Allow)
+    }
+
+    /// Replaces the `${name}` placeholders with their variable, keeping unknown ones as they are.
+    pub fn substitute(&self, value: &str) -> String {
+        let mut result = String::with_capacity(value.len());
+        let mut rest = value;
+        while let Some(start) = rest.find("${") {
+            let Some(len) = rest[start..].find('}') else {
+                break;
+            };
+            let placeholder = &rest[start..start + len + 1];
+            result.push_str(&rest[..start]);
+            match self.variables.get(&placeholder[2..len]) {
+                Some(value) => result.push_str(value),
+                None => result.push_str(placeholder),
+            }
+            rest = &rest[start + len + 1..];
+        }
+        result.push_str(rest);
+        result
+    }
+
+    /// The arguments allowed by their rules, with their placeholders replaced.
+    pub fn resolve(&self, arguments: &[Argument]) -> Vec<String> {
+        arguments
+            .iter()
+            .flat_map(|argument| match argument {
+                Argument::Plain(value) => slice::from_ref(value),
+                Argument::Conditional { rules, value } if self.allows(rules) => value.values(),
+                Argument::Conditional { .. } => &[],
+            })
+            .map(|value| self.substitute(value))
+            .collect()
+    }
+}
+
+impl Rule {
+    /// Whether the rule applies, its action then deciding.
+    pub fn matches(&self, env: &LaunchEnvironment) -> bool {
+        self.os.as_ref().is_none_or(|os| os.matches(env))
+            && self.features.iter().all(|(name, &enabled)| {
+                env.features.get(name).copied().unwrap_or_default() == enabled
+            })
+    }
+}
+
+impl OsRule {
+    pub fn matches(&self, env: &LaunchEnvironment) -> bool {
+        self.name.is_none_or(|name| name == env.os)
+            && self.arch.as_ref().is_none_or(|arch| *arch == env.arch)
+    }
+}
+
+impl ArgumentValue {
+    pub fn values(&self) -> &[String] {
+        match self {
+            Self::One(value) => slice::from_ref(value),
+            Self::Many(values) => values,
+        }
+    }
+}
+
+impl LoggingConfig {
+    /// The JVM argument to pass with the configuration file downloaded at `path`.
+    pub fn argument_for(&self, path: &Path) -> String {
+        self.argument.replace("${path}", &path.to_string_lossy())
+    }
+}
+
+impl VersionManifest {
+    /// The JVM arguments, or the ones launchers passed before 1.13 for older versions.
+    pub fn jvm_arguments(&self, env: &LaunchEnvironment) -> Vec<String> {
+        match &self.arguments {
+            Some(arguments) => env.resolve(&arguments.jvm),
+            None => [
+                "-Djava.library.path=${natives_directory}",
+                "-cp",
+                "${classpath}",
+            ]
+            .into_iter()
+            .map(|argument| env.substitute(argument))
+            .collect(),
+        }
+    }
+
+    pub fn game_arguments(&self, env: &LaunchEnvironment) -> Vec<String> {
+        match (&self.arguments, &self.minecraft_arguments) {
+            (Some(arguments), _) => env.resolve(&arguments.game),
+            (None, Some(arguments)) => arguments
+                .split_whitespace()
+                .map(|argument| env.substitute(argument))
+                .collect(),
+            (None, None) => Vec::new(),
+        }
+    }
+
+    /// The jars of the libraries allowed in the environment, in classpath order.
+    pub fn classpath_libraries(&self, env: &LaunchEnvironment) -> Vec<&LibraryDownload> {
+        self.libraries
+            .iter()
+            .filter(|library| env.allows(&library.rules))
+            .filter_map(|library| library.artifact())
+            .collect()
+    }
+
+    /// The natives jars to extract for the environment, with what to leave out of them.
+    pub fn native_libraries(
+        &self,
+        env: &LaunchEnvironment,
+    ) -> Vec<(&LibraryDownload, &LibraryExtractInstructions)> {
+        self.libraries
+            .iter()
+            .filter(|library| env.allows(&library.rules))
+            .filter_map(|library| Some((library.native_for(env)?, &library.extract)))
+            .collect()
+    }
+}
+
+/// Extracts a natives jar into `dir`, leaving out the entries under an excluded prefix such as
+/// `META-INF/` and the ones that would escape `dir`.
+pub fn extract_natives(
+    jar: &Path,
+    dir: &Path,
+    extract: &LibraryExtractInstructions,
+) -> io::Result<()> {
+    let mut archive = ZipArchive::new(BufReader::new(File::open(jar)?))?;
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i)?;
+        if entry.is_dir()
+            || extract
+                .exclude
+                .iter()
+                .any(|prefix| entry.name().starts_with(prefix.as_str()))
+        {
+            continue;
+        }
+        let Some(name) = entry.enclosed_name() else {
+            continue;
+        };
+
+        let target = dir.join(name);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        io::copy(&mut entry, &mut File::create(target)?)?;
+    }
+    Ok(())
+}
+
+/// Joins paths into a classpath, separated by `;` on Windows and `:` elsewhere.
+pub fn join_classpath<I>(paths: I) -> Result<OsString, JoinPathsError>
+where
+    I: IntoIterator,
+    I::Item: AsRef<OsStr>,
+{
+    std::env::join_paths(paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs::{self, File},
+        io::Write,
+        path::Path,
+    };
+
+    use zip::{ZipWriter, write::SimpleFileOptions};
+
+    use crate::{
+        LaunchEnvironment, OsName, Rule, VersionManifest, extract_natives, join_classpath,
+    };
+
+    fn manifest(json: &str) -> VersionManifest {
+        serde_json::from_str(json).unwrap()
+    }
+
+    fn modern() -> VersionManifest {
+        manifest(include_str!("../fixtures/1.21.4.json"))
+    }
+
+    fn legacy() -> VersionManifest {
+        manifest(include_str!("../fixtures/1.12.2.json"))
+    }
+
+    fn env(os: OsName, arch: &str) -> LaunchEnvironment {
+        LaunchEnvironment::new(os, arch)
+            .with_variable("auth_player_name", "Steve")
+            .with_variable("natives_directory", "/natives")
+            .with_variable("classpath", "a.jar:b.jar")
+            .with_variable("resolution_width", "854")
+            .with_variable("resolution_height", "480")
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let modern = modern();
+        assert_eq!(modern.main_class, "net.minecraft.client.main.Main");
+        assert_eq!(modern.java_version.as_ref().unwrap().major_version, 21);
+        assert_eq!(modern.asset_index.id, "19");
+        let logging = modern.logging.as_ref().unwrap().client.as_ref().unwrap();
+        assert_eq!(
+            logging.argument_for(Path::new("client.xml")),
+            "-Dlog4j.configurationFile=client.xml"
+        );
+
+        let legacy = legacy();
+        assert!(legacy.arguments.is_none() && legacy.downloads.client_mappings.is_none());
+        assert_eq!(legacy.libraries[1].extract.exclude, ["META-INF/"]);
+
+        // manifests survive a round trip
+        let json = serde_json::to_string(&modern).unwrap();
+        assert_eq!(manifest(&json), modern);
+    }
+
+    #[test]
+    fn test_rules() {
+        let rules: Vec<Rule> = serde_json::from_str(
+            r#"[{"action": "allow"}, {"action": "disallow", "os": {"name": "osx"}}]"#,
+        )
+        .unwrap();
+        assert!(env(OsName::Linux, "x86_64").allows(&rules));
+        assert!(!env(OsName::Osx, "aarch64").allows(&rules));
+        assert!(env(OsName::Osx, "aarch64").allows(&[]));
+
+        let demo: Vec<Rule> =
+            serde_json::from_str(r#"[{"action": "allow", "features": {"is_demo_user": true}}]"#)
+                .unwrap();
+        let env = env(OsName::Linux, "x86_64");
+        assert!(!env.allows(&demo));
+        assert!(env.with_feature("is_demo_user", true).allows(&demo));
+    }
+
+    #[test]
+    fn test_substitute() {
+        let env = env(OsName::Linux, "x86_64");
+        assert_eq!(
+            env.substitute("-Djava.library.path=${natives_directory}"),
+            "-Djava.library.path=/natives"
+        );
+        assert_eq!(
+            env.substitute("${auth_player_name}:${unknown}:${"),
+            "Steve:${unknown}:${"
+        );
+    }
+
+    #[test]
+    fn test_arguments() {
+        let modern = modern();
+        let linux = env(OsName::Linux, "x86_64");
+        let jvm = modern.jvm_arguments(&linux);
+        assert_eq!(jvm[0], "-Djava.library.path=/natives");
+        assert_eq!(jvm[jvm.len() - 2..], ["-cp", "a.jar:b.jar"]);
+
+        let mac = env(OsName::Osx, "aarch64");
+        assert_eq!(modern.jvm_arguments(&mac)[0], "-XstartOnFirstThread");
+        let windows = env(OsName::Windows, "x86");
+        assert_eq!(modern.jvm_arguments(&windows)[1], "-Xss1M");
+
+        let game = modern.game_arguments(&linux);
+        assert_eq!(game[..2], ["--username", "Steve"]);
+        assert!(!game.contains(&"--demo".to_owned()));
+        let game = modern.game_arguments(
+            &linux
+                .clone()
+                .with_feature("is_demo_user", true)
+                .with_feature("has_custom_resolution", true),
+        );
+        assert_eq!(
+            game[game.len() - 5..],
+            ["--demo", "--width", "854", "--height", "480"]
+        );
+
+        let legacy = legacy();
+        assert_eq!(
+            legacy.jvm_arguments(&linux),
+            ["-Djava.library.path=/natives", "-cp", "a.jar:b.jar"]
+        );
+        assert_eq!(legacy.game_arguments(&linux)[..2], ["--username", "Steve"]);
+    }
+
+    #[test]
+    fn test_libraries() {
+        let modern = modern();
+        let paths = |os, arch| {
+            modern
+                .classpath_libraries(&LaunchEnvironment::new(os, arch))
+                .into_iter()
+                .map(|library| library.path.rsplit('/').next().unwrap())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            paths(OsName::Linux, "x86_64"),
+            [
+                "brigadier-1.3.10.jar",
+                "lwjgl-3.3.3.jar",
+                "lwjgl-3.3.3-natives-linux.jar"
+            ]
+        );
+        assert_eq!(
+            paths(OsName::Osx, "aarch64")[2],
+            "lwjgl-3.3.3-natives-macos.jar"
+        );
+        assert!(
+            modern
+                .native_libraries(&LaunchEnvironment::new(OsName::Linux, "x86_64"))
+                .is_empty()
+        );
+
+        let legacy = legacy();
+        let natives = |os, arch| {
+            legacy
+                .native_libraries(&LaunchEnvironment::new(os, arch))
+                .into_iter()
+                .map(|(library, extract)| {
+                    (
+                        library.path.rsplit('/').next().unwrap(),
+                        extract.exclude.len(),
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            natives(OsName::Linux, "x86_64"),
+            [("lwjgl-platform-2.9.4-nightly-20150209-natives-linux.jar", 1)]
+        );
+        assert_eq!(
+            natives(OsName::Windows, "x86")[1].0,
+            "twitch-platform-6.5-natives-windows-32.jar"
+        );
+        assert!(natives(OsName::Osx, "x86_64").is_empty());
+        assert_eq!(
+            legacy
+                .classpath_libraries(&LaunchEnvironment::new(OsName::Linux, "x86_64"))
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_extract_natives() {
+        let dir =
+            std::env::temp_dir().join(format!("mcje_downloader_natives_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        let jar = dir.join("natives.jar");
+        let mut zip = ZipWriter::new(File::create(&jar).unwrap());
+        for (name, content) in [
+            ("META-INF/MANIFEST.MF", "Manifest-Version: 1.0"),
+            ("liblwjgl.so", "elf"),
+            ("linux/x64/libglfw.so", "elf"),
+            ("../escape.so", "elf"),
+        ] {
+            zip.start_file(name, SimpleFileOptions::default()).unwrap();
+            zip.write_all(content.as_bytes()).unwrap();
+        }
+        zip.add_directory("empty/", SimpleFileOptions::default())
+            .unwrap();
+        zip.finish().unwrap();
+
+        let natives = dir.join("natives");
+        let legacy = legacy();
+        extract_natives(&jar, &natives, &legacy.libraries[1].extract).unwrap();
+        assert_eq!(
+            fs::read_to_string(natives.join("liblwjgl.so")).unwrap(),
+            "elf"
+        );
+        assert!(natives.join("linux/x64/libglfw.so").exists());
+        assert!(!natives.join("META-INF").exists());
+        assert!(!natives.join("empty").exists());
+        assert!(!dir.join("escape.so").exists());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_join_classpath() {
+        let classpath = join_classpath(["a.jar", "libs/b.jar"]).unwrap();
+        let separator = if cfg!(windows) { ";" } else { ":" };
+        assert_eq!(classpath, format!("a.jar{separator}libs/b.jar").as_str());
+    }
+}
@@ -1,12 +1,45 @@
+mod assets;
+mod cache;
+mod class_file;
+mod launch;
+mod manager;
+mod mappings;
+mod remap;
+#[cfg(test)]
+mod test_server;
+
+pub use assets::{AssetIndex, AssetIndexInfo, AssetObject, AssetStore};
+pub use cache::{CacheError, DownloadCache, DownloadSource};
+pub use launch::{LaunchEnvironment, extract_natives, join_classpath};
+pub use manager::{
+    Canceller, DownloadEvent, DownloadManager, DownloadRequest, Downloads, Progress,
+};
+pub use mappings::{ClassMapping, FieldMapping, LineRange, Mappings, MappingsError, MethodMapping};
+pub use remap::{RemapError, Remapper};
+
 use bytes::Bytes;
 use chrono::{DateTime, Utc};
 use futures_core::Stream;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use url::Url;
 
 const MANIFEST_URL: &str = "https://piston-meta.mojang.com/mc/game/version_manifest_v2.json";
 
+/// Game versions the crates can target, newest first, as `(cargo feature, version id)`.
+pub const SUPPORTED_VERSIONS: [(&str, &str); 1] = [("v1_21_11", "1.21.11")];
+
+/// The version id selected by the version features of the crate whose build script calls this,
+/// the newest one if several are enabled.
+pub fn feature_version() -> Option<&'static str> {
+    SUPPORTED_VERSIONS
+        .into_iter()
+        .find(|(feature, _)| {
+            std::env::var_os(format!("CARGO_FEATURE_{}", feature.to_uppercase())).is_some()
+        })
+        .map(|(_, id)| id)
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub struct LatestReleases {
     pub release: String,
@@ -41,20 +74,102 @@ pub struct RootManifest {
     pub versions: Vec<VersionRelease>,
 }
 
-// many fields omitted
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
 pub struct VersionManifest {
+    /// The arguments of the JVM and the game since 1.13.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub arguments: Option<Arguments>,
+    pub asset_index: AssetIndexInfo,
+    /// The id of the asset index.
+    pub assets: String,
+    #[serde(default)]
+    pub compliance_level: u8,
     pub downloads: VersionDownloads,
     pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub java_version: Option<JavaVersion>,
     pub libraries: Vec<Library>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub logging: Option<Logging>,
+    pub main_class: String,
+    /// The arguments of the game before 1.13, separated by spaces.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub minecraft_arguments: Option<String>,
+    pub minimum_launcher_version: u32,
+    pub release_time: DateTime<Utc>,
+    pub time: DateTime<Utc>,
+    #[serde(rename = "type")]
+    pub kind: ReleaseKind,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct Arguments {
+    #[serde(default)]
+    pub game: Vec<Argument>,
+    #[serde(default)]
+    pub jvm: Vec<Argument>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum Argument {
+    Plain(String),
+    /// Arguments only passed when the rules allow them.
+    Conditional {
+        rules: Vec<Rule>,
+        value: ArgumentValue,
+    },
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum ArgumentValue {
+    One(String),
+    Many(Vec<String>),
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct JavaVersion {
+    /// The Java runtime of the launcher, like `java-runtime-delta`.
+    pub component: String,
+    pub major_version: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct Logging {
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub client: Option<LoggingConfig>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct LoggingConfig {
+    /// The JVM argument pointing to the configuration, with a `${path}` placeholder.
+    pub argument: String,
+    pub file: LoggingFile,
+    /// The format of the configuration, like `log4j2-xml`.
+    #[serde(rename = "type")]
+    pub kind: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct LoggingFile {
+    pub id: String,
+    pub sha1: String,
+    pub size: u64,
+    pub url: Url,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub struct VersionDownloads {
     pub client: DownloadInfo,
-    pub client_mappings: DownloadInfo,
+    /// The ProGuard mappings of the client, published since 1.14.4.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub client_mappings: Option<DownloadInfo>,
     pub server: DownloadInfo,
-    pub server_mappings: DownloadInfo,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub server_mappings: Option<DownloadInfo>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
@@ -83,7 +198,9 @@ pub struct LibraryExtractInstructions {
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub struct LibraryDownloads {
-    pub artifact: LibraryDownload,
+    /// Missing for libraries that only have natives.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub artifact: Option<LibraryDownload>,
     #[serde(skip_serializing_if = "HashMap::is_empty", default)]
     pub classifiers: HashMap<String, LibraryDownload>,
 }
@@ -104,10 +221,14 @@ pub enum OsName {
     Osx,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub struct Rule {
     pub action: RuleAction,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
     pub os: Option<OsRule>,
+    /// Launcher features that must be on or off, like `is_demo_user`.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty", default)]
+    pub features: BTreeMap<String, bool>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
@@ -117,9 +238,16 @@ pub enum RuleAction {
     Disallow,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub struct OsRule {
-    pub name: OsName,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub name: Option<OsName>,
+    /// A regex on the OS version, which only old versions use and which is ignored.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub version: Option<String>,
+    /// The architecture, like `x86`.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub arch: Option<String>,
 }
 
 impl RootManifest {
@@ -127,6 +255,11 @@ impl RootManifest {
         Self::fetch_from_url(MANIFEST_URL).await
     }
 
+    /// Takes the release with the given id out of the manifest.
+    pub fn into_version(self, id: &str) -> Option<VersionRelease> {
+        self.versions.into_iter().find(|ver| ver.id == id)
+    }
+
     pub async fn fetch_from_url(url: &str) -> reqwest::Result<Self> {
         reqwest::get(url).await?.error_for_status()?.json().await
     }
@@ -186,81 +319,63 @@ impl OsName {
     }
 }
 
-impl OsRule {
-    pub fn allow(&self) -> bool {
-        self.name.is_current()
-    }
-}
-
-impl Rule {
-    pub fn allow(&self) -> bool {
-        match self.action {
-            RuleAction::Allow => self.os.as_ref().is_none_or(|os| os.allow()),
-            RuleAction::Disallow => self.os.as_ref().is_some_and(|os| !os.allow()),
-        }
-    }
-}
-
 impl Library {
     pub async fn download(
         &self,
-    ) -> reqwest::Result<Option<((String, Bytes), Option<(String, Bytes)>)>> {
-        if !self.rules.iter().all(Rule::allow) {
+    ) -> reqwest::Result<Option<(Option<(String, Bytes)>, Option<(String, Bytes)>)>> {
+        let env = LaunchEnvironment::current();
+        if !env.allows(&self.rules) {
             return Ok(None);
         }
-        let artifact = (
-            self.downloads.artifact.path.clone(),
-            self.downloads.artifact.download().await?,
-        );
-        let natives = if let Some(natives_key) = self.natives.get(&OsName::current()) {
-            if let Some(natives_artifact) = self.downloads.classifiers.get(natives_key) {
-                Some((
-                    natives_artifact.path.clone(),
-                    natives_artifact.download().await?,
-                ))
-            } else {
-                None
-            }
-        } else {
-            None
+        let artifact = match self.artifact() {
+            Some(artifact) => Some((artifact.path.clone(), artifact.download().await?)),
+            None => None,
         };
-        Ok(Some((artifact, natives)))
+        let native = match self.native_for(&env) {
+            Some(native) => Some((native.path.clone(), native.download().await?)),
+            None => None,
+        };
+        Ok(Some((artifact, native)))
     }
 
     pub async fn download_as_stream(
         &self,
     ) -> reqwest::Result<
         Option<(
-            (String, impl Stream<Item = reqwest::Result<Bytes>>),
+            Option<(String, impl Stream<Item = reqwest::Result<Bytes>>)>,
             Option<(String, impl Stream<Item = reqwest::Result<Bytes>>)>,
         )>,
     > {
-        if !self.rules.iter().all(Rule::allow) {
+        let env = LaunchEnvironment::current();
+        if !env.allows(&self.rules) {
             return Ok(None);
         }
-        let artifact = (
-            self.downloads.artifact.path.clone(),
-            self.downloads.artifact.download_as_stream().await?,
-        );
-        let native = if let Some(native_artifact) = self.native() {
-            Some((
-                native_artifact.path.clone(),
-                native_artifact.download_as_stream().await?,
-            ))
-        } else {
-            None
+        let artifact = match self.artifact() {
+            Some(artifact) => Some((artifact.path.clone(), artifact.download_as_stream().await?)),
+            None => None,
+        };
+        let native = match self.native_for(&env) {
+            Some(native) => Some((native.path.clone(), native.download_as_stream().await?)),
+            None => None,
         };
         Ok(Some((artifact, native)))
     }
 
-    pub fn artifact(&self) -> &LibraryDownload {
-        &self.downloads.artifact
+    pub fn artifact(&self) -> Option<&LibraryDownload> {
+        self.downloads.artifact.as_ref()
     }
 
     pub fn native(&self) -> Option<&LibraryDownload> {
-        self.natives
-            .get(&OsName::current())
-            .and_then(|natives_key| self.downloads.classifiers.get(natives_key))
+        self.native_for(&LaunchEnvironment::current())
+    }
+
+    /// The natives jar for the OS and architecture of the environment, if the library has one.
+    pub fn native_for(&self, env: &LaunchEnvironment) -> Option<&LibraryDownload> {
+        let classifier = self.natives.get(&env.os)?;
+        let bits = if env.arch.ends_with("64") { "64" } else { "32" };
+        self.downloads
+            .classifiers
+            .get(&classifier.replace("${arch}", bits))
     }
 }
 
@@ -288,183 +403,3 @@ impl LibraryExtractInstructions {
         self.exclude.is_empty()
     }
 }
-
-/// Remove array brackets and count them
-fn remove_brackets(line: &str) -> (&str, usize) {
-    let mut result = line;
-    let mut count = 0;
-
-    while result.ends_with("[]") {
-        count += 1;
-        result = &result[..result.len() - 2];
-    }
-
-    (result, count)
-}
-
-/// Remap file path according to Java class signature conventions
-fn remap_file_path(path: &str) -> String {
-    let remap_primitives: HashMap<&str, &str> = [
-        ("int", "I"),
-        ("double", "D"),
-        ("boolean", "Z"),
-        ("float", "F"),
-        ("long", "J"),
-        ("byte", "B"),
-        ("short", "S"),
-        ("char", "C"),
-        ("void", "V"),
-    ]
-    .iter()
-    .cloned()
-    .collect();
-
-    if let Some(&primitive) = remap_primitives.get(path) {
-        primitive.to_string()
-    } else {
-        format!("L{};", path.replace(".", "/"))
-    }
-}
-
-/// Convert mappings from one format to another
-pub fn convert_mappings(mappings: &str) -> String {
-    // First pass: collect file names
-    let mut file_name: HashMap<String, String> = HashMap::new();
-
-    for line in mappings.lines() {
-        if line.starts_with('#') || line.starts_with("    ") {
-            continue;
-        }
-
-        let parts: Vec<&str> = line.split(" -> ").collect();
-        if parts.len() < 2 {
-            continue;
-        }
-
-        let deobf_name = parts[0];
-        let obf_name = parts[1].split(':').next().unwrap_or("");
-
-        file_name.insert(remap_file_path(deobf_name), obf_name.to_string());
-    }
-
-    // Second pass: process and store output
-    let mut output = String::new();
-
-    for line in mappings.lines() {
-        if line.starts_with('#') {
-            continue;
-        }
-
-        let parts: Vec<&str> = line.split(" -> ").collect();
-        if parts.len() < 2 {
-            continue;
-        }
-
-        let deobf_name = parts[0];
-        let obf_name = parts[1].trim();
-
-        if line.starts_with("    ") {
-            let deobf_name = deobf_name.trim_start();
-            let method_parts: Vec<&str> = deobf_name.split_whitespace().collect();
-            if method_parts.len() < 2 {
-                continue;
-            }
-
-            let mut method_type = method_parts[0]
-                .split(':')
-                .next_back()
-                .unwrap_or("")
-                .to_string();
-            let method_name = method_parts[1];
-
-            if method_name.contains('(') && method_name.contains(')') {
-                // Process function
-                let function_name = method_name.split('(').next().unwrap_or("");
-                let variables_str = if let Some(v) = method_name.split('(').next_back() {
-                    v.split(')').next().unwrap_or("")
-                } else {
-                    ""
-                };
-
-                let (method_type_clean, array_length_type) = remove_brackets(&method_type);
-                method_type = remap_file_path(method_type_clean);
-
-                // Get obfuscated class name if available
-                if let Some(obf_class) = file_name.get(&method_type) {
-                    method_type = format!("L{};", obf_class);
-                }
-
-                // Handle dots in class names
-                if method_type.contains('.') {
-                    method_type = method_type.replace(".", "/");
-                }
-
-                // Restore array brackets
-                for _ in 0..array_length_type {
-                    if method_type.ends_with(';') {
-                        method_type = format!("[{}", &method_type[..method_type.len() - 1]) + ";";
-                    } else {
-                        method_type = format!("[{}", method_type);
-                    }
-                }
-
-                let mut processed_variables = String::new();
-
-                if !variables_str.is_empty() {
-                    let variables: Vec<&str> = variables_str.split(',').collect();
-                    let mut processed_vars = Vec::new();
-
-                    for variable in variables {
-                        let (var_clean, array_count) = remove_brackets(variable);
-                        let mut remapped = remap_file_path(var_clean);
-
-                        // Get obfuscated class name if available
-                        if file_name.contains_key(&remapped) {
-                            let obf_class = file_name.get(&remapped).unwrap();
-                            remapped = format!("L{};", obf_class);
-                        }
-
-                        // Handle dots in class names
-                        if remapped.contains('.') {
-                            remapped = remapped.replace(".", "/");
-                        }
-
-                        // Restore array brackets
-                        for _ in 0..array_count {
-                            if remapped.ends_with(';') {
-                                remapped = format!("[{}", &remapped[..remapped.len() - 1]) + ";";
-                            } else {
-                                remapped = format!("[{}", remapped);
-                            }
-                        }
-
-                        processed_vars.push(remapped);
-                    }
-
-                    processed_variables = processed_vars.join("");
-                }
-
-                output.push_str(&format!(
-                    "\t{} ({}){} {}\n",
-                    obf_name, processed_variables, method_type, function_name
-                ));
-            } else {
-                // Process field
-                output.push_str(&format!("\t{} {}\n", obf_name, method_name));
-            }
-        } else {
-            // Process class
-            let obf_name = obf_name.split(':').next().unwrap_or("");
-            let deobf_remapped = remap_file_path(deobf_name);
-            let obf_remapped = remap_file_path(obf_name);
-
-            // Remove the L and ; from class names
-            let deobf_clean = &deobf_remapped[1..deobf_remapped.len() - 1];
-            let obf_clean = &obf_remapped[1..obf_remapped.len() - 1];
-
-            output.push_str(&format!("{} {}\n", obf_clean, deobf_clean));
-        }
-    }
-
-    output
-}
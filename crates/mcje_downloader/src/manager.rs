@@ -0,0 +1,568 @@
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use futures_core::Stream;
+use tokio::{
+    sync::{Semaphore, mpsc, watch},
+    task::{JoinHandle, JoinSet},
+    time::{self, Instant},
+};
+use url::Url;
+
+use crate::{CacheError, DownloadCache, DownloadSource};
+
+/// Downloads many files into a [`DownloadCache`] at once, retrying transient failures with an
+/// exponential backoff.
+#[derive(Debug, Clone)]
+pub struct DownloadManager {
+    cache: DownloadCache,
+    concurrency: usize,
+    retries: u32,
+    backoff: Duration,
+    max_backoff: Duration,
+    host_interval: Duration,
+}
+
+/// A file to download, owning what a [`DownloadSource`] may borrow.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DownloadRequest {
+    pub url: Url,
+    pub sha1: String,
+    pub size: u64,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    pub bytes: u64,
+    pub total: u64,
+}
+
+/// What happened to the file at `index` among the requested ones.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DownloadEvent {
+    /// Bytes of the file were received, or it was found in the cache.
+    Progress {
+        index: usize,
+        file: Progress,
+        overall: Progress,
+    },
+    /// The file failed with a transient error and is downloaded again after `delay`.
+    Retry {
+        index: usize,
+        attempt: u32,
+        delay: Duration,
+    },
+    Finished {
+        index: usize,
+        overall: Progress,
+    },
+}
+
+/// Running downloads, also a stream of their [`DownloadEvent`]s.
+pub struct Downloads {
+    events: mpsc::UnboundedReceiver<DownloadEvent>,
+    canceller: Canceller,
+    task: JoinHandle<Result<Vec<PathBuf>, CacheError>>,
+}
+
+/// Cancels [`Downloads`], possibly from another task.
+///
+/// Interrupted files are resumed by the next download of the same cache.
+#[derive(Debug, Clone)]
+pub struct Canceller(Arc<watch::Sender<bool>>);
+
+/// The state shared by the tasks of [`Downloads`].
+struct Shared {
+    manager: DownloadManager,
+    permits: Semaphore,
+    /// When the next request to each host may start.
+    hosts: Mutex<HashMap<String, Instant>>,
+    /// The bytes of each file, and of all of them.
+    progress: Mutex<(Vec<u64>, Progress)>,
+    events: mpsc::UnboundedSender<DownloadEvent>,
+    cancelled: watch::Receiver<bool>,
+}
+
+impl DownloadManager {
+    /// A manager running 8 downloads at once with 3 retries, without rate limit.
+    pub fn new(cache: DownloadCache) -> Self {
+        Self {
+            cache,
+            concurrency: 8,
+            retries: 3,
+            backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            host_interval: Duration::ZERO,
+        }
+    }
+
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Tries failed files `retries` more times, waiting `backoff` before the first retry and
+    /// twice as long before each following one.
+    pub fn with_retries(mut self, retries: u32, backoff: Duration) -> Self {
+        self.retries = retries;
+        self.backoff = backoff;
+        self
+    }
+
+    pub fn with_max_backoff(mut self, max_backoff: Duration) -> Self {
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    /// Starts requests to the same host at least `interval` apart.
+    pub fn with_host_interval(mut self, interval: Duration) -> Self {
+        self.host_interval = interval;
+        self
+    }
+
+    pub fn cache(&self) -> &DownloadCache {
+        &self.cache
+    }
+
+    /// Starts downloading the files in the background.
+    ///
+    /// Files with the same hash are only downloaded once.
+    pub fn start(&self, requests: impl IntoIterator<Item = DownloadRequest>) -> Downloads {
+        let requests = requests.into_iter().collect::<Vec<_>>();
+        let mut first = HashMap::new();
+        let owners = requests
+            .iter()
+            .enumerate()
+            .map(|(index, request)| {
+                *first
+                    .entry(request.sha1.to_ascii_lowercase())
+                    .or_insert(index)
+            })
+            .collect::<Vec<_>>();
+        let unique = (0..requests.len())
+            .filter(|&index| owners[index] == index)
+            .collect::<Vec<_>>();
+
+        let total = unique.iter().map(|&index| requests[index].size).sum();
+        let (events_tx, events) = mpsc::unbounded_channel();
+        let (cancel_tx, cancelled) = watch::channel(false);
+        let shared = Arc::new(Shared {
+            manager: self.clone(),
+            permits: Semaphore::new(self.concurrency),
+            hosts: Mutex::new(HashMap::new()),
+            progress: Mutex::new((vec![0; requests.len()], Progress { bytes: 0, total })),
+            events: events_tx,
+            cancelled,
+        });
+
+        let task = tokio::spawn(async move {
+            let mut tasks = JoinSet::new();
+            for index in unique {
+                let shared = shared.clone();
+                let request = requests[index].clone();
+                tasks.spawn(async move { (index, shared.download(index, &request).await) });
+            }
+            drop(shared);
+
+            let mut paths = vec![None; requests.len()];
+            while let Some(joined) = tasks.join_next().await {
+                let (index, result) = joined.unwrap_or_else(|err| {
+                    std::panic::resume_unwind(err.into_panic());
+                });
+                match result {
+                    Ok(path) => paths[index] = Some(path),
+                    Err(err) => {
+                        tasks.abort_all();
+                        return Err(err);
+                    }
+                }
+            }
+
+            Ok(owners
+                .into_iter()
+                .map(|owner| paths[owner].clone().expect("every file was downloaded"))
+                .collect())
+        });
+
+        Downloads {
+            events,
+            canceller: Canceller(Arc::new(cancel_tx)),
+            task,
+        }
+    }
+
+    /// Downloads the files, returning their paths in the order of the requests.
+    pub async fn download(
+        &self,
+        requests: impl IntoIterator<Item = DownloadRequest>,
+    ) -> Result<Vec<PathBuf>, CacheError> {
+        self.start(requests).finish().await
+    }
+
+    fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 1 << attempt.saturating_sub(1).min(16);
+        self.backoff.saturating_mul(factor).min(self.max_backoff)
+    }
+}
+
+impl Shared {
+    async fn download(
+        &self,
+        index: usize,
+        request: &DownloadRequest,
+    ) -> Result<PathBuf, CacheError> {
+        let mut cancelled = self.cancelled.clone();
+        let cancelled = async move {
+            // without any canceller left, downloads can't be cancelled anymore
+            if cancelled.wait_for(|cancelled| *cancelled).await.is_err() {
+                std::future::pending::<()>().await;
+            }
+        };
+        tokio::select! {
+            biased;
+            () = cancelled => Err(CacheError::Cancelled),
+            result = self.download_retrying(index, request) => result,
+        }
+    }
+
+    async fn download_retrying(
+        &self,
+        index: usize,
+        request: &DownloadRequest,
+    ) -> Result<PathBuf, CacheError> {
+        let _permit = self.permits.acquire().await.expect("never closed");
+        let cache = &self.manager.cache;
+
+        let mut attempt = 0;
+        let path = loop {
+            if let Some(path) = cache.cached(request).await? {
+                self.report(index, request, request.size);
+                break path;
+            }
+
+            self.wait_for_host(&request.url).await;
+            let result = cache
+                .get_with_progress(request, &mut |bytes| self.report(index, request, bytes))
+                .await;
+            match result {
+                Err(err) if err.is_transient() && attempt < self.manager.retries => {
+                    attempt += 1;
+                    let delay = self.manager.backoff(attempt);
+                    self.send(DownloadEvent::Retry {
+                        index,
+                        attempt,
+                        delay,
+                    });
+                    time::sleep(delay).await;
+                }
+                result => break result?,
+            }
+        };
+
+        let overall = self.progress.lock().unwrap().1;
+        self.send(DownloadEvent::Finished { index, overall });
+        Ok(path)
+    }
+
+    async fn wait_for_host(&self, url: &Url) {
+        let interval = self.manager.host_interval;
+        if interval.is_zero() {
+            return;
+        }
+
+        let start = {
+            let mut hosts = self.hosts.lock().unwrap();
+            let now = Instant::now();
+            let next = hosts
+                .entry(url.host_str().unwrap_or_default().to_owned())
+                .or_insert(now);
+            let start = (*next).max(now);
+            *next = start + interval;
+            start
+        };
+        time::sleep_until(start).await;
+    }
+
+    fn report(&self, index: usize, request: &DownloadRequest, bytes: u64) {
+        // send while locked so the overall progress of the events never goes back
+        let mut progress = self.progress.lock().unwrap();
+        let (files, overall) = &mut *progress;
+        overall.bytes = overall.bytes - files[index] + bytes;
+        files[index] = bytes;
+        self.send(DownloadEvent::Progress {
+            index,
+            file: Progress {
+                bytes,
+                total: request.size,
+            },
+            overall: *overall,
+        });
+    }
+
+    fn send(&self, event: DownloadEvent) {
+        // nobody listening is fine
+        let _ = self.events.send(event);
+    }
+}
+
+impl Downloads {
+    pub fn canceller(&self) -> Canceller {
+        self.canceller.clone()
+    }
+
+    pub fn cancel(&self) {
+        self.canceller.cancel();
+    }
+
+    /// The next event, or `None` once every download is over.
+    pub async fn next_event(&mut self) -> Option<DownloadEvent> {
+        self.events.recv().await
+    }
+
+    /// Waits for the downloads, returning the paths of the files in the order of the requests
+    /// or the first error.
+    pub async fn finish(self) -> Result<Vec<PathBuf>, CacheError> {
+        self.task
+            .await
+            .unwrap_or_else(|err| std::panic::resume_unwind(err.into_panic()))
+    }
+}
+
+impl Stream for Downloads {
+    type Item = DownloadEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.events.poll_recv(cx)
+    }
+}
+
+impl Canceller {
+    pub fn cancel(&self) {
+        self.0.send_replace(true);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        *self.0.borrow()
+    }
+}
+
+impl DownloadRequest {
+    pub fn of(source: &impl DownloadSource) -> Self {
+        Self {
+            url: source.url().clone(),
+            sha1: source.sha1().to_owned(),
+            size: source.size(),
+        }
+    }
+}
+
+impl DownloadSource for DownloadRequest {
+    fn url(&self) -> &Url {
+        &self.url
+    }
+
+    fn sha1(&self) -> &str {
+        &self.sha1
+    }
+
+    fn size(&self) -> u64 {
+        self.size
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        path::PathBuf,
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    use sha1::{Digest, Sha1};
+    use tokio::time::Instant;
+
+    use crate::{
+        CacheError, DownloadCache, DownloadEvent, DownloadManager, DownloadRequest,
+        cache::hex,
+        test_server::{Request, Response, TestServer},
+    };
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "mcje_downloader_manager_{name}_{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    /// The body served at `/<n>`.
+    fn body(n: usize) -> Vec<u8> {
+        vec![n as u8; 10_000 + n * 1000]
+    }
+
+    fn request(server: &TestServer, n: usize) -> DownloadRequest {
+        let body = body(n);
+        DownloadRequest {
+            url: server.url(&format!("/{n}")),
+            sha1: hex(&Sha1::digest(&body)),
+            size: body.len() as u64,
+        }
+    }
+
+    fn serve(request: &Request) -> Response {
+        let n = request.path[1..].parse().unwrap();
+        Response::file(request, &body(n))
+    }
+
+    /// Fails the first `failures` requests of each path with `failure`.
+    fn flaky(
+        failures: usize,
+        failure: impl Fn(&Request) -> Response + Send + Sync + 'static,
+    ) -> impl Fn(&Request) -> Response + Send + Sync + 'static {
+        let counts = Mutex::new(HashMap::<String, usize>::new());
+        move |request| {
+            let mut counts = counts.lock().unwrap();
+            let count = counts.entry(request.path.clone()).or_default();
+            *count += 1;
+            if *count <= failures {
+                failure(request)
+            } else {
+                serve(request)
+            }
+        }
+    }
+
+    fn manager(name: &str) -> DownloadManager {
+        DownloadManager::new(DownloadCache::new(temp_dir(name)))
+            .with_retries(3, Duration::from_millis(1))
+    }
+
+    #[tokio::test]
+    async fn test_concurrency_and_progress() {
+        let server =
+            TestServer::start(|request| serve(request).delayed(Duration::from_millis(20))).await;
+        let manager = manager("concurrency").with_concurrency(2);
+        // the duplicate is only downloaded once
+        let requests = (0..6)
+            .map(|n| request(&server, n))
+            .chain([request(&server, 3)])
+            .collect::<Vec<_>>();
+        let total = (0..6).map(|n| body(n).len() as u64).sum::<u64>();
+
+        let mut downloads = manager.start(requests.clone());
+        let mut finished = Vec::new();
+        let mut last_overall = 0;
+        while let Some(event) = downloads.next_event().await {
+            match event {
+                DownloadEvent::Progress { file, overall, .. } => {
+                    assert!(file.bytes <= file.total);
+                    assert!(overall.bytes >= last_overall);
+                    assert_eq!(overall.total, total);
+                    last_overall = overall.bytes;
+                }
+                DownloadEvent::Finished { index, .. } => finished.push(index),
+                DownloadEvent::Retry { .. } => panic!("nothing failed"),
+            }
+        }
+        assert_eq!(last_overall, total);
+        finished.sort();
+        assert_eq!(finished, [0, 1, 2, 3, 4, 5]);
+
+        let paths = downloads.finish().await.unwrap();
+        assert_eq!(paths[3], paths[6]);
+        for (n, path) in paths.iter().take(6).enumerate() {
+            assert_eq!(std::fs::read(path).unwrap(), body(n));
+        }
+        assert_eq!(server.requests().len(), 6);
+        assert_eq!(server.max_in_flight(), 2);
+
+        // everything is cached now
+        manager.download(requests).await.unwrap();
+        assert_eq!(server.requests().len(), 6);
+
+        std::fs::remove_dir_all(manager.cache().root()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_retries() {
+        let server = TestServer::start(flaky(2, |_| Response::status(503))).await;
+        let manager = manager("retries");
+        let mut downloads = manager.start((0..3).map(|n| request(&server, n)));
+        let mut retries = 0;
+        while let Some(event) = downloads.next_event().await {
+            if let DownloadEvent::Retry { attempt, delay, .. } = event {
+                assert_eq!(delay, Duration::from_millis(1 << (attempt - 1)));
+                retries += 1;
+            }
+        }
+        downloads.finish().await.unwrap();
+        assert_eq!(retries, 6);
+
+        // truncated transfers resume where they stopped
+        let server = TestServer::start(flaky(1, |request| serve(request).truncated(5000))).await;
+        manager.download([request(&server, 20)]).await.unwrap();
+        assert_eq!(server.requests().len(), 2);
+
+        let server = TestServer::start(flaky(4, |_| Response::status(500))).await;
+        let result = manager.download([request(&server, 7)]).await;
+        assert!(matches!(result, Err(CacheError::Http(_))));
+        assert_eq!(server.requests().len(), 4);
+
+        // client errors aren't retried
+        let server = TestServer::start(|_| Response::status(404)).await;
+        let result = manager.download([request(&server, 7)]).await;
+        assert!(matches!(result, Err(CacheError::Http(_))));
+        assert_eq!(server.requests().len(), 1);
+
+        std::fs::remove_dir_all(manager.cache().root()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_host_interval() {
+        let server = TestServer::start(serve).await;
+        let manager = manager("interval").with_host_interval(Duration::from_millis(30));
+
+        let start = Instant::now();
+        manager
+            .download((0..4).map(|n| request(&server, n)))
+            .await
+            .unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(90));
+
+        std::fs::remove_dir_all(manager.cache().root()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_cancel() {
+        let server =
+            TestServer::start(|request| serve(request).delayed(Duration::from_secs(10))).await;
+        let manager = manager("cancel");
+        let downloads = manager.start((0..4).map(|n| request(&server, n)));
+
+        let canceller = downloads.canceller();
+        let server = Arc::new(server);
+        tokio::spawn(async move {
+            while server.requests().is_empty() {
+                tokio::time::sleep(Duration::from_millis(1)).await;
+            }
+            canceller.cancel();
+        });
+
+        let start = Instant::now();
+        assert!(matches!(
+            downloads.finish().await,
+            Err(CacheError::Cancelled)
+        ));
+        assert!(start.elapsed() < Duration::from_secs(5));
+
+        let _ = std::fs::remove_dir_all(manager.cache().root());
+    }
+}
@@ -0,0 +1,745 @@
+use std::{
+    collections::HashMap,
+    fmt::{self, Display, Write},
+};
+
+/// Names of classes, fields and methods in a source namespace and their names in a target one,
+/// such as `official` (obfuscated) and `named`.
+///
+/// Class names are internal names like `net/minecraft/core/BlockPos`, and member descriptors are
+/// in the source namespace.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mappings {
+    source_namespace: String,
+    target_namespace: String,
+    classes: Vec<ClassMapping>,
+    by_source: HashMap<String, usize>,
+    by_target: HashMap<String, usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClassMapping {
+    pub source: String,
+    pub target: String,
+    pub fields: Vec<FieldMapping>,
+    pub methods: Vec<MethodMapping>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldMapping {
+    pub source: String,
+    pub target: String,
+    pub descriptor: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MethodMapping {
+    pub source: String,
+    pub target: String,
+    pub descriptor: String,
+    /// The line ranges of the method in the obfuscated code, only known from ProGuard.
+    pub lines: Vec<LineRange>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineRange {
+    pub start: u32,
+    pub end: u32,
+    /// The lines in the original source when the range was inlined from it.
+    pub original: Option<(u32, u32)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MappingsError {
+    /// A line, counted from 1, that doesn't follow the format.
+    Syntax { line: usize, content: String },
+    /// A Tiny file without a `tiny 2` header with two namespaces.
+    Header { content: String },
+}
+
+impl Mappings {
+    pub fn new(source_namespace: impl Into<String>, target_namespace: impl Into<String>) -> Self {
+        Self {
+            source_namespace: source_namespace.into(),
+            target_namespace: target_namespace.into(),
+            classes: Vec::new(),
+            by_source: HashMap::new(),
+            by_target: HashMap::new(),
+        }
+    }
+
+    /// Parses the ProGuard mappings published with each version, mapping the `official`
+    /// namespace to the `named` one.
+    pub fn from_proguard(text: &str) -> Result<Self, MappingsError> {
+        // descriptors are in the obfuscated namespace so every class is needed up front
+        let mut obfuscated = HashMap::new();
+        for line in text.lines() {
+            if let Some((named, obf)) = proguard_class(line) {
+                obfuscated.insert(named.replace('.', "/"), obf.replace('.', "/"));
+            }
+        }
+        let to_descriptor = |ty: &str| java_type_descriptor(ty, &obfuscated);
+
+        let mut mappings = Self::new("official", "named");
+        let mut class: Option<ClassMapping> = None;
+        for (index, line) in text.lines().enumerate() {
+            let syntax = || MappingsError::Syntax {
+                line: index + 1,
+                content: line.to_owned(),
+            };
+            if line.trim_start().starts_with('#') || line.trim().is_empty() {
+                continue;
+            }
+
+            if !line.starts_with(' ') {
+                let (named, obf) = proguard_class(line).ok_or_else(syntax)?;
+                if let Some(class) = class.take() {
+                    mappings.insert_class(class);
+                }
+                class = Some(ClassMapping::new(
+                    obf.replace('.', "/"),
+                    named.replace('.', "/"),
+                ));
+                continue;
+            }
+
+            let class = class.as_mut().ok_or_else(syntax)?;
+            let (member, obf) = line.trim().split_once(" -> ").ok_or_else(syntax)?;
+            let (lines, member) = match member.split_once(':') {
+                Some((start, rest)) if start.bytes().all(|b| b.is_ascii_digit()) => {
+                    let (end, member) = rest.split_once(':').ok_or_else(syntax)?;
+                    let start = start.parse().map_err(|_| syntax())?;
+                    let end = end.parse().map_err(|_| syntax())?;
+                    (Some((start, end)), member)
+                }
+                _ => (None, member),
+            };
+            let (ty, name) = member.split_once(' ').ok_or_else(syntax)?;
+
+            let Some((name, rest)) = name.split_once('(') else {
+                class.fields.push(FieldMapping {
+                    source: obf.to_owned(),
+                    target: name.to_owned(),
+                    descriptor: to_descriptor(ty),
+                });
+                continue;
+            };
+
+            let (params, original) = rest.split_once(')').ok_or_else(syntax)?;
+            let mut descriptor = String::from("(");
+            for param in params.split(',').filter(|param| !param.is_empty()) {
+                descriptor.push_str(&to_descriptor(param));
+            }
+            descriptor.push(')');
+            descriptor.push_str(&to_descriptor(ty));
+
+            let original = match original.strip_prefix(':') {
+                None if original.is_empty() => None,
+                None => return Err(syntax()),
+                Some(original) => {
+                    let (start, end) = original.split_once(':').unwrap_or((original, original));
+                    Some((
+                        start.parse().map_err(|_| syntax())?,
+                        end.parse().map_err(|_| syntax())?,
+                    ))
+                }
+            };
+            let range = lines.map(|(start, end)| LineRange {
+                start,
+                end,
+                original,
+            });
+
+            // inlined methods show up once per line range
+            match class
+                .methods
+                .iter_mut()
+                .find(|method| method.source == obf && method.descriptor == descriptor)
+            {
+                Some(method) => method.lines.extend(range),
+                None => class.methods.push(MethodMapping {
+                    source: obf.to_owned(),
+                    target: name.to_owned(),
+                    descriptor,
+                    lines: range.into_iter().collect(),
+                }),
+            }
+        }
+        if let Some(class) = class {
+            mappings.insert_class(class);
+        }
+
+        Ok(mappings)
+    }
+
+    /// Parses Tiny v2 mappings between their first two namespaces, ignoring parameters and
+    /// comments.
+    pub fn from_tiny_v2(text: &str) -> Result<Self, MappingsError> {
+        let mut lines = text.lines().enumerate();
+        let header = lines.next().map_or("", |(_, line)| line);
+        let namespaces = match header.split('\t').collect::<Vec<_>>()[..] {
+            ["tiny", "2", _, source, target, ..] => (source, target),
+            _ => {
+                return Err(MappingsError::Header {
+                    content: header.to_owned(),
+                });
+            }
+        };
+
+        let mut mappings = Self::new(namespaces.0, namespaces.1);
+        let mut class: Option<ClassMapping> = None;
+        for (index, line) in lines {
+            let syntax = || MappingsError::Syntax {
+                line: index + 1,
+                content: line.to_owned(),
+            };
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            // an empty name means the name doesn't change
+            let target = |source: &str, target: &str| {
+                if target.is_empty() { source } else { target }.to_owned()
+            };
+            match line.split('\t').collect::<Vec<_>>()[..] {
+                ["c", source, target_name, ..] => {
+                    if let Some(class) = class.take() {
+                        mappings.insert_class(class);
+                    }
+                    class = Some(ClassMapping::new(source, target(source, target_name)));
+                }
+                ["", kind @ ("f" | "m"), descriptor, source, target_name, ..] => {
+                    let class = class.as_mut().ok_or_else(syntax)?;
+                    let (source, target) = (source.to_owned(), target(source, target_name));
+                    let descriptor = descriptor.to_owned();
+                    if kind == "f" {
+                        class.fields.push(FieldMapping {
+                            source,
+                            target,
+                            descriptor,
+                        });
+                    } else {
+                        class.methods.push(MethodMapping {
+                            source,
+                            target,
+                            descriptor,
+                            lines: Vec::new(),
+                        });
+                    }
+                }
+                ["", "c", ..] | ["", "", ..] => {}
+                _ => return Err(syntax()),
+            }
+        }
+        if let Some(class) = class {
+            mappings.insert_class(class);
+        }
+
+        Ok(mappings)
+    }
+
+    pub fn source_namespace(&self) -> &str {
+        &self.source_namespace
+    }
+
+    pub fn target_namespace(&self) -> &str {
+        &self.target_namespace
+    }
+
+    /// Adds a class, replacing the one with the same source name.
+    pub fn insert_class(&mut self, class: ClassMapping) {
+        match self.by_source.get(&class.source) {
+            Some(&index) => {
+                self.by_target.remove(&self.classes[index].target);
+                self.by_target.insert(class.target.clone(), index);
+                self.classes[index] = class;
+            }
+            None => {
+                let index = self.classes.len();
+                self.by_source.insert(class.source.clone(), index);
+                self.by_target.insert(class.target.clone(), index);
+                self.classes.push(class);
+            }
+        }
+    }
+
+    pub fn classes(&self) -> &[ClassMapping] {
+        &self.classes
+    }
+
+    pub fn class(&self, source: &str) -> Option<&ClassMapping> {
+        self.by_source
+            .get(source)
+            .map(|&index| &self.classes[index])
+    }
+
+    pub fn class_by_target(&self, target: &str) -> Option<&ClassMapping> {
+        self.by_target
+            .get(target)
+            .map(|&index| &self.classes[index])
+    }
+
+    pub fn map_class<'a>(&'a self, source: &'a str) -> &'a str {
+        self.class(source).map_or(source, |class| &class.target)
+    }
+
+    pub fn map_field(&self, owner: &str, name: &str) -> Option<&str> {
+        Some(&self.class(owner)?.field(name)?.target)
+    }
+
+    pub fn map_method(&self, owner: &str, name: &str, descriptor: &str) -> Option<&str> {
+        Some(&self.class(owner)?.method(name, descriptor)?.target)
+    }
+
+    /// Maps the classes of a field or method descriptor to the target namespace.
+    pub fn map_descriptor(&self, descriptor: &str) -> String {
+        map_descriptor_classes(descriptor, |class| {
+            self.class(class).map(|class| class.target.as_str())
+        })
+    }
+
+    /// The mappings from the target namespace back to the source one.
+    pub fn invert(&self) -> Self {
+        let mut inverted = Self::new(&self.target_namespace, &self.source_namespace);
+        for class in &self.classes {
+            inverted.insert_class(ClassMapping {
+                source: class.target.clone(),
+                target: class.source.clone(),
+                fields: class
+                    .fields
+                    .iter()
+                    .map(|field| FieldMapping {
+                        source: field.target.clone(),
+                        target: field.source.clone(),
+                        descriptor: self.map_descriptor(&field.descriptor),
+                    })
+                    .collect(),
+                methods: class
+                    .methods
+                    .iter()
+                    .map(|method| MethodMapping {
+                        source: method.target.clone(),
+                        target: method.source.clone(),
+                        descriptor: self.map_descriptor(&method.descriptor),
+                        lines: method.lines.clone(),
+                    })
+                    .collect(),
+            });
+        }
+        inverted
+    }
+
+    /// Chains these mappings with mappings from their target namespace, like `official` to
+    /// `srg` with `srg` to `named`.
+    ///
+    /// Names `other` doesn't map stay as they are, and what only `other` maps is left out.
+    pub fn compose(&self, other: &Mappings) -> Self {
+        let mut composed = Self::new(&self.source_namespace, &other.target_namespace);
+        for class in &self.classes {
+            let next = other.class(&class.target);
+            composed.insert_class(ClassMapping {
+                source: class.source.clone(),
+                target: next.map_or(&class.target, |next| &next.target).clone(),
+                fields: class
+                    .fields
+                    .iter()
+                    .map(|field| FieldMapping {
+                        source: field.source.clone(),
+                        target: next
+                            .and_then(|next| next.field(&field.target))
+                            .map_or(&field.target, |next| &next.target)
+                            .clone(),
+                        descriptor: field.descriptor.clone(),
+                    })
+                    .collect(),
+                methods: class
+                    .methods
+                    .iter()
+                    .map(|method| {
+                        let descriptor = self.map_descriptor(&method.descriptor);
+                        MethodMapping {
+                            source: method.source.clone(),
+                            target: next
+                                .and_then(|next| next.method(&method.target, &descriptor))
+                                .map_or(&method.target, |next| &next.target)
+                                .clone(),
+                            descriptor: method.descriptor.clone(),
+                            lines: method.lines.clone(),
+                        }
+                    })
+                    .collect(),
+            });
+        }
+        composed
+    }
+
+    /// Writes TSRG, the format of SpecialSource's `--srg-in`.
+    pub fn to_tsrg(&self) -> String {
+        let mut out = String::new();
+        for class in &self.classes {
+            writeln!(out, "{} {}", class.source, class.target).unwrap();
+            for field in &class.fields {
+                writeln!(out, "\t{} {}", field.source, field.target).unwrap();
+            }
+            for method in &class.methods {
+                writeln!(
+                    out,
+                    "\t{} {} {}",
+                    method.source, method.descriptor, method.target
+                )
+                .unwrap();
+            }
+        }
+        out
+    }
+
+    /// Writes TSRG v2, with the namespaces in a header and field descriptors.
+    pub fn to_tsrg2(&self) -> String {
+        let mut out = format!(
+            "tsrg2 {} {}\n",
+            self.source_namespace, self.target_namespace
+        );
+        for class in &self.classes {
+            writeln!(out, "{} {}", class.source, class.target).unwrap();
+            for field in &class.fields {
+                writeln!(
+                    out,
+                    "\t{} {} {}",
+                    field.source, field.descriptor, field.target
+                )
+                .unwrap();
+            }
+            for method in &class.methods {
+                writeln!(
+                    out,
+                    "\t{} {} {}",
+                    method.source, method.descriptor, method.target
+                )
+                .unwrap();
+            }
+        }
+        out
+    }
+
+    /// Writes Tiny v2, the format of Fabric's tooling.
+    pub fn to_tiny_v2(&self) -> String {
+        let mut out = format!(
+            "tiny\t2\t0\t{}\t{}\n",
+            self.source_namespace, self.target_namespace
+        );
+        for class in &self.classes {
+            writeln!(out, "c\t{}\t{}", class.source, class.target).unwrap();
+            for field in &class.fields {
+                writeln!(
+                    out,
+                    "\tf\t{}\t{}\t{}",
+                    field.descriptor, field.source, field.target
+                )
+                .unwrap();
+            }
+            for method in &class.methods {
+                writeln!(
+                    out,
+                    "\tm\t{}\t{}\t{}",
+                    method.descriptor, method.source, method.target
+                )
+                .unwrap();
+            }
+        }
+        out
+    }
+
+    /// Writes SRG, with every class, then every field, then every method.
+    pub fn to_srg(&self) -> String {
+        let mut out = String::new();
+        for class in &self.classes {
+            writeln!(out, "CL: {} {}", class.source, class.target).unwrap();
+        }
+        for class in &self.classes {
+            for field in &class.fields {
+                writeln!(
+                    out,
+                    "FD: {}/{} {}/{}",
+                    class.source, field.source, class.target, field.target
+                )
+                .unwrap();
+            }
+        }
+        for class in &self.classes {
+            for method in &class.methods {
+                writeln!(
+                    out,
+                    "MD: {}/{} {} {}/{} {}",
+                    class.source,
+                    method.source,
+                    method.descriptor,
+                    class.target,
+                    method.target,
+                    self.map_descriptor(&method.descriptor)
+                )
+                .unwrap();
+            }
+        }
+        out
+    }
+}
+
+impl ClassMapping {
+    pub fn new(source: impl Into<String>, target: impl Into<String>) -> Self {
+        Self {
+            source: source.into(),
+            target: target.into(),
+            fields: Vec::new(),
+            methods: Vec::new(),
+        }
+    }
+
+    pub fn field(&self, source: &str) -> Option<&FieldMapping> {
+        self.fields.iter().find(|field| field.source == source)
+    }
+
+    pub fn method(&self, source: &str, descriptor: &str) -> Option<&MethodMapping> {
+        self.methods
+            .iter()
+            .find(|method| method.source == source && method.descriptor == descriptor)
+    }
+}
+
+/// Splits a ProGuard class line like `net.minecraft.Util -> ac:` into its names.
+fn proguard_class(line: &str) -> Option<(&str, &str)> {
+    if line.starts_with([' ', '#']) {
+        return None;
+    }
+    let (named, obf) = line.trim_end().split_once(" -> ")?;
+    Some((named, obf.strip_suffix(':')?))
+}
+
+/// The descriptor of a Java type like `int` or `java.lang.String[]`, with classes renamed.
+fn java_type_descriptor(ty: &str, classes: &HashMap<String, String>) -> String {
+    let mut element = ty;
+    let mut descriptor = String::new();
+    while let Some(inner) = element.strip_suffix("[]") {
+        descriptor.push('[');
+        element = inner;
+    }
+
+    let primitive = match element {
+        "boolean" => 'Z',
+        "byte" => 'B',
+        "char" => 'C',
+        "short" => 'S',
+        "int" => 'I',
+        "long" => 'J',
+        "float" => 'F',
+        "double" => 'D',
+        "void" => 'V',
+        _ => {
+            let class = element.replace('.', "/");
+            let class = classes.get(&class).unwrap_or(&class);
+            descriptor.push('L');
+            descriptor.push_str(class);
+            descriptor.push(';');
+            return descriptor;
+        }
+    };
+    descriptor.push(primitive);
+    descriptor
+}
+
+/// Renames the classes of a descriptor, leaving the rest and the unmapped classes as they are.
+pub(crate) fn map_descriptor_classes<'m>(
+    descriptor: &str,
+    mut map: impl FnMut(&str) -> Option<&'m str>,
+) -> String {
+    let mut mapped = String::with_capacity(descriptor.len());
+    let mut rest = descriptor;
+    while let Some(start) = rest.find('L') {
+        let Some(len) = rest[start..].find(';') else {
+            break;
+        };
+        mapped.push_str(&rest[..=start]);
+        let class = &rest[start + 1..start + len];
+        mapped.push_str(map(class).unwrap_or(class));
+        mapped.push(';');
+        rest = &rest[start + len + 1..];
+    }
+    mapped.push_str(rest);
+    mapped
+}
+
+impl Display for MappingsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Syntax { line, content } => {
+                write!(f, "invalid mapping on line {line}: {content}")
+            }
+            Self::Header { content } => write!(f, "invalid tiny v2 header: {content}"),
+        }
+    }
+}
+
+impl std::error::Error for MappingsError {}
+
+#[cfg(test)]
+mod tests {
+    use crate::{LineRange, Mappings, MappingsError};
+
+    const PROGUARD: &str = include_str!("../fixtures/mappings/client.txt");
+
+    fn mappings() -> Mappings {
+        Mappings::from_proguard(PROGUARD).unwrap()
+    }
+
+    #[test]
+    fn test_proguard() {
+        let mappings = mappings();
+        assert_eq!(mappings.classes().len(), 4);
+        assert_eq!(mappings.map_class("ja"), "net/minecraft/core/BlockPos");
+        assert_eq!(mappings.map_class("java/lang/String"), "java/lang/String");
+        assert_eq!(
+            mappings
+                .class_by_target("net/minecraft/world/level/Level")
+                .map(|class| class.source.as_str()),
+            Some("dcw")
+        );
+
+        assert_eq!(mappings.map_field("ja", "b"), Some("ZERO"));
+        assert_eq!(mappings.class("ja").unwrap().fields[1].descriptor, "Lja;");
+        // overloads of an obfuscated name are told apart by their descriptor
+        assert_eq!(mappings.map_method("dcw", "a", "()V"), Some("tick"));
+        assert_eq!(
+            mappings.map_method("dcw", "a", "(Lja;Ldtc;I)Z"),
+            Some("setBlock")
+        );
+        assert_eq!(mappings.map_method("dcw", "a", "(I)V"), None);
+        assert_eq!(
+            mappings.map_descriptor("(Lja;[Ldtc;Ljava/util/List;)Z"),
+            "(Lnet/minecraft/core/BlockPos;[Lnet/minecraft/world/level/block/state/BlockState;Ljava/util/List;)Z"
+        );
+
+        // an inlined method keeps every range
+        let tick = mappings.class("dcw").unwrap().method("a", "()V").unwrap();
+        assert_eq!(
+            tick.lines,
+            [
+                LineRange {
+                    start: 10,
+                    end: 12,
+                    original: Some((50, 52))
+                },
+                LineRange {
+                    start: 13,
+                    end: 13,
+                    original: None
+                },
+            ]
+        );
+
+        assert_eq!(
+            Mappings::from_proguard("    int x -> a"),
+            Err(MappingsError::Syntax {
+                line: 1,
+                content: "    int x -> a".into()
+            })
+        );
+    }
+
+    #[test]
+    fn test_writers() {
+        let mappings = mappings();
+        assert_eq!(
+            mappings.to_tsrg(),
+            include_str!("../fixtures/mappings/client.tsrg")
+        );
+        assert_eq!(
+            mappings.to_tsrg2(),
+            include_str!("../fixtures/mappings/client.tsrg2")
+        );
+        assert_eq!(
+            mappings.to_tiny_v2(),
+            include_str!("../fixtures/mappings/client.tiny")
+        );
+        assert_eq!(
+            mappings.to_srg(),
+            include_str!("../fixtures/mappings/client.srg")
+        );
+    }
+
+    #[test]
+    fn test_tiny_round_trip() {
+        let mut mappings = mappings();
+        let parsed = Mappings::from_tiny_v2(&mappings.to_tiny_v2()).unwrap();
+        // tiny has no line numbers
+        for class in &mut mappings.classes {
+            for method in &mut class.methods {
+                method.lines.clear();
+            }
+        }
+        assert_eq!(parsed, mappings);
+
+        let parsed = Mappings::from_tiny_v2(
+            "tiny\t2\t0\tofficial\tintermediary\tnamed\nc\ta\tclass_1\tFoo\n\tc\ta comment\n\tm\t()V\tb\t\tbar\n\t\tp\t1\t\targ\n",
+        )
+        .unwrap();
+        assert_eq!(parsed.target_namespace(), "intermediary");
+        assert_eq!(parsed.map_method("a", "b", "()V"), Some("b"));
+
+        assert!(matches!(
+            Mappings::from_tiny_v2("tiny\t1\tofficial"),
+            Err(MappingsError::Header { .. })
+        ));
+    }
+
+    #[test]
+    fn test_invert_and_compose() {
+        let mappings = mappings();
+        let inverted = mappings.invert();
+        assert_eq!(
+            (inverted.source_namespace(), inverted.target_namespace()),
+            ("named", "official")
+        );
+        assert_eq!(
+            inverted.map_method(
+                "net/minecraft/world/level/Level",
+                "setBlock",
+                "(Lnet/minecraft/core/BlockPos;Lnet/minecraft/world/level/block/state/BlockState;I)Z"
+            ),
+            Some("a")
+        );
+        assert_eq!(inverted.invert(), mappings);
+
+        // official -> named -> official
+        let identity = mappings.compose(&inverted);
+        for class in identity.classes() {
+            assert_eq!(class.source, class.target);
+            assert!(
+                class
+                    .fields
+                    .iter()
+                    .all(|field| field.source == field.target)
+            );
+            assert!(
+                class
+                    .methods
+                    .iter()
+                    .all(|method| method.source == method.target)
+            );
+        }
+
+        let mut renames = Mappings::new("named", "custom");
+        let mut level = crate::ClassMapping::new("net/minecraft/world/level/Level", "World");
+        level.methods.push(crate::MethodMapping {
+            source: "tick".into(),
+            target: "update".into(),
+            descriptor: "()V".into(),
+            lines: Vec::new(),
+        });
+        renames.insert_class(level);
+        let composed = mappings.compose(&renames);
+        assert_eq!(composed.target_namespace(), "custom");
+        assert_eq!(composed.map_class("dcw"), "World");
+        assert_eq!(composed.map_method("dcw", "a", "()V"), Some("update"));
+        assert_eq!(composed.map_field("dcw", "a"), Some("players"));
+        assert_eq!(composed.map_class("ja"), "net/minecraft/core/BlockPos");
+    }
+}
@@ -0,0 +1,1136 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fmt::{self, Display},
+    fs::File,
+    io::{self, BufReader, BufWriter, Read},
+    path::Path,
+};
+
+use zip::{ZipArchive, ZipWriter, result::ZipError, write::SimpleFileOptions};
+
+use crate::{
+    ClassMapping, Mappings,
+    class_file::{
+        Attribute, ClassFile, ClassResult, Constant, ConstantPool, Reader, write_attributes,
+        write_u16,
+    },
+};
+
+const LAMBDA_METAFACTORY: &str = "java/lang/invoke/LambdaMetafactory";
+
+/// Renames the classes, fields and methods of class files and jars from the source to the
+/// target namespace of some [`Mappings`], like SpecialSource does.
+///
+/// Members declared by a superclass or interface are found through the classes added with
+/// [`Remapper::add_class`], which [`Remapper::remap_jar`] does for every class of the jar.
+#[derive(Debug, Clone)]
+pub struct Remapper<'m> {
+    mappings: &'m Mappings,
+    kill_lvt: bool,
+    hierarchy: HashMap<String, Parents>,
+}
+
+#[derive(Debug, Clone)]
+struct Parents {
+    super_name: Option<String>,
+    interfaces: Vec<String>,
+}
+
+#[derive(Debug)]
+pub enum RemapError {
+    Io(io::Error),
+    Zip(ZipError),
+    /// A class file that couldn't be parsed, with the jar entry it came from if any.
+    InvalidClass {
+        entry: Option<String>,
+        reason: &'static str,
+    },
+}
+
+impl<'m> Remapper<'m> {
+    pub fn new(mappings: &'m Mappings) -> Self {
+        Self {
+            mappings,
+            kill_lvt: false,
+            hierarchy: HashMap::new(),
+        }
+    }
+
+    /// Removes local variable tables instead of remapping their descriptors, since their names
+    /// are meaningless in obfuscated code.
+    pub fn with_kill_lvt(mut self, kill_lvt: bool) -> Self {
+        self.kill_lvt = kill_lvt;
+        self
+    }
+
+    /// Records the superclass and interfaces of a class, to remap the members it inherits.
+    pub fn add_class(&mut self, bytes: &[u8]) -> Result<(), RemapError> {
+        let class = ClassFile::parse(bytes).map_err(RemapError::class(None))?;
+        let parents = Parents {
+            super_name: class
+                .super_name()
+                .map_err(RemapError::class(None))?
+                .map(Into::into),
+            interfaces: class
+                .interface_names()
+                .map_err(RemapError::class(None))?
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+        };
+        let name = class.name().map_err(RemapError::class(None))?;
+        self.hierarchy.insert(name.into_owned(), parents);
+        Ok(())
+    }
+
+    /// Remaps a class file, returning its new name and bytes.
+    pub fn remap_class(&self, bytes: &[u8]) -> Result<(String, Vec<u8>), RemapError> {
+        let mut class = ClassFile::parse(bytes).map_err(RemapError::class(None))?;
+        self.remap(&mut class).map_err(RemapError::class(None))?;
+        let name = class.name().map_err(RemapError::class(None))?.into_owned();
+        Ok((name, class.write()))
+    }
+
+    /// Writes a copy of `input` with its classes remapped and renamed, leaving out the
+    /// signature files that no longer match.
+    pub fn remap_jar(&mut self, input: &Path, output: &Path) -> Result<(), RemapError> {
+        let mut archive = ZipArchive::new(BufReader::new(File::open(input)?))?;
+        let mut classes = Vec::new();
+        for i in 0..archive.len() {
+            let mut entry = archive.by_index(i)?;
+            if entry.is_file() && entry.name().ends_with(".class") {
+                let mut bytes = Vec::with_capacity(entry.size() as usize);
+                entry.read_to_end(&mut bytes)?;
+                let name = entry.name().to_owned();
+                self.add_class(&bytes).map_err(|err| err.in_entry(&name))?;
+                classes.push((i, name, bytes));
+            }
+        }
+
+        let mut writer = ZipWriter::new(BufWriter::new(File::create(output)?));
+        let mut classes = classes.into_iter().peekable();
+        for i in 0..archive.len() {
+            if let Some((_, entry, bytes)) = classes.next_if(|(index, _, _)| *index == i) {
+                let source = ClassFile::parse(&bytes)
+                    .and_then(|class| class.name().map(|name| name.into_owned()))
+                    .map_err(RemapError::class(Some(&entry)))?;
+                let (target, bytes) = self
+                    .remap_class(&bytes)
+                    .map_err(|err| err.in_entry(&entry))?;
+                let name = entry
+                    .strip_suffix(&format!("{source}.class"))
+                    .map_or(entry.clone(), |prefix| format!("{prefix}{target}.class"));
+                writer.start_file(name, SimpleFileOptions::default())?;
+                io::Write::write_all(&mut writer, &bytes)?;
+                continue;
+            }
+
+            let entry = archive.by_index_raw(i)?;
+            if !is_signature_file(entry.name()) {
+                writer.raw_copy_file(entry)?;
+            }
+        }
+        writer.finish()?;
+        Ok(())
+    }
+
+    fn remap(&self, class: &mut ClassFile) -> ClassResult<()> {
+        // names are looked up in the original pool since constants are repointed as we go
+        let original = class.pool.clone();
+        let this = original.class_name(class.this_class)?;
+        let bootstrap_methods = class
+            .attributes
+            .iter()
+            .find(|attribute| {
+                original
+                    .utf8(attribute.name)
+                    .is_ok_and(|name| name == "BootstrapMethods")
+            })
+            .map(|attribute| parse_bootstrap_methods(&attribute.info))
+            .transpose()?
+            .unwrap_or_default();
+
+        let pool = &mut class.pool;
+        for index in 1..original.len() as u16 {
+            let constant = match *original.get(index)? {
+                Constant::Class(name) => {
+                    let name = original.utf8(name)?;
+                    Constant::Class(pool.add_utf8(&self.map_type(&name))?)
+                }
+                Constant::FieldRef(owner, nat) => {
+                    let owner_name = original.class_name(owner)?;
+                    let (name, descriptor) = original.name_and_type(nat)?;
+                    let name = self.resolve_field(&owner_name, &name).unwrap_or(&name);
+                    Constant::FieldRef(owner, self.name_and_type(pool, name, &descriptor)?)
+                }
+                Constant::MethodRef(owner, nat) | Constant::InterfaceMethodRef(owner, nat) => {
+                    let owner_name = original.class_name(owner)?;
+                    let (name, descriptor) = original.name_and_type(nat)?;
+                    let name = self
+                        .resolve_method(&owner_name, &name, &descriptor)
+                        .unwrap_or(&name);
+                    let nat = self.name_and_type(pool, name, &descriptor)?;
+                    match original.get(index)? {
+                        Constant::MethodRef(..) => Constant::MethodRef(owner, nat),
+                        _ => Constant::InterfaceMethodRef(owner, nat),
+                    }
+                }
+                Constant::MethodType(descriptor) => {
+                    let descriptor = original.utf8(descriptor)?;
+                    Constant::MethodType(pool.add_utf8(&self.mappings.map_descriptor(&descriptor))?)
+                }
+                Constant::InvokeDynamic(bootstrap, nat) => {
+                    let (name, descriptor) = original.name_and_type(nat)?;
+                    let name = self
+                        .lambda_method(
+                            &original,
+                            &bootstrap_methods,
+                            bootstrap,
+                            &name,
+                            &descriptor,
+                        )?
+                        .unwrap_or(&name);
+                    Constant::InvokeDynamic(bootstrap, self.name_and_type(pool, name, &descriptor)?)
+                }
+                Constant::Dynamic(bootstrap, nat) => {
+                    let (name, descriptor) = original.name_and_type(nat)?;
+                    Constant::Dynamic(bootstrap, self.name_and_type(pool, &name, &descriptor)?)
+                }
+                _ => continue,
+            };
+            pool.set(index, constant);
+        }
+
+        for field in &mut class.fields {
+            let name = original.utf8(field.name)?;
+            let descriptor = original.utf8(field.descriptor)?;
+            let name = self.mappings.map_field(&this, &name).unwrap_or(&name);
+            field.name = pool.add_utf8(name)?;
+            field.descriptor = pool.add_utf8(&self.mappings.map_descriptor(&descriptor))?;
+            self.remap_attributes(&original, pool, &mut field.attributes)?;
+        }
+        for method in &mut class.methods {
+            let name = original.utf8(method.name)?;
+            let descriptor = original.utf8(method.descriptor)?;
+            let name = match name.starts_with('<') {
+                true => &name,
+                false => self
+                    .resolve_method(&this, &name, &descriptor)
+                    .unwrap_or(&name),
+            };
+            method.name = pool.add_utf8(name)?;
+            method.descriptor = pool.add_utf8(&self.mappings.map_descriptor(&descriptor))?;
+            self.remap_attributes(&original, pool, &mut method.attributes)?;
+        }
+        self.remap_attributes(&original, pool, &mut class.attributes)?;
+        self.remap_class_attributes(&original, pool, &this, &mut class.attributes)
+    }
+
+    /// Maps a class name, or the descriptor of an array class.
+    fn map_type(&self, name: &str) -> String {
+        match name.starts_with('[') {
+            true => self.mappings.map_descriptor(name),
+            false => self.mappings.map_class(name).to_owned(),
+        }
+    }
+
+    fn name_and_type(
+        &self,
+        pool: &mut ConstantPool,
+        name: &str,
+        descriptor: &str,
+    ) -> ClassResult<u16> {
+        let name = pool.add_utf8(name)?;
+        let descriptor = pool.add_utf8(&self.mappings.map_descriptor(descriptor))?;
+        pool.add(Constant::NameAndType(name, descriptor))
+    }
+
+    /// Finds the first mapping of the class or of one of its ancestors that `find` accepts.
+    fn resolve<'a>(
+        &'a self,
+        owner: &'a str,
+        find: impl Fn(&'m ClassMapping) -> Option<&'m str>,
+    ) -> Option<&'m str> {
+        let mut queue = VecDeque::from([owner]);
+        let mut seen = HashSet::new();
+        while let Some(class) = queue.pop_front() {
+            if !seen.insert(class) {
+                continue;
+            }
+            if let Some(target) = self.mappings.class(class).and_then(&find) {
+                return Some(target);
+            }
+            if let Some(parents) = self.hierarchy.get(class) {
+                queue.extend(parents.super_name.as_deref());
+                queue.extend(parents.interfaces.iter().map(String::as_str));
+            }
+        }
+        None
+    }
+
+    fn resolve_field(&self, owner: &str, name: &str) -> Option<&'m str> {
+        self.resolve(owner, |class| Some(class.field(name)?.target.as_str()))
+    }
+
+    fn resolve_method(&self, owner: &str, name: &str, descriptor: &str) -> Option<&'m str> {
+        if owner.starts_with('[') {
+            return None;
+        }
+        self.resolve(owner, |class| {
+            Some(class.method(name, descriptor)?.target.as_str())
+        })
+    }
+
+    /// The name of the interface method a `LambdaMetafactory` call site implements.
+    fn lambda_method(
+        &self,
+        pool: &ConstantPool,
+        bootstrap_methods: &[(u16, Vec<u16>)],
+        bootstrap: u16,
+        name: &str,
+        descriptor: &str,
+    ) -> ClassResult<Option<&'m str>> {
+        let Some((handle, arguments)) = bootstrap_methods.get(bootstrap as usize) else {
+            return Err("bootstrap method index out of bounds");
+        };
+        let Constant::MethodHandle(_, reference) = *pool.get(*handle)? else {
+            return Err("expected a method handle constant");
+        };
+        let (Constant::MethodRef(owner, _) | Constant::InterfaceMethodRef(owner, _)) =
+            *pool.get(reference)?
+        else {
+            return Ok(None);
+        };
+        let (Some(&method_type), Some(interface)) = (
+            arguments.first(),
+            descriptor.rsplit_once(')').map(|(_, result)| result),
+        ) else {
+            return Ok(None);
+        };
+        if pool.class_name(owner)? != LAMBDA_METAFACTORY || !interface.starts_with('L') {
+            return Ok(None);
+        }
+        let Constant::MethodType(method_descriptor) = *pool.get(method_type)? else {
+            return Ok(None);
+        };
+        let interface = &interface[1..interface.len() - 1];
+        Ok(self.resolve_method(interface, name, &pool.utf8(method_descriptor)?))
+    }
+
+    /// Remaps the attributes any class, field, method or record component may have.
+    fn remap_attributes(
+        &self,
+        original: &ConstantPool,
+        pool: &mut ConstantPool,
+        attributes: &mut [Attribute],
+    ) -> ClassResult<()> {
+        for attribute in attributes {
+            let name = original.utf8(attribute.name)?;
+            let mut reader = Reader::new(&attribute.info);
+            let mut out = Vec::with_capacity(attribute.info.len());
+            match &*name {
+                "Signature" => {
+                    let signature = original.utf8(reader.u16()?)?;
+                    let signature = map_signature(self.mappings, &signature)
+                        .unwrap_or_else(|| signature.into_owned());
+                    write_u16(&mut out, pool.add_utf8(&signature)?);
+                }
+                "Code" => {
+                    let header = reader.bytes(4)?;
+                    let code_len = reader.u32()? as usize;
+                    let code = reader.bytes(code_len)?;
+                    let exceptions_len = reader.u16()? as usize;
+                    let exceptions = reader.bytes(exceptions_len * 8)?;
+                    let mut attributes = reader.attributes()?;
+                    if self.kill_lvt {
+                        attributes.retain(|attribute| {
+                            !original.utf8(attribute.name).is_ok_and(|name| {
+                                name == "LocalVariableTable" || name == "LocalVariableTypeTable"
+                            })
+                        });
+                    }
+                    self.remap_attributes(original, pool, &mut attributes)?;
+
+                    out.extend_from_slice(header);
+                    out.extend_from_slice(&(code_len as u32).to_be_bytes());
+                    out.extend_from_slice(code);
+                    write_u16(&mut out, exceptions_len as u16);
+                    out.extend_from_slice(exceptions);
+                    write_attributes(&mut out, &attributes);
+                }
+                "LocalVariableTable" | "LocalVariableTypeTable" => {
+                    let is_signature = name == "LocalVariableTypeTable";
+                    let count = reader.u16()?;
+                    write_u16(&mut out, count);
+                    for _ in 0..count {
+                        out.extend_from_slice(reader.bytes(6)?);
+                        let descriptor = original.utf8(reader.u16()?)?;
+                        let descriptor = match is_signature {
+                            true => map_signature(self.mappings, &descriptor)
+                                .unwrap_or_else(|| descriptor.into_owned()),
+                            false => self.mappings.map_descriptor(&descriptor),
+                        };
+                        write_u16(&mut out, pool.add_utf8(&descriptor)?);
+                        out.extend_from_slice(reader.bytes(2)?);
+                    }
+                }
+                "RuntimeVisibleAnnotations" | "RuntimeInvisibleAnnotations" => {
+                    self.remap_annotations(original, pool, &mut reader, &mut out)?;
+                }
+                "RuntimeVisibleParameterAnnotations" | "RuntimeInvisibleParameterAnnotations" => {
+                    let count = reader.u8()?;
+                    out.push(count);
+                    for _ in 0..count {
+                        self.remap_annotations(original, pool, &mut reader, &mut out)?;
+                    }
+                }
+                "AnnotationDefault" => self.remap_element(original, pool, &mut reader, &mut out)?,
+                _ => continue,
+            }
+            attribute.info = out;
+        }
+        Ok(())
+    }
+
+    /// Remaps the attributes only classes have.
+    fn remap_class_attributes(
+        &self,
+        original: &ConstantPool,
+        pool: &mut ConstantPool,
+        this: &str,
+        attributes: &mut [Attribute],
+    ) -> ClassResult<()> {
+        for attribute in attributes {
+            let mut reader = Reader::new(&attribute.info);
+            let mut out = Vec::with_capacity(attribute.info.len());
+            match &*original.utf8(attribute.name)? {
+                "InnerClasses" => {
+                    let count = reader.u16()?;
+                    write_u16(&mut out, count);
+                    for _ in 0..count {
+                        let inner = reader.u16()?;
+                        let outer = reader.u16()?;
+                        let simple_name = reader.u16()?;
+                        let flags = reader.u16()?;
+                        let inner_name = original.class_name(inner)?;
+                        let simple_name = match (simple_name, self.mappings.class(&inner_name)) {
+                            (0, _) | (_, None) => simple_name,
+                            (_, Some(class)) => {
+                                let target = &class.target;
+                                let simple = target.rsplit_once('$').map_or(
+                                    target.rsplit('/').next().unwrap_or(target),
+                                    |(_, simple)| simple,
+                                );
+                                pool.add_utf8(simple)?
+                            }
+                        };
+                        for value in [inner, outer, simple_name, flags] {
+                            write_u16(&mut out, value);
+                        }
+                    }
+                }
+                "EnclosingMethod" => {
+                    let owner = reader.u16()?;
+                    let method = reader.u16()?;
+                    write_u16(&mut out, owner);
+                    match method {
+                        0 => write_u16(&mut out, 0),
+                        method => {
+                            let (name, descriptor) = original.name_and_type(method)?;
+                            let owner = original.class_name(owner)?;
+                            let name = self
+                                .resolve_method(&owner, &name, &descriptor)
+                                .unwrap_or(&name);
+                            write_u16(&mut out, self.name_and_type(pool, name, &descriptor)?);
+                        }
+                    }
+                }
+                "Record" => {
+                    let count = reader.u16()?;
+                    write_u16(&mut out, count);
+                    for _ in 0..count {
+                        let name = original.utf8(reader.u16()?)?;
+                        let descriptor = original.utf8(reader.u16()?)?;
+                        let mut attributes = reader.attributes()?;
+                        let name = self.mappings.map_field(this, &name).unwrap_or(&name);
+                        write_u16(&mut out, pool.add_utf8(name)?);
+                        write_u16(
+                            &mut out,
+                            pool.add_utf8(&self.mappings.map_descriptor(&descriptor))?,
+                        );
+                        self.remap_attributes(original, pool, &mut attributes)?;
+                        write_attributes(&mut out, &attributes);
+                    }
+                }
+                _ => continue,
+            }
+            attribute.info = out;
+        }
+        Ok(())
+    }
+
+    fn remap_annotations(
+        &self,
+        original: &ConstantPool,
+        pool: &mut ConstantPool,
+        reader: &mut Reader,
+        out: &mut Vec<u8>,
+    ) -> ClassResult<()> {
+        let count = reader.u16()?;
+        write_u16(out, count);
+        for _ in 0..count {
+            self.remap_annotation(original, pool, reader, out)?;
+        }
+        Ok(())
+    }
+
+    fn remap_annotation(
+        &self,
+        original: &ConstantPool,
+        pool: &mut ConstantPool,
+        reader: &mut Reader,
+        out: &mut Vec<u8>,
+    ) -> ClassResult<()> {
+        let descriptor = original.utf8(reader.u16()?)?;
+        write_u16(
+            out,
+            pool.add_utf8(&self.mappings.map_descriptor(&descriptor))?,
+        );
+        let pairs = reader.u16()?;
+        write_u16(out, pairs);
+        for _ in 0..pairs {
+            write_u16(out, reader.u16()?);
+            self.remap_element(original, pool, reader, out)?;
+        }
+        Ok(())
+    }
+
+    fn remap_element(
+        &self,
+        original: &ConstantPool,
+        pool: &mut ConstantPool,
+        reader: &mut Reader,
+        out: &mut Vec<u8>,
+    ) -> ClassResult<()> {
+        let tag = reader.u8()?;
+        out.push(tag);
+        match tag {
+            b'B' | b'C' | b'D' | b'F' | b'I' | b'J' | b'S' | b'Z' | b's' => {
+                write_u16(out, reader.u16()?)
+            }
+            b'e' => {
+                let descriptor = original.utf8(reader.u16()?)?;
+                let name = original.utf8(reader.u16()?)?;
+                let owner = descriptor
+                    .strip_prefix('L')
+                    .and_then(|owner| owner.strip_suffix(';'))
+                    .unwrap_or_default();
+                let name = self.mappings.map_field(owner, &name).unwrap_or(&name);
+                write_u16(
+                    out,
+                    pool.add_utf8(&self.mappings.map_descriptor(&descriptor))?,
+                );
+                write_u16(out, pool.add_utf8(name)?);
+            }
+            b'c' => {
+                let descriptor = original.utf8(reader.u16()?)?;
+                write_u16(
+                    out,
+                    pool.add_utf8(&self.mappings.map_descriptor(&descriptor))?,
+                );
+            }
+            b'@' => self.remap_annotation(original, pool, reader, out)?,
+            b'[' => {
+                let count = reader.u16()?;
+                write_u16(out, count);
+                for _ in 0..count {
+                    self.remap_element(original, pool, reader, out)?;
+                }
+            }
+            _ => return Err("unknown annotation element tag"),
+        }
+        Ok(())
+    }
+}
+
+/// The bootstrap methods of a class, as their method handle and arguments.
+fn parse_bootstrap_methods(info: &[u8]) -> ClassResult<Vec<(u16, Vec<u16>)>> {
+    let mut reader = Reader::new(info);
+    (0..reader.u16()?)
+        .map(|_| {
+            let handle = reader.u16()?;
+            let arguments = (0..reader.u16()?)
+                .map(|_| reader.u16())
+                .collect::<ClassResult<_>>()?;
+            Ok((handle, arguments))
+        })
+        .collect()
+}
+
+/// Whether a jar entry is part of the jar signature, which remapping invalidates.
+fn is_signature_file(name: &str) -> bool {
+    name.strip_prefix("META-INF/").is_some_and(|name| {
+        !name.contains('/')
+            && [".SF", ".RSA", ".DSA", ".EC"]
+                .iter()
+                .any(|extension| name.ends_with(extension))
+    })
+}
+
+/// Maps the classes of a generic class, method or field signature, or `None` if it's malformed.
+fn map_signature(mappings: &Mappings, signature: &str) -> Option<String> {
+    let mut mapper = SignatureMapper {
+        mappings,
+        rest: signature,
+        out: String::with_capacity(signature.len()),
+    };
+    if mapper.rest.starts_with('<') {
+        mapper.type_parameters()?;
+    }
+    if mapper.eat('(') {
+        while !mapper.eat(')') {
+            mapper.java_type()?;
+        }
+        mapper.java_type()?;
+        while mapper.eat('^') {
+            mapper.java_type()?;
+        }
+    }
+    while !mapper.rest.is_empty() {
+        mapper.java_type()?;
+    }
+    Some(mapper.out)
+}
+
+struct SignatureMapper<'a> {
+    mappings: &'a Mappings,
+    rest: &'a str,
+    out: String,
+}
+
+impl<'a> SignatureMapper<'a> {
+    /// Copies the next character if it's `c`.
+    fn eat(&mut self, c: char) -> bool {
+        let eaten = self.rest.starts_with(c);
+        if eaten {
+            self.out.push(c);
+            self.rest = &self.rest[1..];
+        }
+        eaten
+    }
+
+    /// Takes the characters until one of `ends`.
+    fn identifier(&mut self, ends: &[char]) -> Option<&'a str> {
+        let len = self.rest.find(ends)?;
+        let (identifier, rest) = self.rest.split_at(len);
+        self.rest = rest;
+        Some(identifier)
+    }
+
+    fn type_parameters(&mut self) -> Option<()> {
+        self.eat('<');
+        while !self.eat('>') {
+            let name = self.identifier(&[':'])?;
+            self.out.push_str(name);
+            while self.eat(':') {
+                if !self.rest.starts_with([':', '>']) {
+                    self.java_type()?;
+                }
+            }
+        }
+        Some(())
+    }
+
+    fn java_type(&mut self) -> Option<()> {
+        let c = self.rest.chars().next()?;
+        match c {
+            'B' | 'C' | 'D' | 'F' | 'I' | 'J' | 'S' | 'Z' | 'V' | '*' => {
+                self.eat(c);
+            }
+            '[' | '+' | '-' => {
+                self.eat(c);
+                self.java_type()?;
+            }
+            'T' => {
+                let variable = self.identifier(&[';'])?;
+                self.out.push_str(variable);
+                self.eat(';');
+            }
+            'L' => {
+                self.eat('L');
+                let mut source = self.identifier(&['<', '.', ';'])?.to_owned();
+                let mut target = self.mappings.map_class(&source).to_owned();
+                self.out.push_str(&target);
+                self.type_arguments()?;
+                // inner classes are `Outer<...>.Inner`, named by their simple name
+                while self.eat('.') {
+                    source = format!("{source}${}", self.identifier(&['<', '.', ';'])?);
+                    let mapped = self.mappings.map_class(&source);
+                    let simple = mapped
+                        .strip_prefix(&target)
+                        .and_then(|rest| rest.strip_prefix('$'))
+                        .unwrap_or_else(|| mapped.rsplit(['$', '/']).next().unwrap_or(mapped));
+                    self.out.push_str(simple);
+                    target = mapped.to_owned();
+                    self.type_arguments()?;
+                }
+                if !self.eat(';') {
+                    return None;
+                }
+            }
+            _ => return None,
+        }
+        Some(())
+    }
+
+    fn type_arguments(&mut self) -> Option<()> {
+        if self.eat('<') {
+            while !self.eat('>') {
+                self.java_type()?;
+            }
+        }
+        Some(())
+    }
+}
+
+impl RemapError {
+    fn class(entry: Option<&str>) -> impl Fn(&'static str) -> Self {
+        move |reason| Self::InvalidClass {
+            entry: entry.map(Into::into),
+            reason,
+        }
+    }
+
+    fn in_entry(self, name: &str) -> Self {
+        match self {
+            Self::InvalidClass {
+                entry: None,
+                reason,
+            } => Self::InvalidClass {
+                entry: Some(name.into()),
+                reason,
+            },
+            err => err,
+        }
+    }
+}
+
+impl Display for RemapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "{err}"),
+            Self::Zip(err) => write!(f, "{err}"),
+            Self::InvalidClass {
+                entry: Some(entry),
+                reason,
+            } => write!(f, "invalid class file {entry}: {reason}"),
+            Self::InvalidClass {
+                entry: None,
+                reason,
+            } => write!(f, "invalid class file: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for RemapError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            Self::Zip(err) => Some(err),
+            Self::InvalidClass { .. } => None,
+        }
+    }
+}
+
+impl From<io::Error> for RemapError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<ZipError> for RemapError {
+    fn from(err: ZipError) -> Self {
+        Self::Zip(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs::File,
+        io::{Read, Write},
+        path::PathBuf,
+    };
+
+    use zip::{ZipArchive, ZipWriter, write::SimpleFileOptions};
+
+    use crate::{
+        ClassMapping, FieldMapping, Mappings, MethodMapping, RemapError, Remapper,
+        class_file::{Attribute, ClassFile, Constant, ConstantPool, Member, write_u16},
+        remap::map_signature,
+    };
+
+    fn mappings() -> Mappings {
+        let mut mappings = Mappings::new("official", "named");
+        let mut child = ClassMapping::new("a", "pkg/Child");
+        child.fields.push(FieldMapping {
+            source: "x".into(),
+            target: "count".into(),
+            descriptor: "I".into(),
+        });
+        let mut parent = ClassMapping::new("b", "pkg/Parent");
+        parent.fields.push(FieldMapping {
+            source: "y".into(),
+            target: "parentField".into(),
+            descriptor: "La;".into(),
+        });
+        parent.methods.push(MethodMapping {
+            source: "m".into(),
+            target: "accept".into(),
+            descriptor: "(La;)V".into(),
+            lines: Vec::new(),
+        });
+        let mut function = ClassMapping::new("c", "pkg/Function");
+        function.methods.push(MethodMapping {
+            source: "a".into(),
+            target: "apply".into(),
+            descriptor: "(Ljava/lang/Object;)V".into(),
+            lines: Vec::new(),
+        });
+        for class in [
+            child,
+            parent,
+            function,
+            ClassMapping::new("a$b", "pkg/Child$Inner"),
+        ] {
+            mappings.insert_class(class);
+        }
+        mappings
+    }
+
+    fn class_constant(pool: &mut ConstantPool, name: &str) -> u16 {
+        let name = pool.add_utf8(name).unwrap();
+        pool.add(Constant::Class(name)).unwrap()
+    }
+
+    fn name_and_type(pool: &mut ConstantPool, name: &str, descriptor: &str) -> u16 {
+        let name = pool.add_utf8(name).unwrap();
+        let descriptor = pool.add_utf8(descriptor).unwrap();
+        pool.add(Constant::NameAndType(name, descriptor)).unwrap()
+    }
+
+    fn attribute(pool: &mut ConstantPool, name: &str, info: Vec<u8>) -> Attribute {
+        Attribute {
+            name: pool.add_utf8(name).unwrap(),
+            info,
+        }
+    }
+
+    fn member(pool: &mut ConstantPool, name: &str, descriptor: &str) -> Member {
+        Member {
+            access: 0x1,
+            name: pool.add_utf8(name).unwrap(),
+            descriptor: pool.add_utf8(descriptor).unwrap(),
+            attributes: Vec::new(),
+        }
+    }
+
+    fn class_file(pool: ConstantPool, this_class: u16, super_class: u16) -> ClassFile {
+        ClassFile {
+            minor: 0,
+            major: 65,
+            pool,
+            access: 0x21,
+            this_class,
+            super_class,
+            interfaces: Vec::new(),
+            fields: Vec::new(),
+            methods: Vec::new(),
+            attributes: Vec::new(),
+        }
+    }
+
+    /// `class b { La; y; void m(La;) }`
+    fn parent() -> Vec<u8> {
+        let mut pool = ConstantPool::new();
+        let this = class_constant(&mut pool, "b");
+        let object = class_constant(&mut pool, "java/lang/Object");
+        let mut class = class_file(pool, this, object);
+        class.fields.push(member(&mut class.pool, "y", "La;"));
+        class.methods.push(member(&mut class.pool, "m", "(La;)V"));
+        class.write()
+    }
+
+    /// `class a extends b`, overriding `m` and referring to inherited members, an array class, a
+    /// lambda and an inner class.
+    fn child() -> Vec<u8> {
+        let mut pool = ConstantPool::new();
+        let this = class_constant(&mut pool, "a");
+        let parent = class_constant(&mut pool, "b");
+        let nat = name_and_type(&mut pool, "y", "La;");
+        pool.add(Constant::FieldRef(this, nat)).unwrap();
+        let nat = name_and_type(&mut pool, "m", "(La;)V");
+        pool.add(Constant::MethodRef(this, nat)).unwrap();
+        class_constant(&mut pool, "[[La;");
+
+        let metafactory = class_constant(&mut pool, "java/lang/invoke/LambdaMetafactory");
+        let nat = name_and_type(&mut pool, "metafactory", "(...)Ljava/lang/invoke/CallSite;");
+        let reference = pool.add(Constant::MethodRef(metafactory, nat)).unwrap();
+        let handle = pool.add(Constant::MethodHandle(6, reference)).unwrap();
+        let descriptor = pool.add_utf8("(Ljava/lang/Object;)V").unwrap();
+        let method_type = pool.add(Constant::MethodType(descriptor)).unwrap();
+        let nat = name_and_type(&mut pool, "a", "(La;)Lc;");
+        pool.add(Constant::InvokeDynamic(0, nat)).unwrap();
+
+        let mut class = class_file(pool, this, parent);
+        let pool = &mut class.pool;
+
+        let mut field = member(pool, "x", "I");
+        let signature = pool.add_utf8("Ljava/util/List<La;>;").unwrap();
+        field.attributes.push(attribute(
+            pool,
+            "Signature",
+            signature.to_be_bytes().to_vec(),
+        ));
+        class.fields.push(field);
+        class.fields.push(member(pool, "z", "Lb;"));
+
+        // a method with an empty body and one local variable
+        let mut method = member(pool, "m", "(La;)V");
+        let mut local_variables = Vec::new();
+        write_u16(&mut local_variables, 1);
+        for value in [
+            0,
+            1,
+            pool.add_utf8("a").unwrap(),
+            pool.add_utf8("La;").unwrap(),
+            0,
+        ] {
+            write_u16(&mut local_variables, value);
+        }
+        let local_variables = attribute(pool, "LocalVariableTable", local_variables);
+        let mut code = vec![0, 1, 0, 2, 0, 0, 0, 1, 0xb1, 0, 0, 0, 1];
+        write_u16(&mut code, local_variables.name);
+        code.extend_from_slice(&(local_variables.info.len() as u32).to_be_bytes());
+        code.extend_from_slice(&local_variables.info);
+        method.attributes.push(attribute(pool, "Code", code));
+        class.methods.push(method);
+        class.methods.push(member(pool, "<init>", "()V"));
+
+        let mut bootstrap_methods = Vec::new();
+        for value in [1, handle, 1, method_type] {
+            write_u16(&mut bootstrap_methods, value);
+        }
+        class
+            .attributes
+            .push(attribute(pool, "BootstrapMethods", bootstrap_methods));
+
+        let mut inner_classes = Vec::new();
+        for value in [
+            1,
+            class_constant(pool, "a$b"),
+            this,
+            pool.add_utf8("b").unwrap(),
+            0x8,
+        ] {
+            write_u16(&mut inner_classes, value);
+        }
+        class
+            .attributes
+            .push(attribute(pool, "InnerClasses", inner_classes));
+        class.write()
+    }
+
+    fn utf8(pool: &ConstantPool, index: u16) -> String {
+        pool.utf8(index).unwrap().into_owned()
+    }
+
+    /// The `(name, descriptor)` of each reference constant, by kind.
+    fn references(pool: &ConstantPool) -> Vec<(u8, String, String)> {
+        (1..pool.len() as u16)
+            .filter_map(|index| match *pool.get(index).unwrap() {
+                Constant::FieldRef(_, nat) => Some((9, nat)),
+                Constant::MethodRef(_, nat) => Some((10, nat)),
+                Constant::InvokeDynamic(_, nat) => Some((18, nat)),
+                _ => None,
+            })
+            .map(|(kind, nat)| {
+                let (name, descriptor) = pool.name_and_type(nat).unwrap();
+                (kind, name.into_owned(), descriptor.into_owned())
+            })
+            .collect()
+    }
+
+    fn remapper(mappings: &Mappings) -> Remapper<'_> {
+        let mut remapper = Remapper::new(mappings);
+        remapper.add_class(&parent()).unwrap();
+        remapper.add_class(&child()).unwrap();
+        remapper
+    }
+
+    #[test]
+    fn test_remap_class() {
+        let mappings = mappings();
+        let (name, bytes) = remapper(&mappings).remap_class(&child()).unwrap();
+        assert_eq!(name, "pkg/Child");
+
+        let class = ClassFile::parse(&bytes).unwrap();
+        let pool = &class.pool;
+        assert_eq!(class.super_name().unwrap().unwrap(), "pkg/Parent");
+        assert!((1..pool.len() as u16).any(|index| {
+            pool.class_name(index)
+                .is_ok_and(|name| name == "[[Lpkg/Child;")
+        }));
+        assert_eq!(
+            references(pool),
+            [
+                (9, "parentField".into(), "Lpkg/Child;".into()),
+                (10, "accept".into(), "(Lpkg/Child;)V".into()),
+                (
+                    10,
+                    "metafactory".into(),
+                    "(...)Ljava/lang/invoke/CallSite;".into()
+                ),
+                (18, "apply".into(), "(Lpkg/Child;)Lpkg/Function;".into()),
+            ]
+        );
+
+        let fields: Vec<_> = class
+            .fields
+            .iter()
+            .map(|field| (utf8(pool, field.name), utf8(pool, field.descriptor)))
+            .collect();
+        assert_eq!(
+            fields,
+            [
+                ("count".into(), "I".into()),
+                ("z".into(), "Lpkg/Parent;".into())
+            ]
+        );
+        let signature = &class.fields[0].attributes[0].info;
+        assert_eq!(
+            utf8(pool, u16::from_be_bytes([signature[0], signature[1]])),
+            "Ljava/util/List<Lpkg/Child;>;"
+        );
+
+        let methods: Vec<_> = class
+            .methods
+            .iter()
+            .map(|method| (utf8(pool, method.name), utf8(pool, method.descriptor)))
+            .collect();
+        assert_eq!(
+            methods,
+            [
+                ("accept".into(), "(Lpkg/Child;)V".into()),
+                ("<init>".into(), "()V".into())
+            ]
+        );
+        // the local variable keeps its name with its descriptor remapped
+        let code = &class.methods[0].attributes[0].info;
+        let descriptor = u16::from_be_bytes([code[code.len() - 4], code[code.len() - 3]]);
+        assert_eq!(utf8(pool, descriptor), "Lpkg/Child;");
+
+        let inner_classes = &class.attributes[1].info;
+        assert_eq!(utf8(pool, class.attributes[1].name), "InnerClasses");
+        assert_eq!(
+            utf8(
+                pool,
+                u16::from_be_bytes([inner_classes[6], inner_classes[7]])
+            ),
+            "Inner"
+        );
+    }
+
+    #[test]
+    fn test_kill_lvt() {
+        let mappings = mappings();
+        let (_, bytes) = remapper(&mappings)
+            .with_kill_lvt(true)
+            .remap_class(&child())
+            .unwrap();
+        let class = ClassFile::parse(&bytes).unwrap();
+        let code = &class.methods[0].attributes[0].info;
+        // max stack and locals, the code, no exceptions and no attributes
+        assert_eq!(code, &[0, 1, 0, 2, 0, 0, 0, 1, 0xb1, 0, 0, 0, 0]);
+
+        assert!(matches!(
+            Remapper::new(&mappings).remap_class(b"nope"),
+            Err(RemapError::InvalidClass {
+                entry: None,
+                reason: "not a class file"
+            })
+        ));
+    }
+
+    #[test]
+    fn test_signature() {
+        let mappings = mappings();
+        let map = |signature| map_signature(&mappings, signature).unwrap();
+        assert_eq!(
+            map("<T:La;U::Ljava/lang/Comparable<-TT;>;>Lb;Lc;"),
+            "<T:Lpkg/Child;U::Ljava/lang/Comparable<-TT;>;>Lpkg/Parent;Lpkg/Function;"
+        );
+        assert_eq!(
+            map(
+                "<T:Ljava/lang/Object;>(Ljava/util/Map<La;*>;[TT;)La<+Lb;>.b;^Ljava/io/IOException;"
+            ),
+            "<T:Ljava/lang/Object;>(Ljava/util/Map<Lpkg/Child;*>;[TT;)Lpkg/Child<+Lpkg/Parent;>.Inner;^Ljava/io/IOException;"
+        );
+        assert_eq!(map_signature(&mappings, "Lunterminated"), None);
+    }
+
+    #[test]
+    fn test_remap_jar() {
+        let dir =
+            std::env::temp_dir().join(format!("mcje_downloader_remap_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let input: PathBuf = dir.join("client.jar");
+        let output = dir.join("mapped.jar");
+
+        let mut zip = ZipWriter::new(File::create(&input).unwrap());
+        for (name, content) in [
+            ("META-INF/MANIFEST.MF", b"Manifest-Version: 1.0\n".to_vec()),
+            ("META-INF/MOJANGCS.SF", b"signature".to_vec()),
+            ("META-INF/MOJANGCS.RSA", b"signature".to_vec()),
+            ("a.class", child()),
+            ("b.class", parent()),
+            ("assets/minecraft/lang/en_us.json", b"{}".to_vec()),
+        ] {
+            zip.start_file(name, SimpleFileOptions::default()).unwrap();
+            zip.write_all(&content).unwrap();
+        }
+        zip.finish().unwrap();
+
+        let mappings = mappings();
+        Remapper::new(&mappings)
+            .with_kill_lvt(true)
+            .remap_jar(&input, &output)
+            .unwrap();
+
+        let mut archive = ZipArchive::new(File::open(&output).unwrap()).unwrap();
+        let names: Vec<_> = archive.file_names().collect();
+        assert_eq!(
+            names,
+            [
+                "META-INF/MANIFEST.MF",
+                "pkg/Child.class",
+                "pkg/Parent.class",
+                "assets/minecraft/lang/en_us.json"
+            ]
+        );
+
+        let mut bytes = Vec::new();
+        archive
+            .by_name("pkg/Child.class")
+            .unwrap()
+            .read_to_end(&mut bytes)
+            .unwrap();
+        let class = ClassFile::parse(&bytes).unwrap();
+        assert_eq!(class.name().unwrap(), "pkg/Child");
+        // the override is found through the parent class read from the same jar
+        assert_eq!(class.pool.utf8(class.methods[0].name).unwrap(), "accept");
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
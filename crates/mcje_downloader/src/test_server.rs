@@ -0,0 +1,182 @@
+//! A minimal HTTP/1.1 server standing in for Mojang's servers in tests.
+
+use std::{
+    net::SocketAddr,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
+
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+};
+use url::Url;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Request {
+    pub path: String,
+    /// The start of a `Range: bytes=<start>-` header.
+    pub range_start: Option<u64>,
+}
+
+pub struct Response {
+    pub status: u16,
+    pub headers: Vec<(&'static str, String)>,
+    pub body: Vec<u8>,
+    /// Closes the connection after this many body bytes, despite the announced length.
+    pub truncate_at: Option<usize>,
+    /// Waits this long before answering.
+    pub delay: Option<Duration>,
+}
+
+type Handler = dyn Fn(&Request) -> Response + Send + Sync;
+
+pub struct TestServer {
+    addr: SocketAddr,
+    state: Arc<State>,
+}
+
+#[derive(Default)]
+struct State {
+    requests: Mutex<Vec<Request>>,
+    in_flight: AtomicUsize,
+    max_in_flight: AtomicUsize,
+}
+
+impl Response {
+    pub fn status(status: u16) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body: Vec::new(),
+            truncate_at: None,
+            delay: None,
+        }
+    }
+
+    /// Serves `body`, honoring the range of the request.
+    pub fn file(request: &Request, body: &[u8]) -> Self {
+        match request.range_start {
+            Some(start) if start as usize >= body.len() => Self::status(416),
+            Some(start) => {
+                let mut response = Self::status(206);
+                response.headers.push((
+                    "Content-Range",
+                    format!("bytes {start}-{}/{}", body.len() - 1, body.len()),
+                ));
+                response.body = body[start as usize..].to_vec();
+                response
+            }
+            None => {
+                let mut response = Self::status(200);
+                response.body = body.to_vec();
+                response
+            }
+        }
+    }
+
+    pub fn truncated(mut self, at: usize) -> Self {
+        self.truncate_at = Some(at);
+        self
+    }
+
+    pub fn delayed(mut self, delay: Duration) -> Self {
+        self.delay = Some(delay);
+        self
+    }
+}
+
+impl TestServer {
+    pub async fn start(handler: impl Fn(&Request) -> Response + Send + Sync + 'static) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let state = Arc::new(State::default());
+        let handler: Arc<Handler> = Arc::new(handler);
+
+        let server_state = state.clone();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let handler = handler.clone();
+                let state = server_state.clone();
+                tokio::spawn(async move {
+                    let _ = serve(stream, &*handler, &state).await;
+                });
+            }
+        });
+
+        Self { addr, state }
+    }
+
+    pub fn url(&self, path: &str) -> Url {
+        Url::parse(&format!("http://{}{path}", self.addr)).unwrap()
+    }
+
+    pub fn requests(&self) -> Vec<Request> {
+        self.state.requests.lock().unwrap().clone()
+    }
+
+    /// The most requests that were being answered at the same time.
+    pub fn max_in_flight(&self) -> usize {
+        self.state.max_in_flight.load(Ordering::SeqCst)
+    }
+}
+
+async fn serve(mut stream: TcpStream, handler: &Handler, state: &State) -> std::io::Result<()> {
+    let mut head = Vec::new();
+    let mut buf = [0; 1024];
+    while !head.ends_with(b"\r\n\r\n") {
+        let read = stream.read(&mut buf[..1]).await?;
+        if read == 0 {
+            return Ok(());
+        }
+        head.extend_from_slice(&buf[..read]);
+    }
+
+    let head = String::from_utf8_lossy(&head);
+    let mut lines = head.lines();
+    let path = lines
+        .next()
+        .and_then(|line| line.split(' ').nth(1))
+        .unwrap_or("/")
+        .to_owned();
+    let range_start = lines
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.eq_ignore_ascii_case("range"))
+        .and_then(|(_, value)| value.trim().strip_prefix("bytes="))
+        .and_then(|range| range.trim_end_matches('-').parse().ok());
+
+    let request = Request { path, range_start };
+    state.requests.lock().unwrap().push(request.clone());
+    let in_flight = state.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+    state.max_in_flight.fetch_max(in_flight, Ordering::SeqCst);
+    let result = respond(&mut stream, handler(&request)).await;
+    state.in_flight.fetch_sub(1, Ordering::SeqCst);
+    result
+}
+
+async fn respond(stream: &mut TcpStream, response: Response) -> std::io::Result<()> {
+    if let Some(delay) = response.delay {
+        tokio::time::sleep(delay).await;
+    }
+
+    let mut out = format!(
+        "HTTP/1.1 {} Test\r\nContent-Length: {}\r\nConnection: close\r\n",
+        response.status,
+        response.body.len()
+    );
+    for (name, value) in &response.headers {
+        out.push_str(&format!("{name}: {value}\r\n"));
+    }
+    out.push_str("\r\n");
+
+    stream.write_all(out.as_bytes()).await?;
+    let body = match response.truncate_at {
+        Some(at) => &response.body[..at.min(response.body.len())],
+        None => &response.body,
+    };
+    stream.write_all(body).await?;
+    stream.shutdown().await
+}
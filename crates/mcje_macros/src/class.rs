@@ -0,0 +1,358 @@
+use proc_macro2::TokenStream;
+use quote::{ToTokens, quote};
+use syn::{
+    Attribute, Error, FnArg, GenericArgument, Ident, ItemTrait, LitStr, Pat, PathArguments, Result,
+    ReturnType, TraitItem, TraitItemFn, Type, parse_quote,
+};
+
+/// Types passed by value rather than as references to local objects.
+const PRIMITIVES: [&str; 8] = ["bool", "i8", "u16", "i16", "i32", "i64", "f32", "f64"];
+
+enum Kind {
+    Constructor,
+    Method,
+    Field,
+}
+
+struct Member {
+    kind: Kind,
+    java_name: String,
+    is_static: bool,
+    attrs: Vec<Attribute>,
+    ident: Ident,
+    params: Vec<Param>,
+    /// The Rust type of the Java return type, `()` for `void` and constructors.
+    output: Type,
+}
+
+struct Param {
+    ident: Ident,
+    ty: Type,
+    by_ref: bool,
+}
+
+pub(crate) fn expand(class: LitStr, item: ItemTrait) -> Result<TokenStream> {
+    if !item.generics.params.is_empty() || !item.supertraits.is_empty() {
+        return Err(Error::new_spanned(
+            &item.generics,
+            "bound classes can't have generics or supertraits",
+        ));
+    }
+
+    let members = item
+        .items
+        .iter()
+        .map(|item| match item {
+            TraitItem::Fn(function) => Member::parse(function),
+            item => Err(Error::new_spanned(
+                item,
+                "expected a method, constructor or field",
+            )),
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let attrs = &item.attrs;
+    let vis = &item.vis;
+    let name = &item.ident;
+    let class_name = class.value();
+    let descriptor = format!("L{class_name};");
+    let functions = members.iter().map(Member::expand);
+    let descriptors = members.iter().map(|member| {
+        let java_name = &member.java_name;
+        let descriptor = member.descriptor();
+        quote! { (#java_name, #descriptor) }
+    });
+
+    Ok(quote! {
+        #(#attrs)*
+        #[repr(transparent)]
+        #[derive(Debug, Default)]
+        #vis struct #name<'local>(::mcje::jni::objects::JObject<'local>);
+
+        #[allow(non_snake_case)]
+        impl<'local> #name<'local> {
+            /// The internal name of the bound class.
+            pub const CLASS: &'static str = #class_name;
+
+            /// The Java name and descriptor of each bound member, to check the bindings without
+            /// calling them.
+            pub fn descriptors() -> ::std::vec::Vec<(&'static str, ::std::string::String)> {
+                ::std::vec![#(#descriptors),*]
+            }
+
+            #(#functions)*
+        }
+
+        impl<'local> ::mcje::JavaType<'local> for #name<'local> {
+            const DESCRIPTOR: &'static str = #descriptor;
+
+            fn as_jvalue(&self) -> ::mcje::jni::objects::JValue<'local, '_> {
+                ::mcje::jni::objects::JValue::Object(&self.0)
+            }
+
+            fn from_jvalue(
+                value: ::mcje::jni::objects::JValueOwned<'local>,
+            ) -> ::mcje::jni::errors::Result<Self> {
+                value.l().map(Self)
+            }
+        }
+
+        impl<'local> ::std::convert::From<::mcje::jni::objects::JObject<'local>> for #name<'local> {
+            fn from(object: ::mcje::jni::objects::JObject<'local>) -> Self {
+                Self(object)
+            }
+        }
+
+        impl<'local> ::std::convert::From<#name<'local>> for ::mcje::jni::objects::JObject<'local> {
+            fn from(object: #name<'local>) -> Self {
+                object.0
+            }
+        }
+
+        impl<'local> ::std::convert::AsRef<::mcje::jni::objects::JObject<'local>> for #name<'local> {
+            fn as_ref(&self) -> &::mcje::jni::objects::JObject<'local> {
+                &self.0
+            }
+        }
+
+        impl<'local> ::std::ops::Deref for #name<'local> {
+            type Target = ::mcje::jni::objects::JObject<'local>;
+
+            fn deref(&self) -> &Self::Target {
+                &self.0
+            }
+        }
+    })
+}
+
+impl Member {
+    fn parse(function: &TraitItemFn) -> Result<Self> {
+        let sig = &function.sig;
+        if let Some(body) = &function.default {
+            return Err(Error::new_spanned(body, "bound members can't have a body"));
+        }
+        if !sig.generics.params.is_empty() || sig.asyncness.is_some() {
+            return Err(Error::new_spanned(
+                sig,
+                "bound members can't be generic or async",
+            ));
+        }
+
+        let mut kind = None;
+        let mut java_name = sig.ident.to_string();
+        let mut attrs = Vec::new();
+        for attr in &function.attrs {
+            if !attr.path().is_ident("mcje") {
+                attrs.push(attr.clone());
+                continue;
+            }
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("name") {
+                    java_name = meta.value()?.parse::<LitStr>()?.value();
+                } else if meta.path.is_ident("constructor") {
+                    kind = Some(Kind::Constructor);
+                } else if meta.path.is_ident("field") {
+                    kind = Some(Kind::Field);
+                } else {
+                    return Err(meta.error("expected `name`, `constructor` or `field`"));
+                }
+                Ok(())
+            })?;
+        }
+        let kind = kind.unwrap_or(match sig.ident == "new" {
+            true => Kind::Constructor,
+            false => Kind::Method,
+        });
+
+        let mut is_static = true;
+        let mut params = Vec::new();
+        for input in &sig.inputs {
+            match input {
+                FnArg::Receiver(receiver)
+                    if receiver.reference.is_some() && receiver.mutability.is_none() =>
+                {
+                    is_static = false;
+                }
+                FnArg::Receiver(receiver) => {
+                    return Err(Error::new_spanned(receiver, "expected `&self`"));
+                }
+                FnArg::Typed(typed) => {
+                    let Pat::Ident(pat) = &*typed.pat else {
+                        return Err(Error::new_spanned(&typed.pat, "expected a parameter name"));
+                    };
+                    let (ty, by_ref) = java_type(&typed.ty)?;
+                    params.push(Param {
+                        ident: pat.ident.clone(),
+                        ty,
+                        by_ref,
+                    });
+                }
+            }
+        }
+
+        let output = match &sig.output {
+            ReturnType::Default => parse_quote!(()),
+            ReturnType::Type(_, ty) => java_type(ty)?.0,
+        };
+        match kind {
+            Kind::Constructor if !is_static => {
+                return Err(Error::new_spanned(sig, "constructors can't take `&self`"));
+            }
+            Kind::Constructor
+                if !matches!(&output, Type::Tuple(tuple) if tuple.elems.is_empty())
+                    && !is_self(&output) =>
+            {
+                return Err(Error::new_spanned(
+                    &sig.output,
+                    "constructors return `Self`",
+                ));
+            }
+            Kind::Field if !params.is_empty() => {
+                return Err(Error::new_spanned(
+                    &sig.inputs,
+                    "fields take no parameters besides `&self`",
+                ));
+            }
+            _ => {}
+        }
+
+        Ok(Self {
+            java_name: match kind {
+                Kind::Constructor => "<init>".into(),
+                _ => java_name,
+            },
+            kind,
+            is_static,
+            attrs,
+            ident: sig.ident.clone(),
+            params,
+            output,
+        })
+    }
+
+    /// An expression building the descriptor of the member.
+    fn descriptor(&self) -> TokenStream {
+        let output = match self.kind {
+            Kind::Constructor => quote! { "V" },
+            _ => descriptor_of(&self.output),
+        };
+        if let Kind::Field = self.kind {
+            return quote! { ::std::string::String::from(#output) };
+        }
+        let params = self.params.iter().map(|param| descriptor_of(&param.ty));
+        quote! { ::mcje::method_descriptor(&[#(#params),*], #output) }
+    }
+
+    fn expand(&self) -> TokenStream {
+        let attrs = &self.attrs;
+        let ident = &self.ident;
+        let java_name = &self.java_name;
+        let descriptor = self.descriptor();
+        let receiver = (!self.is_static).then(|| quote! { &self, });
+        let params = self.params.iter().map(|param| {
+            let ident = &param.ident;
+            let ty = &param.ty;
+            match param.by_ref {
+                true => quote! { #ident: &#ty },
+                false => quote! { #ident: #ty },
+            }
+        });
+        let args = self.params.iter().map(|param| {
+            let ident = &param.ident;
+            let value = match param.by_ref {
+                true => ident.to_token_stream(),
+                false => quote! { &#ident },
+            };
+            quote! { ::mcje::JavaType::as_jvalue(#value) }
+        });
+        let (output, conversion) = match self.kind {
+            Kind::Constructor => (
+                quote! { Self },
+                quote! { ::mcje::jni::errors::Result::Ok(Self(value)) },
+            ),
+            _ => {
+                let output = &self.output;
+                (
+                    output.to_token_stream(),
+                    quote! { <#output as ::mcje::JavaType<'local>>::from_jvalue(value) },
+                )
+            }
+        };
+        let call = match (&self.kind, self.is_static) {
+            (Kind::Constructor, _) => quote! {
+                env.new_object(Self::CLASS, &descriptor, &[#(#args),*])
+            },
+            (Kind::Method, false) => quote! {
+                env.call_method(&self.0, #java_name, &descriptor, &[#(#args),*])
+            },
+            (Kind::Method, true) => quote! {
+                env.call_static_method(Self::CLASS, #java_name, &descriptor, &[#(#args),*])
+            },
+            (Kind::Field, false) => quote! { env.get_field(&self.0, #java_name, &descriptor) },
+            (Kind::Field, true) => quote! {
+                env.get_static_field(Self::CLASS, #java_name, &descriptor)
+            },
+        };
+
+        quote! {
+            #(#attrs)*
+            pub fn #ident(
+                #receiver
+                env: &mut ::mcje::jni::JNIEnv<'local>,
+                #(#params),*
+            ) -> ::mcje::Result<#output> {
+                let descriptor = #descriptor;
+                let value = ::mcje::Catch::catch(#call, env)?;
+                ::mcje::Catch::catch(#conversion, env)
+            }
+        }
+    }
+}
+
+/// The type to use in the generated code for a declared type, and whether parameters of that
+/// type are passed by reference.
+///
+/// Types other than primitives and `Self` are local references, given the `'local` lifetime when
+/// they don't have one.
+fn java_type(ty: &Type) -> Result<(Type, bool)> {
+    match ty {
+        Type::Tuple(tuple) if tuple.elems.is_empty() => Ok((ty.clone(), false)),
+        Type::Path(path) if path.qself.is_none() => {
+            let Some(last) = path.path.segments.last() else {
+                return Err(Error::new_spanned(ty, "expected a Java type"));
+            };
+            if path.path.segments.len() == 1 && PRIMITIVES.iter().any(|p| last.ident == p) {
+                return Ok((ty.clone(), false));
+            }
+            if is_self(ty) {
+                return Ok((ty.clone(), true));
+            }
+            let mut path = path.clone();
+            let last = path.path.segments.last_mut().unwrap();
+            match &last.arguments {
+                PathArguments::None => {
+                    last.arguments = PathArguments::AngleBracketed(parse_quote!(<'local>))
+                }
+                PathArguments::AngleBracketed(args)
+                    if args
+                        .args
+                        .iter()
+                        .all(|arg| matches!(arg, GenericArgument::Lifetime(_))) => {}
+                _ => return Err(Error::new_spanned(ty, "expected a Java type")),
+            }
+            Ok((Type::Path(path), true))
+        }
+        _ => Err(Error::new_spanned(
+            ty,
+            "expected a primitive, `Self` or a bound class",
+        )),
+    }
+}
+
+fn is_self(ty: &Type) -> bool {
+    matches!(ty, Type::Path(path) if path.qself.is_none() && path.path.is_ident("Self"))
+}
+
+fn descriptor_of(ty: &Type) -> TokenStream {
+    quote! { <#ty as ::mcje::JavaType<'local>>::DESCRIPTOR }
+}
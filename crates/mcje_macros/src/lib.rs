@@ -1,6 +1,8 @@
+mod class;
+
 use proc_macro::TokenStream;
 use quote::quote;
-use syn::{ItemFn, parse_macro_input};
+use syn::{ItemFn, ItemTrait, LitStr, parse_macro_input};
 
 /// Generates the setup code for the main entry point.
 #[proc_macro_attribute]
@@ -8,12 +10,47 @@ pub fn main(_attr: TokenStream, item: TokenStream) -> TokenStream {
     generate_runner(item, false)
 }
 
-/// Generates the setup code for test functions.
+/// Generates the setup code for test functions, which share the JVM of the test binary.
 #[proc_macro_attribute]
 pub fn test(_attr: TokenStream, item: TokenStream) -> TokenStream {
     generate_runner(item, true)
 }
 
+/// Generates a typed wrapper around a Java class from the declarations of the members to bind.
+///
+/// The declarations are written as a trait, which becomes a struct wrapping a `JObject`. A
+/// declaration taking `&self` binds an instance member, and one without binds a static member.
+/// Declarations named `new` or marked `#[mcje(constructor)]` bind constructors,
+/// `#[mcje(field)]` ones read fields, and the others call methods of the same name unless
+/// renamed with `#[mcje(name = "...")]`.
+///
+/// Descriptors are derived from the Rust types through `mcje::JavaType`: the primitives, `()`
+/// as `void`, `JObject`, `JString` and other bound classes, which are passed by reference.
+///
+/// ```ignore
+/// #[mcje::class("net/minecraft/core/BlockPos")]
+/// pub trait BlockPos {
+///     fn new(x: i32, y: i32, z: i32);
+///     fn getX(&self) -> i32;
+///     fn offset(&self, x: i32, y: i32, z: i32) -> Self;
+///     #[mcje(field)]
+///     fn ZERO() -> Self;
+/// }
+///
+/// let pos = BlockPos::new(env, 1, 2, 3)?;
+/// assert_eq!(pos.offset(env, 1, 0, 0)?.getX(env)?, 2);
+/// ```
+#[proc_macro_attribute]
+pub fn class(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let class = parse_macro_input!(attr as LitStr);
+    let item = parse_macro_input!(item as ItemTrait);
+
+    match class::expand(class, item) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
 fn generate_runner(item: TokenStream, is_test: bool) -> TokenStream {
     // 1. Parse the input function (the user's code)
     let input_fn = parse_macro_input!(item as ItemFn);
@@ -51,18 +88,21 @@ fn generate_runner(item: TokenStream, is_test: bool) -> TokenStream {
         quote! {}
     };
 
-    // 7. Generate the final code
+    // 7. Generate the final code, returning what the user's function returns
+    let fn_output = &input_fn.sig.output;
     let output = quote! {
         #macro_attr
-        #fn_vis async fn #wrapper_name() {
+        #fn_vis async fn #wrapper_name() #fn_output {
             // Define the user's function inside the scope (renamed)
             #inner_fn
 
-            let jvm = ::mcje::init().await;
-            let mut env = jvm.attach_current_thread().unwrap();
-            ::mcje::bootstrap(&mut env);
+            // The JVM is shared by the whole process, each test attaching its own thread
+            let jvm = ::mcje::jvm().unwrap_or_else(|err| panic!("{err}"));
+            let mut env = jvm
+                .attach_current_thread()
+                .unwrap_or_else(|err| panic!("{err}"));
             // Call the user's function with the prepared environment
-            #inner_name(&mut env)#await_call;
+            #inner_name(&mut env)#await_call
         }
     };
 
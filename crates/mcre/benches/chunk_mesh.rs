@@ -0,0 +1,139 @@
+//! Compares greedy meshing with a quad per face on fixture chunks, 16 wide and as high as the
+//! overworld.
+
+use std::hint::black_box;
+
+use bevy::platform::collections::HashMap;
+use criterion::{Criterion, criterion_group, criterion_main};
+use mcre::{
+    chunk::{
+        Chunk,
+        generate::spawn_test_chunk,
+        math::{pos::ChunkPosition, size::ChunkSize},
+        mesh::{ChunkMeshBuilder, Neighbors},
+    },
+    models::{BakedModels, BlockModels},
+};
+use mcre_assets::{BlockModelDefinition, BlockStateDefinition};
+use mcre_core::Block;
+use mcre_worldgen::DimensionType;
+
+/// Every block as a cube with its own texture
+fn models() -> BlockModels {
+    let faces = ["down", "up", "north", "south", "west", "east"]
+        .map(|face| format!(r##""{face}": {{ "texture": "#all", "cullface": "{face}" }}"##))
+        .join(",");
+    let blockstates = Block::all()
+        .map(|block| {
+            let json = format!(
+                r#"{{ "variants": {{ "": {{ "model": "block/{}" }} }} }}"#,
+                block.name()
+            );
+            (
+                block,
+                serde_json::from_str::<BlockStateDefinition>(&json).unwrap(),
+            )
+        })
+        .collect::<HashMap<_, _>>();
+    let models = Block::all()
+        .map(|block| {
+            let json = format!(
+                r#"{{
+                    "textures": {{ "all": "block/{}" }},
+                    "elements": [{{ "from": [0, 0, 0], "to": [16, 16, 16], "faces": {{ {faces} }} }}]
+                }}"#,
+                block.name()
+            );
+            let model = serde_json::from_str::<BlockModelDefinition>(&json).unwrap();
+            (format!("minecraft:block/{}", block.name()), model)
+        })
+        .collect::<HashMap<_, _>>();
+    let layers = Block::all()
+        .zip(0..)
+        .map(|(block, layer)| {
+            (
+                format!("minecraft/textures/block/{}.png", block.name()),
+                layer,
+            )
+        })
+        .collect();
+    BakedModels::new(|block| blockstates.get(&block), |id| models.get(id)).resolve(&layers)
+}
+
+fn chunk(fill: impl Fn(u8, i32, u8) -> Option<Block>) -> Chunk {
+    let size = ChunkSize::new(16);
+    let dimension = DimensionType::overworld();
+    let mut chunk = Chunk::empty(size, &dimension, ChunkPosition::new(0, 0));
+    for y in dimension.min_y..dimension.max_y() {
+        for z in 0..16 {
+            for x in 0..16 {
+                if let Some(block) = fill(x, y, z) {
+                    chunk.set((x, y, z), block).unwrap();
+                }
+            }
+        }
+    }
+    chunk
+}
+
+/// Stone with scattered ores up to 60, then dirt and grass, then air
+fn terrain() -> Chunk {
+    chunk(|x, y, z| {
+        let hash = (x as usize * 73_856_093)
+            ^ ((y + 64) as usize * 19_349_663)
+            ^ (z as usize * 83_492_791);
+        match y {
+            -64 => Some(Block::BEDROCK),
+            -63..60 => Some(match hash % 64 {
+                0 => Block::COAL_ORE,
+                1 => Block::IRON_ORE,
+                2 => Block::GRAVEL,
+                3 => Block::ANDESITE,
+                _ => Block::STONE,
+            }),
+            60..64 => Some(Block::DIRT),
+            64 => Some(Block::GRASS_BLOCK),
+            _ => None,
+        }
+    })
+}
+
+/// Alternating stone and dirt in every direction, where no faces can merge
+fn checkerboard() -> Chunk {
+    chunk(|x, y, z| match y {
+        0..16 if (x as i32 + y + z as i32) % 2 == 0 => Some(Block::STONE),
+        0..16 => Some(Block::DIRT),
+        _ => None,
+    })
+}
+
+fn chunk_mesh(c: &mut Criterion) {
+    let models = models();
+    let fixtures = [
+        ("terrain", terrain()),
+        ("checkerboard", checkerboard()),
+        (
+            "test_chunk",
+            spawn_test_chunk(
+                ChunkSize::new(16),
+                &DimensionType::overworld(),
+                ChunkPosition::new(0, 0),
+            ),
+        ),
+    ];
+    for (name, chunk) in &fixtures {
+        let mut group = c.benchmark_group(*name);
+        for (mode, greedy) in [("naive", false), ("greedy", true)] {
+            group.bench_function(mode, |b| {
+                b.iter(|| {
+                    let builder = ChunkMeshBuilder::new(chunk, Neighbors::default()).greedy(greedy);
+                    black_box(builder.build(&models))
+                })
+            });
+        }
+        group.finish();
+    }
+}
+
+criterion_group!(benches, chunk_mesh);
+criterion_main!(benches);
@@ -0,0 +1,164 @@
+//! Compares the paletted sections of chunks with the `SparseVec` storage they replaced, on a
+//! 16 wide and 384 high column of terrain.
+
+use std::{
+    collections::HashMap,
+    hash::{BuildHasherDefault, Hasher},
+    hint::black_box,
+};
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use mcre_core::{Block, BlockState};
+
+// modules of the game, whose test imports go unused when the bench is built as a test
+#[allow(dead_code, unused_imports)]
+#[path = "../src/chunk/palette.rs"]
+mod palette;
+#[allow(dead_code, unused_imports)]
+#[path = "../src/chunk/section.rs"]
+mod section;
+
+use section::{SECTION_HEIGHT, Section};
+
+const SIZE: usize = 16;
+const HEIGHT: usize = 384;
+
+trait Storage {
+    fn empty() -> Self;
+    fn set(&mut self, x: usize, y: usize, z: usize, state: BlockState);
+    fn get(&self, x: usize, y: usize, z: usize) -> BlockState;
+    fn count(&self) -> usize;
+}
+
+/// The `SparseVec` chunks used before sections, a hash map by block index
+struct Sparse(HashMap<usize, BlockState, BuildHasherDefault<IdentityHasher>>);
+
+#[derive(Default)]
+struct IdentityHasher(usize);
+
+impl Hasher for IdentityHasher {
+    fn finish(&self) -> u64 {
+        self.0 as u64
+    }
+
+    fn write(&mut self, _bytes: &[u8]) {
+        unimplemented!("IdentityHasher only supports usize keys")
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.0 = i;
+    }
+}
+
+impl Storage for Sparse {
+    fn empty() -> Self {
+        Sparse(HashMap::default())
+    }
+
+    fn set(&mut self, x: usize, y: usize, z: usize, state: BlockState) {
+        self.0.insert((y * SIZE + x) * SIZE + z, state);
+    }
+
+    fn get(&self, x: usize, y: usize, z: usize) -> BlockState {
+        self.0
+            .get(&((y * SIZE + x) * SIZE + z))
+            .copied()
+            .unwrap_or(Block::AIR.default_state())
+    }
+
+    fn count(&self) -> usize {
+        self.0.values().filter(|state| !state.is_air()).count()
+    }
+}
+
+struct Sections(Vec<Section>);
+
+impl Storage for Sections {
+    fn empty() -> Self {
+        Sections(
+            (0..HEIGHT / SECTION_HEIGHT)
+                .map(|_| Section::empty(SIZE * SIZE * SECTION_HEIGHT))
+                .collect(),
+        )
+    }
+
+    fn set(&mut self, x: usize, y: usize, z: usize, state: BlockState) {
+        let index = ((y % SECTION_HEIGHT) * SIZE + z) * SIZE + x;
+        self.0[y / SECTION_HEIGHT].set(index, state);
+    }
+
+    fn get(&self, x: usize, y: usize, z: usize) -> BlockState {
+        let index = ((y % SECTION_HEIGHT) * SIZE + z) * SIZE + x;
+        self.0[y / SECTION_HEIGHT].get(index)
+    }
+
+    fn count(&self) -> usize {
+        self.0
+            .iter()
+            .filter(|section| !section.is_empty())
+            .map(|section| section.iter().count())
+            .sum()
+    }
+}
+
+/// Stone with scattered ores up to 124, then dirt and grass, then air
+fn terrain(x: usize, y: usize, z: usize) -> BlockState {
+    let hash = (x * 73_856_093) ^ (y * 19_349_663) ^ (z * 83_492_791);
+    let block = match y {
+        0 => Block::BEDROCK,
+        1..124 => match hash % 64 {
+            0 => Block::COAL_ORE,
+            1 => Block::IRON_ORE,
+            2 => Block::GRAVEL,
+            3 => Block::ANDESITE,
+            _ => Block::STONE,
+        },
+        124..128 => Block::DIRT,
+        128 => Block::GRASS_BLOCK,
+        _ => Block::AIR,
+    };
+    block.default_state()
+}
+
+fn filled<S: Storage>() -> S {
+    let mut storage = S::empty();
+    for y in 0..HEIGHT {
+        for z in 0..SIZE {
+            for x in 0..SIZE {
+                let state = terrain(x, y, z);
+                if !state.is_air() {
+                    storage.set(x, y, z, state);
+                }
+            }
+        }
+    }
+    storage
+}
+
+fn bench_storage<S: Storage>(c: &mut Criterion, name: &str) {
+    let mut group = c.benchmark_group(name);
+    group.bench_function("fill", |b| b.iter(|| black_box(filled::<S>())));
+
+    let storage = filled::<S>();
+    group.bench_function("get", |b| {
+        b.iter(|| {
+            for y in 0..HEIGHT {
+                for z in 0..SIZE {
+                    for x in 0..SIZE {
+                        black_box(storage.get(x, y, z));
+                    }
+                }
+            }
+        })
+    });
+    group.bench_function("iter", |b| b.iter(|| black_box(storage.count())));
+    group.finish();
+}
+
+fn chunk_storage(c: &mut Criterion) {
+    bench_storage::<Sparse>(c, "sparse");
+    bench_storage::<Sections>(c, "sections");
+}
+
+criterion_group!(benches, chunk_storage);
+criterion_main!(benches);
@@ -22,7 +22,7 @@ impl Plugin for FirstPersonPlugin {
                     transform,
                     camera: Camera3d::default(),
                 });
-                next.set(LoadingState::Textures);
+                next.set(LoadingState::Models);
             },
         )
         .add_systems(
@@ -1,5 +1,6 @@
 use bevy::math::U8Vec2;
 use mcre_core::Block;
+use mcre_worldgen::DimensionType;
 
 use crate::chunk::{
     Chunk,
@@ -7,28 +8,32 @@ use crate::chunk::{
 };
 
 // TODO: Create procedural
-pub fn spawn_test_chunk(chunk_size: ChunkSize, loc: ChunkPosition) -> Chunk {
-    let mut chunk = Chunk::empty(chunk_size, loc);
+pub fn spawn_test_chunk(
+    chunk_size: ChunkSize,
+    dimension: &DimensionType,
+    loc: ChunkPosition,
+) -> Chunk {
+    let mut chunk = Chunk::empty(chunk_size, dimension, loc);
 
     for x in chunk.size().iter() {
         for y in chunk.size().iter() {
-            chunk.set((x as u8, 3, y as u8), Block::DIRT);
-            chunk.set((x as u8, 2, y as u8), Block::DIRT);
-            chunk.set((x as u8, 1, y as u8), Block::DIRT);
-            chunk.set((x as u8, 0, y as u8), Block::BEDROCK);
+            set(&mut chunk, (x as u8, 3, y as u8), Block::DIRT);
+            set(&mut chunk, (x as u8, 2, y as u8), Block::DIRT);
+            set(&mut chunk, (x as u8, 1, y as u8), Block::DIRT);
+            set(&mut chunk, (x as u8, 0, y as u8), Block::BEDROCK);
         }
     }
 
     let loc = U8Vec2::new(4, 4);
     for y in 4..10 {
-        chunk.set((loc.x, y, loc.y), Block::OAK_LOG);
+        set(&mut chunk, (loc.x, y, loc.y), Block::OAK_LOG);
     }
     for x in 1..8 {
         for z in 1..8 {
             if x == z && x == loc.x {
                 continue;
             }
-            chunk.set((x, 7, z), Block::OAK_LEAVES);
+            set(&mut chunk, (x, 7, z), Block::OAK_LEAVES);
         }
     }
 
@@ -37,7 +42,7 @@ pub fn spawn_test_chunk(chunk_size: ChunkSize, loc: ChunkPosition) -> Chunk {
             if x == z && x == loc.x {
                 continue;
             }
-            chunk.set((x, 8, z), Block::OAK_LEAVES);
+            set(&mut chunk, (x, 8, z), Block::OAK_LEAVES);
         }
     }
 
@@ -46,24 +51,24 @@ pub fn spawn_test_chunk(chunk_size: ChunkSize, loc: ChunkPosition) -> Chunk {
             if x == z && x == loc.x {
                 continue;
             }
-            chunk.set((x, 9, z), Block::OAK_LEAVES);
+            set(&mut chunk, (x, 9, z), Block::OAK_LEAVES);
         }
     }
 
     for x in 3..6 {
         for z in 3..6 {
-            chunk.set((x, 10, z), Block::OAK_LEAVES);
+            set(&mut chunk, (x, 10, z), Block::OAK_LEAVES);
         }
     }
 
-    chunk.set((0, 11, 0), Block::DIAMOND_ORE);
-    chunk.set(
-        (
-            chunk.chunk_size.as_u8() - 1,
-            11,
-            chunk.chunk_size.as_u8() - 1,
-        ),
-        Block::IRON_ORE,
-    );
+    set(&mut chunk, (0, 11, 0), Block::DIAMOND_ORE);
+    let last = chunk.chunk_size.as_u8() - 1;
+    set(&mut chunk, (last, 11, last), Block::IRON_ORE);
     chunk
 }
+
+fn set(chunk: &mut Chunk, pos: (u8, i32, u8), block: Block) {
+    chunk
+        .set(pos, block)
+        .expect("the test chunk is within the build height");
+}
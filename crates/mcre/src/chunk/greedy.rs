@@ -0,0 +1,102 @@
+use bevy::{color::Srgba, math::Vec3};
+use mcre_core::{Axis, Direction};
+
+use crate::chunk::{mesh::MeshBuilder, section::SECTION_HEIGHT};
+
+/// How a face is drawn, only faces drawn the same way are merged
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FaceStyle {
+    /// Layer of the block texture
+    pub layer: u32,
+    pub tint: Srgba,
+}
+
+/// Visible faces of the full cubes of a section, merged into quads as large as possible when the
+/// section is flushed. Faces are merged within a slice of the section facing the same direction,
+/// so quads are at most a section high.
+pub struct GreedyFaces {
+    size: usize,
+    /// `y` of the bottom of the section whose faces are collected
+    min_y: i32,
+    /// Faces by direction then block index, ordered by y then z then x like sections
+    faces: [Vec<Option<FaceStyle>>; 6],
+}
+
+impl GreedyFaces {
+    pub fn new(size: usize) -> Self {
+        GreedyFaces {
+            size,
+            min_y: 0,
+            faces: std::array::from_fn(|_| vec![None; size * size * SECTION_HEIGHT]),
+        }
+    }
+
+    /// Adds the face of the block at `pos`, in the section starting at `min_y`. Faces of the
+    /// previous section are flushed to `builder` when the section changes.
+    pub fn insert(
+        &mut self,
+        builder: &mut MeshBuilder,
+        min_y: i32,
+        pos: [usize; 3],
+        direction: Direction,
+        style: FaceStyle,
+    ) {
+        if min_y != self.min_y {
+            self.flush(builder);
+            self.min_y = min_y;
+        }
+        self.faces[direction as usize][index(self.size, pos)] = Some(style);
+    }
+
+    /// Pushes the merged faces of the current section to `builder`
+    pub fn flush(&mut self, builder: &mut MeshBuilder) {
+        let size = self.size;
+        let dims = [size, SECTION_HEIGHT, size];
+        for direction in Direction::ALL {
+            let (n, u, v) = match direction.axis() {
+                Axis::X => (0, 2, 1),
+                Axis::Y => (1, 0, 2),
+                Axis::Z => (2, 0, 1),
+            };
+            for slice in 0..dims[n] {
+                for v0 in 0..dims[v] {
+                    for u0 in 0..dims[u] {
+                        let at = |u_pos: usize, v_pos: usize| {
+                            let mut pos = [0; 3];
+                            (pos[n], pos[u], pos[v]) = (slice, u_pos, v_pos);
+                            index(size, pos)
+                        };
+                        let faces = &mut self.faces[direction as usize];
+                        let Some(style) = faces[at(u0, v0)] else {
+                            continue;
+                        };
+
+                        let width = (u0..dims[u])
+                            .take_while(|&u_pos| faces[at(u_pos, v0)] == Some(style))
+                            .count();
+                        let height = (v0..dims[v])
+                            .take_while(|&v_pos| {
+                                (u0..u0 + width).all(|u_pos| faces[at(u_pos, v_pos)] == Some(style))
+                            })
+                            .count();
+                        for v_pos in v0..v0 + height {
+                            for u_pos in u0..u0 + width {
+                                faces[at(u_pos, v_pos)] = None;
+                            }
+                        }
+
+                        let (mut min, mut extent) = (Vec3::ZERO, Vec3::ONE);
+                        (min[n], min[u], min[v]) = (slice as f32, u0 as f32, v0 as f32);
+                        (extent[u], extent[v]) = (width as f32, height as f32);
+                        min.y += self.min_y as f32;
+                        builder.push_face(direction, min, extent, style.layer, style.tint);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn index(size: usize, [x, y, z]: [usize; 3]) -> usize {
+    (y * size + z) * size + x
+}
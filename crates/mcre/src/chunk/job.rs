@@ -0,0 +1,47 @@
+use std::time::Duration;
+
+use bevy::{
+    platform::time::Instant,
+    tasks::{AsyncComputeTaskPool, Task, futures::check_ready},
+};
+
+/// Work running on the `AsyncComputeTaskPool`, cancelled when dropped
+pub struct Job<T> {
+    task: Task<T>,
+    started: Instant,
+}
+
+impl<T: Send + 'static> Job<T> {
+    pub fn spawn(work: impl FnOnce() -> T + Send + 'static) -> Self {
+        Job {
+            task: AsyncComputeTaskPool::get().spawn(async move { work() }),
+            started: Instant::now(),
+        }
+    }
+
+    /// The result and how long the job took since it was spawned, `None` while it's running.
+    /// Must not be polled again once it returned a result.
+    pub fn poll(&mut self) -> Option<(T, Duration)> {
+        check_ready(&mut self.task).map(|result| (result, self.started.elapsed()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::tasks::{AsyncComputeTaskPool, TaskPool};
+
+    use crate::chunk::job::Job;
+
+    #[test]
+    fn test_poll() {
+        AsyncComputeTaskPool::get_or_init(TaskPool::new);
+        let mut job = Job::spawn(|| 1 + 2);
+        let result = loop {
+            if let Some((result, _)) = job.poll() {
+                break result;
+            }
+            std::thread::yield_now();
+        };
+        assert_eq!(result, 3);
+    }
+}
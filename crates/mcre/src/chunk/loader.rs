@@ -1,8 +1,12 @@
+use std::time::Duration;
+
 use bevy::{
     asset::LoadState,
+    diagnostic::{Diagnostic, DiagnosticPath, Diagnostics, RegisterDiagnostic},
     platform::collections::{HashMap, HashSet},
     prelude::*,
 };
+use mcre_worldgen::DimensionType;
 
 use crate::{
     AppState, LoadingState,
@@ -10,9 +14,11 @@ use crate::{
         Chunk, ChunkComponent,
         asset::ChunkAssetLoader,
         generate::spawn_test_chunk,
+        job::Job,
         math::{pos::ChunkPosition, size::ChunkSize},
-        mesh::ChunkMeshBuilder,
+        mesh::{ChunkMeshBuilder, Neighbors},
     },
+    models::BlockModels,
     textures::BlockTextures,
 };
 
@@ -28,67 +34,133 @@ impl Plugin for ChunkLoaderPlugin {
             .init_asset_loader::<ChunkAssetLoader>()
             .insert_resource(self.config.clone())
             .insert_resource(Time::from_seconds(1. / 20.))
+            .register_diagnostic(Diagnostic::new(ChunkLoader::GENERATE_QUEUE))
+            .register_diagnostic(Diagnostic::new(ChunkLoader::MESH_QUEUE))
+            .register_diagnostic(Diagnostic::new(ChunkLoader::GENERATE_LATENCY).with_suffix("ms"))
+            .register_diagnostic(Diagnostic::new(ChunkLoader::MESH_LATENCY).with_suffix("ms"))
             .add_systems(
                 FixedUpdate,
                 (
                     ChunkLoader::read_chunks,
                     ChunkLoader::load_chunks,
                     ChunkLoader::generate_chunks,
-                    ChunkLoader::spawn_chunks,
-                    |loader: Res<ChunkLoader>, mut next_state: ResMut<NextState<AppState>>| {
-                        if loader.unloaded_chunks.is_empty() && loader.rendering_chunks.is_empty() {
-                            next_state.set(AppState::InGame);
-                        }
-                    },
+                    ChunkLoader::mesh_chunks,
                 )
                     .chain()
-                    .run_if(in_state(LoadingState::Chunks)),
+                    .run_if(in_state(LoadingState::Chunks).or(in_state(AppState::InGame))),
             )
             .add_systems(
                 FixedUpdate,
+                ChunkLoader::despawn_chunks
+                    .before(ChunkLoader::read_chunks)
+                    .run_if(in_state(AppState::InGame)),
+            )
+            .add_systems(
+                Update,
                 (
-                    ChunkLoader::read_chunks,
-                    ChunkLoader::load_chunks,
-                    ChunkLoader::generate_chunks,
-                    ChunkLoader::spawn_chunks,
-                    ChunkLoader::despawn_chunks,
+                    ChunkLoader::poll_generated_chunks,
+                    ChunkLoader::upload_meshes,
+                    ChunkLoader::measure_queues,
                 )
                     .chain()
-                    .run_if(in_state(AppState::InGame)),
+                    .run_if(in_state(LoadingState::Chunks).or(in_state(AppState::InGame))),
+            )
+            .add_systems(
+                Update,
+                (|loader: Res<ChunkLoader>, mut next_state: ResMut<NextState<AppState>>| {
+                    if loader.is_idle() {
+                        next_state.set(AppState::InGame);
+                    }
+                })
+                .after(ChunkLoader::upload_meshes)
+                .run_if(in_state(LoadingState::Chunks)),
             );
     }
 }
 
-#[derive(Resource, Default, Debug)]
+/// Chunks by loading step. Generation and meshing run as jobs on the `AsyncComputeTaskPool`,
+/// dropped to cancel them when their chunk leaves the radius.
+#[derive(Resource, Default)]
 pub struct ChunkLoader {
     //TODO: Convert to some faster lookups
     unloaded_chunks: HashMap<ChunkPosition, Handle<Chunk>>,
+    /// Chunks waiting for a generation job
     generating_chunks: HashSet<ChunkPosition>,
+    generate_jobs: HashMap<ChunkPosition, Job<Chunk>>,
+    /// Chunks waiting for a job building their first mesh
     rendering_chunks: HashMap<ChunkPosition, Handle<Chunk>>,
+    mesh_jobs: HashMap<ChunkPosition, MeshJob>,
     loaded_chunks: HashMap<ChunkPosition, Handle<Chunk>>,
 }
 
+struct MeshJob {
+    job: Job<Mesh>,
+    chunk: Handle<Chunk>,
+    /// Mesh of the spawned chunk to replace, `None` to spawn the chunk
+    mesh: Option<Handle<Mesh>>,
+    /// Which neighbors were loaded when the job started, see [`Neighbors::positions`]
+    neighbors: [bool; 4],
+}
+
 impl ChunkLoader {
+    /// Chunks waiting for or being generated
+    pub const GENERATE_QUEUE: DiagnosticPath = DiagnosticPath::const_new("chunks/generate_queue");
+    /// Chunks waiting for or being meshed
+    pub const MESH_QUEUE: DiagnosticPath = DiagnosticPath::const_new("chunks/mesh_queue");
+    /// Mean time from spawning to finishing of the generation jobs finished in a frame
+    pub const GENERATE_LATENCY: DiagnosticPath =
+        DiagnosticPath::const_new("chunks/generate_latency");
+    /// Mean time from spawning to finishing of the mesh jobs finished in a frame
+    pub const MESH_LATENCY: DiagnosticPath = DiagnosticPath::const_new("chunks/mesh_latency");
+
     pub fn unloaded_chunks(&self) -> usize {
         self.unloaded_chunks.len()
     }
 
     pub fn generating_chunks(&self) -> usize {
-        self.generating_chunks.len()
+        self.generating_chunks.len() + self.generate_jobs.len()
     }
 
     pub fn rendering_chunks(&self) -> usize {
-        self.rendering_chunks.len()
+        let spawning = self.mesh_jobs.values().filter(|job| job.mesh.is_none());
+        self.rendering_chunks.len() + spawning.count()
     }
 
     pub fn loaded_chunks(&self) -> usize {
         self.loaded_chunks.len()
     }
 
+    /// Whether every chunk around the camera is loaded and no job is running
+    pub fn is_idle(&self) -> bool {
+        self.unloaded_chunks.is_empty()
+            && self.generating_chunks() == 0
+            && self.rendering_chunks.is_empty()
+            && self.mesh_jobs.is_empty()
+    }
+
+    /// Handle of the chunk at `pos` if it's loaded and spawned
+    pub fn get(&self, pos: &ChunkPosition) -> Option<&Handle<Chunk>> {
+        self.loaded_chunks.get(pos)
+    }
+
+    /// Handles of the loaded and spawned chunks
+    pub fn loaded(&self) -> impl Iterator<Item = &Handle<Chunk>> {
+        self.loaded_chunks.values()
+    }
+
+    /// Loaded chunks around `loc`
+    pub fn neighbors<'a>(&self, loc: ChunkPosition, chunks: &'a Assets<Chunk>) -> Neighbors<'a> {
+        Neighbors::new(loc, |pos| {
+            self.get(&pos).and_then(|handle| chunks.get(handle.id()))
+        })
+    }
+
     fn contains(&self, pos: &ChunkPosition) -> bool {
         self.unloaded_chunks.contains_key(pos)
             || self.generating_chunks.contains(pos)
+            || self.generate_jobs.contains_key(pos)
             || self.rendering_chunks.contains_key(pos)
+            || self.mesh_jobs.contains_key(pos)
             || self.loaded_chunks.contains_key(pos)
     }
 
@@ -148,69 +220,188 @@ impl ChunkLoader {
         }
     }
 
-    pub fn generate_chunks(
+    /// Starts generation jobs for the queued chunks
+    pub fn generate_chunks(mut loader: ResMut<ChunkLoader>, config: Res<ChunkLoaderConfig>) {
+        while loader.generate_jobs.len() < config.batching.generating {
+            let Some(&loc) = loader.generating_chunks.iter().next() else {
+                break;
+            };
+            loader.generating_chunks.remove(&loc);
+            let (chunk_size, dimension) = (config.chunk_size, config.dimension.clone());
+            let job = Job::spawn(move || spawn_test_chunk(chunk_size, &dimension, loc));
+            loader.generate_jobs.insert(loc, job);
+        }
+    }
+
+    /// Adds the chunks of finished generation jobs, to be meshed
+    pub fn poll_generated_chunks(
         mut loader: ResMut<ChunkLoader>,
         mut chunks: ResMut<Assets<Chunk>>,
+        mut diagnostics: Diagnostics,
+    ) {
+        let ChunkLoader {
+            generate_jobs,
+            rendering_chunks,
+            ..
+        } = &mut *loader;
+        let mut latencies = Vec::new();
+        generate_jobs.retain(|loc, job| match job.poll() {
+            Some((chunk, latency)) => {
+                rendering_chunks.insert(*loc, chunks.add(chunk));
+                latencies.push(latency);
+                false
+            }
+            None => true,
+        });
+        if let Some(latency) = mean_millis(&latencies) {
+            diagnostics.add_measurement(&Self::GENERATE_LATENCY, || latency);
+        }
+    }
+
+    /// Starts mesh jobs for the spawned chunks whose blocks or neighbors changed, then for the
+    /// chunks waiting for their first mesh. A chunk only has one job at a time, changes made while
+    /// it runs are meshed by the next one.
+    pub fn mesh_chunks(
+        mut loader: ResMut<ChunkLoader>,
+        components: Query<(&ChunkComponent, &Mesh3d)>,
+        mut chunks: ResMut<Assets<Chunk>>,
+        state: Res<State<AppState>>,
+        models: Res<BlockModels>,
         config: Res<ChunkLoaderConfig>,
     ) {
-        let batch = loader
-            .generating_chunks
-            .iter()
-            .take(config.batching.generating)
-            .copied()
-            .collect::<Vec<_>>();
-        for loc in batch {
-            if loader.generating_chunks.remove(&loc) {
-                let handle = chunks.add(spawn_test_chunk(config.chunk_size, loc));
-                loader.rendering_chunks.insert(loc, handle);
+        let limit = config.batching.rendering(state.get());
+        for (component, mesh) in &components {
+            if loader.mesh_jobs.len() >= limit {
+                return;
             }
+            // checked first as mutable access marks the chunk asset as modified
+            let Some(loc) = chunks
+                .get(component.0.id())
+                .filter(|chunk| chunk.is_dirty() && !loader.mesh_jobs.contains_key(&chunk.loc))
+                .map(|chunk| chunk.loc)
+            else {
+                continue;
+            };
+            let job =
+                loader.start_mesh_job(&component.0, Some(mesh.0.clone()), &mut chunks, &models);
+            loader.mesh_jobs.insert(loc, job);
+        }
+
+        while loader.mesh_jobs.len() < limit {
+            let Some(&loc) = loader.rendering_chunks.keys().next() else {
+                break;
+            };
+            let handle = loader.rendering_chunks.remove(&loc).unwrap();
+            let job = loader.start_mesh_job(&handle, None, &mut chunks, &models);
+            loader.mesh_jobs.insert(loc, job);
         }
     }
 
-    /// Spawn chunks that are in the `UnloadedChunk` state
-    pub fn spawn_chunks(
+    /// Meshes a copy of the chunk and its loaded neighbors, marking the chunk as meshed
+    fn start_mesh_job(
+        &self,
+        handle: &Handle<Chunk>,
+        mesh: Option<Handle<Mesh>>,
+        chunks: &mut Assets<Chunk>,
+        models: &BlockModels,
+    ) -> MeshJob {
+        let chunk = chunks.get_mut(handle.id()).unwrap();
+        chunk.clear_dirty();
+        let chunk = chunk.clone();
+        let neighbors = Neighbors::positions(chunk.loc).map(|pos| {
+            self.get(&pos)
+                .and_then(|handle| chunks.get(handle.id()))
+                .cloned()
+        });
+        let loaded = neighbors.each_ref().map(Option::is_some);
+        let models = models.clone();
+        let job = Job::spawn(move || {
+            let neighbors = Neighbors::new(chunk.loc, |pos| {
+                neighbors.iter().flatten().find(|chunk| chunk.loc == pos)
+            });
+            ChunkMeshBuilder::new(&chunk, neighbors).build(&models)
+        });
+        MeshJob {
+            job,
+            chunk: handle.clone(),
+            mesh,
+            neighbors: loaded,
+        }
+    }
+
+    /// Uploads the meshes of finished jobs, at most `Batching::uploads` a frame. Chunks meshed for
+    /// the first time are spawned.
+    pub fn upload_meshes(
         mut commands: Commands,
         mut loader: ResMut<ChunkLoader>,
         mut meshes: ResMut<Assets<Mesh>>,
-        state: Res<State<AppState>>,
+        mut chunks: ResMut<Assets<Chunk>>,
         textures: Res<BlockTextures>,
         config: Res<ChunkLoaderConfig>,
-        chunks: Res<Assets<Chunk>>,
+        mut diagnostics: Diagnostics,
     ) {
-        if loader.rendering_chunks.is_empty() {
-            return;
-        }
-        let length = loader.rendering_chunks.len();
-        let batch_size = config.batching.rendering(state.get()).min(length);
-        let batch = loader
-            .rendering_chunks
-            .keys()
-            .copied()
-            .take(batch_size)
+        let finished = loader
+            .mesh_jobs
+            .iter_mut()
+            .filter_map(|(loc, job)| job.job.poll().map(|result| (*loc, result)))
+            .take(config.batching.uploads)
             .collect::<Vec<_>>();
-        if batch.is_empty() {
+        if finished.is_empty() {
             return;
         }
 
-        let span = info_span!("chunk_spawning");
-        span.in_scope(|| {
-            let batch = batch
-                .into_iter()
-                .filter_map(|i| loader.rendering_chunks.remove(&i))
-                .collect::<Vec<_>>();
-            for new_chunk in batch {
-                let chunk = chunks.get(new_chunk.id()).unwrap();
-                loader.loaded_chunks.insert(chunk.loc, new_chunk.clone());
-                commands.spawn((
-                    ChunkComponent(new_chunk),
-                    chunk.transform(),
-                    MeshMaterial3d(textures.texture().unwrap().clone()),
-                    Mesh3d(meshes.add(ChunkMeshBuilder::new(chunk).build(&textures))),
-                ));
+        let span = info_span!("chunk_uploading");
+        let _enter = span.enter();
+        let mut latencies = Vec::with_capacity(finished.len());
+        for (loc, (mesh, latency)) in finished {
+            latencies.push(latency);
+            let job = loader.mesh_jobs.remove(&loc).unwrap();
+            if let Some(handle) = job.mesh {
+                // the chunk was despawned if its mesh is gone
+                let _ = meshes.insert(handle.id(), mesh);
+                continue;
+            }
+
+            let Some(chunk) = chunks.get(job.chunk.id()) else {
+                continue;
+            };
+            commands.spawn((
+                ChunkComponent(job.chunk.clone()),
+                chunk.transform(),
+                MeshMaterial3d(textures.texture().unwrap().clone()),
+                Mesh3d(meshes.add(mesh)),
+            ));
+
+            // faces of the loaded neighbors against the chunk are now hidden, and the faces of
+            // the chunk against neighbors loaded while it was meshed
+            let neighbors = Neighbors::positions(loc).map(|pos| loader.get(&pos).cloned());
+            for (handle, was_loaded) in neighbors.iter().zip(job.neighbors) {
+                let Some(neighbor) = handle
+                    .as_ref()
+                    .and_then(|handle| chunks.get_mut(handle.id()))
+                else {
+                    continue;
+                };
+                neighbor.mark_dirty();
+                if !was_loaded && let Some(chunk) = chunks.get_mut(job.chunk.id()) {
+                    chunk.mark_dirty();
+                }
             }
+            loader.loaded_chunks.insert(loc, job.chunk);
+        }
+        if let Some(latency) = mean_millis(&latencies) {
+            diagnostics.add_measurement(&Self::MESH_LATENCY, || latency);
+        }
+    }
+
+    pub fn measure_queues(loader: Res<ChunkLoader>, mut diagnostics: Diagnostics) {
+        diagnostics.add_measurement(&Self::GENERATE_QUEUE, || loader.generating_chunks() as f64);
+        diagnostics.add_measurement(&Self::MESH_QUEUE, || {
+            (loader.rendering_chunks.len() + loader.mesh_jobs.len()) as f64
         });
     }
 
+    /// Despawns the chunks outside of the radius, and cancels the loading of those not spawned
     pub fn despawn_chunks(
         mut commands: Commands,
         camera: Query<&Transform, With<Camera>>,
@@ -219,21 +410,37 @@ impl ChunkLoader {
         config: Res<ChunkLoaderConfig>,
         mut loader: ResMut<ChunkLoader>,
     ) {
-        if components.is_empty() {
-            return;
-        }
         let camera_loc = camera.single().unwrap().translation;
         let cur_chunk = config.chunk_size.chunk_coord(camera_loc);
         let radius = config.chunk_radius as u64;
+        let inside = |loc: &ChunkPosition| !cur_chunk.outside_radius(*loc, radius);
+
+        let loader = &mut *loader;
+        loader.unloaded_chunks.retain(|loc, _| inside(loc));
+        loader.generating_chunks.retain(inside);
+        loader.generate_jobs.retain(|loc, _| inside(loc));
+        loader.rendering_chunks.retain(|loc, handle| {
+            inside(loc) || {
+                chunks.remove(handle.id());
+                false
+            }
+        });
+        loader.mesh_jobs.retain(|loc, job| {
+            inside(loc) || {
+                // spawned chunks are removed with their entity
+                if job.mesh.is_none() {
+                    chunks.remove(job.chunk.id());
+                }
+                false
+            }
+        });
+
         let remove_chunks = components
             .iter()
             .filter_map(|(entity, chunk)| {
                 let id = chunk.0.id();
                 let chunk = chunks.get(id)?;
-
-                cur_chunk
-                    .outside_radius(chunk.loc, radius)
-                    .then_some((entity, chunk.loc, id))
+                (!inside(&chunk.loc)).then_some((entity, chunk.loc, id))
             })
             .collect::<Vec<_>>();
         for (entity, loc, id) in remove_chunks {
@@ -245,11 +452,22 @@ impl ChunkLoader {
     }
 }
 
+/// Mean of `durations` in milliseconds, `None` if there are none
+fn mean_millis(durations: &[Duration]) -> Option<f64> {
+    if durations.is_empty() {
+        return None;
+    }
+    let total = durations.iter().sum::<Duration>();
+    Some(total.as_secs_f64() * 1000. / durations.len() as f64)
+}
+
 #[derive(Clone, Resource)]
 pub struct ChunkLoaderConfig {
     /// Number of chunks rendered around the camera in the x, y, z directions
     pub chunk_radius: usize,
     pub chunk_size: ChunkSize,
+    /// Height of the chunks
+    pub dimension: DimensionType,
     pub batching: Batching,
 }
 
@@ -258,16 +476,23 @@ impl Default for ChunkLoaderConfig {
         ChunkLoaderConfig {
             chunk_radius: 10,
             chunk_size: ChunkSize::new(16),
+            dimension: DimensionType::overworld(),
             batching: Default::default(),
         }
     }
 }
 
+/// Limits of the work started and finished at once, to avoid frame hitches
 #[derive(Clone)]
 pub struct Batching {
+    /// Mesh jobs running at once while loading
     loading: usize,
+    /// Generation jobs running at once
     generating: usize,
+    /// Mesh jobs running at once in game
     rendering: usize,
+    /// Meshes uploaded a frame
+    uploads: usize,
 }
 
 impl Batching {
@@ -283,9 +508,10 @@ impl Batching {
 impl Default for Batching {
     fn default() -> Self {
         Self {
-            loading: 100,
-            generating: 50,
-            rendering: 50,
+            loading: 64,
+            generating: 32,
+            rendering: 16,
+            uploads: 8,
         }
     }
 }
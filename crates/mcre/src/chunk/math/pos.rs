@@ -10,6 +10,10 @@ use crate::chunk::math::size::ChunkSize;
 pub struct ChunkPosition(I64Vec2);
 
 impl ChunkPosition {
+    pub const fn new(x: i64, z: i64) -> Self {
+        ChunkPosition(I64Vec2::new(x, z))
+    }
+
     pub fn into_coords(pos: Vec3) -> Self {
         let chunk = pos.floor();
         ChunkPosition(I64Vec2::new(chunk.x as i64, chunk.z as i64))
@@ -71,42 +75,15 @@ impl Iterator for ChunkIterator {
     }
 }
 
+/// Position of a block inside a chunk, with the `y` of the world
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct BlockPosition {
     pub x: u8,
-    pub y: i64,
+    pub y: i32,
     pub z: u8,
 }
 
 impl BlockPosition {
-    /// Index of block inside the chunk array based off this size
-    /// These are local coordinates to the chunk
-    pub const fn to_index(self, size: ChunkSize) -> usize {
-        let mut y = self.y.abs() * 2;
-        let y_sign = self.y.signum();
-        y -= (y_sign.abs() - y_sign) / 2;
-
-        let size = size.as_usize();
-        y as usize * size.pow(2) + self.x as usize * size + self.z as usize
-    }
-
-    /// Constructs the x,y,z position from the index in the chunk array
-    pub fn from_index(mut index: usize, size: ChunkSize) -> Self {
-        let size = size.as_usize();
-        let d = size.pow(2);
-        let y = index / d;
-        let j = y / 2;
-        let k = (y % 2) as i64;
-        let y = j as i64 * (1 - 2 * k);
-        index %= d;
-
-        let x = (index / size) as u8;
-        index %= size;
-
-        let z = index as u8;
-        BlockPosition { x, y, z }
-    }
-
     pub const fn in_bounds(self, size: ChunkSize) -> BVec2 {
         let size = size.as_usize();
         BVec2::new((self.x as usize) < size, (self.z as usize) < size)
@@ -146,8 +123,8 @@ impl BlockPosition {
     }
 }
 
-impl From<(u8, i64, u8)> for BlockPosition {
-    fn from((x, y, z): (u8, i64, u8)) -> Self {
+impl From<(u8, i32, u8)> for BlockPosition {
+    fn from((x, y, z): (u8, i32, u8)) -> Self {
         BlockPosition { x, y, z }
     }
 }
@@ -158,7 +135,7 @@ impl Add<u8> for BlockPosition {
     fn add(self, rhs: u8) -> Self::Output {
         BlockPosition {
             x: self.x + rhs,
-            y: self.y + rhs as i64,
+            y: self.y + rhs as i32,
             z: self.z + rhs,
         }
     }
@@ -166,30 +143,7 @@ impl Add<u8> for BlockPosition {
 
 #[cfg(test)]
 mod tests {
-    use crate::chunk::math::{pos::BlockPosition, size::ChunkSize};
-
-    #[test]
-    fn test_to_index() {
-        let size = ChunkSize::new(16);
-        let pos = BlockPosition { x: 0, y: 3, z: 0 };
-
-        let idx = pos.to_index(size);
-        assert_eq!(idx, 6 * 16 * 16);
-        assert_eq!(BlockPosition::from_index(idx, size), pos);
-
-        let pos = BlockPosition { x: 0, y: -10, z: 0 };
-        let idx = pos.to_index(size);
-        assert_eq!(idx, 19 * 16 * 16);
-
-        let pos = BlockPosition { x: 0, y: 10, z: 0 };
-        let idx = pos.to_index(size);
-        assert_eq!(idx, 20 * 16 * 16);
-        assert_eq!(pos, BlockPosition::from_index(idx, size));
-
-        let pos = BlockPosition { x: 4, y: 8, z: 4 };
-        let idx = pos.to_index(size);
-        assert_eq!(pos, BlockPosition::from_index(idx, size));
-    }
+    use crate::chunk::math::pos::BlockPosition;
 
     #[test]
     fn test_direction() {
@@ -1,5 +1,5 @@
 use bevy::prelude::*;
-
+use mcre_core::BlockPos;
 use serde::{Deserialize, Serialize};
 
 use crate::chunk::math::pos::ChunkPosition;
@@ -32,4 +32,13 @@ impl ChunkSize {
     pub fn chunk_coord(self, world_coord: Vec3) -> ChunkPosition {
         ChunkPosition::into_coords(world_coord / (self.0 as f32))
     }
+
+    /// Position of the chunk containing the block at `pos`
+    pub const fn block_chunk(self, pos: BlockPos) -> ChunkPosition {
+        let size = self.0 as i64;
+        ChunkPosition::new(
+            (pos.x as i64).div_euclid(size),
+            (pos.z as i64).div_euclid(size),
+        )
+    }
 }
@@ -1,98 +1,197 @@
 use bevy::{
     asset::RenderAssetUsages,
-    color::palettes::css::{GREEN, WHITE},
+    color::palettes::css::WHITE,
     mesh::{Indices, PrimitiveTopology},
     prelude::*,
 };
-use mcre_core::{Block, BlockState};
+use mcre_core::{Block, BlockState, Direction, LevelHeightAccessor};
 
 use crate::{
-    chunk::{Chunk, math::pos::BlockPosition},
-    textures::BlockTextures,
+    chunk::{
+        Chunk,
+        greedy::{FaceStyle, GreedyFaces},
+        math::pos::{BlockPosition, ChunkPosition},
+        section::SECTION_HEIGHT,
+    },
+    models::{BlockModels, ModelQuad},
 };
 
 pub struct ChunkMeshBuilder<'a> {
     chunk: &'a Chunk,
+    neighbors: Neighbors<'a>,
+    greedy: bool,
+}
+
+/// The chunks around a meshed chunk, to cull the faces on its borders. Faces next to a chunk
+/// that isn't loaded are kept.
+#[derive(Clone, Copy, Default)]
+pub struct Neighbors<'a> {
+    pub north: Option<&'a Chunk>,
+    pub south: Option<&'a Chunk>,
+    pub west: Option<&'a Chunk>,
+    pub east: Option<&'a Chunk>,
+}
+
+impl<'a> Neighbors<'a> {
+    /// Positions of the chunks around `loc`
+    pub fn positions(loc: ChunkPosition) -> [ChunkPosition; 4] {
+        [
+            ChunkPosition::new(loc.x, loc.y - 1),
+            ChunkPosition::new(loc.x, loc.y + 1),
+            ChunkPosition::new(loc.x - 1, loc.y),
+            ChunkPosition::new(loc.x + 1, loc.y),
+        ]
+    }
+
+    /// The chunks around `loc`, looked up with `get`
+    pub fn new(
+        loc: ChunkPosition,
+        mut get: impl FnMut(ChunkPosition) -> Option<&'a Chunk>,
+    ) -> Self {
+        let [north, south, west, east] = Self::positions(loc).map(&mut get);
+        Neighbors {
+            north,
+            south,
+            west,
+            east,
+        }
+    }
 }
 
 impl<'a> ChunkMeshBuilder<'a> {
-    pub fn new(chunk: &'a Chunk) -> Self {
-        ChunkMeshBuilder { chunk }
+    pub fn new(chunk: &'a Chunk, neighbors: Neighbors<'a>) -> Self {
+        ChunkMeshBuilder {
+            chunk,
+            neighbors,
+            greedy: true,
+        }
+    }
+
+    /// Whether the faces of full opaque cubes are merged into larger quads, on by default. Other
+    /// blocks always get the quads of their model.
+    pub fn greedy(mut self, greedy: bool) -> Self {
+        self.greedy = greedy;
+        self
     }
 
-    pub fn update_mesh(&self, mesh: &mut Mesh, textures: &BlockTextures) {
+    pub fn update_mesh(&self, mesh: &mut Mesh, models: &BlockModels) {
         let mut builder = MeshBuilder::default();
-        self.update_mesh_attributes(&mut builder, textures);
+        self.update_mesh_attributes(&mut builder, models);
         //TODO Optimize in place?
         mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, builder.vertices);
         mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, builder.uvs);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_UV_1, builder.layers);
         mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, builder.normals);
         mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, builder.vert_colors);
         mesh.insert_indices(Indices::U32(builder.indices));
     }
 
-    fn cull_faces(&self, pos: BlockPosition) -> (BVec3, BVec3) {
-        fn check_occude(block: BlockState) -> bool {
-            block.is_air() || !block.can_occlude()
+    /// Whether the face of the block at `pos` towards each direction is visible, by direction.
+    /// Only full opaque cubes hide the faces next to them, as the shapes of blocks aren't known.
+    fn visible_faces(&self, pos: BlockPosition) -> [bool; 6] {
+        fn check_occude(block: Option<BlockState>) -> bool {
+            block.is_none_or(|block| !block.solid_render())
         }
 
-        let bounds = (pos + 1).in_bounds(*self.chunk.size());
-        let positive_faces = BVec3::new(
-            !bounds.x || self.chunk.get(pos.east()).is_none_or(check_occude),
-            self.chunk.get(pos.up()).is_none_or(check_occude),
-            !bounds.y || self.chunk.get(pos.south()).is_none_or(check_occude),
-        );
+        // the block next to `pos`, in `neighbor` at `wrapped` when `pos` is on that border
+        let side = |on_border: bool,
+                    neighbor: Option<&Chunk>,
+                    wrapped: BlockPosition,
+                    step: fn(BlockPosition) -> BlockPosition| match on_border {
+            true => neighbor.and_then(|chunk| chunk.get(wrapped)),
+            false => self.chunk.get(step(pos)),
+        };
+        let last = self.chunk.size().as_u8() - 1;
 
-        let negative_faces = BVec3::new(
-            pos.x < 1 || self.chunk.get(pos.west()).is_none_or(check_occude),
-            self.chunk.get(pos.down()).is_none_or(check_occude),
-            pos.z < 1 || self.chunk.get(pos.north()).is_none_or(check_occude),
-        );
-        (positive_faces, negative_faces)
+        let mut faces = [false; 6];
+        faces[Direction::Down as usize] = check_occude(self.chunk.get(pos.down()));
+        faces[Direction::Up as usize] = check_occude(self.chunk.get(pos.up()));
+        faces[Direction::North as usize] = check_occude(side(
+            pos.z == 0,
+            self.neighbors.north,
+            BlockPosition { z: last, ..pos },
+            BlockPosition::north,
+        ));
+        faces[Direction::South as usize] = check_occude(side(
+            pos.z == last,
+            self.neighbors.south,
+            BlockPosition { z: 0, ..pos },
+            BlockPosition::south,
+        ));
+        faces[Direction::West as usize] = check_occude(side(
+            pos.x == 0,
+            self.neighbors.west,
+            BlockPosition { x: last, ..pos },
+            BlockPosition::west,
+        ));
+        faces[Direction::East as usize] = check_occude(side(
+            pos.x == last,
+            self.neighbors.east,
+            BlockPosition { x: 0, ..pos },
+            BlockPosition::east,
+        ));
+        faces
     }
 
-    fn update_mesh_attributes(&self, builder: &mut MeshBuilder, textures: &BlockTextures) {
-        let chunk_size = self.chunk.size();
-        for (i, block) in self.chunk.iter() {
+    fn update_mesh_attributes(&self, builder: &mut MeshBuilder, models: &BlockModels) {
+        let mut greedy = GreedyFaces::new(self.chunk.size().as_usize());
+        for (cur, block) in self.chunk.iter() {
             if block.is_air() {
                 continue;
             }
-            let Some(uv_rect) = textures.get_uv_rect(*block) else {
+            let parts = models.parts(block);
+            if parts.is_empty() {
                 continue;
-            };
-            let cur = BlockPosition::from_index(i, *chunk_size);
-            //TODO: Fix to use known data about block states
-            let block_color = match block.block() {
-                Block::OAK_LEAVES => GREEN,
-                _ => WHITE,
-            };
-
-            let (positive, negative) = self.cull_faces(cur);
-
-            if positive.x {
-                builder.push_east(cur, uv_rect, block_color);
             }
-            if positive.y {
-                builder.push_up(cur, uv_rect, block_color);
-            }
-            if positive.z {
-                builder.push_south(cur, uv_rect, block_color);
-            }
-            if negative.x {
-                builder.push_west(cur, uv_rect, block_color);
-            }
-            if negative.y {
-                builder.push_down(cur, uv_rect, block_color);
+            let visible = self.visible_faces(cur);
+            let world_pos = self.chunk.world_pos(cur);
+            let (x, y, z) = block.offset_type().offset(
+                world_pos,
+                block.max_horizontal_offset(),
+                block.max_vertical_offset(),
+            );
+            let origin = Vec3::new(cur.x as f32, cur.y as f32, cur.z as f32)
+                + Vec3::new(x as f32, y as f32, z as f32);
+            let tint = tint(block.block());
+            let merge = self.greedy && block.solid_render();
+            let section_y = (cur.y - self.chunk.min_y()) as usize % SECTION_HEIGHT;
+
+            let seed = world_pos.seed();
+            let quads = parts
+                .iter()
+                .filter_map(|variants| variants.pick(seed))
+                .flatten()
+                .filter(|quad| {
+                    quad.cullface
+                        .is_none_or(|cullface| visible[cullface as usize])
+                });
+            // A greedy face holds a single style, so sides with several full faces like the
+            // overlay of grass blocks are drawn quad by quad
+            let mut full_faces = [0; 6];
+            for direction in quads.clone().filter_map(|quad| quad.full_face) {
+                full_faces[direction as usize] += 1;
             }
-            if negative.z {
-                builder.push_north(cur, uv_rect, block_color);
+            for quad in quads {
+                let color = if quad.tinted { tint } else { WHITE };
+                match quad.full_face {
+                    Some(direction) if merge && full_faces[direction as usize] == 1 => {
+                        let pos = [cur.x as usize, section_y, cur.z as usize];
+                        let style = FaceStyle {
+                            layer: quad.layer,
+                            tint: color,
+                        };
+                        greedy.insert(builder, cur.y - section_y as i32, pos, direction, style);
+                    }
+                    _ => builder.push_quad(origin, quad, color),
+                }
             }
         }
+        greedy.flush(builder);
     }
 
-    pub fn build(self, textures: &BlockTextures) -> Mesh {
+    pub fn build(self, models: &BlockModels) -> Mesh {
         let mut builder = MeshBuilder::default();
-        self.update_mesh_attributes(&mut builder, textures);
+        self.update_mesh_attributes(&mut builder, models);
 
         Mesh::new(
             PrimitiveTopology::TriangleList,
@@ -100,17 +199,34 @@ impl<'a> ChunkMeshBuilder<'a> {
         )
         .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, builder.vertices)
         .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, builder.uvs)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_UV_1, builder.layers)
         .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, builder.normals)
         .with_inserted_attribute(Mesh::ATTRIBUTE_COLOR, builder.vert_colors)
         .with_inserted_indices(Indices::U32(builder.indices))
     }
 }
 
+/// Color of the tinted faces of `block`
+//TODO: Use the colors of the biome
+fn tint(block: Block) -> Srgba {
+    match block {
+        Block::SPRUCE_LEAVES => Srgba::rgb_u8(0x61, 0x99, 0x61),
+        Block::BIRCH_LEAVES => Srgba::rgb_u8(0x80, 0xa7, 0x55),
+        _ if block.name().ends_with("_leaves") || block == Block::VINE => {
+            Srgba::rgb_u8(0x77, 0xab, 0x2f)
+        }
+        _ => Srgba::rgb_u8(0x91, 0xbd, 0x59),
+    }
+}
+
 #[derive(Default)]
-struct MeshBuilder {
+pub struct MeshBuilder {
     vertices: Vec<[f32; 3]>,
     normals: Vec<[f32; 3]>,
+    /// Position on the texture, repeating once per block on merged faces
     uvs: Vec<[f32; 2]>,
+    /// Texture array layer of the face, in `x`
+    layers: Vec<[f32; 2]>,
     indices: Vec<u32>,
     vert_colors: Vec<[f32; 4]>,
 }
@@ -118,89 +234,82 @@ struct MeshBuilder {
 // -Z is North, +Z is South
 // -X is West, +X is East
 impl MeshBuilder {
-    fn push_north(&mut self, cur: BlockPosition, uv: Rect, face_color: Srgba) {
-        self.push_indices();
-        self.push_face_color(face_color);
-        let (x, y, z) = (cur.x as f32, cur.y as f32, cur.z as f32);
-        let normal = [0., 0., -1.];
-        self.push([x + 1., y + 1., z + 0.], normal, [uv.min.x, uv.min.y]);
-        self.push([x + 0., y + 1., z + 0.], normal, [uv.max.x, uv.min.y]);
-        self.push([x + 0., y + 0., z + 0.], normal, [uv.max.x, uv.max.y]);
-        self.push([x + 1., y + 0., z + 0.], normal, [uv.min.x, uv.max.y]);
-    }
-
-    fn push_east(&mut self, cur: BlockPosition, uv: Rect, face_color: Srgba) {
+    /// Pushes the `direction` face of the box of blocks from `min` to `min + size`, with the
+    /// texture upright like the full faces of block models
+    pub fn push_face(
+        &mut self,
+        direction: Direction,
+        min: Vec3,
+        size: Vec3,
+        layer: u32,
+        face_color: Srgba,
+    ) {
+        let ([x0, y0, z0], [x1, y1, z1]) = (min.to_array(), (min + size).to_array());
+        let Vec3 { x: w, y: h, z: d } = size;
+        // counter-clockwise from the top left of the texture
+        let (corners, uvs) = match direction {
+            Direction::North => (
+                [[x1, y1, z0], [x1, y0, z0], [x0, y0, z0], [x0, y1, z0]],
+                [[0., 0.], [0., h], [w, h], [w, 0.]],
+            ),
+            Direction::East => (
+                [[x1, y1, z1], [x1, y0, z1], [x1, y0, z0], [x1, y1, z0]],
+                [[0., 0.], [0., h], [d, h], [d, 0.]],
+            ),
+            Direction::South => (
+                [[x0, y1, z1], [x0, y0, z1], [x1, y0, z1], [x1, y1, z1]],
+                [[0., 0.], [0., h], [w, h], [w, 0.]],
+            ),
+            Direction::West => (
+                [[x0, y1, z0], [x0, y0, z0], [x0, y0, z1], [x0, y1, z1]],
+                [[0., 0.], [0., h], [d, h], [d, 0.]],
+            ),
+            Direction::Up => (
+                [[x0, y1, z0], [x0, y1, z1], [x1, y1, z1], [x1, y1, z0]],
+                [[0., 0.], [0., d], [w, d], [w, 0.]],
+            ),
+            Direction::Down => (
+                [[x0, y0, z1], [x0, y0, z0], [x1, y0, z0], [x1, y0, z1]],
+                [[0., 0.], [0., d], [w, d], [w, 0.]],
+            ),
+        };
+        let normal = match direction {
+            Direction::Down => Vec3::NEG_Y,
+            Direction::Up => Vec3::Y,
+            Direction::North => Vec3::NEG_Z,
+            Direction::South => Vec3::Z,
+            Direction::West => Vec3::NEG_X,
+            Direction::East => Vec3::X,
+        };
         self.push_indices();
         self.push_face_color(face_color);
-        let (x, y, z) = (cur.x as f32, cur.y as f32, cur.z as f32);
-        let normal = [-1., 0., 0.];
-        self.push([x + 1., y + 0., z + 0.], normal, [uv.min.x, uv.max.y]);
-        self.push([x + 1., y + 0., z + 1.], normal, [uv.max.x, uv.max.y]);
-        self.push([x + 1., y + 1., z + 1.], normal, [uv.max.x, uv.min.y]);
-        self.push([x + 1., y + 1., z + 0.], normal, [uv.min.x, uv.min.y]);
-    }
-
-    fn push_south(&mut self, cur: BlockPosition, uv: Rect, face_color: Srgba) {
-        self.push_indices();
-        self.push_face_color(face_color);
-        let (x, y, z) = (cur.x as f32, cur.y as f32, cur.z as f32);
-        let normal = [0., 0., 1.];
-        self.push([x + 0., y + 0., z + 1.], normal, [uv.max.x, uv.max.y]);
-        self.push([x + 0., y + 1., z + 1.], normal, [uv.max.x, uv.min.y]);
-        self.push([x + 1., y + 1., z + 1.], normal, [uv.min.x, uv.min.y]);
-        self.push([x + 1., y + 0., z + 1.], normal, [uv.min.x, uv.max.y]);
-    }
-
-    fn push_west(&mut self, cur: BlockPosition, uv: Rect, face_color: Srgba) {
-        self.push_indices();
-        self.push_face_color(face_color);
-        let (x, y, z) = (cur.x as f32, cur.y as f32, cur.z as f32);
-        let normal = [1., 0., 0.];
-        self.push([x + 0., y + 1., z + 1.], normal, [uv.max.x, uv.min.y]);
-        self.push([x + 0., y + 0., z + 1.], normal, [uv.max.x, uv.max.y]);
-        self.push([x + 0., y + 0., z + 0.], normal, [uv.min.x, uv.max.y]);
-        self.push([x + 0., y + 1., z + 0.], normal, [uv.min.x, uv.min.y]);
-    }
-
-    fn push_up(&mut self, cur: BlockPosition, uv: Rect, face_color: Srgba) {
-        self.push_indices();
-        self.push_face_color(face_color);
-        let (x, y, z) = (cur.x as f32, cur.y as f32, cur.z as f32);
-        let normal = [0., 1., 0.];
-        self.push([x + 0., y + 1., z + 0.], normal, [uv.min.x, uv.max.y]);
-        self.push([x + 1., y + 1., z + 0.], normal, [uv.min.x, uv.min.y]);
-        self.push([x + 1., y + 1., z + 1.], normal, [uv.max.x, uv.min.y]);
-        self.push([x + 0., y + 1., z + 1.], normal, [uv.max.x, uv.max.y]);
+        for (corner, uv) in corners.into_iter().zip(uvs) {
+            self.push(corner, normal.to_array(), uv, layer);
+        }
     }
 
-    fn push_down(&mut self, cur: BlockPosition, uv: Rect, face_color: Srgba) {
+    /// Pushes a quad of a block model placed at `origin`
+    pub fn push_quad(&mut self, origin: Vec3, quad: &ModelQuad, face_color: Srgba) {
         self.push_indices();
         self.push_face_color(face_color);
-        let (x, y, z) = (cur.x as f32, cur.y as f32, cur.z as f32);
-        let normal = [0., -1., 0.];
-        self.push([x + 1., y + 0., z + 0.], normal, [uv.min.x, uv.min.y]);
-        self.push([x + 0., y + 0., z + 0.], normal, [uv.min.x, uv.max.y]);
-        self.push([x + 0., y + 0., z + 1.], normal, [uv.max.x, uv.max.y]);
-        self.push([x + 1., y + 0., z + 1.], normal, [uv.max.x, uv.min.y]);
+        for (vertex, uv) in quad.vertices.into_iter().zip(quad.uvs) {
+            let vertex = (origin + vertex).to_array();
+            self.push(vertex, quad.normal.to_array(), uv.to_array(), quad.layer);
+        }
     }
 
+    /// Indices of the next quad, its corners counter-clockwise
     fn push_indices(&mut self) {
         let vertex_count = self.vertices.len() as u32;
-
-        // 0, 3, 1, 1, 3, 2, // triangles making up the top (+y) facing side.
-        self.indices.push(vertex_count);
-        self.indices.push(vertex_count + 3);
-        self.indices.push(vertex_count + 1);
-
-        self.indices.push(vertex_count + 1);
-        self.indices.push(vertex_count + 3);
-        self.indices.push(vertex_count + 2);
+        self.indices
+            .extend([0, 1, 2, 0, 2, 3].map(|i| vertex_count + i));
     }
 
-    fn push(&mut self, vertex: [f32; 3], normal: [f32; 3], uv: [f32; 2]) {
+    fn push(&mut self, vertex: [f32; 3], normal: [f32; 3], uv: [f32; 2], layer: u32) {
         self.vertices.push(vertex);
         self.normals.push(normal);
         self.uvs.push(uv);
+        self.layers.push([layer as f32, 0.]);
     }
 
     fn push_face_color(&mut self, face_color: Srgba) {
@@ -215,3 +324,377 @@ impl MeshBuilder {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        mesh::{MeshVertexAttributeId, VertexAttributeValues},
+        platform::collections::HashMap,
+        prelude::*,
+    };
+    use mcre_assets::{BlockModelDefinition, BlockStateDefinition};
+    use mcre_core::{Block, PropVal};
+    use mcre_worldgen::DimensionType;
+
+    use crate::{
+        chunk::{
+            Chunk,
+            math::{pos::ChunkPosition, size::ChunkSize},
+            mesh::{ChunkMeshBuilder, Neighbors},
+        },
+        models::{BakedModels, BlockModels},
+    };
+
+    /// A cube of `#all` culled by its neighbors
+    const CUBE: &str = r##"{
+        "elements": [{
+            "from": [0, 0, 0],
+            "to": [16, 16, 16],
+            "faces": {
+                "down": { "texture": "#all", "cullface": "down" },
+                "up": { "texture": "#all", "cullface": "up" },
+                "north": { "texture": "#all", "cullface": "north" },
+                "south": { "texture": "#all", "cullface": "south" },
+                "west": { "texture": "#all", "cullface": "west" },
+                "east": { "texture": "#all", "cullface": "east" }
+            }
+        }]
+    }"##;
+
+    /// Models of a few blocks, each texture in its own layer
+    fn models() -> BlockModels {
+        let blockstates = [
+            (
+                Block::STONE,
+                r#"{ "variants": { "": { "model": "block/stone" } } }"#,
+            ),
+            (
+                Block::DIRT,
+                r#"{ "variants": { "": { "model": "block/dirt" } } }"#,
+            ),
+            (
+                Block::GLASS,
+                r#"{ "variants": { "": { "model": "block/glass" } } }"#,
+            ),
+            (
+                Block::SMOOTH_STONE_SLAB,
+                r#"{ "variants": { "type=bottom": { "model": "block/slab" } } }"#,
+            ),
+            // the same model facing north or east
+            (
+                Block::PUMPKIN,
+                r#"{ "variants": { "": [
+                    { "model": "block/pumpkin" },
+                    { "model": "block/pumpkin", "y": 90 }
+                ] } }"#,
+            ),
+            (
+                Block::OAK_FENCE,
+                r#"{ "multipart": [
+                    { "apply": { "model": "block/pumpkin" } },
+                    { "when": { "north": "true" }, "apply": { "model": "block/stone" } }
+                ] }"#,
+            ),
+            (
+                Block::POPPY,
+                r#"{ "variants": { "": { "model": "block/pumpkin" } } }"#,
+            ),
+            (
+                Block::GRASS_BLOCK,
+                r#"{ "variants": { "snowy=false": { "model": "block/grass_block" } } }"#,
+            ),
+        ];
+        let models = [
+            ("cube", CUBE),
+            (
+                "stone",
+                r#"{ "parent": "block/cube", "textures": { "all": "block/stone" } }"#,
+            ),
+            (
+                "dirt",
+                r#"{ "parent": "block/cube", "textures": { "all": "block/dirt" } }"#,
+            ),
+            (
+                "glass",
+                r#"{ "parent": "block/cube", "textures": { "all": "block/glass" } }"#,
+            ),
+            (
+                "slab",
+                r##"{
+                    "textures": { "all": "block/stone" },
+                    "elements": [{
+                        "from": [0, 0, 0],
+                        "to": [16, 8, 16],
+                        "faces": {
+                            "down": { "texture": "#all", "cullface": "down" },
+                            "up": { "texture": "#all" },
+                            "north": { "texture": "#all", "cullface": "north" },
+                            "south": { "texture": "#all", "cullface": "south" },
+                            "west": { "texture": "#all", "cullface": "west" },
+                            "east": { "texture": "#all", "cullface": "east" }
+                        }
+                    }]
+                }"##,
+            ),
+            // a single face looking north from the middle of the block
+            (
+                "pumpkin",
+                r##"{
+                    "textures": { "face": "block/dirt" },
+                    "elements": [{
+                        "from": [0, 0, 8],
+                        "to": [16, 16, 8],
+                        "faces": { "north": { "texture": "#face" } }
+                    }]
+                }"##,
+            ),
+            // a cube with a tinted overlay over each side, like grass blocks
+            (
+                "grass_block",
+                r##"{
+                    "textures": { "top": "block/stone", "side": "block/dirt", "overlay": "block/glass" },
+                    "elements": [
+                        {
+                            "from": [0, 0, 0],
+                            "to": [16, 16, 16],
+                            "faces": {
+                                "down": { "texture": "#side", "cullface": "down" },
+                                "up": { "texture": "#top", "cullface": "up", "tintindex": 0 },
+                                "north": { "texture": "#side", "cullface": "north" },
+                                "south": { "texture": "#side", "cullface": "south" },
+                                "west": { "texture": "#side", "cullface": "west" },
+                                "east": { "texture": "#side", "cullface": "east" }
+                            }
+                        },
+                        {
+                            "from": [0, 0, 0],
+                            "to": [16, 16, 16],
+                            "faces": {
+                                "north": { "texture": "#overlay", "cullface": "north", "tintindex": 0 },
+                                "south": { "texture": "#overlay", "cullface": "south", "tintindex": 0 },
+                                "west": { "texture": "#overlay", "cullface": "west", "tintindex": 0 },
+                                "east": { "texture": "#overlay", "cullface": "east", "tintindex": 0 }
+                            }
+                        }
+                    ]
+                }"##,
+            ),
+        ];
+
+        let blockstates = blockstates
+            .map(|(block, json)| {
+                (
+                    block,
+                    serde_json::from_str::<BlockStateDefinition>(json).unwrap(),
+                )
+            })
+            .into_iter()
+            .collect::<HashMap<_, _>>();
+        let models = models
+            .map(|(id, json)| {
+                let model = serde_json::from_str::<BlockModelDefinition>(json).unwrap();
+                (format!("minecraft:block/{id}"), model)
+            })
+            .into_iter()
+            .collect::<HashMap<_, _>>();
+        let layers = ["stone", "dirt", "glass"]
+            .into_iter()
+            .enumerate()
+            .map(|(layer, name)| (format!("minecraft/textures/block/{name}.png"), layer as u32))
+            .collect();
+        BakedModels::new(|block| blockstates.get(&block), |id| models.get(id)).resolve(&layers)
+    }
+
+    fn empty(x: i64, z: i64) -> Chunk {
+        Chunk::empty(
+            ChunkSize::new(16),
+            &DimensionType::overworld(),
+            ChunkPosition::new(x, z),
+        )
+    }
+
+    /// A chunk with a layer of stone at y 0
+    fn layer(x: i64, z: i64) -> Chunk {
+        let mut chunk = empty(x, z);
+        for x in 0..16 {
+            for z in 0..16 {
+                chunk.set((x, 0, z), Block::STONE).unwrap();
+            }
+        }
+        chunk
+    }
+
+    fn faces(builder: ChunkMeshBuilder) -> usize {
+        builder.build(&models()).count_vertices() / 4
+    }
+
+    /// Quads of the greedy mesh of `chunk` without neighbors
+    fn quads(chunk: &Chunk) -> usize {
+        faces(ChunkMeshBuilder::new(chunk, Neighbors::default()))
+    }
+
+    fn attribute(mesh: &Mesh, attribute: impl Into<MeshVertexAttributeId>) -> &Vec<[f32; 3]> {
+        let Some(VertexAttributeValues::Float32x3(values)) = mesh.attribute(attribute) else {
+            panic!("the mesh has the attribute");
+        };
+        values
+    }
+
+    #[test]
+    fn test_cull_borders() {
+        let chunk = layer(0, 0);
+        let (north, east) = (layer(0, -1), layer(1, 0));
+
+        // the top and bottom of every block, and the sides of the layer
+        let alone = ChunkMeshBuilder::new(&chunk, Neighbors::default()).greedy(false);
+        assert_eq!(faces(alone), 2 * 256 + 4 * 16);
+
+        let neighbors = Neighbors::new(chunk.loc, |pos| {
+            [&north, &east].into_iter().find(|chunk| chunk.loc == pos)
+        });
+        assert!(neighbors.south.is_none() && neighbors.west.is_none());
+        let surrounded = ChunkMeshBuilder::new(&chunk, neighbors).greedy(false);
+        assert_eq!(faces(surrounded), 2 * 256 + 2 * 16);
+
+        // a quad per side of the layer
+        assert_eq!(quads(&chunk), 6);
+        assert_eq!(faces(ChunkMeshBuilder::new(&chunk, neighbors)), 4);
+    }
+
+    #[test]
+    fn test_greedy_textures() {
+        // stone and dirt halves only merge with themselves
+        let mut chunk = layer(0, 0);
+        for x in 8..16 {
+            for z in 0..16 {
+                chunk.set((x, 0, z), Block::DIRT).unwrap();
+            }
+        }
+        assert_eq!(quads(&chunk), 10);
+
+        // the texture repeats once per block across the top of the layer
+        let mesh = ChunkMeshBuilder::new(&layer(0, 0), Neighbors::default()).build(&models());
+        let Some(VertexAttributeValues::Float32x2(uvs)) = mesh.attribute(Mesh::ATTRIBUTE_UV_0)
+        else {
+            panic!("the mesh has uvs");
+        };
+        assert!(uvs.contains(&[16., 16.]));
+    }
+
+    #[test]
+    fn test_greedy_sections() {
+        // a cube of 16 blocks merges into a quad per side, split where it crosses sections
+        let mut chunk = empty(0, 0);
+        for x in 0..16 {
+            for y in 0..16 {
+                for z in 0..16 {
+                    chunk.set((x, y, z), Block::STONE).unwrap();
+                }
+            }
+        }
+        assert_eq!(quads(&chunk), 6);
+
+        for x in 0..16 {
+            for z in 0..16 {
+                chunk.set((x, 0, z), Block::AIR).unwrap();
+                chunk.set((x, 16, z), Block::STONE).unwrap();
+            }
+        }
+        assert_eq!(quads(&chunk), 2 + 4 * 2);
+    }
+
+    #[test]
+    fn test_greedy_skips_non_cubes() {
+        // glass isn't a full opaque cube, every face is kept
+        let mut chunk = empty(0, 0);
+        for x in 0..4 {
+            chunk.set((x, 0, 0), Block::GLASS).unwrap();
+        }
+        assert_eq!(quads(&chunk), 4 * 6);
+    }
+
+    #[test]
+    fn test_model_quads() {
+        // a bottom slab keeps its top against the stone above, and doesn't hide its bottom
+        let mut chunk = empty(0, 0);
+        chunk.set((0, 0, 0), Block::SMOOTH_STONE_SLAB).unwrap();
+        let mesh = ChunkMeshBuilder::new(&chunk, Neighbors::default()).build(&models());
+        assert_eq!(mesh.count_vertices() / 4, 6);
+        assert!(
+            attribute(&mesh, Mesh::ATTRIBUTE_POSITION)
+                .iter()
+                .all(|[_, y, _]| *y <= 0.5)
+        );
+
+        chunk.set((0, 1, 0), Block::STONE).unwrap();
+        assert_eq!(quads(&chunk), 6 + 6);
+
+        // states without a model are skipped
+        chunk
+            .set((2, 0, 0), Block::SMOOTH_STONE_SLAB.max_state())
+            .unwrap();
+        assert_eq!(quads(&chunk), 6 + 6);
+    }
+
+    #[test]
+    fn test_model_overlays() {
+        // the tops and bottoms of a row of grass merge, each side keeps its face and overlay
+        let mut chunk = empty(0, 0);
+        for x in 0..2 {
+            chunk.set((x, 0, 0), Block::GRASS_BLOCK).unwrap();
+        }
+        let mesh = ChunkMeshBuilder::new(&chunk, Neighbors::default()).build(&models());
+        assert_eq!(mesh.count_vertices() / 4, 2 + 2 * 2 * 2 + 2 * 2);
+        let Some(VertexAttributeValues::Float32x2(layers)) = mesh.attribute(Mesh::ATTRIBUTE_UV_1)
+        else {
+            panic!("the mesh has layers");
+        };
+        let sides = |layer: f32| layers.iter().filter(|[l, _]| *l == layer).count() / 4;
+        assert_eq!((sides(1.), sides(2.)), (1 + 6, 6));
+    }
+
+    #[test]
+    fn test_model_variants() {
+        // the variant of each pumpkin only depends on its position, and both are used
+        let mut chunk = empty(0, 0);
+        for x in 0..16 {
+            chunk.set((x, 0, 0), Block::PUMPKIN).unwrap();
+        }
+        let mesh = ChunkMeshBuilder::new(&chunk, Neighbors::default()).build(&models());
+        let normals = attribute(&mesh, Mesh::ATTRIBUTE_NORMAL);
+        assert!(normals.contains(&[0., 0., -1.]) && normals.contains(&[1., 0., 0.]));
+        let again = ChunkMeshBuilder::new(&chunk, Neighbors::default()).build(&models());
+        assert_eq!(attribute(&again, Mesh::ATTRIBUTE_NORMAL), normals);
+
+        // the parts whose condition holds are all drawn
+        let mut chunk = empty(0, 0);
+        let fence = Block::OAK_FENCE.default_state();
+        chunk.set((0, 0, 0), fence).unwrap();
+        assert_eq!(quads(&chunk), 1);
+        let north = fence.with_prop("north=true".parse::<PropVal>().unwrap());
+        chunk.set((0, 0, 0), north.unwrap()).unwrap();
+        assert_eq!(quads(&chunk), 1 + 6);
+    }
+
+    #[test]
+    fn test_model_offset() {
+        // flowers are moved horizontally by their position
+        let mut chunk = empty(0, 0);
+        chunk.set((3, 0, 5), Block::POPPY).unwrap();
+        let state = Block::POPPY.default_state();
+        let (x, _, z) = state.offset_type().offset(
+            chunk.world_pos((3, 0, 5).into()),
+            state.max_horizontal_offset(),
+            state.max_vertical_offset(),
+        );
+        assert!(x != 0.0 || z != 0.0);
+
+        let mesh = ChunkMeshBuilder::new(&chunk, Neighbors::default()).build(&models());
+        let positions = attribute(&mesh, Mesh::ATTRIBUTE_POSITION);
+        let center = positions.iter().fold(Vec3::ZERO, |sum, position| {
+            sum + Vec3::from_array(*position)
+        }) / positions.len() as f32;
+        let expected = Vec3::new(3.5 + x as f32, 0.5, 5.5 + z as f32);
+        assert!(center.abs_diff_eq(expected, 1e-5), "{center} != {expected}");
+    }
+}
@@ -1,33 +1,50 @@
 pub mod asset;
-mod generate;
+pub mod generate;
+mod greedy;
+mod job;
 pub mod loader;
 pub mod math;
 pub mod mesh;
+mod palette;
+mod section;
 
 use bevy::prelude::*;
-use mcre_core::BlockState;
+use mcre_core::{Block, BlockGetter, BlockPos, BlockState, LevelHeightAccessor, RandomSource};
+use mcre_worldgen::DimensionType;
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 
-use crate::{
-    chunk::math::{
+use crate::chunk::{
+    math::{
         pos::{BlockPosition, ChunkPosition},
         size::ChunkSize,
     },
-    utils::sparse::SparseVec,
+    section::{SECTION_HEIGHT, Section},
 };
 
+/// A column of blocks spanning the height of its dimension
 #[derive(Asset, Clone, Debug, TypePath, Deserialize, Serialize)]
 pub struct Chunk {
     pub loc: ChunkPosition,
-    pub blocks: SparseVec<BlockState>,
+    min_y: i32,
+    /// Sections from the bottom up, each 16 blocks high
+    sections: Vec<Section>,
     chunk_size: ChunkSize,
 }
 
 impl Chunk {
-    pub fn empty<P: Into<ChunkPosition>>(chunk_size: ChunkSize, loc: P) -> Self {
+    pub fn empty<P: Into<ChunkPosition>>(
+        chunk_size: ChunkSize,
+        dimension: &DimensionType,
+        loc: P,
+    ) -> Self {
+        let len = chunk_size.as_usize().pow(2) * SECTION_HEIGHT;
         Chunk {
             loc: loc.into(),
-            blocks: SparseVec::empty(),
+            min_y: dimension.min_y,
+            sections: (0..dimension.section_count())
+                .map(|_| Section::empty(len))
+                .collect(),
             chunk_size,
         }
     }
@@ -36,57 +53,225 @@ impl Chunk {
         &self.chunk_size
     }
 
-    pub fn iter(&self) -> impl Iterator<Item = (usize, &BlockState)> {
-        self.blocks.iter()
+    /// Position and state of every block other than air, skipping empty sections
+    pub fn iter(&self) -> impl Iterator<Item = (BlockPosition, BlockState)> + '_ {
+        let size = self.chunk_size.as_usize();
+        self.sections
+            .iter()
+            .enumerate()
+            .filter(|(_, section)| !section.is_empty())
+            .flat_map(move |(section_index, section)| {
+                let min_y = self.min_y + (section_index * SECTION_HEIGHT) as i32;
+                section.iter().map(move |(index, state)| {
+                    let pos = BlockPosition {
+                        x: (index % size) as u8,
+                        y: min_y + (index / size.pow(2)) as i32,
+                        z: (index / size % size) as u8,
+                    };
+                    (pos, state)
+                })
+            })
+    }
+
+    /// `count` random world positions in every section holding blocks other than air, the blocks
+    /// getting a random tick
+    pub fn random_tick_positions(
+        &self,
+        count: usize,
+        random: &mut dyn RandomSource,
+    ) -> Vec<BlockPos> {
+        let size = self.chunk_size.as_usize() as i32;
+        let mut positions = Vec::new();
+        for (section_index, section) in self.sections.iter().enumerate() {
+            if section.is_empty() {
+                continue;
+            }
+            let min_y = self.min_y + (section_index * SECTION_HEIGHT) as i32;
+            for _ in 0..count {
+                let pos = BlockPosition {
+                    x: random.next_int_bounded(size) as u8,
+                    y: min_y + random.next_int_bounded(SECTION_HEIGHT as i32),
+                    z: random.next_int_bounded(size) as u8,
+                };
+                positions.push(self.world_pos(pos));
+            }
+        }
+        positions
     }
 
     pub fn transform(&self) -> Transform {
         Transform::from_translation(self.loc.world_coord(self.chunk_size))
     }
 
-    pub fn set<P: Into<BlockPosition>, B: Into<BlockState>>(&mut self, pos: P, new_block: B) {
+    /// Sets the block at `pos`, returning the previous one
+    pub fn set<P: Into<BlockPosition>, B: Into<BlockState>>(
+        &mut self,
+        pos: P,
+        new_block: B,
+    ) -> Result<BlockState, OutOfBounds> {
         let pos = pos.into();
-        let idx = pos.to_index(self.chunk_size);
-        let new_block = new_block.into();
-        if let Some(block) = self.blocks.get_mut(idx) {
-            *block = new_block
-        } else {
-            self.blocks.insert(idx, new_block);
-        }
+        let (section, index) = self.section_index_of(pos).ok_or(OutOfBounds(pos))?;
+        Ok(self.sections[section].set(index, new_block.into()))
     }
 
+    /// Block at `pos`, `None` if it's outside of the chunk
     pub fn get<P: Into<BlockPosition>>(&self, pos: P) -> Option<BlockState> {
-        let index = pos.into().to_index(self.chunk_size);
-        self.blocks.get(index).copied()
+        let (section, index) = self.section_index_of(pos.into())?;
+        Some(self.sections[section].get(index))
     }
-}
 
-impl From<Chunk> for ChunkData {
-    fn from(value: Chunk) -> Self {
-        ChunkData {
-            loc: value.loc,
-            blocks: SparseVec::from(value.blocks),
-            chunk_size: value.chunk_size,
+    /// Whether a block changed since the chunk was last meshed
+    pub fn is_dirty(&self) -> bool {
+        self.sections.iter().any(Section::is_dirty)
+    }
+
+    /// Marks every section to be remeshed, like when a neighboring chunk loads
+    pub fn mark_dirty(&mut self) {
+        self.sections.iter_mut().for_each(Section::mark_dirty);
+    }
+
+    /// Marks the section containing `y` to be remeshed, like when a block next to it changes in a
+    /// neighboring chunk
+    pub fn mark_section_dirty(&mut self, y: i32) {
+        if !self.is_outside_build_height(y) {
+            let section = self.section_index(y);
+            self.sections[section].mark_dirty();
         }
     }
+
+    /// Marks the chunk as meshed
+    pub fn clear_dirty(&mut self) {
+        self.sections.iter_mut().for_each(Section::clear_dirty);
+    }
+
+    /// Section of a block and its index in the section, ordered by y then z then x like vanilla,
+    /// `None` if it's outside of the chunk
+    fn section_index_of(&self, pos: BlockPosition) -> Option<(usize, usize)> {
+        if !pos.in_bounds(self.chunk_size).all() || self.is_outside_build_height(pos.y) {
+            return None;
+        }
+        let size = self.chunk_size.as_usize();
+        let y = (pos.y - self.min_y) as usize % SECTION_HEIGHT;
+        let index = (y * size + pos.z as usize) * size + pos.x as usize;
+        Some((self.section_index(pos.y), index))
+    }
+
+    /// World position of a block of this chunk
+    pub fn world_pos(&self, pos: BlockPosition) -> BlockPos {
+        let size = self.chunk_size.as_usize() as i64;
+        BlockPos::new(
+            (self.loc.x * size + pos.x as i64) as i32,
+            pos.y,
+            (self.loc.y * size + pos.z as i64) as i32,
+        )
+    }
+
+    /// Position inside this chunk of a world position, `None` if it's part of another chunk
+    pub fn local_pos(&self, pos: BlockPos) -> Option<BlockPosition> {
+        (self.chunk_size.block_chunk(pos) == self.loc).then(|| {
+            let size = self.chunk_size.as_usize() as i32;
+            BlockPosition {
+                x: pos.x.rem_euclid(size) as u8,
+                y: pos.y,
+                z: pos.z.rem_euclid(size) as u8,
+            }
+        })
+    }
 }
 
-#[derive(Deserialize, Serialize)]
-struct ChunkData {
-    pub loc: ChunkPosition,
-    pub blocks: SparseVec<u16>,
-    chunk_size: ChunkSize,
+impl LevelHeightAccessor for Chunk {
+    fn min_y(&self) -> i32 {
+        self.min_y
+    }
+
+    fn height(&self) -> u32 {
+        (self.sections.len() * SECTION_HEIGHT) as u32
+    }
 }
 
-impl From<ChunkData> for Chunk {
-    fn from(value: ChunkData) -> Self {
-        Chunk {
-            loc: value.loc,
-            blocks: SparseVec::from(value.blocks),
-            chunk_size: value.chunk_size,
-        }
+impl BlockGetter for Chunk {
+    fn get_block_state(&self, pos: BlockPos) -> BlockState {
+        self.local_pos(pos)
+            .and_then(|pos| self.get(pos))
+            .unwrap_or(Block::AIR.default_state())
     }
 }
 
 #[derive(Component)]
 pub struct ChunkComponent(pub Handle<Chunk>);
+
+#[derive(Error, Debug, PartialEq, Eq)]
+#[error("{0:?} is outside of the chunk")]
+pub struct OutOfBounds(pub BlockPosition);
+
+#[cfg(test)]
+mod tests {
+    use mcre_core::{Block, XoroshiroRandom};
+    use mcre_worldgen::DimensionType;
+
+    use crate::chunk::{
+        Chunk, OutOfBounds,
+        math::{pos::ChunkPosition, size::ChunkSize},
+    };
+
+    #[test]
+    fn test_get_set() {
+        let size = ChunkSize::new(16);
+        let mut chunk = Chunk::empty(size, &DimensionType::overworld(), ChunkPosition::new(0, 0));
+        assert_eq!(chunk.sections.len(), 24);
+        assert_eq!(chunk.get((0, -64, 0)), Some(Block::AIR.default_state()));
+
+        chunk.set((1, -1, 2), Block::BEDROCK).unwrap();
+        chunk.set((1, -1, 2), Block::AIR).unwrap();
+        chunk.set((1, 2, 2), Block::STONE).unwrap();
+        chunk.set((15, 319, 15), Block::DIRT).unwrap();
+        assert_eq!(chunk.get((1, -1, 2)), Some(Block::AIR.default_state()));
+        assert_eq!(chunk.get((1, 2, 2)), Some(Block::STONE.default_state()));
+        assert_eq!(chunk.get((15, 319, 15)), Some(Block::DIRT.default_state()));
+        assert!(chunk.sections[3].is_empty() && chunk.sections[3].is_dirty());
+        assert!(!chunk.sections[5].is_dirty());
+
+        let blocks = chunk
+            .iter()
+            .map(|(pos, state)| (pos, state.block()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            blocks,
+            [
+                ((1, 2, 2).into(), Block::STONE),
+                ((15, 319, 15).into(), Block::DIRT),
+            ]
+        );
+
+        assert!(chunk.is_dirty());
+        chunk.clear_dirty();
+        assert!(!chunk.is_dirty());
+    }
+
+    #[test]
+    fn test_random_tick_positions() {
+        let size = ChunkSize::new(16);
+        let mut chunk = Chunk::empty(size, &DimensionType::overworld(), ChunkPosition::new(2, -1));
+        let mut random = XoroshiroRandom::new(0);
+        assert!(chunk.random_tick_positions(3, &mut random).is_empty());
+
+        chunk.set((1, 18, 2), Block::STONE).unwrap();
+        let positions = chunk.random_tick_positions(3, &mut random);
+        assert_eq!(positions.len(), 3);
+        for pos in positions {
+            assert!((32..48).contains(&pos.x) && (-16..0).contains(&pos.z));
+            assert!((16..32).contains(&pos.y));
+        }
+    }
+
+    #[test]
+    fn test_out_of_bounds() {
+        let size = ChunkSize::new(16);
+        let mut chunk = Chunk::empty(size, &DimensionType::overworld(), ChunkPosition::new(0, 0));
+        for pos in [(16, 0, 0), (0, 0, 16), (0, -65, 0), (0, 320, 0)] {
+            assert_eq!(chunk.get(pos), None);
+            assert_eq!(chunk.set(pos, Block::STONE), Err(OutOfBounds(pos.into())));
+        }
+        assert!(!chunk.is_dirty());
+    }
+}
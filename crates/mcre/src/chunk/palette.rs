@@ -0,0 +1,396 @@
+use std::collections::HashMap;
+
+use mcre_core::BlockState;
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de::Error as _};
+use thiserror::Error;
+
+/// Bits per entry of the global palette, enough for the id of every block state
+const GLOBAL_BITS: u32 = u16::BITS - BlockState::MAX.id().leading_zeros();
+
+/// A fixed number of block states, stored like vanilla as indices into a palette packed in `u64`
+/// words.
+///
+/// The palette grows with the number of distinct states: a single value takes no storage, up to
+/// 16 states use a linear palette of 4 bits per entry, up to 256 a hash map palette of 5 to 8
+/// bits, and above that entries are the global block state ids.
+#[derive(Clone, Debug)]
+pub struct PalettedContainer {
+    palette: Palette,
+    storage: BitStorage,
+}
+
+impl PalettedContainer {
+    /// A container of `len` entries all set to `value`
+    pub fn new(len: usize, value: BlockState) -> Self {
+        Self::with_bits(len, 0, value)
+    }
+
+    /// A container of `len` entries set to `value`, using the palette for `bits` bits per entry
+    fn with_bits(len: usize, bits: u32, value: BlockState) -> Self {
+        PalettedContainer {
+            palette: Palette::new(bits, value),
+            storage: BitStorage::new(bits, len),
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.storage.len
+    }
+
+    pub fn get(&self, index: usize) -> BlockState {
+        self.palette.value(self.storage.get(index))
+    }
+
+    /// Sets the entry at `index`, returning the previous state
+    pub fn set(&mut self, index: usize, value: BlockState) -> BlockState {
+        let id = match self.palette.id(value) {
+            Some(id) => id,
+            None if self.palette.len() < 1 << self.storage.bits => self.palette.insert(value),
+            None => {
+                self.resize(bits_for(self.palette.len() + 1));
+                return self.set(index, value);
+            }
+        };
+        let previous = self.storage.get(index);
+        self.storage.set(index, id);
+        self.palette.value(previous)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = BlockState> + '_ {
+        self.storage.iter().map(|id| self.palette.value(id))
+    }
+
+    /// Moves the entries to a container with `bits` bits per entry
+    fn resize(&mut self, bits: u32) {
+        let mut resized = Self::with_bits(self.len(), bits, self.get(0));
+        for (index, value) in self.iter().enumerate() {
+            resized.set(index, value);
+        }
+        *self = resized;
+    }
+}
+
+/// Fewest bits able to tell `count` values apart
+const fn ceil_log2(count: usize) -> u32 {
+    match count {
+        0 | 1 => 0,
+        count => usize::BITS - (count - 1).leading_zeros(),
+    }
+}
+
+/// Bits per entry of a container holding `count` distinct states
+const fn bits_for(count: usize) -> u32 {
+    match ceil_log2(count) {
+        0 => 0,
+        1..=4 => 4,
+        bits @ 5..=8 => bits,
+        _ => GLOBAL_BITS,
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Palette {
+    Single(BlockState),
+    Linear(Vec<BlockState>),
+    HashMap {
+        values: Vec<BlockState>,
+        ids: HashMap<BlockState, u32>,
+    },
+    Global,
+}
+
+impl Palette {
+    /// The palette used for `bits` bits per entry, starting with `value`
+    fn new(bits: u32, value: BlockState) -> Self {
+        match bits {
+            0 => Palette::Single(value),
+            1..=4 => Palette::Linear(vec![value]),
+            5..=8 => Palette::HashMap {
+                values: vec![value],
+                ids: HashMap::from([(value, 0)]),
+            },
+            _ => Palette::Global,
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Palette::Single(_) => 1,
+            Palette::Linear(values) | Palette::HashMap { values, .. } => values.len(),
+            Palette::Global => usize::MAX,
+        }
+    }
+
+    fn id(&self, value: BlockState) -> Option<u32> {
+        match self {
+            Palette::Single(single) => (*single == value).then_some(0),
+            Palette::Linear(values) => values
+                .iter()
+                .position(|state| *state == value)
+                .map(|id| id as u32),
+            Palette::HashMap { ids, .. } => ids.get(&value).copied(),
+            Palette::Global => Some(value.id() as u32),
+        }
+    }
+
+    /// Adds `value` to the palette, which must have room for it
+    fn insert(&mut self, value: BlockState) -> u32 {
+        match self {
+            Palette::Single(_) | Palette::Global => unreachable!("palette can't grow"),
+            Palette::Linear(values) => {
+                values.push(value);
+                values.len() as u32 - 1
+            }
+            Palette::HashMap { values, ids } => {
+                let id = values.len() as u32;
+                values.push(value);
+                ids.insert(value, id);
+                id
+            }
+        }
+    }
+
+    fn value(&self, id: u32) -> BlockState {
+        match self {
+            Palette::Single(value) => *value,
+            Palette::Linear(values) | Palette::HashMap { values, .. } => values[id as usize],
+            Palette::Global => BlockState::from_id(id as u16),
+        }
+    }
+}
+
+/// Fixed size entries of `bits` bits packed in `u64` words, without entries spanning two words
+#[derive(Clone, Debug)]
+struct BitStorage {
+    bits: u32,
+    len: usize,
+    data: Vec<u64>,
+}
+
+impl BitStorage {
+    fn new(bits: u32, len: usize) -> Self {
+        BitStorage {
+            bits,
+            len,
+            data: vec![0; Self::words(bits, len)],
+        }
+    }
+
+    /// Storage over packed `data`, `None` if its length doesn't match
+    fn from_data(bits: u32, len: usize, data: Vec<u64>) -> Option<Self> {
+        (data.len() == Self::words(bits, len)).then_some(BitStorage { bits, len, data })
+    }
+
+    /// Number of words needed for `len` entries
+    const fn words(bits: u32, len: usize) -> usize {
+        match bits {
+            0 => 0,
+            bits => len.div_ceil((u64::BITS / bits) as usize),
+        }
+    }
+
+    const fn mask(&self) -> u64 {
+        (1 << self.bits) - 1
+    }
+
+    /// Word and bit offset of the entry at `index`
+    const fn locate(&self, index: usize) -> (usize, u32) {
+        let per_word = (u64::BITS / self.bits) as usize;
+        (index / per_word, (index % per_word) as u32 * self.bits)
+    }
+
+    fn get(&self, index: usize) -> u32 {
+        assert!(index < self.len, "index {index} out of {}", self.len);
+        if self.bits == 0 {
+            return 0;
+        }
+        let (word, offset) = self.locate(index);
+        ((self.data[word] >> offset) & self.mask()) as u32
+    }
+
+    /// Entries in order, unpacked a word at a time
+    fn iter(&self) -> impl Iterator<Item = u32> + '_ {
+        let bits = self.bits;
+        let mask = self.mask();
+        // storage of zero bits has no words, read every entry from a single empty one instead
+        let per_word = match bits {
+            0 => self.len,
+            bits => (u64::BITS / bits) as usize,
+        };
+        let words = self.data.iter().copied().chain((bits == 0).then_some(0));
+        words
+            .flat_map(move |word| {
+                (0..per_word as u32).map(move |i| ((word >> (i * bits)) & mask) as u32)
+            })
+            .take(self.len)
+    }
+
+    fn set(&mut self, index: usize, value: u32) {
+        assert!(index < self.len, "index {index} out of {}", self.len);
+        if self.bits == 0 {
+            return;
+        }
+        let (word, offset) = self.locate(index);
+        let mask = self.mask();
+        let word = &mut self.data[word];
+        *word = (*word & !(mask << offset)) | ((value as u64 & mask) << offset);
+    }
+}
+
+/// Serialized form of a container: the distinct states and the packed indices into them, using
+/// as few bits as the palette needs.
+#[derive(Deserialize, Serialize)]
+struct PalettedData {
+    len: usize,
+    palette: Vec<BlockState>,
+    data: Vec<u64>,
+}
+
+impl From<&PalettedContainer> for PalettedData {
+    fn from(container: &PalettedContainer) -> Self {
+        let mut palette = Vec::new();
+        let mut ids = HashMap::new();
+        let indices = container
+            .iter()
+            .map(|value| {
+                *ids.entry(value).or_insert_with(|| {
+                    palette.push(value);
+                    palette.len() as u32 - 1
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let bits = ceil_log2(palette.len());
+        let mut storage = BitStorage::new(bits, container.len());
+        for (index, id) in indices.into_iter().enumerate() {
+            storage.set(index, id);
+        }
+        PalettedData {
+            len: container.len(),
+            palette,
+            data: storage.data,
+        }
+    }
+}
+
+impl TryFrom<PalettedData> for PalettedContainer {
+    type Error = PaletteError;
+
+    fn try_from(value: PalettedData) -> Result<Self, Self::Error> {
+        let PalettedData { len, palette, data } = value;
+        let Some(&first) = palette.first() else {
+            return match len {
+                0 => Ok(PalettedContainer::new(0, BlockState::from_id(0))),
+                _ => Err(PaletteError::EmptyPalette),
+            };
+        };
+        let bits = ceil_log2(palette.len());
+        let found = data.len();
+        let storage = BitStorage::from_data(bits, len, data).ok_or(PaletteError::DataLength {
+            expected: BitStorage::words(bits, len),
+            found,
+        })?;
+
+        let mut container = Self::with_bits(len, bits_for(palette.len()), first);
+        for index in 0..len {
+            let id = storage.get(index);
+            let value = *palette
+                .get(id as usize)
+                .ok_or(PaletteError::InvalidId(id))?;
+            container.set(index, value);
+        }
+        Ok(container)
+    }
+}
+
+impl Serialize for PalettedContainer {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        PalettedData::from(self).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for PalettedContainer {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        PalettedData::deserialize(deserializer)?
+            .try_into()
+            .map_err(D::Error::custom)
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum PaletteError {
+    #[error("palette of a non-empty container is empty")]
+    EmptyPalette,
+    #[error("expected {expected} words of packed data, found {found}")]
+    DataLength { expected: usize, found: usize },
+    #[error("palette has no entry {0}")]
+    InvalidId(u32),
+}
+
+#[cfg(test)]
+mod tests {
+    use mcre_core::{Block, BlockState};
+
+    use super::{GLOBAL_BITS, PalettedContainer};
+
+    const LEN: usize = 4096;
+
+    fn states(count: u16) -> impl Iterator<Item = BlockState> {
+        (0..count).map(|id| BlockState::from_id(id * 7))
+    }
+
+    #[test]
+    fn test_resize() {
+        let air = Block::AIR.default_state();
+        let mut container = PalettedContainer::new(LEN, air);
+        assert_eq!(container.storage.bits, 0);
+        assert!(container.iter().all(|state| state == air));
+
+        for (count, bits) in [(2, 4), (16, 4), (17, 5), (256, 8), (257, GLOBAL_BITS)] {
+            for (index, state) in states(count).enumerate() {
+                container.set(index * 3, state);
+            }
+            assert_eq!(container.storage.bits, bits, "{count} states");
+            for (index, state) in states(count).enumerate() {
+                assert_eq!(container.get(index * 3), state);
+            }
+            assert_eq!(container.get(1), air);
+        }
+        assert_eq!(GLOBAL_BITS, 15);
+    }
+
+    #[test]
+    fn test_set() {
+        let mut container = PalettedContainer::new(LEN, Block::AIR.default_state());
+        let stone = Block::STONE.default_state();
+        assert_eq!(container.set(42, stone), Block::AIR.default_state());
+        assert_eq!(container.set(42, Block::DIRT.default_state()), stone);
+        assert_eq!(container.get(42), Block::DIRT.default_state());
+        assert_eq!(container.get(43), Block::AIR.default_state());
+        assert_eq!(container.get(LEN - 1), Block::AIR.default_state());
+    }
+
+    #[test]
+    fn test_serde() {
+        let config = bincode::config::standard();
+        let mut container = PalettedContainer::new(LEN, Block::AIR.default_state());
+        for (index, state) in states(300).enumerate() {
+            container.set(index * 5, state);
+        }
+        // overwritten states are left in the palette but not serialized
+        for index in 0..300 {
+            container.set(index * 5, Block::STONE.default_state());
+        }
+        container.set(7, Block::DIRT.default_state());
+
+        let bytes = bincode::serde::encode_to_vec(&container, config).unwrap();
+        let (decoded, _): (PalettedContainer, _) =
+            bincode::serde::decode_from_slice(&bytes, config).unwrap();
+        assert_eq!(decoded.storage.bits, 4);
+        assert!(container.iter().eq(decoded.iter()));
+
+        let single = PalettedContainer::new(LEN, Block::STONE.default_state());
+        let bytes = bincode::serde::encode_to_vec(&single, config).unwrap();
+        assert!(bytes.len() < 8);
+    }
+}
@@ -0,0 +1,83 @@
+use mcre_core::{Block, BlockState};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::palette::PalettedContainer;
+
+/// Height in blocks of a section
+pub const SECTION_HEIGHT: usize = 16;
+
+/// A 16 blocks high slice of a chunk, 16³ blocks in a chunk 16 blocks wide
+#[derive(Clone, Debug)]
+pub struct Section {
+    blocks: PalettedContainer,
+    /// Number of blocks other than air, to skip empty sections
+    non_air: usize,
+    /// Whether a block changed since the section was last meshed
+    dirty: bool,
+}
+
+impl Section {
+    /// A section of `len` blocks of air
+    pub fn empty(len: usize) -> Self {
+        Section {
+            blocks: PalettedContainer::new(len, Block::AIR.default_state()),
+            non_air: 0,
+            dirty: false,
+        }
+    }
+
+    /// Whether the section only has air
+    pub fn is_empty(&self) -> bool {
+        self.non_air == 0
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    pub fn mark_dirty(&mut self) {
+        self.dirty = true;
+    }
+
+    pub fn clear_dirty(&mut self) {
+        self.dirty = false;
+    }
+
+    pub fn get(&self, index: usize) -> BlockState {
+        self.blocks.get(index)
+    }
+
+    /// Sets the block at `index`, returning the previous one
+    pub fn set(&mut self, index: usize, state: BlockState) -> BlockState {
+        let previous = self.blocks.set(index, state);
+        self.non_air = self.non_air + !state.is_air() as usize - !previous.is_air() as usize;
+        self.dirty |= previous != state;
+        previous
+    }
+
+    /// Index and state of every block other than air
+    pub fn iter(&self) -> impl Iterator<Item = (usize, BlockState)> + '_ {
+        self.blocks
+            .iter()
+            .enumerate()
+            .filter(|(_, state)| !state.is_air())
+    }
+}
+
+impl Serialize for Section {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.blocks.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Section {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let blocks = PalettedContainer::deserialize(deserializer)?;
+        let non_air = blocks.iter().filter(|state| !state.is_air()).count();
+        Ok(Section {
+            blocks,
+            non_air,
+            dirty: false,
+        })
+    }
+}
@@ -0,0 +1,164 @@
+use bevy::prelude::*;
+use mcre_core::{
+    Block, BlockBehaviors, BlockGetter, BlockPos, BlockState, ChunkSource, Direction, LevelHeight,
+    LevelHeightAccessor, LevelWriter, ScheduledTicks, UpdateFlags, XoroshiroRandom,
+};
+use mcre_worldgen::DimensionType;
+
+use crate::chunk::{
+    Chunk,
+    loader::{ChunkLoader, ChunkLoaderConfig},
+    math::size::ChunkSize,
+};
+
+/// Blocks of each section getting a random tick every tick, vanilla's default `randomTickSpeed`
+const RANDOM_TICK_SPEED: usize = 3;
+
+/// The block behaviors every gameplay system dispatches through.
+#[derive(Resource, Deref)]
+pub struct Behaviors(BlockBehaviors);
+
+impl Default for Behaviors {
+    fn default() -> Self {
+        Self(BlockBehaviors::vanilla())
+    }
+}
+
+#[derive(Resource, Default, Deref, DerefMut)]
+pub struct BlockTicks(ScheduledTicks);
+
+/// Random source of block ticks
+#[derive(Resource)]
+pub struct TickRandom(XoroshiroRandom);
+
+impl Default for TickRandom {
+    fn default() -> Self {
+        Self(XoroshiroRandom::new(0))
+    }
+}
+
+impl BlockTicks {
+    /// Runs the scheduled ticks that are due, then random ticks in the sections of the loaded
+    /// chunks
+    pub fn tick_blocks(
+        mut ticks: ResMut<BlockTicks>,
+        mut random: ResMut<TickRandom>,
+        mut chunks: ResMut<Assets<Chunk>>,
+        behaviors: Res<Behaviors>,
+        loader: Res<ChunkLoader>,
+        config: Res<ChunkLoaderConfig>,
+    ) {
+        let random = &mut random.0;
+        let positions = loader
+            .loaded()
+            .filter_map(|handle| chunks.get(handle.id()))
+            .flat_map(|chunk| chunk.random_tick_positions(RANDOM_TICK_SPEED, random))
+            .collect::<Vec<_>>();
+
+        let mut storage = ChunkMap::new(&loader, &mut chunks, config.chunk_size, &config.dimension);
+        let mut level = behaviors.level(&mut storage, &mut ticks);
+        level.tick(random);
+        for pos in positions {
+            level.random_tick(pos, random);
+        }
+    }
+}
+
+/// The loaded chunks seen as one level. Blocks of unloaded chunks read as air and can't be
+/// changed, changed chunks are marked dirty to be remeshed.
+pub struct ChunkMap<'a> {
+    loader: &'a ChunkLoader,
+    chunks: &'a mut Assets<Chunk>,
+    chunk_size: ChunkSize,
+    height: LevelHeight,
+}
+
+impl<'a> ChunkMap<'a> {
+    pub fn new(
+        loader: &'a ChunkLoader,
+        chunks: &'a mut Assets<Chunk>,
+        chunk_size: ChunkSize,
+        dimension: &DimensionType,
+    ) -> Self {
+        Self {
+            loader,
+            chunks,
+            chunk_size,
+            height: LevelHeight::new(dimension.min_y, dimension.height),
+        }
+    }
+
+    /// A chunk of the map by its asset id, loaded or not
+    pub fn get_chunk(&self, id: AssetId<Chunk>) -> Option<&Chunk> {
+        self.chunks.get(id)
+    }
+}
+
+impl BlockGetter for ChunkMap<'_> {
+    fn get_block_state(&self, pos: BlockPos) -> BlockState {
+        self.get_chunk_at(pos)
+            .map(|chunk| chunk.get_block_state(pos))
+            .unwrap_or(Block::AIR.default_state())
+    }
+}
+
+impl LevelWriter for ChunkMap<'_> {
+    fn set_block_state(&mut self, pos: BlockPos, state: BlockState, _flags: UpdateFlags) -> bool {
+        if self.is_outside_build_height(pos.y) {
+            return false;
+        }
+
+        let loc = self.chunk_size.block_chunk(pos);
+        let Some(chunk) = self
+            .loader
+            .get(&loc)
+            .and_then(|handle| self.chunks.get_mut(handle.id()))
+        else {
+            return false;
+        };
+        let changed = chunk
+            .local_pos(pos)
+            .and_then(|local| chunk.set(local, state).ok())
+            .is_some_and(|previous| previous != state);
+
+        if changed {
+            // faces of neighboring chunks against the block are culled by it
+            for direction in [
+                Direction::North,
+                Direction::South,
+                Direction::West,
+                Direction::East,
+            ] {
+                let neighbor = self.chunk_size.block_chunk(pos.relative(direction));
+                if neighbor != loc
+                    && let Some(chunk) = self
+                        .loader
+                        .get(&neighbor)
+                        .and_then(|handle| self.chunks.get_mut(handle.id()))
+                {
+                    chunk.mark_section_dirty(pos.y);
+                }
+            }
+        }
+        changed
+    }
+}
+
+impl LevelHeightAccessor for ChunkMap<'_> {
+    fn min_y(&self) -> i32 {
+        self.height.min_y
+    }
+
+    fn height(&self) -> u32 {
+        self.height.height
+    }
+}
+
+impl ChunkSource for ChunkMap<'_> {
+    type Chunk = Chunk;
+
+    fn get_chunk_at(&self, pos: BlockPos) -> Option<&Chunk> {
+        let handle = self.loader.get(&self.chunk_size.block_chunk(pos))?;
+        self.chunks.get(handle.id())
+    }
+}
@@ -0,0 +1,90 @@
+mod camera;
+pub mod chunk;
+mod level;
+pub mod models;
+mod player;
+pub mod textures;
+mod ui;
+
+use bevy::{color::palettes::css::WHITE, prelude::*};
+
+use crate::{
+    camera::FirstPersonPlugin,
+    chunk::loader::ChunkLoaderPlugin,
+    models::BlockModelPlugin,
+    player::PlayerInteractionPlugin,
+    textures::{BlockMaterialPlugin, BlockTextures},
+    ui::{debug::DebugMenuPlugin, load::LoadingUi},
+};
+
+/// The game, added on top of bevy's `DefaultPlugins`
+pub struct McrePlugin;
+
+impl Plugin for McrePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((
+            FirstPersonPlugin {
+                transform: Transform::from_xyz(-2.0, 10.0, 10.0)
+                    .looking_at(Vec3::new(4.0, 0.0, 0.0), Vec3::Y),
+                camera_movement_speed: 0.2,
+                camera_rotation_speed: 0.3,
+            },
+            DebugMenuPlugin,
+            PlayerInteractionPlugin,
+            BlockMaterialPlugin,
+            BlockModelPlugin,
+        ))
+        .add_plugins(ChunkLoaderPlugin::default())
+        .init_state::<AppState>()
+        .add_sub_state::<LoadingState>()
+        .init_resource::<BlockTextures>()
+        .add_systems(Startup, setup_light)
+        .add_systems(OnEnter(AppState::Loading), LoadingUi::add_ui_system)
+        .add_systems(
+            OnEnter(LoadingState::Textures),
+            BlockTextures::load_textures_system,
+        )
+        .add_systems(
+            Update,
+            BlockTextures::check_loaded_textures_system.run_if(in_state(LoadingState::Textures)),
+        )
+        .add_systems(
+            Update,
+            LoadingUi::update_ui_system.run_if(in_state(AppState::Loading)),
+        )
+        .add_systems(OnExit(AppState::Loading), LoadingUi::remove_ui_system);
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Default, Hash, Debug, States)]
+pub enum AppState {
+    #[default]
+    Loading,
+    InGame,
+    Paused,
+}
+
+#[derive(SubStates, Clone, PartialEq, Eq, Hash, Debug, Default)]
+#[source(AppState = AppState::Loading)]
+pub enum LoadingState {
+    #[default]
+    Camera,
+    Models,
+    Textures,
+    Chunks,
+}
+
+fn setup_light(mut commands: Commands) {
+    commands.insert_resource(AmbientLight {
+        color: WHITE.into(),
+        brightness: 300.0,
+        ..default()
+    });
+    commands.spawn((
+        DirectionalLight {
+            illuminance: 5000.0,
+            ..Default::default()
+        },
+        Transform::from_xyz(20.0, 10.0, 10.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+}
@@ -0,0 +1,475 @@
+use std::{marker::PhantomData, sync::Arc};
+
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadState, UntypedAssetId, io::Reader},
+    platform::collections::{HashMap, HashSet},
+    prelude::*,
+};
+use mcre_assets::{
+    BakedQuad, BlockModelDefinition, BlockModelId, BlockModelResolution, BlockStateDefinition,
+    ModelVariant, TextureId, default_uv,
+};
+use mcre_core::{Block, BlockState, Direction};
+use serde::{Deserialize, de::DeserializeOwned};
+use thiserror::Error;
+
+use crate::{LoadingState, textures::BlockTextures};
+
+/// Loads the blockstate and model files of the blocks then bakes the model of every block state,
+/// see [`BlockModels`]
+pub struct BlockModelPlugin;
+
+impl Plugin for BlockModelPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<BlockStateAsset>()
+            .init_asset::<BlockModelAsset>()
+            .init_asset_loader::<JsonAssetLoader<BlockStateAsset>>()
+            .init_asset_loader::<JsonAssetLoader<BlockModelAsset>>()
+            .init_resource::<BlockModels>()
+            .add_systems(
+                OnEnter(LoadingState::Models),
+                BlockModels::load_models_system,
+            )
+            .add_systems(
+                Update,
+                BlockModels::check_loaded_models_system.run_if(in_state(LoadingState::Models)),
+            )
+            .add_systems(
+                OnEnter(LoadingState::Chunks),
+                BlockModels::resolve_textures_system,
+            );
+    }
+}
+
+/// A blockstate file, the models used by each state of a block
+#[derive(Asset, TypePath, Deserialize)]
+#[serde(transparent)]
+pub struct BlockStateAsset(pub BlockStateDefinition);
+
+/// A block model file
+#[derive(Asset, TypePath, Deserialize)]
+#[serde(transparent)]
+pub struct BlockModelAsset(pub BlockModelDefinition);
+
+/// Loads `.json` files as `A`, the asset type picks the loader
+pub struct JsonAssetLoader<A>(PhantomData<fn() -> A>);
+
+impl<A> Default for JsonAssetLoader<A> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<A: Asset + DeserializeOwned> AssetLoader for JsonAssetLoader<A> {
+    type Asset = A;
+
+    type Settings = ();
+
+    type Error = JsonLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &Self::Settings,
+        _load_context: &mut LoadContext<'_>,
+    ) -> std::result::Result<Self::Asset, Self::Error> {
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data).await?;
+        Ok(serde_json::from_slice(&data)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["json"]
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum JsonLoaderError {
+    #[error("Could not load json file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Could not parse json file: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// Asset path of a model
+fn model_path(id: &BlockModelId) -> String {
+    format!("{}/models/block/{}.json", id.namespace, id.id)
+}
+
+/// Asset path of a texture, textures are looked up by path once loaded
+pub fn texture_path(texture: &TextureId) -> String {
+    match texture {
+        TextureId::Block(id) => format!("{}/textures/block/{}.png", id.namespace, id.id),
+        TextureId::Item(id) => format!("{}/textures/item/{}.png", id.namespace, id.id),
+    }
+}
+
+/// A quad of a block model ready to be meshed, in blocks from 0 to 1 across the block
+#[derive(Clone, Debug, PartialEq)]
+pub struct ModelQuad {
+    /// Corners counter-clockwise seen from the front
+    pub vertices: [Vec3; 4],
+    /// Texture coordinates of the corners, from 0 to 1 across the texture
+    pub uvs: [Vec2; 4],
+    pub normal: Vec3,
+    /// Layer of the block texture array
+    pub layer: u32,
+    /// Direction of the neighbor hiding the quad when it occludes
+    pub cullface: Option<Direction>,
+    /// Whether the quad takes the color of the block, like grass and leaves
+    pub tinted: bool,
+    /// The side of the block covered by the quad, when it covers it entirely with the texture
+    /// upright. Such quads can be merged with the same quads of neighbors.
+    pub full_face: Option<Direction>,
+}
+
+impl ModelQuad {
+    fn new(quad: &BakedQuad, layer: u32) -> Self {
+        let on_block_face = quad.vertices.iter().all(|vertex| {
+            let depth = quad.direction.axis().select(*vertex);
+            let [a, b] = quad
+                .direction
+                .axis()
+                .complementary_axes()
+                .map(|axis| axis.select(*vertex));
+            depth
+                == if quad.direction.is_positive() {
+                    16.0
+                } else {
+                    0.0
+                }
+                && [a, b].iter().all(|&coord| coord == 0.0 || coord == 16.0)
+        });
+        let distinct = (0..4).all(|i| (i + 1..4).all(|j| quad.vertices[i] != quad.vertices[j]));
+        let upright = (quad.vertices.iter().zip(quad.uvs))
+            .all(|(vertex, uv)| uv == default_uv(quad.direction, *vertex));
+        let full_face =
+            (quad.cullface == Some(quad.direction) && on_block_face && distinct && upright)
+                .then_some(quad.direction);
+
+        let vertices = quad.vertices.map(|vertex| Vec3::from_array(*vertex) / 16.0);
+        let [a, b, c, _] = vertices;
+        ModelQuad {
+            vertices,
+            uvs: quad.uvs.map(|uv| Vec2::from_array(uv) / 16.0),
+            normal: (b - a).cross(c - a).normalize_or_zero(),
+            layer,
+            cullface: quad.cullface,
+            tinted: quad.tintindex.is_some(),
+            full_face,
+        }
+    }
+}
+
+/// The models a part of a block state picks from by weight
+#[derive(Clone, Debug, Default)]
+pub struct Variants(Vec<(u32, Arc<[ModelQuad]>)>);
+
+impl Variants {
+    /// The quads of the variant picked by `seed`, the seed of the position of the block so the
+    /// same block always looks the same
+    pub fn pick(&self, seed: i64) -> Option<&[ModelQuad]> {
+        let total = self.0.iter().map(|(weight, _)| *weight as i64).sum::<i64>();
+        if total == 0 {
+            return None;
+        }
+        let mut pick = seed.rem_euclid(total);
+        self.0.iter().find_map(|(weight, quads)| {
+            pick -= *weight as i64;
+            (pick < 0).then_some(&quads[..])
+        })
+    }
+}
+
+/// A variant of a blockstate file, variants with the same key have the same quads
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct VariantKey {
+    model: String,
+    x: usize,
+    y: usize,
+    uvlock: bool,
+}
+
+impl VariantKey {
+    fn new(variant: &ModelVariant) -> Self {
+        VariantKey {
+            model: variant.model.to_string(),
+            x: variant.x.quarter_turns(),
+            y: variant.y.quarter_turns(),
+            uvlock: variant.uvlock,
+        }
+    }
+}
+
+/// The models of every block state with the textures they use, before the textures are loaded
+pub struct BakedModels {
+    /// The parts of each state by id, each a list of weighted indices in `variants`. Unified
+    /// variants have one part, multipart definitions a part per matching rule.
+    states: Vec<Vec<Vec<(u32, usize)>>>,
+    /// Quads of each distinct variant, rotated
+    variants: Vec<Vec<BakedQuad>>,
+}
+
+impl BakedModels {
+    /// Bakes the models of every block state, skipping the variants whose model is missing or
+    /// fails to bake
+    pub fn new<'a>(
+        blockstates: impl Fn(Block) -> Option<&'a BlockStateDefinition>,
+        models: impl Fn(&str) -> Option<&'a BlockModelDefinition>,
+    ) -> Self {
+        let mut keys: HashMap<VariantKey, Option<usize>> = HashMap::new();
+        let mut variants = Vec::new();
+        let mut bake = |variant: &ModelVariant| {
+            let key = VariantKey::new(variant);
+            if let Some(&index) = keys.get(&key) {
+                return index;
+            }
+            let parent = |id: &BlockModelId| models(&id.to_string()).cloned();
+            let index = match models(&key.model).map(|model| model.bake(parent)) {
+                Some(Ok(mut quads)) => {
+                    for quad in &mut quads {
+                        quad.rotate(variant.x, variant.y, variant.uvlock);
+                    }
+                    variants.push(quads);
+                    Some(variants.len() - 1)
+                }
+                Some(Err(err)) => {
+                    warn!("Failed to bake model {}: {err}", key.model);
+                    None
+                }
+                None => None,
+            };
+            keys.insert(key, index);
+            index
+        };
+
+        let states = BlockState::all()
+            .map(|state| {
+                let resolution = blockstates(state.block()).and_then(|def| def.resolve(state));
+                let parts = match resolution {
+                    Some(BlockModelResolution::Unified(variants)) => vec![variants],
+                    Some(BlockModelResolution::Multipart(parts)) => parts.into_vec(),
+                    None => Vec::new(),
+                };
+                parts
+                    .into_iter()
+                    .map(|part| {
+                        part.iter()
+                            .filter_map(|variant| Some((variant.weight as u32, bake(variant)?)))
+                            .collect()
+                    })
+                    .collect()
+            })
+            .collect();
+        BakedModels { states, variants }
+    }
+
+    /// Asset paths of the textures used by the models
+    pub fn textures(&self) -> Vec<String> {
+        let textures = self
+            .variants
+            .iter()
+            .flatten()
+            .map(|quad| texture_path(&quad.texture))
+            .collect::<HashSet<_>>();
+        let mut textures = textures.into_iter().collect::<Vec<_>>();
+        textures.sort();
+        textures
+    }
+
+    /// The models ready to be meshed with the texture array `layers` by texture path. Quads whose
+    /// texture isn't in the array are left out.
+    pub fn resolve(&self, layers: &HashMap<String, u32>) -> BlockModels {
+        let variants = self
+            .variants
+            .iter()
+            .map(|quads| {
+                quads
+                    .iter()
+                    .filter_map(|quad| {
+                        let layer = *layers.get(&texture_path(&quad.texture))?;
+                        Some(ModelQuad::new(quad, layer))
+                    })
+                    .collect::<Arc<[_]>>()
+            })
+            .collect::<Vec<_>>();
+        let states = self
+            .states
+            .iter()
+            .map(|parts| {
+                parts
+                    .iter()
+                    .map(|part| {
+                        let part = part
+                            .iter()
+                            .map(|&(weight, index)| (weight, variants[index].clone()));
+                        Variants(part.collect())
+                    })
+                    .collect()
+            })
+            .collect();
+        BlockModels::Loaded { states }
+    }
+}
+
+/// Models of the block states, cloned cheaply into meshing jobs once loaded
+#[derive(Resource, Clone)]
+pub enum BlockModels {
+    Loading {
+        blockstates: Vec<(Block, Handle<BlockStateAsset>)>,
+        /// Models used by the blockstates and their parents, by id
+        models: HashMap<String, Handle<BlockModelAsset>>,
+    },
+    /// Waiting for the textures of the models
+    Baked(Arc<BakedModels>),
+    Loaded {
+        /// The parts of each state by id
+        states: Arc<[Box<[Variants]>]>,
+    },
+}
+
+impl Default for BlockModels {
+    fn default() -> Self {
+        BlockModels::Loading {
+            blockstates: Vec::new(),
+            models: HashMap::new(),
+        }
+    }
+}
+
+impl BlockModels {
+    /// The parts of the model of `state`, all drawn
+    pub fn parts(&self, state: BlockState) -> &[Variants] {
+        match self {
+            BlockModels::Loaded { states } => states
+                .get(state.id() as usize)
+                .map_or(&[], |parts| &parts[..]),
+            _ => &[],
+        }
+    }
+
+    /// Asset paths of the textures used by the models, once baked
+    pub fn textures(&self) -> Vec<String> {
+        match self {
+            BlockModels::Baked(baked) => baked.textures(),
+            _ => Vec::new(),
+        }
+    }
+
+    pub fn loading_percent(&self, asset_server: &AssetServer) -> f32 {
+        match self {
+            BlockModels::Loading {
+                blockstates,
+                models,
+            } => {
+                let handles = blockstates
+                    .iter()
+                    .map(|(_, handle)| handle.id().untyped())
+                    .chain(models.values().map(|handle| handle.id().untyped()))
+                    .collect::<Vec<_>>();
+                let done = handles
+                    .iter()
+                    .filter(|&&id| is_done(asset_server, id))
+                    .count();
+                done as f32 / handles.len().max(1) as f32
+            }
+            _ => 1.0,
+        }
+    }
+
+    pub fn load_models_system(mut models: ResMut<BlockModels>, asset_server: Res<AssetServer>) {
+        *models = BlockModels::Loading {
+            blockstates: Block::all()
+                .map(|block| {
+                    let path = format!("minecraft/blockstates/{}.json", block.name());
+                    (block, asset_server.load(path))
+                })
+                .collect(),
+            models: HashMap::new(),
+        };
+    }
+
+    /// Loads the models used by the loaded blockstates and the parents of the loaded models, then
+    /// bakes them once every file is loaded
+    pub fn check_loaded_models_system(
+        mut next_state: ResMut<NextState<LoadingState>>,
+        mut models: ResMut<BlockModels>,
+        asset_server: Res<AssetServer>,
+        blockstate_assets: Res<Assets<BlockStateAsset>>,
+        model_assets: Res<Assets<BlockModelAsset>>,
+    ) {
+        let BlockModels::Loading {
+            blockstates,
+            models: requested,
+        } = &mut *models
+        else {
+            return;
+        };
+
+        let used = blockstates
+            .iter()
+            .filter_map(|(_, handle)| blockstate_assets.get(handle.id()))
+            .flat_map(|blockstate| blockstate.0.variants().map(|variant| &variant.model));
+        let parents = requested
+            .values()
+            .filter_map(|handle| model_assets.get(handle.id()))
+            .filter_map(|model| model.0.parent.as_ref());
+        let missing = used
+            .chain(parents)
+            .filter(|id| !requested.contains_key(&id.to_string()))
+            .cloned()
+            .collect::<Vec<_>>();
+        if !missing.is_empty() {
+            for id in missing {
+                requested.insert(id.to_string(), asset_server.load(model_path(&id)));
+            }
+            return;
+        }
+
+        let handles = blockstates.iter().map(|(_, handle)| handle.id().untyped());
+        if !handles
+            .chain(requested.values().map(|handle| handle.id().untyped()))
+            .all(|id| is_done(&asset_server, id))
+        {
+            return;
+        }
+
+        let span = info_span!("model_baking");
+        let _enter = span.enter();
+        let baked = {
+            let blockstates = blockstates
+                .iter()
+                .filter_map(|(block, handle)| {
+                    Some((*block, &blockstate_assets.get(handle.id())?.0))
+                })
+                .collect::<HashMap<_, _>>();
+            let models = requested
+                .iter()
+                .filter_map(|(id, handle)| Some((id.as_str(), &model_assets.get(handle.id())?.0)))
+                .collect::<HashMap<_, _>>();
+            BakedModels::new(
+                |block| blockstates.get(&block).copied(),
+                |id| models.get(id).copied(),
+            )
+        };
+        *models = BlockModels::Baked(Arc::new(baked));
+        next_state.set(LoadingState::Textures);
+    }
+
+    /// Gives the baked models the layers of their textures once the texture array is built
+    pub fn resolve_textures_system(mut models: ResMut<BlockModels>, textures: Res<BlockTextures>) {
+        if let BlockModels::Baked(baked) = &*models
+            && let Some(layers) = textures.layers()
+        {
+            *models = baked.resolve(layers);
+        }
+    }
+}
+
+/// Whether the asset is loaded or failed to load, a missing file is reported as failed
+fn is_done(asset_server: &AssetServer, id: UntypedAssetId) -> bool {
+    matches!(
+        asset_server.get_load_state(id),
+        Some(LoadState::Loaded | LoadState::Failed(_))
+    )
+}
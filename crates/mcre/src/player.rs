@@ -1,10 +1,14 @@
 use bevy::{platform::collections::HashMap, prelude::*, window::CursorOptions};
-use mcre_core::Block;
+use mcre_core::{Block, LevelWriter, UpdateFlags};
 
 use crate::{
     AppState,
-    chunk::{Chunk, ChunkComponent, math::pos::BlockPosition, mesh::ChunkMeshBuilder},
-    textures::BlockTextures,
+    chunk::{
+        Chunk, ChunkComponent,
+        loader::{ChunkLoader, ChunkLoaderConfig},
+        math::pos::BlockPosition,
+    },
+    level::{Behaviors, BlockTicks, ChunkMap, TickRandom},
     ui::player::PlayerUi,
 };
 
@@ -13,10 +17,21 @@ pub struct PlayerInteractionPlugin;
 impl Plugin for PlayerInteractionPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<ChunkUpdates>()
+            .init_resource::<Behaviors>()
+            .init_resource::<BlockTicks>()
+            .init_resource::<TickRandom>()
             .add_systems(Update, Self::handle_esc)
+            .add_systems(
+                FixedUpdate,
+                BlockTicks::tick_blocks.run_if(in_state(AppState::InGame)),
+            )
             .add_systems(
                 Update,
-                (ChunkUpdates::block_interaction, ChunkUpdates::block_updates)
+                (
+                    ChunkUpdates::block_interaction,
+                    ChunkUpdates::block_updates,
+                    ChunkLoader::mesh_chunks,
+                )
                     .chain()
                     .run_if(in_state(AppState::InGame)),
             )
@@ -92,18 +107,33 @@ impl ChunkUpdates {
 
     pub fn block_updates(
         mut updates: ResMut<ChunkUpdates>,
-        components: Query<(&ChunkComponent, &Mesh3d)>,
-        mut meshes: ResMut<Assets<Mesh>>,
+        components: Query<&ChunkComponent>,
         mut chunks: ResMut<Assets<Chunk>>,
-        textures: Res<BlockTextures>,
+        behaviors: Res<Behaviors>,
+        mut ticks: ResMut<BlockTicks>,
+        loader: Res<ChunkLoader>,
+        config: Res<ChunkLoaderConfig>,
     ) {
+        if updates.updates.is_empty() {
+            return;
+        }
+
+        let mut storage = ChunkMap::new(&loader, &mut chunks, config.chunk_size, &config.dimension);
         for (pos, (entity, val)) in updates.updates.drain() {
-            let (component, mesh) = components.get(entity).unwrap();
-            let chunk = chunks.get_mut(component.0.id()).unwrap();
-            let mesh = meshes.get_mut(mesh.0.id()).unwrap();
-            chunk.set(pos, val.unwrap_or(Block::AIR));
-            let builder = ChunkMeshBuilder::new(chunk);
-            builder.update_mesh(mesh, &textures);
+            let Ok(component) = components.get(entity) else {
+                continue;
+            };
+            let Some(pos) = storage
+                .get_chunk(component.0.id())
+                .map(|chunk| chunk.world_pos(pos))
+            else {
+                continue;
+            };
+
+            let state = val.unwrap_or(Block::AIR).default_state();
+            behaviors
+                .level(&mut storage, &mut ticks)
+                .set_block_state(pos, state, UpdateFlags::ALL);
         }
     }
 
@@ -126,7 +156,7 @@ impl ChunkUpdates {
             let relative = (hit.point + normal / 2.) - transform.translation;
             let pos = BlockPosition {
                 x: relative.x.floor() as u8,
-                y: relative.y.floor() as i64,
+                y: relative.y.floor() as i32,
                 z: relative.z.floor() as u8,
             };
             return Some((pos, *entity));
@@ -1,42 +1,88 @@
-use bevy::{asset::LoadState, platform::collections::HashMap, prelude::*};
-use mcre_core::{Block, BlockState};
+use bevy::{
+    asset::{LoadState, RenderAssetUsages, embedded_asset},
+    pbr::{ExtendedMaterial, MaterialExtension},
+    platform::collections::HashMap,
+    prelude::*,
+    render::render_resource::{
+        AsBindGroup, Extent3d, TextureDimension, TextureFormat, TextureViewDescriptor,
+        TextureViewDimension,
+    },
+    shader::ShaderRef,
+};
 
-use crate::LoadingState;
+use crate::{LoadingState, models::BlockModels};
 
 const BATCH_SIZE: usize = 10;
 
+/// Width and height in pixels of a block texture, animated textures only keep their first frame
+const TEXTURE_SIZE: u32 = 16;
+
+const SHADER_PATH: &str = "embedded://mcre/block.wgsl";
+
+/// The material of chunk meshes, see [`BlockTextureArray`]
+pub type BlockMaterial = ExtendedMaterial<StandardMaterial, BlockTextureArray>;
+
+/// Block textures as the layers of one texture array. Meshes give the layer of a face in the `x`
+/// of `UV_1`, and `UV_0` in blocks so merged faces repeat the texture once per block.
+#[derive(Asset, AsBindGroup, Reflect, Debug, Clone)]
+pub struct BlockTextureArray {
+    #[texture(100, dimension = "2d_array")]
+    #[sampler(101)]
+    pub textures: Handle<Image>,
+}
+
+impl MaterialExtension for BlockTextureArray {
+    fn fragment_shader() -> ShaderRef {
+        SHADER_PATH.into()
+    }
+}
+
+pub struct BlockMaterialPlugin;
+
+impl Plugin for BlockMaterialPlugin {
+    fn build(&self, app: &mut App) {
+        embedded_asset!(app, "block.wgsl");
+        app.add_plugins(MaterialPlugin::<BlockMaterial>::default());
+    }
+}
+
+/// Textures of the block models, by asset path
 #[derive(Resource)]
 pub enum BlockTextures {
     Loading {
-        // Should probably be `TextureId` not `Block`
-        all: Vec<(Block, Option<Handle<Image>>)>,
+        all: Vec<(String, Option<Handle<Image>>)>,
         cur_index: usize,
         batch: Vec<(usize, Handle<Image>)>,
     },
     Loaded {
-        texture: Handle<StandardMaterial>,
-        atlas: TextureAtlasLayout,
-        blocks: HashMap<Block, usize>,
+        texture: Handle<BlockMaterial>,
+        /// Layer of the texture array by texture path
+        layers: HashMap<String, u32>,
     },
 }
 
 impl Default for BlockTextures {
     fn default() -> Self {
+        BlockTextures::new(Vec::new())
+    }
+}
+
+impl BlockTextures {
+    /// Textures loading from the asset `paths`
+    pub fn new(paths: Vec<String>) -> Self {
         BlockTextures::Loading {
-            all: Block::all().map(|b| (b, None)).collect(),
+            all: paths.into_iter().map(|path| (path, None)).collect(),
             cur_index: 0,
             batch: Vec::with_capacity(BATCH_SIZE),
         }
     }
-}
 
-impl BlockTextures {
     // Updates batch processing and returns true if finished
     pub fn update_batch(
         &mut self,
         asset_server: &AssetServer,
         images: &mut Assets<Image>,
-        materials: &mut Assets<StandardMaterial>,
+        materials: &mut Assets<BlockMaterial>,
     ) -> bool {
         let handles = match self {
             BlockTextures::Loading {
@@ -61,28 +107,17 @@ impl BlockTextures {
                         _ => true,
                     },
                 );
-                if *cur_index < all.len() - 1 {
-                    let diff = BATCH_SIZE - batch.len();
-                    if diff > 0 {
-                        for i in 0..diff {
-                            let new_index = *cur_index + i;
-                            if new_index >= all.len() {
-                                break;
-                            }
-                            //TODO: Fix for different textures i.e. using texture id from
-                            //BlockState
-                            let handle = asset_server.load(format!(
-                                "minecraft/textures/block/{}.png",
-                                all[new_index].0.name()
-                            ));
-                            batch.push((new_index, handle));
-                        }
-                        *cur_index += diff;
+                if *cur_index < all.len() || !batch.is_empty() {
+                    let end = (*cur_index + BATCH_SIZE - batch.len()).min(all.len());
+                    for (new_index, (path, _)) in all.iter().enumerate().take(end).skip(*cur_index)
+                    {
+                        batch.push((new_index, asset_server.load(path.clone())));
                     }
+                    *cur_index = end;
                     return false;
                 }
                 all.drain(..)
-                    .filter_map(|(block, handle)| handle.map(|handle| (block, handle)))
+                    .filter_map(|(path, handle)| handle.map(|handle| (path, handle)))
                     .collect::<Vec<_>>()
             }
             BlockTextures::Loaded { .. } => {
@@ -90,82 +125,88 @@ impl BlockTextures {
             }
         };
 
-        let mut builder = TextureAtlasBuilder::default();
-        let mut blocks = HashMap::new();
-        for (i, (block, handle)) in handles.iter().enumerate() {
-            let texture = images.get(handle.id()).unwrap();
-            builder.add_texture(Some(handle.id()), texture);
-            blocks.insert(*block, i);
+        let mut data = Vec::new();
+        let mut layers = HashMap::new();
+        for (path, handle) in &handles {
+            let image = images.get(handle.id()).unwrap();
+            match first_frame(image) {
+                Some(frame) => {
+                    layers.insert(path.clone(), layers.len() as u32);
+                    data.extend_from_slice(&frame);
+                }
+                None => warn!("Skipping texture {path} which isn't {TEXTURE_SIZE} pixels wide"),
+            }
         }
 
-        let (atlas, _sources, texture) = builder.build().unwrap();
-
         for (_, handle) in handles {
             images.remove(handle.id());
         }
-        let texture = images.add(texture);
-
-        let texture = materials.add(StandardMaterial {
-            base_color_texture: Some(texture),
-            alpha_mode: AlphaMode::Mask(0.5),
-            reflectance: 0.0,
-            // unlit: true,
+        let mut array = Image::new(
+            Extent3d {
+                width: TEXTURE_SIZE,
+                height: TEXTURE_SIZE,
+                depth_or_array_layers: layers.len() as u32,
+            },
+            TextureDimension::D2,
+            data,
+            TextureFormat::Rgba8UnormSrgb,
+            RenderAssetUsages::RENDER_WORLD,
+        );
+        // a single layer would otherwise be viewed as a 2d texture
+        array.texture_view_descriptor = Some(TextureViewDescriptor {
+            dimension: Some(TextureViewDimension::D2Array),
             ..default()
         });
 
-        *self = BlockTextures::Loaded {
-            atlas,
-            blocks,
-            texture,
-        };
+        let texture = materials.add(BlockMaterial {
+            base: StandardMaterial {
+                alpha_mode: AlphaMode::Mask(0.5),
+                reflectance: 0.0,
+                // unlit: true,
+                ..default()
+            },
+            extension: BlockTextureArray {
+                textures: images.add(array),
+            },
+        });
+
+        *self = BlockTextures::Loaded { layers, texture };
         true
     }
 
     pub fn loading_percent(&self) -> f32 {
         match self {
-            BlockTextures::Loading { all, cur_index, .. } => *cur_index as f32 / all.len() as f32,
+            BlockTextures::Loading { all, cur_index, .. } => {
+                *cur_index as f32 / all.len().max(1) as f32
+            }
             BlockTextures::Loaded { .. } => 1.0,
         }
     }
 
-    pub fn texture(&self) -> Option<&Handle<StandardMaterial>> {
+    pub fn texture(&self) -> Option<&Handle<BlockMaterial>> {
         match self {
             BlockTextures::Loading { .. } => None,
             BlockTextures::Loaded { texture, .. } => Some(texture),
         }
     }
 
-    pub fn get_uv_rect(&self, block: BlockState) -> Option<Rect> {
+    /// Layers of the texture array by texture path
+    pub fn layers(&self) -> Option<&HashMap<String, u32>> {
         match self {
             BlockTextures::Loading { .. } => None,
-            BlockTextures::Loaded { atlas, blocks, .. } => {
-                let idx = blocks.get(&block.block())?;
-                let size = atlas.textures[*idx];
-                Some(Rect {
-                    min: Vec2::new(
-                        size.min.x as f32 / atlas.size.x as f32,
-                        size.min.y as f32 / atlas.size.y as f32,
-                    ),
-                    max: Vec2::new(
-                        size.max.x as f32 / atlas.size.x as f32,
-                        size.max.y as f32 / atlas.size.y as f32,
-                    ),
-                })
-            }
+            BlockTextures::Loaded { layers, .. } => Some(layers),
         }
     }
 
+    /// Loads the textures used by the baked block models
     pub fn load_textures_system(
         mut commands: Commands,
+        models: Res<BlockModels>,
         asset_server: Res<AssetServer>,
         mut images: ResMut<Assets<Image>>,
-        mut materials: ResMut<Assets<StandardMaterial>>,
+        mut materials: ResMut<Assets<BlockMaterial>>,
     ) {
-        // TODO: Fix, currently
-        // Some blocks like `grindstone` have different states and thus its not just the name
-        //    `grindstone` for the texture to use. Another example is GrassBlock has grass_block_side,
-        //    grass_block_top, etc.
-        let mut textures = BlockTextures::default();
+        let mut textures = BlockTextures::new(models.textures());
         textures.update_batch(&asset_server, &mut images, &mut materials);
         commands.insert_resource(textures);
     }
@@ -175,7 +216,7 @@ impl BlockTextures {
         mut textures: ResMut<BlockTextures>,
         asset_server: Res<AssetServer>,
         mut images: ResMut<Assets<Image>>,
-        mut materials: ResMut<Assets<StandardMaterial>>,
+        mut materials: ResMut<Assets<BlockMaterial>>,
     ) {
         if textures.update_batch(&asset_server, &mut images, &mut materials) {
             //TODO: Setup event here instead
@@ -183,3 +224,20 @@ impl BlockTextures {
         }
     }
 }
+
+/// Pixels of the top `TEXTURE_SIZE` square of a texture, the first frame of animated ones
+fn first_frame(image: &Image) -> Option<Vec<u8>> {
+    if image.width() != TEXTURE_SIZE || image.height() < TEXTURE_SIZE {
+        return None;
+    }
+    let converted;
+    let image = match image.texture_descriptor.format {
+        TextureFormat::Rgba8UnormSrgb => image,
+        _ => {
+            converted = image.convert(TextureFormat::Rgba8UnormSrgb)?;
+            &converted
+        }
+    };
+    let len = (TEXTURE_SIZE * TEXTURE_SIZE * 4) as usize;
+    image.data.as_ref()?.get(..len).map(<[u8]>::to_vec)
+}
@@ -1,4 +1,7 @@
-use bevy::prelude::*;
+use bevy::{
+    diagnostic::{DiagnosticPath, DiagnosticsStore},
+    prelude::*,
+};
 
 use std::fmt::Write;
 
@@ -8,10 +11,10 @@ use crate::chunk::loader::ChunkLoader;
 pub struct ChunkText;
 
 impl ChunkText {
-    pub fn into_bundle(self, loader: &ChunkLoader) -> impl Bundle {
+    pub fn into_bundle(self, loader: &ChunkLoader, diagnostics: &DiagnosticsStore) -> impl Bundle {
         (
             self,
-            Text::new(Self::format_text(loader)),
+            Text::new(Self::format_text(loader, diagnostics)),
             TextFont {
                 font_size: 20.0,
                 ..Default::default()
@@ -20,7 +23,7 @@ impl ChunkText {
         )
     }
 
-    fn format_text(loader: &ChunkLoader) -> String {
+    fn format_text(loader: &ChunkLoader, diagnostics: &DiagnosticsStore) -> String {
         let mut output = "Chunks:\n".to_owned();
 
         let _ = writeln!(&mut output, "\tUnloaded: {}", loader.unloaded_chunks());
@@ -28,11 +31,34 @@ impl ChunkText {
         let _ = writeln!(&mut output, "\tRendering: {}", loader.rendering_chunks());
         let _ = writeln!(&mut output, "\tLoaded: {}", loader.loaded_chunks());
 
+        let smoothed = |path: &DiagnosticPath| {
+            diagnostics
+                .get(path)
+                .and_then(|diagnostic| diagnostic.smoothed())
+                .unwrap_or_default()
+        };
+        let _ = writeln!(
+            &mut output,
+            "\tGeneration jobs: {:.0} ({:.2} ms)",
+            smoothed(&ChunkLoader::GENERATE_QUEUE),
+            smoothed(&ChunkLoader::GENERATE_LATENCY)
+        );
+        let _ = writeln!(
+            &mut output,
+            "\tMesh jobs: {:.0} ({:.2} ms)",
+            smoothed(&ChunkLoader::MESH_QUEUE),
+            smoothed(&ChunkLoader::MESH_LATENCY)
+        );
+
         output
     }
 
-    pub fn update_text_system(mut ui: Query<&mut Text, With<ChunkText>>, loader: Res<ChunkLoader>) {
+    pub fn update_text_system(
+        mut ui: Query<&mut Text, With<ChunkText>>,
+        loader: Res<ChunkLoader>,
+        diagnostics: Res<DiagnosticsStore>,
+    ) {
         let mut ui = ui.single_mut().unwrap();
-        ui.0 = Self::format_text(&loader);
+        ui.0 = Self::format_text(&loader, &diagnostics);
     }
 }
@@ -3,7 +3,11 @@ mod chunk;
 mod title;
 use std::f32::consts::PI;
 
-use bevy::{color::palettes::css::RED, diagnostic::FrameTimeDiagnosticsPlugin, prelude::*};
+use bevy::{
+    color::palettes::css::RED,
+    diagnostic::{DiagnosticsStore, FrameTimeDiagnosticsPlugin},
+    prelude::*,
+};
 
 use crate::{
     AppState,
@@ -54,6 +58,7 @@ impl DebugMenuPlugin {
         mut commands: Commands,
         camera: Query<&Transform, With<Camera>>,
         loader: Res<ChunkLoader>,
+        diagnostics: Res<DiagnosticsStore>,
     ) {
         let camera = camera.single().unwrap();
         commands
@@ -61,7 +66,7 @@ impl DebugMenuPlugin {
             .with_children(|parent| {
                 parent.spawn(TitleText.into_bundle());
                 parent.spawn(PlayerText.into_bundle(camera));
-                parent.spawn(ChunkText.into_bundle(&loader));
+                parent.spawn(ChunkText.into_bundle(&loader, &diagnostics));
             });
     }
 
@@ -3,7 +3,9 @@ use bevy::{
     prelude::*,
 };
 
-use crate::{LoadingState, chunk::loader::ChunkLoader, textures::BlockTextures};
+use crate::{
+    LoadingState, chunk::loader::ChunkLoader, models::BlockModels, textures::BlockTextures,
+};
 
 const MARGIN: Val = Val::Px(12.);
 
@@ -57,11 +59,19 @@ impl LoadingUi {
         mut text: Query<&mut Text, With<LoadText>>,
         mut load: Query<&mut Node, With<LoadBar>>,
         state: Res<State<LoadingState>>,
+        models: Res<BlockModels>,
         textures: Res<BlockTextures>,
+        asset_server: Res<AssetServer>,
         loader: Res<ChunkLoader>,
     ) {
         match state.get() {
             LoadingState::Camera => {}
+            LoadingState::Models => {
+                let mut load = load.single_mut().unwrap();
+                let mut text = text.single_mut().unwrap();
+                text.0 = "Loading Models...".to_owned();
+                load.width = Val::Vw(LOAD_BAR_MAX * models.loading_percent(&asset_server));
+            }
             LoadingState::Textures => {
                 let mut load = load.single_mut().unwrap();
                 let mut text = text.single_mut().unwrap();
@@ -5,7 +5,7 @@ use std::{
     path::PathBuf,
 };
 
-use mcje_downloader::RootManifest;
+use mcje_downloader::{DownloadCache, RootManifest};
 use tokio::fs;
 use zip::ZipArchive;
 
@@ -13,19 +13,22 @@ use zip::ZipArchive;
 async fn main() {
     let manifest_dir = env!("CARGO_MANIFEST_DIR");
     let manifest_dir = PathBuf::from(manifest_dir);
-    if manifest_dir.join("assets").exists() {
+    let version = mcje_downloader::feature_version().expect("no version feature enabled");
+    let assets_dir = manifest_dir.join("assets").join(version);
+    println!("cargo:rustc-env=MCRE_ASSETS_DIR={}", assets_dir.display());
+    if assets_dir.exists() {
         return;
     }
     let root_manifest = RootManifest::fetch().await.unwrap();
-    let version_release = root_manifest
-        .versions
-        .into_iter()
-        .find(|ver| ver.id == "1.21.11")
-        .unwrap();
+    let version_release = root_manifest.into_version(version).unwrap();
 
     let version_manifest = version_release.fetch_manifest().await.unwrap();
 
-    let jar = version_manifest.downloads.client.download().await.unwrap();
+    let cache = DownloadCache::new(manifest_dir.join("../../target/downloads/cache"));
+    let jar = cache
+        .get_bytes(&version_manifest.downloads.client)
+        .await
+        .unwrap();
     let jar_cursor = Cursor::new(jar);
 
     let mut jar_archive = ZipArchive::new(jar_cursor).unwrap();
@@ -34,8 +37,10 @@ async fn main() {
         let mut entry = jar_archive.by_index(i).unwrap();
         let name = entry.name();
 
-        if name.starts_with("assets/minecraft") {
-            let outpath = manifest_dir.join(name);
+        if let Some(name) = name.strip_prefix("assets/")
+            && name.starts_with("minecraft")
+        {
+            let outpath = assets_dir.join(name);
             if let Some(parent) = outpath.parent()
                 && !parent.exists()
             {
@@ -4,7 +4,7 @@ use alloc::{
     boxed::Box,
     vec::{self, Vec},
 };
-use mcre_core::{BlockState, PropFilter, PropVal, Vec4f};
+use mcre_core::{BlockState, PropFilter, PropVal};
 use serde::{Deserialize, Deserializer};
 
 use crate::BlockModelId;
@@ -100,28 +100,14 @@ pub enum Condition {
 }
 
 impl RotationDegrees {
-    pub fn rotate_uv(self, uv: Vec4f) -> Vec4f {
-        let [u1, v1, u2, v2] = *uv;
-
-        // UV corners in Minecraft order:
-        // 0 = top-left, 1 = top-right, 2 = bottom-right, 3 = bottom-left
-        // but you can treat them as a loop
-        let mut pts = [(u1, v1), (u2, v1), (u2, v2), (u1, v2)];
-
+    /// Clockwise quarter turns of the rotation
+    pub fn quarter_turns(self) -> usize {
         match self {
-            Self::R0 => {}
-            Self::R90 => pts.rotate_right(1), // 1-step rotate
-            Self::R180 => pts.rotate_right(2),
-            Self::R270 => pts.rotate_right(3),
+            Self::R0 => 0,
+            Self::R90 => 1,
+            Self::R180 => 2,
+            Self::R270 => 3,
         }
-
-        // After rotation, recompute bounding box
-        let min_u = pts.iter().map(|p| p.0).fold(f32::INFINITY, f32::min);
-        let max_u = pts.iter().map(|p| p.0).fold(f32::NEG_INFINITY, f32::max);
-        let min_v = pts.iter().map(|p| p.1).fold(f32::INFINITY, f32::min);
-        let max_v = pts.iter().map(|p| p.1).fold(f32::NEG_INFINITY, f32::max);
-
-        Vec4f::new(min_u, min_v, max_u, max_v)
     }
 }
 
@@ -146,7 +132,27 @@ pub enum BlockModelResolution<'a> {
     Multipart(Box<[&'a [ModelVariant]]>),
 }
 
+impl VariantDefinition {
+    pub fn variants(&self) -> &[ModelVariant] {
+        match self {
+            VariantDefinition::Single(model) => slice::from_ref(model),
+            VariantDefinition::Multiple(models) => models,
+        }
+    }
+}
+
 impl BlockStateDefinition {
+    /// Every variant of the definition, whichever states use them
+    pub fn variants(&self) -> impl Iterator<Item = &ModelVariant> {
+        let (variants, rules) = match self {
+            Self::Variants(variants) => (&variants[..], &[][..]),
+            Self::Multipart(rules) => (&[][..], &rules[..]),
+        };
+        let variants = variants.iter().map(|variant| &variant.definition);
+        let rules = rules.iter().map(|rule| &rule.apply);
+        variants.chain(rules).flat_map(VariantDefinition::variants)
+    }
+
     pub fn resolve<'a>(&'a self, state: BlockState) -> Option<BlockModelResolution<'a>> {
         match self {
             Self::Variants(variants) => {
@@ -161,12 +167,7 @@ impl BlockStateDefinition {
                         };
                     }
 
-                    let models = match &variant.definition {
-                        VariantDefinition::Single(model) => slice::from_ref(model),
-                        VariantDefinition::Multiple(models) => models,
-                    };
-
-                    return Some(BlockModelResolution::Unified(models));
+                    return Some(BlockModelResolution::Unified(variant.definition.variants()));
                 }
 
                 None
@@ -182,12 +183,7 @@ impl BlockStateDefinition {
                     };
 
                     if condition_met {
-                        match &rule.apply {
-                            VariantDefinition::Single(model) => {
-                                resolved_models.push(slice::from_ref(model))
-                            }
-                            VariantDefinition::Multiple(models) => resolved_models.push(models),
-                        }
+                        resolved_models.push(rule.apply.variants());
                     }
                 }
 
@@ -212,9 +208,8 @@ mod tests {
 
     #[tokio::test]
     async fn test_parse_and_resolve_block_state_definition() {
-        let manifest_dir = env!("CARGO_MANIFEST_DIR");
-        let manifest_dir = PathBuf::from(manifest_dir);
-        let root_dir = manifest_dir.join("assets/minecraft/blockstates");
+        let assets_dir = PathBuf::from(env!("MCRE_ASSETS_DIR"));
+        let root_dir = assets_dir.join("minecraft/blockstates");
 
         let mut total = 0;
         let mut passed = 0;
@@ -137,6 +137,10 @@ impl ReferenceId {
     pub fn new(id: String) -> Self {
         ReferenceId(id)
     }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
 }
 
 impl Display for ReferenceId {
@@ -13,4 +13,8 @@ pub use models::*;
 use hashbrown::HashMap;
 use rustc_hash::FxBuildHasher;
 
+/// Directory of the extracted vanilla assets, holding the blockstates, models and textures under
+/// `minecraft/`
+pub const ASSETS_DIR: &str = env!("MCRE_ASSETS_DIR");
+
 pub(crate) type FxHashMap<K, V> = HashMap<K, V, FxBuildHasher>;
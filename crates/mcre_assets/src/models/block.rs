@@ -1,4 +1,4 @@
-use core::array;
+use core::{array, fmt, iter};
 
 use alloc::{
     string::{String, ToString},
@@ -9,6 +9,9 @@ use serde::Deserialize;
 
 use crate::{BlockModelId, FxHashMap, RefOr, ReferenceId, RotationDegrees, TextureId};
 
+/// Parents followed before a model is considered to inherit from itself
+const MAX_PARENTS: usize = 32;
+
 #[derive(Debug, Clone)]
 pub struct BlockModelDefinition {
     pub gui_light: Option<GuiLight>,
@@ -58,6 +61,7 @@ fn default_shade() -> bool {
 pub struct BlockModelElementRotation {
     pub origin: Vec3f,
     pub axis: Axis,
+    /// In degrees
     pub angle: f32,
     #[serde(default)]
     pub rescale: bool,
@@ -77,15 +81,11 @@ pub struct BlockModelFace {
 impl BlockModelElementRotation {
     pub fn apply_on_point(&self, point: Vec3f) -> Vec3f {
         let mut point = point - self.origin;
-        let (sin, cos) = self.angle.sin_cos();
+        let (sin, cos) = self.angle.to_radians().sin_cos();
 
         // Minecraft Java Edition Rescale Logic
-        // Formula: 1.0 / (|cos(angle)| + |sin(angle)|)
-        let scale = if self.rescale {
-            1.0 / (cos.abs() + sin.abs())
-        } else {
-            1.0
-        };
+        // The rotated faces are stretched back across the whole block: 1.0 / cos(angle)
+        let scale = if self.rescale { 1.0 / cos.abs() } else { 1.0 };
 
         match self.axis {
             Axis::X => {
@@ -113,51 +113,96 @@ impl BlockModelElementRotation {
         point + self.origin
     }
 
-    // apply_on_quad remains the same, but must now also pass 'from' and 'to'
     pub fn apply_on_quad(&self, quad: [Vec3f; 4]) -> [Vec3f; 4] {
         array::from_fn(|i| self.apply_on_point(quad[i]))
     }
 }
 
-fn build_quad(min: Vec3f, max: Vec3f, dir: Direction) -> [Vec3f; 4] {
-    // 1. The two axes that span the rectangle
-    let [a1, a2] = dir.axis().complementary_axes();
+/// Texture coordinates of `point` on a face looking towards `direction` when the face doesn't
+/// set its `uv`, in pixels. The texture is upright seen from the front of the face, and the top
+/// faces have north at the top.
+pub fn default_uv(direction: Direction, point: Vec3f) -> [f32; 2] {
+    let [x, y, z] = *point;
+    match direction {
+        Direction::Down => [x, 16.0 - z],
+        Direction::Up => [x, z],
+        Direction::North => [16.0 - x, 16.0 - y],
+        Direction::South => [x, 16.0 - y],
+        Direction::West => [z, 16.0 - y],
+        Direction::East => [16.0 - z, 16.0 - y],
+    }
+}
 
-    // 2. Get min/max for variable axes
-    let range = |axis: Axis| -> (f32, f32) {
-        let min = axis.select(min);
-        let max = axis.select(max);
-        (min, max)
-    };
+/// The point of the plane at `depth` along the axis of `direction` with `uv` as default texture
+/// coordinates, the inverse of `default_uv`
+fn face_point(direction: Direction, depth: f32, [u, v]: [f32; 2]) -> Vec3f {
+    match direction {
+        Direction::Down => Vec3f::new(u, depth, 16.0 - v),
+        Direction::Up => Vec3f::new(u, depth, v),
+        Direction::North => Vec3f::new(16.0 - u, 16.0 - v, depth),
+        Direction::South => Vec3f::new(u, 16.0 - v, depth),
+        Direction::West => Vec3f::new(depth, 16.0 - v, u),
+        Direction::East => Vec3f::new(depth, 16.0 - v, 16.0 - u),
+    }
+}
 
-    let (a1_min, a1_max) = range(a1);
-    let (a2_min, a2_max) = range(a2);
+/// Corners of the `dir` face of the box from `min` to `max`, counter-clockwise seen from the front
+/// starting at the top left of the texture, and the default uv rectangle of the face
+fn build_quad(min: Vec3f, max: Vec3f, dir: Direction) -> ([Vec3f; 4], Vec4f) {
+    let depth = dir.axis().select(if dir.is_positive() { max } else { min });
+    let ([u1, v1], [u2, v2]) = (default_uv(dir, min), default_uv(dir, max));
+    let (u1, u2) = (u1.min(u2), u1.max(u2));
+    let (v1, v2) = (v1.min(v2), v1.max(v2));
+
+    let corners = [[u1, v1], [u1, v2], [u2, v2], [u2, v1]];
+    (
+        corners.map(|uv| face_point(dir, depth, uv)),
+        Vec4f::new(u1, v1, u2, v2),
+    )
+}
 
-    // 3. Build the quad (CCW)
-    fn make(axis: Axis, v: f32, x: f32, y: f32, z: f32) -> Vec3f {
-        let mut out = Vec3f::new(x, y, z);
-        *axis.select_mut(&mut out) = v;
-        out
-    }
+/// Texture coordinates of the corners of a quad from `build_quad`, from the `uv` rectangle of its
+/// face turned clockwise by `rotation`
+fn quad_uvs(uv: Vec4f, rotation: RotationDegrees) -> [[f32; 2]; 4] {
+    let [u1, v1, u2, v2] = *uv;
+    // top left, top right, bottom right and bottom left of the texture
+    let corners = [[u1, v1], [u2, v1], [u2, v2], [u1, v2]];
+    let turns = rotation.quarter_turns();
+    // the corners of `build_quad` go top left, bottom left, bottom right then top right
+    [0, 3, 2, 1].map(|corner| corners[(corner + 4 - turns) % 4])
+}
 
-    let axis = dir.axis();
-    let v = if dir.is_positive() {
-        axis.select(max)
-    } else {
-        axis.select(min)
-    };
+/// `direction` turned 90 degrees around the X axis, the way variants rotate: up becomes north
+fn rotate_x(direction: Direction) -> Direction {
+    match direction {
+        Direction::Up => Direction::North,
+        Direction::North => Direction::Down,
+        Direction::Down => Direction::South,
+        Direction::South => Direction::Up,
+        horizontal => horizontal,
+    }
+}
 
-    [
-        make(axis, v, a1_min, a2_min, 0.0),
-        make(axis, v, a1_min, a2_max, 0.0),
-        make(axis, v, a1_max, a2_max, 0.0),
-        make(axis, v, a1_max, a2_min, 0.0),
-    ]
+/// `direction` turned 90 degrees around the Y axis, the way variants rotate: north becomes east
+fn rotate_y(direction: Direction) -> Direction {
+    match direction {
+        Direction::North => Direction::East,
+        Direction::East => Direction::South,
+        Direction::South => Direction::West,
+        Direction::West => Direction::North,
+        vertical => vertical,
+    }
 }
 
+/// A face of a baked model, in pixels from 0 to 16 across the block
+#[derive(Debug, Clone)]
 pub struct BakedQuad {
+    /// Corners counter-clockwise seen from the front of the face
     pub vertices: [Vec3f; 4],
-    pub uv: Vec4f,
+    /// Texture coordinates of each corner, in pixels
+    pub uvs: [[f32; 2]; 4],
+    /// The direction the face looks towards, ignoring the rotation of its element
+    pub direction: Direction,
     pub texture: TextureId,
     pub cullface: Option<Direction>,
     pub tintindex: Option<u8>,
@@ -165,31 +210,101 @@ pub struct BakedQuad {
     pub light_emission: u8,
 }
 
+impl BakedQuad {
+    /// Rotates the quad around the center of the block by the `x` then `y` rotation of a
+    /// blockstate variant. With `uvlock` the texture keeps its orientation in the world instead of
+    /// turning with the quad, its coordinates are projected again from the rotated corners.
+    pub fn rotate(&mut self, x: RotationDegrees, y: RotationDegrees, uvlock: bool) {
+        for _ in 0..x.quarter_turns() {
+            self.vertices = self
+                .vertices
+                .map(|vertex| Vec3f::new(vertex[0], vertex[2], 16.0 - vertex[1]));
+            self.direction = rotate_x(self.direction);
+            self.cullface = self.cullface.map(rotate_x);
+        }
+        for _ in 0..y.quarter_turns() {
+            self.vertices = self
+                .vertices
+                .map(|vertex| Vec3f::new(16.0 - vertex[2], vertex[1], vertex[0]));
+            self.direction = rotate_y(self.direction);
+            self.cullface = self.cullface.map(rotate_y);
+        }
+        if uvlock {
+            self.uvs = self
+                .vertices
+                .map(|vertex| default_uv(self.direction, vertex));
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
 pub enum ModelBakeError {
     TextureNotFound(String),
     ParentNotFound(String),
+    /// The parents go on for more than `MAX_PARENTS` models, they probably loop
+    TooManyParents(String),
+}
+
+impl fmt::Display for ModelBakeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TextureNotFound(id) => write!(f, "texture {id} not found"),
+            Self::ParentNotFound(id) => write!(f, "parent model {id} not found"),
+            Self::TooManyParents(id) => write!(f, "too many parents above model {id}"),
+        }
+    }
 }
 
 impl BlockModelDefinition {
-    fn _build_texture_map<F>(
-        &self,
-        parent_resolver: F,
-        texture_map: &mut FxHashMap<ReferenceId, TextureId>,
-    ) -> Result<(), ModelBakeError>
+    /// The parent, grandparent and so on of the model
+    fn ancestors<F>(&self, parent_resolver: F) -> Result<Vec<BlockModelDefinition>, ModelBakeError>
     where
         F: Fn(&BlockModelId) -> Option<BlockModelDefinition>,
     {
-        for (name, texture) in &self.textures {
-            if let RefOr::Value(texture_id) = texture {
-                texture_map.insert(ReferenceId::new(name.clone()), texture_id.clone());
+        let mut ancestors: Vec<BlockModelDefinition> = Vec::new();
+        let mut parent = self.parent.clone();
+        while let Some(parent_id) = parent {
+            if ancestors.len() == MAX_PARENTS {
+                return Err(ModelBakeError::TooManyParents(parent_id.to_string()));
             }
+            let model = parent_resolver(&parent_id)
+                .ok_or_else(|| ModelBakeError::ParentNotFound(parent_id.to_string()))?;
+            parent = model.parent.clone();
+            ancestors.push(model);
+        }
+        Ok(ancestors)
+    }
+
+    /// Textures of the model and its parents, with references to other textures resolved. A
+    /// texture of the model replaces the one of the same name in its parents.
+    fn texture_map(ancestors: &[&BlockModelDefinition]) -> FxHashMap<ReferenceId, TextureId> {
+        let mut textures = FxHashMap::default();
+        for model in ancestors.iter().rev() {
+            textures.extend(
+                model
+                    .textures
+                    .iter()
+                    .map(|(name, texture)| (name.as_str(), texture)),
+            );
         }
 
-        self.parent.as_ref().map_or(Ok(()), |parent_id| {
-            parent_resolver(parent_id)
-                .ok_or_else(|| ModelBakeError::ParentNotFound(parent_id.to_string()))
-                .and_then(|parent| parent._build_texture_map(parent_resolver, texture_map))
-        })
+        let mut texture_map = FxHashMap::default();
+        for (name, mut texture) in textures.iter().map(|(name, texture)| (*name, *texture)) {
+            // follow references, giving up on ones missing or looping
+            for _ in 0..textures.len() {
+                let RefOr::Ref(reference) = texture else {
+                    break;
+                };
+                match textures.get(reference.as_str()) {
+                    Some(next) => texture = next,
+                    None => break,
+                }
+            }
+            if let RefOr::Value(texture_id) = texture {
+                texture_map.insert(ReferenceId::new(name.to_string()), texture_id.clone());
+            }
+        }
+        texture_map
     }
 
     pub fn build_texture_map<F>(
@@ -199,19 +314,28 @@ impl BlockModelDefinition {
     where
         F: Fn(&BlockModelId) -> Option<BlockModelDefinition>,
     {
-        let mut texture_map = FxHashMap::default();
-        self._build_texture_map(parent_resolver, &mut texture_map)?;
-        Ok(texture_map)
+        let ancestors = self.ancestors(parent_resolver)?;
+        Ok(Self::texture_map(
+            &iter::once(self).chain(&ancestors).collect::<Vec<_>>(),
+        ))
     }
 
-    pub fn bake<F, E>(&self, parent_resolver: F) -> Result<(), ModelBakeError>
+    /// The faces of the elements of the model, or of its closest parent with elements
+    pub fn bake<F>(&self, parent_resolver: F) -> Result<Vec<BakedQuad>, ModelBakeError>
     where
         F: Fn(&BlockModelId) -> Option<BlockModelDefinition>,
     {
-        let texture_map = self.build_texture_map(parent_resolver)?;
+        let ancestors = self.ancestors(parent_resolver)?;
+        let models = iter::once(self).chain(&ancestors).collect::<Vec<_>>();
+        let texture_map = Self::texture_map(&models);
+        let elements = models
+            .iter()
+            .map(|model| &model.elements)
+            .find(|elements| !elements.is_empty())
+            .map_or(&[][..], |elements| elements);
 
         let mut quads = Vec::new();
-        for element in &self.elements {
+        for element in elements {
             let min = Vec3f::new(
                 element.from[0].min(element.to[0]),
                 element.from[1].min(element.to[1]),
@@ -224,14 +348,13 @@ impl BlockModelDefinition {
             );
             for direction in Direction::ALL {
                 if let Some(face) = element.faces.get(&direction) {
-                    let quad_vertices = build_quad(min, max, direction);
+                    let (quad_vertices, default_uv) = build_quad(min, max, direction);
                     let rotated_quad_vertices = if let Some(rotation) = &element.rotation {
                         rotation.apply_on_quad(quad_vertices)
                     } else {
                         quad_vertices
                     };
-                    let uv = face.uv.unwrap_or(Vec4f::new(0.0, 0.0, 16.0, 16.0));
-                    let rotated_uv = face.rotation.rotate_uv(uv);
+                    let uvs = quad_uvs(face.uv.unwrap_or(default_uv), face.rotation);
 
                     let texture = match &face.texture {
                         RefOr::Ref(id) => {
@@ -246,7 +369,8 @@ impl BlockModelDefinition {
 
                     quads.push(BakedQuad {
                         vertices: rotated_quad_vertices,
-                        uv: rotated_uv,
+                        uvs,
+                        direction,
                         texture,
                         tintindex: face.tintindex,
                         cullface: face.cullface,
@@ -257,7 +381,7 @@ impl BlockModelDefinition {
             }
         }
 
-        Ok(())
+        Ok(quads)
     }
 }
 
@@ -268,13 +392,198 @@ mod tests {
         path::PathBuf,
     };
 
-    use crate::{FxHashMap, block::BlockModelDefinition};
+    use mcre_core::{Direction, Vec3f};
+
+    use crate::{
+        BlockModelId, FxHashMap, RotationDegrees, TextureId,
+        block::{BakedQuad, BlockModelDefinition, default_uv},
+    };
+
+    /// A cube with a texture per face and `cube_all` using the `all` texture on every face, like
+    /// the vanilla models
+    fn parent(id: &BlockModelId) -> Option<BlockModelDefinition> {
+        let json = match id.id.as_str() {
+            "cube" => {
+                r##"{
+                    "elements": [{
+                        "from": [0, 0, 0],
+                        "to": [16, 16, 16],
+                        "faces": {
+                            "down": { "texture": "#down", "cullface": "down" },
+                            "up": { "texture": "#up", "cullface": "up" },
+                            "north": { "texture": "#north", "cullface": "north" },
+                            "south": { "texture": "#south", "cullface": "south" },
+                            "west": { "texture": "#west", "cullface": "west" },
+                            "east": { "texture": "#east", "cullface": "east" }
+                        }
+                    }]
+                }"##
+            }
+            "cube_all" => {
+                r##"{
+                    "parent": "block/cube",
+                    "textures": {
+                        "particle": "#all",
+                        "down": "#all",
+                        "up": "#all",
+                        "north": "#all",
+                        "south": "#all",
+                        "west": "#all",
+                        "east": "#all"
+                    }
+                }"##
+            }
+            _ => return None,
+        };
+        Some(serde_json::from_str(json).unwrap())
+    }
+
+    fn bake(json: &str) -> Vec<BakedQuad> {
+        let model: BlockModelDefinition = serde_json::from_str(json).unwrap();
+        model.bake(parent).ok().unwrap()
+    }
+
+    fn texture(quad: &BakedQuad) -> &str {
+        match &quad.texture {
+            TextureId::Block(id) => &id.id,
+            TextureId::Item(id) => &id.id,
+        }
+    }
+
+    fn quad(quads: &[BakedQuad], direction: Direction) -> &BakedQuad {
+        quads
+            .iter()
+            .find(|quad| quad.direction == direction)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_bake_parents() {
+        let quads = bake(
+            r#"{ "parent": "block/cube_all", "textures": { "all": "block/stone", "up": "block/dirt" } }"#,
+        );
+        assert_eq!(quads.len(), 6);
+        for quad in &quads {
+            assert_eq!(quad.cullface, Some(quad.direction));
+            let expected = match quad.direction {
+                Direction::Up => "dirt",
+                _ => "stone",
+            };
+            assert_eq!(texture(quad), expected);
+        }
+
+        let missing: BlockModelDefinition =
+            serde_json::from_str(r#"{ "parent": "block/cube" }"#).unwrap();
+        assert!(missing.bake(parent).is_err());
+    }
+
+    #[test]
+    fn test_bake_faces() {
+        let quads = bake(r#"{ "parent": "block/cube_all", "textures": { "all": "block/stone" } }"#);
+        for quad in &quads {
+            // counter-clockwise seen from outside the block
+            let [a, b, c, _] = quad.vertices;
+            let (ab, ac) = (b - a, c - a);
+            let normal = Vec3f::new(
+                ab[1] * ac[2] - ab[2] * ac[1],
+                ab[2] * ac[0] - ab[0] * ac[2],
+                ab[0] * ac[1] - ab[1] * ac[0],
+            );
+            let axis = quad.direction.axis();
+            let sign = if quad.direction.is_positive() {
+                1.0
+            } else {
+                -1.0
+            };
+            assert_eq!(axis.select(normal) * sign, 256.0, "{:?}", quad.direction);
+
+            for (vertex, uv) in quad.vertices.into_iter().zip(quad.uvs) {
+                assert_eq!(uv, default_uv(quad.direction, vertex));
+            }
+        }
+    }
+
+    #[test]
+    fn test_bake_face_uv() {
+        let quads = bake(
+            r##"{
+                "textures": { "side": "block/stone" },
+                "elements": [{
+                    "from": [0, 0, 0],
+                    "to": [16, 8, 16],
+                    "faces": {
+                        "north": { "texture": "#side" },
+                        "up": { "texture": "#side", "uv": [0, 0, 16, 8], "rotation": 90 }
+                    }
+                }]
+            }"##,
+        );
+        // the bottom half of the texture on the side of a slab
+        let north = quad(&quads, Direction::North);
+        assert!(north.uvs.iter().all(|&[_, v]| v >= 8.0));
+
+        // the top left of the texture turns to the top right corner, north east
+        let up = quad(&quads, Direction::Up);
+        let (corner, _) = up
+            .vertices
+            .iter()
+            .zip(up.uvs)
+            .find(|(_, uv)| *uv == [0.0, 0.0])
+            .unwrap();
+        assert_eq!(*corner, Vec3f::new(16.0, 8.0, 0.0));
+    }
+
+    #[test]
+    fn test_bake_element_rotation() {
+        // a cross plane turned 45 degrees and stretched back across the block
+        let quads = bake(
+            r##"{
+                "textures": { "cross": "block/poppy" },
+                "elements": [{
+                    "from": [0.8, 0, 8],
+                    "to": [15.2, 16, 8],
+                    "rotation": { "origin": [8, 8, 8], "axis": "y", "angle": 45, "rescale": true },
+                    "faces": { "north": { "texture": "#cross" } }
+                }]
+            }"##,
+        );
+        let xs = quads[0].vertices.map(|vertex| vertex[0]);
+        let zs = quads[0].vertices.map(|vertex| vertex[2]);
+        let max = |values: [f32; 4]| values.into_iter().fold(f32::MIN, f32::max);
+        let min = |values: [f32; 4]| values.into_iter().fold(f32::MAX, f32::min);
+        assert!((max(xs) - min(xs) - 14.4).abs() < 1e-3);
+        assert!((max(zs) - min(zs) - 14.4).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_rotate() {
+        let quads = bake(r#"{ "parent": "block/cube_all", "textures": { "all": "block/stone" } }"#);
+        let mut north = quad(&quads, Direction::North).clone();
+        north.rotate(RotationDegrees::R0, RotationDegrees::R90, false);
+        assert_eq!(north.direction, Direction::East);
+        assert_eq!(north.cullface, Some(Direction::East));
+        assert!(north.vertices.iter().all(|vertex| vertex[0] == 16.0));
+
+        let mut up = quad(&quads, Direction::Up).clone();
+        up.rotate(RotationDegrees::R90, RotationDegrees::R0, false);
+        assert_eq!(up.direction, Direction::North);
+        assert!(up.vertices.iter().all(|vertex| vertex[2] == 0.0));
+
+        // the top of the block turned a quarter keeps its texture pointing north with uvlock
+        let mut locked = quad(&quads, Direction::Up).clone();
+        locked.rotate(RotationDegrees::R0, RotationDegrees::R90, true);
+        let mut turned = quad(&quads, Direction::Up).clone();
+        turned.rotate(RotationDegrees::R0, RotationDegrees::R90, false);
+        for (vertex, uv) in locked.vertices.into_iter().zip(locked.uvs) {
+            assert_eq!(uv, default_uv(Direction::Up, vertex));
+        }
+        assert_ne!(locked.uvs, turned.uvs);
+    }
 
     #[tokio::test]
     async fn test_parse_block_model_definition() {
-        let manifest_dir = env!("CARGO_MANIFEST_DIR");
-        let manifest_dir = PathBuf::from(manifest_dir);
-        let root_dir = manifest_dir.join("assets/minecraft/models/block");
+        let assets_dir = PathBuf::from(env!("MCRE_ASSETS_DIR"));
+        let root_dir = assets_dir.join("minecraft/models/block");
 
         let mut total = 0;
         let mut passed = 0;
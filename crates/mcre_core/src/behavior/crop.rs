@@ -0,0 +1,58 @@
+use crate::{
+    Block, BlockBehavior, BlockGetter, BlockPos, BlockState, Direction, LevelAccess, PropVal,
+    RandomSource, UpdateFlags,
+};
+
+/// Crops planted on farmland, growing one age step at a time.
+///
+/// Growth speed only considers the farmland directly below, neighboring farmland and light are
+/// not taken into account yet.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct CropBehavior;
+
+impl CropBehavior {
+    fn growth_speed(level: &dyn BlockGetter, pos: BlockPos) -> f32 {
+        let below = level.get_block_state(pos.below());
+        match below.block() {
+            Block::FARMLAND if below.moisture() > 0 => 4.0,
+            Block::FARMLAND => 2.0,
+            _ => 1.0,
+        }
+    }
+}
+
+impl BlockBehavior for CropBehavior {
+    fn update_shape(
+        &self,
+        state: BlockState,
+        _level: &dyn BlockGetter,
+        _pos: BlockPos,
+        direction: Direction,
+        _neighbor_pos: BlockPos,
+        neighbor_state: BlockState,
+    ) -> BlockState {
+        if direction == Direction::Down && neighbor_state.block() != Block::FARMLAND {
+            Block::AIR.default_state()
+        } else {
+            state
+        }
+    }
+
+    fn random_tick(
+        &self,
+        state: BlockState,
+        level: &mut dyn LevelAccess,
+        pos: BlockPos,
+        random: &mut dyn RandomSource,
+    ) {
+        let speed = Self::growth_speed(level, pos);
+        if random.next_int_bounded((25.0 / speed) as i32 + 1) != 0 {
+            return;
+        }
+
+        // ages past the maximum of the crop don't exist, so fully grown crops stay as they are
+        if let Some(grown) = state.with_prop(PropVal::Age(state.age() + 1)) {
+            level.set_block_state(pos, grown, UpdateFlags::CLIENTS);
+        }
+    }
+}
@@ -0,0 +1,48 @@
+use crate::{BlockBehavior, BlockPos, BlockState, LevelAccess, Vec3f};
+
+/// The parts of an entity blocks can act on, with no-op defaults.
+pub trait EntityAccess {
+    /// Slows the entity down by `multiplier` for the current tick.
+    fn make_stuck_in_block(&mut self, state: BlockState, multiplier: Vec3f) {
+        let _ = (state, multiplier);
+    }
+
+    /// Deals `amount` damage, returning whether it was applied.
+    fn hurt(&mut self, amount: f32) -> bool {
+        let _ = amount;
+        false
+    }
+}
+
+#[derive(Debug, Copy, Clone, Default)]
+pub struct CobwebBehavior;
+
+impl BlockBehavior for CobwebBehavior {
+    fn entity_inside(
+        &self,
+        state: BlockState,
+        _level: &mut dyn LevelAccess,
+        _pos: BlockPos,
+        entity: &mut dyn EntityAccess,
+    ) {
+        entity.make_stuck_in_block(state, Vec3f::new(0.25, 0.05, 0.25));
+    }
+}
+
+#[derive(Debug, Copy, Clone, Default)]
+pub struct SweetBerryBushBehavior;
+
+impl BlockBehavior for SweetBerryBushBehavior {
+    fn entity_inside(
+        &self,
+        state: BlockState,
+        _level: &mut dyn LevelAccess,
+        _pos: BlockPos,
+        entity: &mut dyn EntityAccess,
+    ) {
+        entity.make_stuck_in_block(state, Vec3f::new(0.8, 0.75, 0.8));
+        if state.age() > 0 {
+            entity.hurt(1.0);
+        }
+    }
+}
@@ -0,0 +1,164 @@
+use crate::{
+    Block, BlockBehavior, BlockGetter, BlockPos, BlockState, Direction, LevelAccess, PropVal,
+    RandomSource, UpdateFlags,
+};
+
+const HORIZONTAL: [Direction; 4] = [
+    Direction::North,
+    Direction::South,
+    Direction::West,
+    Direction::East,
+];
+
+/// A flowing fluid, stored in the `level` property: `0` is a source, `1..=7` flowing with
+/// decreasing height and `8..` falling.
+///
+/// Spreading goes to every open side, vanilla's search for the nearest slope is not implemented.
+#[derive(Debug, Copy, Clone)]
+pub struct FluidBehavior {
+    block: Block,
+    drop_off: u8,
+    tick_delay: u32,
+    /// Whether two neighboring sources turn flowing fluid into a new source.
+    infinite: bool,
+}
+
+impl FluidBehavior {
+    pub const WATER: Self = Self {
+        block: Block::WATER,
+        drop_off: 1,
+        tick_delay: 5,
+        infinite: true,
+    };
+
+    pub const LAVA: Self = Self {
+        block: Block::LAVA,
+        drop_off: 2,
+        tick_delay: 30,
+        infinite: false,
+    };
+
+    /// Height of the fluid in eighths of a block.
+    fn amount(state: BlockState) -> u8 {
+        match state.level() {
+            0 | 8.. => 8,
+            level => 8 - level,
+        }
+    }
+
+    fn is_source(&self, state: BlockState) -> bool {
+        state.block() == self.block && state.level() == 0
+    }
+
+    fn can_flow_into(level: &dyn LevelAccess, pos: BlockPos) -> bool {
+        let state = level.get_block_state(pos);
+        let is_fluid = level.behaviors().get(state.block()).is_fluid(state);
+        state.is_air() || (state.replaceable() && !is_fluid)
+    }
+
+    fn with_level(&self, level: u8) -> BlockState {
+        self.block
+            .default_state()
+            .with_prop(PropVal::Level(level))
+            .expect("fluids have levels 0 to 15")
+    }
+
+    /// The state a non-source fluid at `pos` should have, based on what feeds it.
+    fn new_state(&self, level: &dyn BlockGetter, pos: BlockPos) -> BlockState {
+        if level.get_block_state(pos.above()).block() == self.block {
+            return self.with_level(8);
+        }
+
+        let mut sources = 0;
+        let mut amount = 0;
+        for direction in HORIZONTAL {
+            let neighbor = level.get_block_state(pos.relative(direction));
+            if neighbor.block() == self.block {
+                sources += self.is_source(neighbor) as u8;
+                amount = amount.max(Self::amount(neighbor));
+            }
+        }
+
+        if self.infinite && sources >= 2 {
+            let below = level.get_block_state(pos.below());
+            if self.is_source(below) || !(below.is_air() || below.replaceable()) {
+                return self.with_level(0);
+            }
+        }
+
+        match amount.checked_sub(self.drop_off) {
+            Some(amount @ 1..) => self.with_level(8 - amount),
+            _ => Block::AIR.default_state(),
+        }
+    }
+
+    fn spread(&self, state: BlockState, level: &mut dyn LevelAccess, pos: BlockPos) {
+        let below = pos.below();
+        if Self::can_flow_into(level, below) {
+            level.set_block_state(below, self.with_level(8), UpdateFlags::ALL);
+            if !self.is_source(state) {
+                return;
+            }
+        }
+
+        let amount = match Self::amount(state).checked_sub(self.drop_off) {
+            Some(amount @ 1..) => amount,
+            _ => return,
+        };
+
+        for direction in HORIZONTAL {
+            let neighbor = pos.relative(direction);
+            if Self::can_flow_into(level, neighbor) {
+                level.set_block_state(neighbor, self.with_level(8 - amount), UpdateFlags::ALL);
+            }
+        }
+    }
+}
+
+impl BlockBehavior for FluidBehavior {
+    fn is_fluid(&self, _state: BlockState) -> bool {
+        true
+    }
+
+    fn on_place(
+        &self,
+        _state: BlockState,
+        level: &mut dyn LevelAccess,
+        pos: BlockPos,
+        _old_state: BlockState,
+    ) {
+        level.schedule_tick(pos, self.block, self.tick_delay);
+    }
+
+    fn neighbor_changed(
+        &self,
+        _state: BlockState,
+        level: &mut dyn LevelAccess,
+        pos: BlockPos,
+        _source: Block,
+        _source_pos: BlockPos,
+    ) {
+        level.schedule_tick(pos, self.block, self.tick_delay);
+    }
+
+    fn scheduled_tick(
+        &self,
+        mut state: BlockState,
+        level: &mut dyn LevelAccess,
+        pos: BlockPos,
+        _random: &mut dyn RandomSource,
+    ) {
+        if !self.is_source(state) {
+            let new_state = self.new_state(level, pos);
+            if new_state != state {
+                level.set_block_state(pos, new_state, UpdateFlags::ALL);
+                if new_state.is_air() {
+                    return;
+                }
+                state = new_state;
+            }
+        }
+
+        self.spread(state, level, pos);
+    }
+}
@@ -0,0 +1,273 @@
+use core::cmp::{Ordering, Reverse};
+
+use alloc::collections::BinaryHeap;
+use hashbrown::HashSet;
+use rustc_hash::FxBuildHasher;
+
+use crate::{
+    Block, BlockBehaviors, BlockGetter, BlockPos, BlockState, Direction, EntityAccess,
+    InteractionResult, LevelAccess, LevelHeightAccessor, LevelWriter, RandomSource, UpdateFlags,
+};
+
+/// How deep shape updates may cascade before further ones are dropped, like vanilla.
+const MAX_UPDATE_DEPTH: u32 = 512;
+
+/// Ticks scheduled by block behaviors, ordered by due time and then by scheduling order.
+#[derive(Debug, Clone, Default)]
+pub struct ScheduledTicks {
+    time: u64,
+    sequence: u64,
+    pending: BinaryHeap<Reverse<PendingTick>>,
+    scheduled: HashSet<(BlockPos, Block), FxBuildHasher>,
+}
+
+#[derive(Debug, Clone)]
+struct PendingTick {
+    due: u64,
+    sequence: u64,
+    pos: BlockPos,
+    block: Block,
+}
+
+/// A storage bound to [`BlockBehaviors`], dispatching hooks for every change made through it.
+pub struct BehaviorLevel<'a, L> {
+    behaviors: &'a BlockBehaviors,
+    storage: &'a mut L,
+    ticks: &'a mut ScheduledTicks,
+    depth: u32,
+}
+
+impl ScheduledTicks {
+    /// Number of ticks processed so far.
+    pub fn time(&self) -> u64 {
+        self.time
+    }
+
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    pub fn has_scheduled(&self, pos: BlockPos, block: Block) -> bool {
+        self.scheduled.contains(&(pos, block))
+    }
+
+    fn schedule(&mut self, pos: BlockPos, block: Block, delay: u32) {
+        if !self.scheduled.insert((pos, block)) {
+            return;
+        }
+
+        let due = self.time + delay as u64;
+        self.pending.push(Reverse(PendingTick {
+            due,
+            sequence: self.sequence,
+            pos,
+            block,
+        }));
+        self.sequence += 1;
+    }
+
+    fn pop_due(&mut self) -> Option<(BlockPos, Block)> {
+        if self.pending.peek()?.0.due > self.time {
+            return None;
+        }
+
+        let Reverse(PendingTick { pos, block, .. }) = self.pending.pop()?;
+        self.scheduled.remove(&(pos, block));
+        Some((pos, block))
+    }
+}
+
+impl PendingTick {
+    fn key(&self) -> (u64, u64) {
+        (self.due, self.sequence)
+    }
+}
+
+impl PartialEq for PendingTick {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for PendingTick {}
+
+impl PartialOrd for PendingTick {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PendingTick {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key().cmp(&other.key())
+    }
+}
+
+impl<'a, L> BehaviorLevel<'a, L>
+where
+    L: BlockGetter + LevelWriter,
+{
+    pub(super) fn new(
+        behaviors: &'a BlockBehaviors,
+        storage: &'a mut L,
+        ticks: &'a mut ScheduledTicks,
+    ) -> Self {
+        Self {
+            behaviors,
+            storage,
+            ticks,
+            depth: 0,
+        }
+    }
+
+    /// Advances time by one tick and runs every scheduled tick that became due.
+    pub fn tick(&mut self, random: &mut dyn RandomSource) {
+        self.ticks.time += 1;
+
+        while let Some((pos, block)) = self.ticks.pop_due() {
+            let state = self.get_block_state(pos);
+            if state.block() == block {
+                let behaviors = self.behaviors;
+                behaviors
+                    .get(block)
+                    .scheduled_tick(state, self, pos, random);
+            }
+        }
+    }
+
+    pub fn random_tick(&mut self, pos: BlockPos, random: &mut dyn RandomSource) {
+        let state = self.get_block_state(pos);
+        if state.is_randomly_ticking() {
+            let behaviors = self.behaviors;
+            behaviors
+                .get(state.block())
+                .random_tick(state, self, pos, random);
+        }
+    }
+
+    pub fn use_without_item(&mut self, pos: BlockPos, hit: Direction) -> InteractionResult {
+        let state = self.get_block_state(pos);
+        let behaviors = self.behaviors;
+        behaviors
+            .get(state.block())
+            .use_without_item(state, self, pos, hit)
+    }
+
+    pub fn entity_inside(&mut self, pos: BlockPos, entity: &mut dyn EntityAccess) {
+        let state = self.get_block_state(pos);
+        let behaviors = self.behaviors;
+        behaviors
+            .get(state.block())
+            .entity_inside(state, self, pos, entity);
+    }
+
+    fn update_neighbor_shapes(&mut self, pos: BlockPos, state: BlockState, flags: UpdateFlags) {
+        for direction in Direction::ALL {
+            let neighbor_pos = pos.relative(direction);
+            let neighbor = self.get_block_state(neighbor_pos);
+            let updated = self.behaviors.get(neighbor.block()).update_shape(
+                neighbor,
+                self,
+                neighbor_pos,
+                direction.opposite(),
+                pos,
+                state,
+            );
+
+            if updated != neighbor {
+                // a destroyed block notifies its own neighbors, a reshaped one doesn't
+                let flags = if updated.is_air() {
+                    UpdateFlags::ALL
+                } else {
+                    flags.without(UpdateFlags::NEIGHBORS)
+                };
+                self.set_block_state(neighbor_pos, updated, flags);
+            }
+        }
+    }
+}
+
+impl<L> BlockGetter for BehaviorLevel<'_, L>
+where
+    L: BlockGetter,
+{
+    fn get_block_state(&self, pos: BlockPos) -> BlockState {
+        self.storage.get_block_state(pos)
+    }
+}
+
+impl<L> LevelWriter for BehaviorLevel<'_, L>
+where
+    L: BlockGetter + LevelWriter,
+{
+    fn set_block_state(&mut self, pos: BlockPos, state: BlockState, flags: UpdateFlags) -> bool {
+        let old = self.storage.get_block_state(pos);
+        if old == state || !self.storage.set_block_state(pos, state, flags) {
+            return false;
+        }
+
+        let behaviors = self.behaviors;
+        if old.block() != state.block() {
+            behaviors.get(old.block()).on_remove(old, self, pos, state);
+        }
+        behaviors.get(state.block()).on_place(state, self, pos, old);
+
+        if flags.contains(UpdateFlags::NEIGHBORS) {
+            self.update_neighbors_at(pos, old.block());
+        }
+
+        if !flags.contains(UpdateFlags::KNOWN_SHAPE) && self.depth < MAX_UPDATE_DEPTH {
+            self.depth += 1;
+            self.update_neighbor_shapes(pos, state, flags);
+            self.depth -= 1;
+        }
+
+        true
+    }
+}
+
+impl<L> LevelHeightAccessor for BehaviorLevel<'_, L>
+where
+    L: LevelHeightAccessor,
+{
+    fn min_y(&self) -> i32 {
+        self.storage.min_y()
+    }
+
+    fn height(&self) -> u32 {
+        self.storage.height()
+    }
+}
+
+impl<L> LevelAccess for BehaviorLevel<'_, L>
+where
+    L: BlockGetter + LevelWriter,
+{
+    fn schedule_tick(&mut self, pos: BlockPos, block: Block, delay: u32) {
+        self.ticks.schedule(pos, block, delay);
+    }
+
+    fn behaviors(&self) -> &BlockBehaviors {
+        self.behaviors
+    }
+
+    fn update_neighbors_at(&mut self, pos: BlockPos, source: Block) {
+        let behaviors = self.behaviors;
+
+        for direction in Direction::ALL {
+            let neighbor_pos = pos.relative(direction);
+            let neighbor = self.get_block_state(neighbor_pos);
+            behaviors.get(neighbor.block()).neighbor_changed(
+                neighbor,
+                self,
+                neighbor_pos,
+                source,
+                pos,
+            );
+        }
+    }
+}
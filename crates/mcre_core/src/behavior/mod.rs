@@ -0,0 +1,479 @@
+mod crop;
+mod entity;
+mod fluid;
+mod level;
+mod redstone;
+
+pub use crop::CropBehavior;
+pub use entity::{CobwebBehavior, EntityAccess, SweetBerryBushBehavior};
+pub use fluid::FluidBehavior;
+pub use level::{BehaviorLevel, ScheduledTicks};
+pub use redstone::{
+    LeverBehavior, RedstoneBlockBehavior, RedstoneLampBehavior, RedstoneTorchBehavior,
+};
+
+use alloc::{boxed::Box, vec, vec::Vec};
+
+use crate::{
+    Block, BlockGetter, BlockPos, BlockState, Direction, LevelAccess, LevelWriter, RandomSource,
+};
+
+/// Outcome of interacting with a block.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum InteractionResult {
+    /// The block didn't react, the interaction continues to the next handler.
+    Pass,
+    Success,
+    /// The interaction was handled without any visible effect.
+    Consume,
+}
+
+/// Game rules of a block, mirroring the overridable hooks of vanilla's `BlockBehaviour`.
+///
+/// Every hook has a no-op default, so implementations only override what they react to.
+pub trait BlockBehavior: Send + Sync {
+    /// Called after `state` replaced `old_state` at `pos`.
+    fn on_place(
+        &self,
+        state: BlockState,
+        level: &mut dyn LevelAccess,
+        pos: BlockPos,
+        old_state: BlockState,
+    ) {
+        let _ = (state, level, pos, old_state);
+    }
+
+    /// Called after `state` was replaced by a state of another block.
+    fn on_remove(
+        &self,
+        state: BlockState,
+        level: &mut dyn LevelAccess,
+        pos: BlockPos,
+        new_state: BlockState,
+    ) {
+        let _ = (state, level, pos, new_state);
+    }
+
+    /// Called when a block next to `pos` changed.
+    fn neighbor_changed(
+        &self,
+        state: BlockState,
+        level: &mut dyn LevelAccess,
+        pos: BlockPos,
+        source: Block,
+        source_pos: BlockPos,
+    ) {
+        let _ = (state, level, pos, source, source_pos);
+    }
+
+    /// Returns the state `state` should take after its neighbor in `direction` became
+    /// `neighbor_state`, e.g. air for a block that can no longer survive.
+    fn update_shape(
+        &self,
+        state: BlockState,
+        level: &dyn BlockGetter,
+        pos: BlockPos,
+        direction: Direction,
+        neighbor_pos: BlockPos,
+        neighbor_state: BlockState,
+    ) -> BlockState {
+        let _ = (level, pos, direction, neighbor_pos, neighbor_state);
+        state
+    }
+
+    fn random_tick(
+        &self,
+        state: BlockState,
+        level: &mut dyn LevelAccess,
+        pos: BlockPos,
+        random: &mut dyn RandomSource,
+    ) {
+        let _ = (state, level, pos, random);
+    }
+
+    /// Called when a tick scheduled through [`LevelAccess::schedule_tick`] is due.
+    fn scheduled_tick(
+        &self,
+        state: BlockState,
+        level: &mut dyn LevelAccess,
+        pos: BlockPos,
+        random: &mut dyn RandomSource,
+    ) {
+        let _ = (state, level, pos, random);
+    }
+
+    /// Called when the block is used with an empty hand, `hit` being the clicked face.
+    fn use_without_item(
+        &self,
+        state: BlockState,
+        level: &mut dyn LevelAccess,
+        pos: BlockPos,
+        hit: Direction,
+    ) -> InteractionResult {
+        let _ = (state, level, pos, hit);
+        InteractionResult::Pass
+    }
+
+    fn entity_inside(
+        &self,
+        state: BlockState,
+        level: &mut dyn LevelAccess,
+        pos: BlockPos,
+        entity: &mut dyn EntityAccess,
+    ) {
+        let _ = (state, level, pos, entity);
+    }
+
+    /// Whether `state` can emit a redstone signal.
+    fn is_signal_source(&self, state: BlockState) -> bool {
+        let _ = state;
+        false
+    }
+
+    /// Redstone power from 0 to 15 that `state` emits to the neighbor `direction` points from.
+    fn get_signal(
+        &self,
+        state: BlockState,
+        level: &dyn BlockGetter,
+        pos: BlockPos,
+        direction: Direction,
+    ) -> u8 {
+        let _ = (state, level, pos, direction);
+        0
+    }
+
+    /// Whether `state` is a fluid, which other fluids don't flow into.
+    fn is_fluid(&self, state: BlockState) -> bool {
+        let _ = state;
+        false
+    }
+}
+
+/// The behavior of blocks without any game rules.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct DefaultBehavior;
+
+impl BlockBehavior for DefaultBehavior {}
+
+/// Maps every [`Block`] to its [`BlockBehavior`], falling back to [`DefaultBehavior`].
+pub struct BlockBehaviors {
+    behaviors: Vec<Box<dyn BlockBehavior>>,
+    by_block: Box<[u16]>,
+}
+
+impl BlockBehaviors {
+    /// Every block using [`DefaultBehavior`].
+    pub fn new() -> Self {
+        Self {
+            behaviors: vec![Box::new(DefaultBehavior)],
+            by_block: vec![0; u16::from(Block::MAX) as usize + 1].into_boxed_slice(),
+        }
+    }
+
+    /// The built-in behaviors of vanilla blocks.
+    pub fn vanilla() -> Self {
+        let mut behaviors = Self::new();
+
+        behaviors.set(
+            [
+                Block::WHEAT,
+                Block::CARROTS,
+                Block::POTATOES,
+                Block::BEETROOTS,
+            ],
+            CropBehavior,
+        );
+        behaviors.set([Block::WATER], FluidBehavior::WATER);
+        behaviors.set([Block::LAVA], FluidBehavior::LAVA);
+        behaviors.set([Block::LEVER], LeverBehavior);
+        behaviors.set([Block::REDSTONE_BLOCK], RedstoneBlockBehavior);
+        behaviors.set(
+            [Block::REDSTONE_TORCH, Block::REDSTONE_WALL_TORCH],
+            RedstoneTorchBehavior,
+        );
+        behaviors.set([Block::REDSTONE_LAMP], RedstoneLampBehavior);
+        behaviors.set([Block::COBWEB], CobwebBehavior);
+        behaviors.set([Block::SWEET_BERRY_BUSH], SweetBerryBushBehavior);
+
+        behaviors
+    }
+
+    /// Overrides the behavior of `blocks`, replacing any previous override.
+    pub fn set<I>(&mut self, blocks: I, behavior: impl BlockBehavior + 'static)
+    where
+        I: IntoIterator<Item = Block>,
+    {
+        let index = self.behaviors.len() as u16;
+        self.behaviors.push(Box::new(behavior));
+
+        for block in blocks {
+            self.by_block[u16::from(block) as usize] = index;
+        }
+    }
+
+    pub fn get(&self, block: Block) -> &dyn BlockBehavior {
+        &*self.behaviors[self.by_block[u16::from(block) as usize] as usize]
+    }
+
+    /// Binds these behaviors to a storage, dispatching hooks for every change made through it.
+    pub fn level<'a, L>(
+        &'a self,
+        storage: &'a mut L,
+        ticks: &'a mut ScheduledTicks,
+    ) -> BehaviorLevel<'a, L>
+    where
+        L: BlockGetter + LevelWriter,
+    {
+        BehaviorLevel::new(self, storage, ticks)
+    }
+}
+
+impl Default for BlockBehaviors {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::sync::atomic::{AtomicU32, Ordering};
+
+    use crate::{
+        AttachFace, Block, BlockBehavior, BlockBehaviors, BlockGetter, BlockPos, BlockState,
+        Direction, InteractionResult, LevelAccess, LevelWriter, MemoryLevel, PropVal,
+        ScheduledTicks, UpdateFlags, XoroshiroRandom,
+    };
+
+    fn floor(storage: &mut MemoryLevel, block: Block) {
+        storage.fill(
+            BlockPos::new(-8, -1, -8),
+            BlockPos::new(8, -1, 8),
+            block.default_state(),
+        );
+    }
+
+    #[test]
+    fn test_crop_grows_and_pops_off() {
+        let behaviors = BlockBehaviors::vanilla();
+        let mut ticks = ScheduledTicks::default();
+        let mut storage = MemoryLevel::default();
+        let mut random = XoroshiroRandom::new(0);
+        floor(&mut storage, Block::FARMLAND);
+
+        let pos = BlockPos::ZERO;
+        let mut level = behaviors.level(&mut storage, &mut ticks);
+        level.set_block_state(pos, Block::WHEAT.default_state(), UpdateFlags::ALL);
+
+        for _ in 0..2000 {
+            level.random_tick(pos, &mut random);
+        }
+        assert_eq!(level.get_block_state(pos).age(), 7);
+
+        level.set_block_state(pos.below(), Block::DIRT.default_state(), UpdateFlags::ALL);
+        assert!(level.get_block_state(pos).is_air());
+    }
+
+    #[test]
+    fn test_water_spreads_and_dries_up() {
+        let behaviors = BlockBehaviors::vanilla();
+        let mut ticks = ScheduledTicks::default();
+        let mut storage = MemoryLevel::default();
+        let mut random = XoroshiroRandom::new(0);
+        floor(&mut storage, Block::STONE);
+
+        let source = BlockPos::ZERO;
+        let mut level = behaviors.level(&mut storage, &mut ticks);
+        level.set_block_state(source, Block::WATER.default_state(), UpdateFlags::ALL);
+
+        for _ in 0..200 {
+            level.tick(&mut random);
+        }
+
+        for distance in 1..=7 {
+            let state = level.get_block_state(BlockPos::new(distance, 0, 0));
+            assert_eq!(state.block(), Block::WATER);
+            assert_eq!(state.level(), distance as u8);
+        }
+        assert!(level.get_block_state(BlockPos::new(8, 0, 0)).is_air());
+
+        level.set_block_state(source, Block::AIR.default_state(), UpdateFlags::ALL);
+        for _ in 0..200 {
+            level.tick(&mut random);
+        }
+
+        for distance in 0..=7 {
+            assert!(
+                level
+                    .get_block_state(BlockPos::new(distance, 0, 0))
+                    .is_air()
+            );
+        }
+        assert!(ticks.is_empty());
+    }
+
+    #[test]
+    fn test_lever_powers_lamp() {
+        let behaviors = BlockBehaviors::vanilla();
+        let mut ticks = ScheduledTicks::default();
+        let mut storage = MemoryLevel::default();
+        let mut random = XoroshiroRandom::new(0);
+        floor(&mut storage, Block::STONE);
+
+        let lamp = BlockPos::ZERO;
+        let lever = BlockPos::new(1, 0, 0);
+        let mut level = behaviors.level(&mut storage, &mut ticks);
+        level.set_block_state(lamp, Block::REDSTONE_LAMP.default_state(), UpdateFlags::ALL);
+        let floor_lever = Block::LEVER
+            .default_state()
+            .with_prop(PropVal::Face(AttachFace::Floor))
+            .unwrap();
+        level.set_block_state(lever, floor_lever, UpdateFlags::ALL);
+
+        assert_eq!(
+            level.use_without_item(lever, Direction::Up),
+            InteractionResult::Success
+        );
+        assert!(level.get_block_state(lamp).is_lit());
+
+        level.use_without_item(lever, Direction::Up);
+        assert!(level.get_block_state(lamp).is_lit());
+        for _ in 0..4 {
+            level.tick(&mut random);
+        }
+        assert!(!level.get_block_state(lamp).is_lit());
+
+        // the lever pops off once its support is gone
+        level.set_block_state(lever.below(), Block::AIR.default_state(), UpdateFlags::ALL);
+        assert!(level.get_block_state(lever).is_air());
+    }
+
+    #[test]
+    fn test_signal_sources() {
+        struct Battery;
+
+        impl BlockBehavior for Battery {
+            fn is_signal_source(&self, _state: BlockState) -> bool {
+                true
+            }
+
+            fn get_signal(
+                &self,
+                _state: BlockState,
+                _level: &dyn BlockGetter,
+                _pos: BlockPos,
+                _direction: Direction,
+            ) -> u8 {
+                15
+            }
+        }
+
+        let mut behaviors = BlockBehaviors::vanilla();
+        behaviors.set([Block::GOLD_BLOCK], Battery);
+        let mut ticks = ScheduledTicks::default();
+        let mut storage = MemoryLevel::default();
+
+        let lamp = BlockPos::ZERO;
+        let mut level = behaviors.level(&mut storage, &mut ticks);
+        level.set_block_state(lamp, Block::REDSTONE_LAMP.default_state(), UpdateFlags::ALL);
+        for source in [Block::REDSTONE_BLOCK, Block::GOLD_BLOCK] {
+            level.set_block_state(lamp.above(), source.default_state(), UpdateFlags::ALL);
+            assert!(level.get_block_state(lamp).is_lit(), "{source:?}");
+            level.set_block_state(lamp, Block::REDSTONE_LAMP.default_state(), UpdateFlags::ALL);
+        }
+
+        // a torch doesn't power the block it stands on
+        level.set_block_state(
+            lamp.above(),
+            Block::REDSTONE_TORCH.default_state(),
+            UpdateFlags::ALL,
+        );
+        assert!(!level.get_block_state(lamp).is_lit());
+        level.set_block_state(
+            lamp.relative(Direction::East),
+            Block::REDSTONE_TORCH.default_state(),
+            UpdateFlags::ALL,
+        );
+        assert!(level.get_block_state(lamp).is_lit());
+    }
+
+    #[test]
+    fn test_fluid_override() {
+        struct Honey;
+
+        impl BlockBehavior for Honey {
+            fn is_fluid(&self, _state: BlockState) -> bool {
+                true
+            }
+        }
+
+        // water doesn't wash away grass registered as a fluid
+        let mut behaviors = BlockBehaviors::vanilla();
+        behaviors.set([Block::SHORT_GRASS], Honey);
+        let mut ticks = ScheduledTicks::default();
+        let mut storage = MemoryLevel::default();
+        let mut random = XoroshiroRandom::new(0);
+        floor(&mut storage, Block::STONE);
+
+        let grass = BlockPos::new(1, 0, 0);
+        let mut level = behaviors.level(&mut storage, &mut ticks);
+        level.set_block_state(grass, Block::SHORT_GRASS.default_state(), UpdateFlags::ALL);
+        level.set_block_state(
+            BlockPos::ZERO,
+            Block::WATER.default_state(),
+            UpdateFlags::ALL,
+        );
+        for _ in 0..20 {
+            level.tick(&mut random);
+        }
+
+        assert_eq!(level.get_block_state(grass).block(), Block::SHORT_GRASS);
+        assert_eq!(
+            level.get_block_state(BlockPos::new(-1, 0, 0)).block(),
+            Block::WATER
+        );
+    }
+
+    #[test]
+    fn test_override() {
+        static PLACED: AtomicU32 = AtomicU32::new(0);
+
+        struct Counting;
+
+        impl BlockBehavior for Counting {
+            fn on_place(
+                &self,
+                state: BlockState,
+                level: &mut dyn LevelAccess,
+                pos: BlockPos,
+                _old_state: BlockState,
+            ) {
+                PLACED.fetch_add(1, Ordering::Relaxed);
+                level.schedule_tick(pos, state.block(), 1);
+            }
+        }
+
+        let mut behaviors = BlockBehaviors::vanilla();
+        behaviors.set([Block::STONE, Block::DIRT], Counting);
+
+        let mut ticks = ScheduledTicks::default();
+        let mut storage = MemoryLevel::default();
+        let mut level = behaviors.level(&mut storage, &mut ticks);
+
+        let stone = Block::STONE.default_state();
+        level.set_block_state(BlockPos::ZERO, stone, UpdateFlags::ALL);
+        level.set_block_state(BlockPos::ZERO, stone, UpdateFlags::ALL);
+        level.set_block_state(
+            BlockPos::new(0, 1, 0),
+            Block::DIRT.default_state(),
+            UpdateFlags::ALL,
+        );
+        let farmland = Block::FARMLAND
+            .default_state()
+            .with_prop(PropVal::Moisture(7))
+            .unwrap();
+        level.set_block_state(BlockPos::new(0, 2, 0), farmland, UpdateFlags::ALL);
+
+        assert_eq!(PLACED.load(Ordering::Relaxed), 2);
+        assert_eq!(ticks.len(), 2);
+    }
+}
@@ -0,0 +1,190 @@
+use crate::{
+    AttachFace, Block, BlockBehavior, BlockGetter, BlockPos, BlockState, Direction,
+    InteractionResult, LevelAccess, PropKey, PropVal, RandomSource, UpdateFlags,
+};
+
+/// Strongest redstone signal.
+const MAX_SIGNAL: u8 = 15;
+
+/// Delay before a lamp turns off, so short pulses stay visible.
+const LAMP_OFF_DELAY: u32 = 4;
+
+/// Whether any direct neighbor of `pos` powers it. Power conducted through solid blocks isn't
+/// modelled yet.
+fn has_neighbor_signal(level: &dyn LevelAccess, pos: BlockPos) -> bool {
+    Direction::ALL.into_iter().any(|direction| {
+        let neighbor_pos = pos.relative(direction);
+        let neighbor = level.get_block_state(neighbor_pos);
+        let behavior = level.behaviors().get(neighbor.block());
+        behavior.is_signal_source(neighbor)
+            && behavior.get_signal(neighbor, level, neighbor_pos, direction) > 0
+    })
+}
+
+#[derive(Debug, Copy, Clone, Default)]
+pub struct LeverBehavior;
+
+impl LeverBehavior {
+    /// Direction of the block the lever is attached to.
+    fn attached_direction(state: BlockState) -> Direction {
+        match state.face() {
+            AttachFace::Floor => Direction::Down,
+            AttachFace::Ceiling => Direction::Up,
+            AttachFace::Wall => state.facing().opposite(),
+        }
+    }
+}
+
+impl BlockBehavior for LeverBehavior {
+    fn update_shape(
+        &self,
+        state: BlockState,
+        _level: &dyn BlockGetter,
+        _pos: BlockPos,
+        direction: Direction,
+        _neighbor_pos: BlockPos,
+        neighbor_state: BlockState,
+    ) -> BlockState {
+        if direction == Self::attached_direction(state) && neighbor_state.is_air() {
+            Block::AIR.default_state()
+        } else {
+            state
+        }
+    }
+
+    fn use_without_item(
+        &self,
+        state: BlockState,
+        level: &mut dyn LevelAccess,
+        pos: BlockPos,
+        _hit: Direction,
+    ) -> InteractionResult {
+        let Some(toggled) = state.cycle_prop(PropKey::Powered) else {
+            return InteractionResult::Pass;
+        };
+
+        level.set_block_state(pos, toggled, UpdateFlags::ALL);
+        InteractionResult::Success
+    }
+
+    fn is_signal_source(&self, _state: BlockState) -> bool {
+        true
+    }
+
+    fn get_signal(
+        &self,
+        state: BlockState,
+        _level: &dyn BlockGetter,
+        _pos: BlockPos,
+        _direction: Direction,
+    ) -> u8 {
+        if state.is_powered() { MAX_SIGNAL } else { 0 }
+    }
+}
+
+/// Always powers its neighbors.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct RedstoneBlockBehavior;
+
+impl BlockBehavior for RedstoneBlockBehavior {
+    fn is_signal_source(&self, _state: BlockState) -> bool {
+        true
+    }
+
+    fn get_signal(
+        &self,
+        _state: BlockState,
+        _level: &dyn BlockGetter,
+        _pos: BlockPos,
+        _direction: Direction,
+    ) -> u8 {
+        MAX_SIGNAL
+    }
+}
+
+/// Powers its neighbors while lit, except the block it's attached to. Turning off when that
+/// block is powered isn't implemented yet.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct RedstoneTorchBehavior;
+
+impl BlockBehavior for RedstoneTorchBehavior {
+    fn is_signal_source(&self, _state: BlockState) -> bool {
+        true
+    }
+
+    fn get_signal(
+        &self,
+        state: BlockState,
+        _level: &dyn BlockGetter,
+        _pos: BlockPos,
+        direction: Direction,
+    ) -> u8 {
+        let attached = match state.block() {
+            Block::REDSTONE_WALL_TORCH => state.facing(),
+            _ => Direction::Up,
+        };
+        if state.is_lit() && direction != attached {
+            MAX_SIGNAL
+        } else {
+            0
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Default)]
+pub struct RedstoneLampBehavior;
+
+impl RedstoneLampBehavior {
+    fn check_power(state: BlockState, level: &mut dyn LevelAccess, pos: BlockPos) {
+        let powered = has_neighbor_signal(level, pos);
+
+        if state.is_lit() && !powered {
+            level.schedule_tick(pos, Block::REDSTONE_LAMP, LAMP_OFF_DELAY);
+        } else if !state.is_lit()
+            && powered
+            && let Some(lit) = state.with_prop(PropVal::Lit(true))
+        {
+            level.set_block_state(pos, lit, UpdateFlags::CLIENTS);
+        }
+    }
+}
+
+impl BlockBehavior for RedstoneLampBehavior {
+    fn on_place(
+        &self,
+        state: BlockState,
+        level: &mut dyn LevelAccess,
+        pos: BlockPos,
+        old_state: BlockState,
+    ) {
+        if old_state.block() != Block::REDSTONE_LAMP {
+            Self::check_power(state, level, pos);
+        }
+    }
+
+    fn neighbor_changed(
+        &self,
+        state: BlockState,
+        level: &mut dyn LevelAccess,
+        pos: BlockPos,
+        _source: Block,
+        _source_pos: BlockPos,
+    ) {
+        Self::check_power(state, level, pos);
+    }
+
+    fn scheduled_tick(
+        &self,
+        state: BlockState,
+        level: &mut dyn LevelAccess,
+        pos: BlockPos,
+        _random: &mut dyn RandomSource,
+    ) {
+        if state.is_lit()
+            && !has_neighbor_signal(level, pos)
+            && let Some(unlit) = state.with_prop(PropVal::Lit(false))
+        {
+            level.set_block_state(pos, unlit, UpdateFlags::CLIENTS);
+        }
+    }
+}
@@ -1,6 +1,6 @@
-use crate::Vec3i;
+use crate::{Direction, Vec3i};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct BlockPos {
     pub x: i32,
     pub y: i32,
@@ -28,6 +28,33 @@ impl BlockPos {
 
         i >> 16
     }
+
+    #[inline]
+    pub const fn offset(self, dx: i32, dy: i32, dz: i32) -> Self {
+        Self::new(self.x + dx, self.y + dy, self.z + dz)
+    }
+
+    #[inline]
+    pub const fn relative(self, direction: Direction) -> Self {
+        match direction {
+            Direction::Down => self.offset(0, -1, 0),
+            Direction::Up => self.offset(0, 1, 0),
+            Direction::North => self.offset(0, 0, -1),
+            Direction::South => self.offset(0, 0, 1),
+            Direction::West => self.offset(-1, 0, 0),
+            Direction::East => self.offset(1, 0, 0),
+        }
+    }
+
+    #[inline]
+    pub const fn above(self) -> Self {
+        self.relative(Direction::Up)
+    }
+
+    #[inline]
+    pub const fn below(self) -> Self {
+        self.relative(Direction::Down)
+    }
 }
 
 impl From<Vec3i> for BlockPos {
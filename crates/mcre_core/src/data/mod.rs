@@ -1,8 +1,2 @@
-mod block;
-mod fields;
-mod props;
-mod state;
-pub use block::*;
-pub use fields::*;
-pub use props::*;
-pub use state::*;
+pub mod v1_21_11;
+pub use v1_21_11::*;
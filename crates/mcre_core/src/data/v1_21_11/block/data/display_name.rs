@@ -1166,6 +1166,6 @@ static VALUES: [&str; 1166usize] = [
     "Potted Closed Eyeblossom",
     "Firefly Bush",
 ];
-pub(crate) fn get(idx: u16) -> &'static str {
+pub(crate) const fn get(idx: u16) -> &'static str {
     VALUES[idx as usize]
 }
@@ -0,0 +1,115 @@
+static DATA: [u8; 1166usize] = *include_bytes!("./fields_present.bin");
+static PALETTE: [u128; 104usize] = [
+    0u128,
+    1u128,
+    2u128,
+    4u128,
+    60u128,
+    64u128,
+    128u128,
+    32u128,
+    800u128,
+    3072u128,
+    28672u128,
+    99328u128,
+    309485009821345068724797472u128,
+    132096u128,
+    1237940039285380274899124224u128,
+    4951760157141521099596760064u128,
+    4951760157141521099596497920u128,
+    524288u128,
+    66061312u128,
+    67126304u128,
+    1024u128,
+    10101590720568703043181014417416u128,
+    12884901890u128,
+    3094850098213450687247811616u128,
+    9903520314283042199192994848u128,
+    3367196906856234347742797758464u128,
+    8u128,
+    34359738368u128,
+    68719477760u128,
+    137438953504u128,
+    1237940039285381099532862464u128,
+    1056u128,
+    309485009821345068724781088u128,
+    1236950581280u128,
+    2199023272960u128,
+    16384u128,
+    68719476736u128,
+    4398046511104u128,
+    8796093022208u128,
+    10101590720568703043179940675616u128,
+    17592186044416u128,
+    105553116283904u128,
+    2475880078570761099554079776u128,
+    10101590720568703183918502772736u128,
+    34u128,
+    10101590720568703043181014417408u128,
+    10101590720568703183918502772768u128,
+    282024732541952u128,
+    19807040628566084398385987616u128,
+    6734393813712468695452309520416u128,
+    3940649673949184u128,
+    4503599627371520u128,
+    1032u128,
+    1099511645184u128,
+    10101590720568712051478707060736u128,
+    18014398509483008u128,
+    137438969856u128,
+    17408u128,
+    17179869184u128,
+    10141204801825835211973625660416u128,
+    36028814198833152u128,
+    72057594037928960u128,
+    96u128,
+    137438953472u128,
+    1237940039285380274899124232u128,
+    432345564227567616u128,
+    288230376151711744u128,
+    576460752303424544u128,
+    1152921504606847008u128,
+    2305843009213693964u128,
+    4611686018427387904u128,
+    9223372036854776096u128,
+    549755814912u128,
+    2199023256576u128,
+    18446744073709569024u128,
+    36893488147419120640u128,
+    48u128,
+    73786976363557684256u128,
+    20282409603651670423947251286016u128,
+    147573952589676412928u128,
+    40564819207303340847894502572032u128,
+    295147905179352826880u128,
+    590295810358705651712u128,
+    1180591620786130780192u128,
+    2361183241452002476064u128,
+    2361183241452002477088u128,
+    4722366482869645213696u128,
+    28334198897217871282208u128,
+    68719493120u128,
+    37778931862957161710624u128,
+    17440u128,
+    226673591177742970257440u128,
+    302231454903657293676552u128,
+    302231454903657293676544u128,
+    604462909807314587354112u128,
+    1208925819614629174707200u128,
+    2417851639229258349413408u128,
+    1237940039285380274899125280u128,
+    4835703278458516698825760u128,
+    9671554130869623074064384u128,
+    58028439341502200385896448u128,
+    96714065569170333976495104u128,
+    6734393813721692067488090554368u128,
+    154742504910672534362390528u128,
+];
+/// Reads the `idx`th packed value of the table starting at `offset`.
+const fn read(offset: usize, idx: usize) -> u8 {
+    DATA[offset + idx]
+}
+pub(crate) const fn get(idx: u16) -> u128 {
+    let value = read(0, idx as usize);
+    PALETTE[value as usize]
+}
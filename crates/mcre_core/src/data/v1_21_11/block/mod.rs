@@ -1,6 +1,6 @@
 mod consts;
 mod data;
-use crate::{BlockState, FieldKey};
+use super::{BlockState, FieldKey};
 use serde::{Deserialize, Serialize};
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Block(u16);
@@ -21,22 +21,28 @@ impl From<Block> for BlockState {
 }
 impl Block {
     pub const MAX: Self = Self(1165u16);
-    pub fn name(self) -> &'static str {
+    pub const fn from_id(id: u16) -> Self {
+        Self(id)
+    }
+    pub const fn id(self) -> u16 {
+        self.0
+    }
+    pub const fn name(self) -> &'static str {
         data::name::get(self.0)
     }
-    pub fn display_name(self) -> &'static str {
+    pub const fn display_name(self) -> &'static str {
         data::display_name::get(self.0)
     }
-    pub fn default_state(self) -> BlockState {
-        data::default_state::get(self.0).into()
+    pub const fn default_state(self) -> BlockState {
+        BlockState::from_id(data::default_state::get(self.0))
     }
-    pub fn min_state(self) -> BlockState {
-        data::min_state::get(self.0).into()
+    pub const fn min_state(self) -> BlockState {
+        BlockState::from_id(data::min_state::get(self.0))
     }
-    pub fn max_state(self) -> BlockState {
-        data::max_state::get(self.0).into()
+    pub const fn max_state(self) -> BlockState {
+        BlockState::from_id(data::max_state::get(self.0))
     }
-    pub fn is_field_present(self, field: FieldKey) -> bool {
+    pub const fn is_field_present(self, field: FieldKey) -> bool {
         let fields_present = data::fields_present::get(self.0);
         ((fields_present >> (field as u8)) & 1) == 1
     }
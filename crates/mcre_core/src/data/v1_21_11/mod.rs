@@ -0,0 +1,14 @@
+mod block;
+mod fields;
+mod props;
+mod state;
+use crate::DataVersion;
+pub use block::*;
+pub use fields::*;
+pub use props::*;
+pub use state::*;
+pub const DATA_VERSION: DataVersion = DataVersion {
+    id: "1.21.11",
+    world_version: 4671u32,
+    protocol_version: 774u32,
+};
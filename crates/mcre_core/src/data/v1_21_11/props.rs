@@ -20,7 +20,7 @@ pub enum ShapePropVal {
     OuterRight,
 }
 impl ShapePropVal {
-    pub fn as_str(self) -> &'static str {
+    pub const fn as_str(self) -> &'static str {
         match self {
             Self::NorthSouth => "north_south",
             Self::EastWest => "east_west",
@@ -98,7 +98,7 @@ pub enum HalfPropVal {
     Bottom,
 }
 impl HalfPropVal {
-    pub fn as_str(self) -> &'static str {
+    pub const fn as_str(self) -> &'static str {
         match self {
             Self::Upper => "upper",
             Self::Lower => "lower",
@@ -147,7 +147,7 @@ pub enum TypePropVal {
     Double,
 }
 impl TypePropVal {
-    pub fn as_str(self) -> &'static str {
+    pub const fn as_str(self) -> &'static str {
         match self {
             Self::Normal => "normal",
             Self::Sticky => "sticky",
@@ -213,7 +213,7 @@ pub enum EastPropVal {
     Tall,
 }
 impl EastPropVal {
-    pub fn as_str(self) -> &'static str {
+    pub const fn as_str(self) -> &'static str {
         match self {
             Self::True => "true",
             Self::False => "false",
@@ -274,7 +274,7 @@ pub enum NorthPropVal {
     Tall,
 }
 impl NorthPropVal {
-    pub fn as_str(self) -> &'static str {
+    pub const fn as_str(self) -> &'static str {
         match self {
             Self::True => "true",
             Self::False => "false",
@@ -335,7 +335,7 @@ pub enum SouthPropVal {
     Tall,
 }
 impl SouthPropVal {
-    pub fn as_str(self) -> &'static str {
+    pub const fn as_str(self) -> &'static str {
         match self {
             Self::True => "true",
             Self::False => "false",
@@ -396,7 +396,7 @@ pub enum WestPropVal {
     Tall,
 }
 impl WestPropVal {
-    pub fn as_str(self) -> &'static str {
+    pub const fn as_str(self) -> &'static str {
         match self {
             Self::True => "true",
             Self::False => "false",
@@ -460,7 +460,7 @@ pub enum ModePropVal {
     Accept,
 }
 impl ModePropVal {
-    pub fn as_str(self) -> &'static str {
+    pub const fn as_str(self) -> &'static str {
         match self {
             Self::Compare => "compare",
             Self::Subtract => "subtract",
@@ -810,7 +810,7 @@ pub enum PropFilter {
     Tip(Box<[bool]>) = 91u8,
 }
 impl PropKey {
-    pub fn as_str(self) -> &'static str {
+    pub const fn as_str(self) -> &'static str {
         match self {
             Self::Snowy => "snowy",
             Self::Axis => "axis",
@@ -908,7 +908,7 @@ impl PropKey {
     }
 }
 impl PropVal {
-    pub fn key(self) -> PropKey {
+    pub const fn key(self) -> PropKey {
         match self {
             Self::Snowy(_) => PropKey::Snowy,
             Self::Axis(_) => PropKey::Axis,
@@ -1110,7 +1110,7 @@ impl PropVal {
     }
 }
 impl PropFilter {
-    pub fn key(&self) -> PropKey {
+    pub const fn key(&self) -> PropKey {
         match self {
             Self::Snowy(_) => PropKey::Snowy,
             Self::Axis(_) => PropKey::Axis,
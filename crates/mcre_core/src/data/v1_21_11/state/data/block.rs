@@ -1,4 +1,4 @@
 static VALUES: [u16; 29671usize] = unsafe { core::mem::transmute(*include_bytes!("./block.bin")) };
-pub(crate) fn get(idx: u16) -> u16 {
+pub(crate) const fn get(idx: u16) -> u16 {
     VALUES[idx as usize]
 }
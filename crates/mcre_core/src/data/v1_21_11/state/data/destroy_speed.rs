@@ -0,0 +1,44 @@
+static DATA: [u8; 1166usize] = *include_bytes!("./destroy_speed.bin");
+static PALETTE: [f32; 32usize] = [
+    f32::from_bits(0u32),
+    f32::from_bits(1069547520u32),
+    f32::from_bits(1058642330u32),
+    f32::from_bits(1056964608u32),
+    f32::from_bits(1073741824u32),
+    f32::from_bits(3212836864u32),
+    f32::from_bits(1120403456u32),
+    f32::from_bits(1048576000u32),
+    f32::from_bits(1077936128u32),
+    f32::from_bits(1083179008u32),
+    f32::from_bits(1060320051u32),
+    f32::from_bits(1045220557u32),
+    f32::from_bits(1050253722u32),
+    f32::from_bits(1080033280u32),
+    f32::from_bits(1061997773u32),
+    f32::from_bits(1082130432u32),
+    f32::from_bits(1084227584u32),
+    f32::from_bits(1112014848u32),
+    f32::from_bits(1092616192u32),
+    f32::from_bits(1075838976u32),
+    f32::from_bits(1065353216u32),
+    f32::from_bits(1053609165u32),
+    f32::from_bits(1036831949u32),
+    f32::from_bits(1067450368u32),
+    f32::from_bits(1061158912u32),
+    f32::from_bits(1102315520u32),
+    f32::from_bits(1059481190u32),
+    f32::from_bits(1068708659u32),
+    f32::from_bits(1072064102u32),
+    f32::from_bits(1077097267u32),
+    f32::from_bits(1106247680u32),
+    f32::from_bits(1113325568u32),
+];
+/// Reads the `idx`th packed value of the table starting at `offset`.
+const fn read(offset: usize, idx: usize) -> u8 {
+    DATA[offset + idx]
+}
+pub(crate) const fn get(idx: u16) -> f32 {
+    let block = super::block::get(idx) as usize;
+    let value = read(0, block);
+    PALETTE[value as usize]
+}
@@ -0,0 +1,21 @@
+static DATA: [u8; 1490usize] = *include_bytes!("./half.bin");
+/// Reads the `idx`th packed value of the table starting at `offset`.
+const fn read(offset: usize, idx: usize) -> u8 {
+    (DATA[offset + idx / 8] >> (idx % 8)) & 1
+}
+pub(crate) const fn get(idx: u16) -> u8 {
+    let block = super::super::block::get(idx) as usize;
+    let varying = DATA[146usize + block / 8];
+    let bit = block % 8;
+    if (varying >> bit) & 1 == 0 {
+        read(0, block)
+    } else {
+        let rank = u16::from_le_bytes([
+            DATA[292usize + block / 8 * 2],
+            DATA[292usize + block / 8 * 2 + 1],
+        ]) as usize
+            + (varying & ((1 << bit) - 1)).count_ones() as usize;
+        let base = u16::from_le_bytes([DATA[584usize + rank * 2], DATA[584usize + rank * 2 + 1]]);
+        read(742usize, base.wrapping_add(idx) as usize)
+    }
+}
@@ -0,0 +1,9 @@
+static DATA: [u8; 3709usize] = *include_bytes!("./is_waterlogged.bin");
+/// Reads the `idx`th packed value of the table starting at `offset`.
+const fn read(offset: usize, idx: usize) -> u8 {
+    (DATA[offset + idx / 8] >> (idx % 8)) & 1
+}
+pub(crate) const fn get(idx: u16) -> bool {
+    let value = read(0, idx as usize);
+    value == 1
+}
@@ -0,0 +1,10 @@
+static DATA: [u8; 146usize] = *include_bytes!("./is_air.bin");
+/// Reads the `idx`th packed value of the table starting at `offset`.
+const fn read(offset: usize, idx: usize) -> u8 {
+    (DATA[offset + idx / 8] >> (idx % 8)) & 1
+}
+pub(crate) const fn get(idx: u16) -> bool {
+    let block = super::block::get(idx) as usize;
+    let value = read(0, block);
+    value == 1
+}
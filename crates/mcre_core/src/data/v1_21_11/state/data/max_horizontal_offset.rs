@@ -0,0 +1,11 @@
+static DATA: [u8; 146usize] = *include_bytes!("./max_horizontal_offset.bin");
+static PALETTE: [f32; 2usize] = [f32::from_bits(1048576000u32), f32::from_bits(1040187392u32)];
+/// Reads the `idx`th packed value of the table starting at `offset`.
+const fn read(offset: usize, idx: usize) -> u8 {
+    (DATA[offset + idx / 8] >> (idx % 8)) & 1
+}
+pub(crate) const fn get(idx: u16) -> f32 {
+    let block = super::block::get(idx) as usize;
+    let value = read(0, block);
+    PALETTE[value as usize]
+}
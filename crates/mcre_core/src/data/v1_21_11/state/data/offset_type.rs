@@ -0,0 +1,9 @@
+static DATA: [u8; 292usize] = *include_bytes!("./offset_type.bin");
+/// Reads the `idx`th packed value of the table starting at `offset`.
+const fn read(offset: usize, idx: usize) -> u8 {
+    (DATA[offset + idx / 4usize] >> (idx % 4usize * 2usize)) & 3u8
+}
+pub(crate) const fn get(idx: u16) -> u8 {
+    let block = super::block::get(idx) as usize;
+    read(0, block)
+}
@@ -28,7 +28,7 @@ pub enum NoteBlockInstrument {
     CustomHead = 22u8,
 }
 impl NoteBlockInstrument {
-    pub fn as_str(self) -> &'static str {
+    pub const fn as_str(self) -> &'static str {
         match self {
             Self::Harp => "harp",
             Self::Basedrum => "basedrum",
@@ -94,7 +94,7 @@ pub enum BedPart {
     Foot = 1u8,
 }
 impl BedPart {
-    pub fn as_str(self) -> &'static str {
+    pub const fn as_str(self) -> &'static str {
         match self {
             Self::Head => "head",
             Self::Foot => "foot",
@@ -126,7 +126,7 @@ pub enum RailShape {
     NorthEast = 9u8,
 }
 impl RailShape {
-    pub fn as_str(self) -> &'static str {
+    pub const fn as_str(self) -> &'static str {
         match self {
             Self::NorthSouth => "north_south",
             Self::EastWest => "east_west",
@@ -166,7 +166,7 @@ pub enum DoubleBlockHalf {
     Lower = 1u8,
 }
 impl DoubleBlockHalf {
-    pub fn as_str(self) -> &'static str {
+    pub const fn as_str(self) -> &'static str {
         match self {
             Self::Upper => "upper",
             Self::Lower => "lower",
@@ -190,7 +190,7 @@ pub enum PistonType {
     Sticky = 1u8,
 }
 impl PistonType {
-    pub fn as_str(self) -> &'static str {
+    pub const fn as_str(self) -> &'static str {
         match self {
             Self::Normal => "normal",
             Self::Sticky => "sticky",
@@ -216,7 +216,7 @@ pub enum SideChainPart {
     Left = 3u8,
 }
 impl SideChainPart {
-    pub fn as_str(self) -> &'static str {
+    pub const fn as_str(self) -> &'static str {
         match self {
             Self::Unconnected => "unconnected",
             Self::Right => "right",
@@ -245,7 +245,7 @@ pub enum CreakingHeartState {
     Awake = 2u8,
 }
 impl CreakingHeartState {
-    pub fn as_str(self) -> &'static str {
+    pub const fn as_str(self) -> &'static str {
         match self {
             Self::Uprooted => "uprooted",
             Self::Dormant => "dormant",
@@ -271,7 +271,7 @@ pub enum Half {
     Bottom = 1u8,
 }
 impl Half {
-    pub fn as_str(self) -> &'static str {
+    pub const fn as_str(self) -> &'static str {
         match self {
             Self::Top => "top",
             Self::Bottom => "bottom",
@@ -298,7 +298,7 @@ pub enum StairsShape {
     OuterRight = 4u8,
 }
 impl StairsShape {
-    pub fn as_str(self) -> &'static str {
+    pub const fn as_str(self) -> &'static str {
         match self {
             Self::Straight => "straight",
             Self::InnerLeft => "inner_left",
@@ -329,7 +329,7 @@ pub enum ChestType {
     Right = 2u8,
 }
 impl ChestType {
-    pub fn as_str(self) -> &'static str {
+    pub const fn as_str(self) -> &'static str {
         match self {
             Self::Single => "single",
             Self::Left => "left",
@@ -356,7 +356,7 @@ pub enum RedstoneSide {
     None = 2u8,
 }
 impl RedstoneSide {
-    pub fn as_str(self) -> &'static str {
+    pub const fn as_str(self) -> &'static str {
         match self {
             Self::Up => "up",
             Self::Side => "side",
@@ -382,7 +382,7 @@ pub enum DoorHingeSide {
     Right = 1u8,
 }
 impl DoorHingeSide {
-    pub fn as_str(self) -> &'static str {
+    pub const fn as_str(self) -> &'static str {
         match self {
             Self::Left => "left",
             Self::Right => "right",
@@ -407,7 +407,7 @@ pub enum AttachFace {
     Ceiling = 2u8,
 }
 impl AttachFace {
-    pub fn as_str(self) -> &'static str {
+    pub const fn as_str(self) -> &'static str {
         match self {
             Self::Floor => "floor",
             Self::Wall => "wall",
@@ -434,7 +434,7 @@ pub enum SlabType {
     Double = 2u8,
 }
 impl SlabType {
-    pub fn as_str(self) -> &'static str {
+    pub const fn as_str(self) -> &'static str {
         match self {
             Self::Top => "top",
             Self::Bottom => "bottom",
@@ -461,7 +461,7 @@ pub enum WallSide {
     Tall = 2u8,
 }
 impl WallSide {
-    pub fn as_str(self) -> &'static str {
+    pub const fn as_str(self) -> &'static str {
         match self {
             Self::None => "none",
             Self::Low => "low",
@@ -487,7 +487,7 @@ pub enum ComparatorMode {
     Subtract = 1u8,
 }
 impl ComparatorMode {
-    pub fn as_str(self) -> &'static str {
+    pub const fn as_str(self) -> &'static str {
         match self {
             Self::Compare => "compare",
             Self::Subtract => "subtract",
@@ -512,7 +512,7 @@ pub enum BambooLeaves {
     Large = 2u8,
 }
 impl BambooLeaves {
-    pub fn as_str(self) -> &'static str {
+    pub const fn as_str(self) -> &'static str {
         match self {
             Self::None => "none",
             Self::Small => "small",
@@ -540,7 +540,7 @@ pub enum BellAttachType {
     DoubleWall = 3u8,
 }
 impl BellAttachType {
-    pub fn as_str(self) -> &'static str {
+    pub const fn as_str(self) -> &'static str {
         match self {
             Self::Floor => "floor",
             Self::Ceiling => "ceiling",
@@ -570,7 +570,7 @@ pub enum StructureMode {
     Data = 3u8,
 }
 impl StructureMode {
-    pub fn as_str(self) -> &'static str {
+    pub const fn as_str(self) -> &'static str {
         match self {
             Self::Save => "save",
             Self::Load => "load",
@@ -608,7 +608,7 @@ pub enum FrontAndTop {
     SouthUp = 11u8,
 }
 impl FrontAndTop {
-    pub fn as_str(self) -> &'static str {
+    pub const fn as_str(self) -> &'static str {
         match self {
             Self::DownEast => "down_east",
             Self::DownNorth => "down_north",
@@ -654,7 +654,7 @@ pub enum TestBlockMode {
     Accept = 3u8,
 }
 impl TestBlockMode {
-    pub fn as_str(self) -> &'static str {
+    pub const fn as_str(self) -> &'static str {
         match self {
             Self::Start => "start",
             Self::Log => "log",
@@ -683,7 +683,7 @@ pub enum SculkSensorPhase {
     Cooldown = 2u8,
 }
 impl SculkSensorPhase {
-    pub fn as_str(self) -> &'static str {
+    pub const fn as_str(self) -> &'static str {
         match self {
             Self::Inactive => "inactive",
             Self::Active => "active",
@@ -711,7 +711,7 @@ pub enum Pose {
     Star = 3u8,
 }
 impl Pose {
-    pub fn as_str(self) -> &'static str {
+    pub const fn as_str(self) -> &'static str {
         match self {
             Self::Standing => "standing",
             Self::Sitting => "sitting",
@@ -742,7 +742,7 @@ pub enum DripstoneThickness {
     Base = 4u8,
 }
 impl DripstoneThickness {
-    pub fn as_str(self) -> &'static str {
+    pub const fn as_str(self) -> &'static str {
         match self {
             Self::TipMerge => "tip_merge",
             Self::Tip => "tip",
@@ -774,7 +774,7 @@ pub enum Tilt {
     Full = 3u8,
 }
 impl Tilt {
-    pub fn as_str(self) -> &'static str {
+    pub const fn as_str(self) -> &'static str {
         match self {
             Self::None => "none",
             Self::Unstable => "unstable",
@@ -806,7 +806,7 @@ pub enum TrialSpawnerState {
     Cooldown = 5u8,
 }
 impl TrialSpawnerState {
-    pub fn as_str(self) -> &'static str {
+    pub const fn as_str(self) -> &'static str {
         match self {
             Self::Inactive => "inactive",
             Self::WaitingForPlayers => "waiting_for_players",
@@ -840,7 +840,7 @@ pub enum VaultState {
     Ejecting = 3u8,
 }
 impl VaultState {
-    pub fn as_str(self) -> &'static str {
+    pub const fn as_str(self) -> &'static str {
         match self {
             Self::Inactive => "inactive",
             Self::Active => "active",
@@ -1,6 +1,7 @@
 mod data;
 mod enums;
-use crate::{Block, FieldKey, FieldVal, OffsetType, PropKey, PropVal};
+use super::{Block, FieldKey, FieldVal, PropKey, PropVal};
+use crate::OffsetType;
 pub use enums::*;
 use serde::{Deserialize, Serialize};
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -17,58 +18,64 @@ impl From<BlockState> for u16 {
 }
 impl BlockState {
     pub const MAX: Self = Self(29670u16);
-    pub fn block(self) -> Block {
-        data::block::get(self.0).into()
+    pub const fn from_id(id: u16) -> Self {
+        Self(id)
+    }
+    pub const fn id(self) -> u16 {
+        self.0
     }
-    pub fn light_emission(self) -> u8 {
+    pub const fn block(self) -> Block {
+        Block::from_id(data::block::get(self.0))
+    }
+    pub const fn light_emission(self) -> u8 {
         data::light_emission::get(self.0)
     }
-    pub fn use_shape_for_light_occlusion(self) -> bool {
+    pub const fn use_shape_for_light_occlusion(self) -> bool {
         data::use_shape_for_light_occlusion::get(self.0)
     }
-    pub fn propagates_skylight_down(self) -> bool {
+    pub const fn propagates_skylight_down(self) -> bool {
         data::propagates_skylight_down::get(self.0)
     }
-    pub fn light_block(self) -> u8 {
+    pub const fn light_block(self) -> u8 {
         data::light_block::get(self.0)
     }
-    pub fn solid_render(self) -> bool {
+    pub const fn solid_render(self) -> bool {
         data::solid_render::get(self.0)
     }
-    pub fn is_air(self) -> bool {
+    pub const fn is_air(self) -> bool {
         data::is_air::get(self.0)
     }
-    pub fn ignited_by_lava(self) -> bool {
+    pub const fn ignited_by_lava(self) -> bool {
         data::ignited_by_lava::get(self.0)
     }
-    pub fn can_occlude(self) -> bool {
+    pub const fn can_occlude(self) -> bool {
         data::can_occlude::get(self.0)
     }
-    pub fn is_randomly_ticking(self) -> bool {
+    pub const fn is_randomly_ticking(self) -> bool {
         data::is_randomly_ticking::get(self.0)
     }
-    pub fn replaceable(self) -> bool {
+    pub const fn replaceable(self) -> bool {
         data::replaceable::get(self.0)
     }
-    pub fn spawn_terrain_particles(self) -> bool {
+    pub const fn spawn_terrain_particles(self) -> bool {
         data::spawn_terrain_particles::get(self.0)
     }
-    pub fn requires_correct_tool_for_drops(self) -> bool {
+    pub const fn requires_correct_tool_for_drops(self) -> bool {
         data::requires_correct_tool_for_drops::get(self.0)
     }
-    pub fn destroy_speed(self) -> f32 {
+    pub const fn destroy_speed(self) -> f32 {
         data::destroy_speed::get(self.0)
     }
-    pub fn offset_type(self) -> OffsetType {
+    pub const fn offset_type(self) -> OffsetType {
         unsafe { core::mem::transmute::<u8, OffsetType>(data::offset_type::get(self.0)) }
     }
-    pub fn max_horizontal_offset(self) -> f32 {
+    pub const fn max_horizontal_offset(self) -> f32 {
         data::max_horizontal_offset::get(self.0)
     }
-    pub fn max_vertical_offset(self) -> f32 {
+    pub const fn max_vertical_offset(self) -> f32 {
         data::max_vertical_offset::get(self.0)
     }
-    pub fn get_field(self, field: FieldKey) -> Option<FieldVal> {
+    pub const fn get_field(self, field: FieldKey) -> Option<FieldVal> {
         if !self.block().is_field_present(field) {
             return None;
         }
@@ -613,357 +620,357 @@ impl BlockState {
                 .then_some(PropVal::Tip(self.is_tip())),
         }
     }
-    pub fn all() -> impl Iterator<Item = Self> {
+    pub fn all() -> impl ExactSizeIterator<Item = Self> {
         BlockStateIter::new(BlockState(0), Self::MAX)
     }
-    pub fn is_snowy(self) -> bool {
+    pub const fn is_snowy(self) -> bool {
         data::fields::is_snowy::get(self.0)
     }
-    pub fn axis(self) -> Axis {
+    pub const fn axis(self) -> Axis {
         unsafe { core::mem::transmute::<u8, Axis>(data::fields::axis::get(self.0)) }
     }
-    pub fn stage(self) -> u8 {
+    pub const fn stage(self) -> u8 {
         data::fields::stage::get(self.0)
     }
-    pub fn age(self) -> u8 {
+    pub const fn age(self) -> u8 {
         data::fields::age::get(self.0)
     }
-    pub fn is_hanging(self) -> bool {
+    pub const fn is_hanging(self) -> bool {
         data::fields::is_hanging::get(self.0)
     }
-    pub fn is_waterlogged(self) -> bool {
+    pub const fn is_waterlogged(self) -> bool {
         data::fields::is_waterlogged::get(self.0)
     }
-    pub fn level(self) -> u8 {
+    pub const fn level(self) -> u8 {
         data::fields::level::get(self.0)
     }
-    pub fn dusted(self) -> u8 {
+    pub const fn dusted(self) -> u8 {
         data::fields::dusted::get(self.0)
     }
-    pub fn distance(self) -> u8 {
+    pub const fn distance(self) -> u8 {
         data::fields::distance::get(self.0)
     }
-    pub fn is_persistent(self) -> bool {
+    pub const fn is_persistent(self) -> bool {
         data::fields::is_persistent::get(self.0)
     }
-    pub fn facing(self) -> Direction {
+    pub const fn facing(self) -> Direction {
         unsafe { core::mem::transmute::<u8, Direction>(data::fields::facing::get(self.0)) }
     }
-    pub fn is_triggered(self) -> bool {
+    pub const fn is_triggered(self) -> bool {
         data::fields::is_triggered::get(self.0)
     }
-    pub fn instrument(self) -> NoteBlockInstrument {
+    pub const fn instrument(self) -> NoteBlockInstrument {
         unsafe {
             core::mem::transmute::<u8, NoteBlockInstrument>(data::fields::instrument::get(self.0))
         }
     }
-    pub fn note(self) -> u8 {
+    pub const fn note(self) -> u8 {
         data::fields::note::get(self.0)
     }
-    pub fn is_powered(self) -> bool {
+    pub const fn is_powered(self) -> bool {
         data::fields::is_powered::get(self.0)
     }
-    pub fn is_occupied(self) -> bool {
+    pub const fn is_occupied(self) -> bool {
         data::fields::is_occupied::get(self.0)
     }
-    pub fn part(self) -> BedPart {
+    pub const fn part(self) -> BedPart {
         unsafe { core::mem::transmute::<u8, BedPart>(data::fields::part::get(self.0)) }
     }
-    pub fn is_extended(self) -> bool {
+    pub const fn is_extended(self) -> bool {
         data::fields::is_extended::get(self.0)
     }
-    pub fn is_short(self) -> bool {
+    pub const fn is_short(self) -> bool {
         data::fields::is_short::get(self.0)
     }
-    pub fn is_unstable(self) -> bool {
+    pub const fn is_unstable(self) -> bool {
         data::fields::is_unstable::get(self.0)
     }
-    pub fn is_slot_0_occupied(self) -> bool {
+    pub const fn is_slot_0_occupied(self) -> bool {
         data::fields::is_slot_0_occupied::get(self.0)
     }
-    pub fn is_slot_1_occupied(self) -> bool {
+    pub const fn is_slot_1_occupied(self) -> bool {
         data::fields::is_slot_1_occupied::get(self.0)
     }
-    pub fn is_slot_2_occupied(self) -> bool {
+    pub const fn is_slot_2_occupied(self) -> bool {
         data::fields::is_slot_2_occupied::get(self.0)
     }
-    pub fn is_slot_3_occupied(self) -> bool {
+    pub const fn is_slot_3_occupied(self) -> bool {
         data::fields::is_slot_3_occupied::get(self.0)
     }
-    pub fn is_slot_4_occupied(self) -> bool {
+    pub const fn is_slot_4_occupied(self) -> bool {
         data::fields::is_slot_4_occupied::get(self.0)
     }
-    pub fn is_slot_5_occupied(self) -> bool {
+    pub const fn is_slot_5_occupied(self) -> bool {
         data::fields::is_slot_5_occupied::get(self.0)
     }
-    pub fn side_chain(self) -> SideChainPart {
+    pub const fn side_chain(self) -> SideChainPart {
         unsafe { core::mem::transmute::<u8, SideChainPart>(data::fields::side_chain::get(self.0)) }
     }
-    pub fn is_east(self) -> bool {
+    pub const fn is_east(self) -> bool {
         data::fields::is_east::get(self.0)
     }
-    pub fn is_north(self) -> bool {
+    pub const fn is_north(self) -> bool {
         data::fields::is_north::get(self.0)
     }
-    pub fn is_south(self) -> bool {
+    pub const fn is_south(self) -> bool {
         data::fields::is_south::get(self.0)
     }
-    pub fn is_up(self) -> bool {
+    pub const fn is_up(self) -> bool {
         data::fields::is_up::get(self.0)
     }
-    pub fn is_west(self) -> bool {
+    pub const fn is_west(self) -> bool {
         data::fields::is_west::get(self.0)
     }
-    pub fn creaking_heart_state(self) -> CreakingHeartState {
+    pub const fn creaking_heart_state(self) -> CreakingHeartState {
         unsafe {
             core::mem::transmute::<u8, CreakingHeartState>(data::fields::creaking_heart_state::get(
                 self.0,
             ))
         }
     }
-    pub fn is_natural(self) -> bool {
+    pub const fn is_natural(self) -> bool {
         data::fields::is_natural::get(self.0)
     }
-    pub fn power(self) -> u8 {
+    pub const fn power(self) -> u8 {
         data::fields::power::get(self.0)
     }
-    pub fn moisture(self) -> u8 {
+    pub const fn moisture(self) -> u8 {
         data::fields::moisture::get(self.0)
     }
-    pub fn is_lit(self) -> bool {
+    pub const fn is_lit(self) -> bool {
         data::fields::is_lit::get(self.0)
     }
-    pub fn rotation(self) -> u8 {
+    pub const fn rotation(self) -> u8 {
         data::fields::rotation::get(self.0)
     }
-    pub fn hinge(self) -> DoorHingeSide {
+    pub const fn hinge(self) -> DoorHingeSide {
         unsafe { core::mem::transmute::<u8, DoorHingeSide>(data::fields::hinge::get(self.0)) }
     }
-    pub fn is_open(self) -> bool {
+    pub const fn is_open(self) -> bool {
         data::fields::is_open::get(self.0)
     }
-    pub fn is_attached(self) -> bool {
+    pub const fn is_attached(self) -> bool {
         data::fields::is_attached::get(self.0)
     }
-    pub fn face(self) -> AttachFace {
+    pub const fn face(self) -> AttachFace {
         unsafe { core::mem::transmute::<u8, AttachFace>(data::fields::face::get(self.0)) }
     }
-    pub fn layers(self) -> u8 {
+    pub const fn layers(self) -> u8 {
         data::fields::layers::get(self.0)
     }
-    pub fn is_has_record(self) -> bool {
+    pub const fn is_has_record(self) -> bool {
         data::fields::is_has_record::get(self.0)
     }
-    pub fn bites(self) -> u8 {
+    pub const fn bites(self) -> u8 {
         data::fields::bites::get(self.0)
     }
-    pub fn delay(self) -> u8 {
+    pub const fn delay(self) -> u8 {
         data::fields::delay::get(self.0)
     }
-    pub fn is_locked(self) -> bool {
+    pub const fn is_locked(self) -> bool {
         data::fields::is_locked::get(self.0)
     }
-    pub fn is_down(self) -> bool {
+    pub const fn is_down(self) -> bool {
         data::fields::is_down::get(self.0)
     }
-    pub fn is_in_wall(self) -> bool {
+    pub const fn is_in_wall(self) -> bool {
         data::fields::is_in_wall::get(self.0)
     }
-    pub fn is_has_bottle_0(self) -> bool {
+    pub const fn is_has_bottle_0(self) -> bool {
         data::fields::is_has_bottle_0::get(self.0)
     }
-    pub fn is_has_bottle_1(self) -> bool {
+    pub const fn is_has_bottle_1(self) -> bool {
         data::fields::is_has_bottle_1::get(self.0)
     }
-    pub fn is_has_bottle_2(self) -> bool {
+    pub const fn is_has_bottle_2(self) -> bool {
         data::fields::is_has_bottle_2::get(self.0)
     }
-    pub fn is_eye(self) -> bool {
+    pub const fn is_eye(self) -> bool {
         data::fields::is_eye::get(self.0)
     }
-    pub fn is_disarmed(self) -> bool {
+    pub const fn is_disarmed(self) -> bool {
         data::fields::is_disarmed::get(self.0)
     }
-    pub fn is_conditional(self) -> bool {
+    pub const fn is_conditional(self) -> bool {
         data::fields::is_conditional::get(self.0)
     }
-    pub fn is_inverted(self) -> bool {
+    pub const fn is_inverted(self) -> bool {
         data::fields::is_inverted::get(self.0)
     }
-    pub fn is_enabled(self) -> bool {
+    pub const fn is_enabled(self) -> bool {
         data::fields::is_enabled::get(self.0)
     }
-    pub fn eggs(self) -> u8 {
+    pub const fn eggs(self) -> u8 {
         data::fields::eggs::get(self.0)
     }
-    pub fn hatch(self) -> u8 {
+    pub const fn hatch(self) -> u8 {
         data::fields::hatch::get(self.0)
     }
-    pub fn hydration(self) -> u8 {
+    pub const fn hydration(self) -> u8 {
         data::fields::hydration::get(self.0)
     }
-    pub fn pickles(self) -> u8 {
+    pub const fn pickles(self) -> u8 {
         data::fields::pickles::get(self.0)
     }
-    pub fn leaves(self) -> BambooLeaves {
+    pub const fn leaves(self) -> BambooLeaves {
         unsafe { core::mem::transmute::<u8, BambooLeaves>(data::fields::leaves::get(self.0)) }
     }
-    pub fn is_drag(self) -> bool {
+    pub const fn is_drag(self) -> bool {
         data::fields::is_drag::get(self.0)
     }
-    pub fn is_bottom(self) -> bool {
+    pub const fn is_bottom(self) -> bool {
         data::fields::is_bottom::get(self.0)
     }
-    pub fn is_has_book(self) -> bool {
+    pub const fn is_has_book(self) -> bool {
         data::fields::is_has_book::get(self.0)
     }
-    pub fn attachment(self) -> BellAttachType {
+    pub const fn attachment(self) -> BellAttachType {
         unsafe { core::mem::transmute::<u8, BellAttachType>(data::fields::attachment::get(self.0)) }
     }
-    pub fn is_signal_fire(self) -> bool {
+    pub const fn is_signal_fire(self) -> bool {
         data::fields::is_signal_fire::get(self.0)
     }
-    pub fn orientation(self) -> FrontAndTop {
+    pub const fn orientation(self) -> FrontAndTop {
         unsafe { core::mem::transmute::<u8, FrontAndTop>(data::fields::orientation::get(self.0)) }
     }
-    pub fn honey_level(self) -> u8 {
+    pub const fn honey_level(self) -> u8 {
         data::fields::honey_level::get(self.0)
     }
-    pub fn charges(self) -> u8 {
+    pub const fn charges(self) -> u8 {
         data::fields::charges::get(self.0)
     }
-    pub fn candles(self) -> u8 {
+    pub const fn candles(self) -> u8 {
         data::fields::candles::get(self.0)
     }
-    pub fn sculk_sensor_phase(self) -> SculkSensorPhase {
+    pub const fn sculk_sensor_phase(self) -> SculkSensorPhase {
         unsafe {
             core::mem::transmute::<u8, SculkSensorPhase>(data::fields::sculk_sensor_phase::get(
                 self.0,
             ))
         }
     }
-    pub fn is_bloom(self) -> bool {
+    pub const fn is_bloom(self) -> bool {
         data::fields::is_bloom::get(self.0)
     }
-    pub fn is_can_summon(self) -> bool {
+    pub const fn is_can_summon(self) -> bool {
         data::fields::is_can_summon::get(self.0)
     }
-    pub fn is_shrieking(self) -> bool {
+    pub const fn is_shrieking(self) -> bool {
         data::fields::is_shrieking::get(self.0)
     }
-    pub fn copper_golem_pose(self) -> Pose {
+    pub const fn copper_golem_pose(self) -> Pose {
         unsafe { core::mem::transmute::<u8, Pose>(data::fields::copper_golem_pose::get(self.0)) }
     }
-    pub fn thickness(self) -> DripstoneThickness {
+    pub const fn thickness(self) -> DripstoneThickness {
         unsafe {
             core::mem::transmute::<u8, DripstoneThickness>(data::fields::thickness::get(self.0))
         }
     }
-    pub fn vertical_direction(self) -> Direction {
+    pub const fn vertical_direction(self) -> Direction {
         unsafe {
             core::mem::transmute::<u8, Direction>(data::fields::vertical_direction::get(self.0))
         }
     }
-    pub fn is_berries(self) -> bool {
+    pub const fn is_berries(self) -> bool {
         data::fields::is_berries::get(self.0)
     }
-    pub fn flower_amount(self) -> u8 {
+    pub const fn flower_amount(self) -> u8 {
         data::fields::flower_amount::get(self.0)
     }
-    pub fn segment_amount(self) -> u8 {
+    pub const fn segment_amount(self) -> u8 {
         data::fields::segment_amount::get(self.0)
     }
-    pub fn tilt(self) -> Tilt {
+    pub const fn tilt(self) -> Tilt {
         unsafe { core::mem::transmute::<u8, Tilt>(data::fields::tilt::get(self.0)) }
     }
-    pub fn is_cracked(self) -> bool {
+    pub const fn is_cracked(self) -> bool {
         data::fields::is_cracked::get(self.0)
     }
-    pub fn is_crafting(self) -> bool {
+    pub const fn is_crafting(self) -> bool {
         data::fields::is_crafting::get(self.0)
     }
-    pub fn is_ominous(self) -> bool {
+    pub const fn is_ominous(self) -> bool {
         data::fields::is_ominous::get(self.0)
     }
-    pub fn trial_spawner_state(self) -> TrialSpawnerState {
+    pub const fn trial_spawner_state(self) -> TrialSpawnerState {
         unsafe {
             core::mem::transmute::<u8, TrialSpawnerState>(data::fields::trial_spawner_state::get(
                 self.0,
             ))
         }
     }
-    pub fn vault_state(self) -> VaultState {
+    pub const fn vault_state(self) -> VaultState {
         unsafe { core::mem::transmute::<u8, VaultState>(data::fields::vault_state::get(self.0)) }
     }
-    pub fn is_tip(self) -> bool {
+    pub const fn is_tip(self) -> bool {
         data::fields::is_tip::get(self.0)
     }
-    pub fn rail_shape(self) -> RailShape {
+    pub const fn rail_shape(self) -> RailShape {
         unsafe { core::mem::transmute::<u8, RailShape>(data::fields::rail_shape::get(self.0)) }
     }
-    pub fn stairs_shape(self) -> StairsShape {
+    pub const fn stairs_shape(self) -> StairsShape {
         unsafe { core::mem::transmute::<u8, StairsShape>(data::fields::stairs_shape::get(self.0)) }
     }
-    pub fn doubleblock_half(self) -> DoubleBlockHalf {
+    pub const fn doubleblock_half(self) -> DoubleBlockHalf {
         unsafe {
             core::mem::transmute::<u8, DoubleBlockHalf>(data::fields::doubleblock_half::get(self.0))
         }
     }
-    pub fn half(self) -> Half {
+    pub const fn half(self) -> Half {
         unsafe { core::mem::transmute::<u8, Half>(data::fields::half::get(self.0)) }
     }
-    pub fn piston_type(self) -> PistonType {
+    pub const fn piston_type(self) -> PistonType {
         unsafe { core::mem::transmute::<u8, PistonType>(data::fields::piston_type::get(self.0)) }
     }
-    pub fn chest_type(self) -> ChestType {
+    pub const fn chest_type(self) -> ChestType {
         unsafe { core::mem::transmute::<u8, ChestType>(data::fields::chest_type::get(self.0)) }
     }
-    pub fn slab_type(self) -> SlabType {
+    pub const fn slab_type(self) -> SlabType {
         unsafe { core::mem::transmute::<u8, SlabType>(data::fields::slab_type::get(self.0)) }
     }
-    pub fn redstone_east(self) -> RedstoneSide {
+    pub const fn redstone_east(self) -> RedstoneSide {
         unsafe {
             core::mem::transmute::<u8, RedstoneSide>(data::fields::redstone_east::get(self.0))
         }
     }
-    pub fn wall_east(self) -> WallSide {
+    pub const fn wall_east(self) -> WallSide {
         unsafe { core::mem::transmute::<u8, WallSide>(data::fields::wall_east::get(self.0)) }
     }
-    pub fn redstone_north(self) -> RedstoneSide {
+    pub const fn redstone_north(self) -> RedstoneSide {
         unsafe {
             core::mem::transmute::<u8, RedstoneSide>(data::fields::redstone_north::get(self.0))
         }
     }
-    pub fn wall_north(self) -> WallSide {
+    pub const fn wall_north(self) -> WallSide {
         unsafe { core::mem::transmute::<u8, WallSide>(data::fields::wall_north::get(self.0)) }
     }
-    pub fn redstone_south(self) -> RedstoneSide {
+    pub const fn redstone_south(self) -> RedstoneSide {
         unsafe {
             core::mem::transmute::<u8, RedstoneSide>(data::fields::redstone_south::get(self.0))
         }
     }
-    pub fn wall_south(self) -> WallSide {
+    pub const fn wall_south(self) -> WallSide {
         unsafe { core::mem::transmute::<u8, WallSide>(data::fields::wall_south::get(self.0)) }
     }
-    pub fn redstone_west(self) -> RedstoneSide {
+    pub const fn redstone_west(self) -> RedstoneSide {
         unsafe {
             core::mem::transmute::<u8, RedstoneSide>(data::fields::redstone_west::get(self.0))
         }
     }
-    pub fn wall_west(self) -> WallSide {
+    pub const fn wall_west(self) -> WallSide {
         unsafe { core::mem::transmute::<u8, WallSide>(data::fields::wall_west::get(self.0)) }
     }
-    pub fn comparator_mode(self) -> ComparatorMode {
+    pub const fn comparator_mode(self) -> ComparatorMode {
         unsafe {
             core::mem::transmute::<u8, ComparatorMode>(data::fields::comparator_mode::get(self.0))
         }
     }
-    pub fn structure_mode(self) -> StructureMode {
+    pub const fn structure_mode(self) -> StructureMode {
         unsafe {
             core::mem::transmute::<u8, StructureMode>(data::fields::structure_mode::get(self.0))
         }
     }
-    pub fn testblock_mode(self) -> TestBlockMode {
+    pub const fn testblock_mode(self) -> TestBlockMode {
         unsafe {
             core::mem::transmute::<u8, TestBlockMode>(data::fields::testblock_mode::get(self.0))
         }
@@ -993,7 +1000,12 @@ impl Iterator for BlockStateIter {
         }
     }
     fn size_hint(&self) -> (usize, Option<usize>) {
-        let remaining = (self.end - self.current) as usize;
+        let remaining = if self.current > self.end {
+            0
+        } else {
+            (self.end - self.current + 1) as usize
+        };
         (remaining, Some(remaining))
     }
 }
+impl ExactSizeIterator for BlockStateIter {}
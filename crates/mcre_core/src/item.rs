@@ -0,0 +1,82 @@
+use alloc::vec::Vec;
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::{Registry, ResourceLocation};
+
+/// An item and a count.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct ItemStack {
+    pub id: ResourceLocation,
+    pub count: u32,
+}
+
+/// Resolves item tags such as `#minecraft:planks`.
+pub trait ItemTags {
+    fn has_tag(&self, item: &ResourceLocation, tag: &ResourceLocation) -> bool;
+
+    /// Every item in `tag`, empty for unknown tags.
+    fn tag_items(&self, tag: &ResourceLocation) -> Vec<ResourceLocation>;
+}
+
+/// An item registry with its bound tags.
+impl<T> ItemTags for Registry<T> {
+    fn has_tag(&self, item: &ResourceLocation, tag: &ResourceLocation) -> bool {
+        self.id_of(item)
+            .is_some_and(|id| self.tag(tag).is_some_and(|ids| ids.contains(&id)))
+    }
+
+    fn tag_items(&self, tag: &ResourceLocation) -> Vec<ResourceLocation> {
+        self.tag(tag)
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|&id| self.key_of(id).cloned())
+            .collect()
+    }
+}
+
+/// No tags at all, no item being in any tag.
+impl ItemTags for () {
+    fn has_tag(&self, _item: &ResourceLocation, _tag: &ResourceLocation) -> bool {
+        false
+    }
+
+    fn tag_items(&self, _tag: &ResourceLocation) -> Vec<ResourceLocation> {
+        Vec::new()
+    }
+}
+
+impl ItemStack {
+    pub fn new(id: ResourceLocation, count: u32) -> Self {
+        Self { id, count }
+    }
+}
+
+impl<'de> Deserialize<'de> for ItemStack {
+    /// Accepts `{"id": ..., "count": ...}`, the `item` key used before 1.20.5 and plain ids used
+    /// by cooking recipes before 1.21.
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        fn default_count() -> u32 {
+            1
+        }
+
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Helper {
+            Id(ResourceLocation),
+            Full {
+                #[serde(alias = "item")]
+                id: ResourceLocation,
+                #[serde(default = "default_count")]
+                count: u32,
+            },
+        }
+
+        Ok(match Helper::deserialize(deserializer)? {
+            Helper::Id(id) => Self::new(id, 1),
+            Helper::Full { id, count } => Self::new(id, count),
+        })
+    }
+}
@@ -0,0 +1,247 @@
+use core::ops::BitOr;
+
+use crate::{Block, BlockBehaviors, BlockPos, BlockState, FxHashMap};
+
+/// Side effects requested when changing a block, mirroring vanilla's `Block.UPDATE_*` flags.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct UpdateFlags(u8);
+
+impl UpdateFlags {
+    pub const NONE: Self = Self(0);
+    /// Notify neighbors through [`crate::BlockBehavior::neighbor_changed`].
+    pub const NEIGHBORS: Self = Self(1);
+    /// Mark the block for re-rendering.
+    pub const CLIENTS: Self = Self(2);
+    /// Skip [`crate::BlockBehavior::update_shape`] of the neighbors.
+    pub const KNOWN_SHAPE: Self = Self(16);
+    pub const ALL: Self = Self(Self::NEIGHBORS.0 | Self::CLIENTS.0);
+
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub const fn without(self, other: Self) -> Self {
+        Self(self.0 & !other.0)
+    }
+}
+
+impl BitOr for UpdateFlags {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+pub trait BlockGetter {
+    /// The state at `pos`, air for positions that aren't loaded.
+    fn get_block_state(&self, pos: BlockPos) -> BlockState;
+}
+
+/// Vertical bounds of a level, mirroring vanilla's `LevelHeightAccessor`.
+pub trait LevelHeightAccessor {
+    /// Lowest buildable y.
+    fn min_y(&self) -> i32;
+
+    /// Number of buildable layers above [`Self::min_y`].
+    fn height(&self) -> u32;
+
+    /// Highest buildable y.
+    fn max_y(&self) -> i32 {
+        self.min_y() + self.height() as i32 - 1
+    }
+
+    fn is_outside_build_height(&self, y: i32) -> bool {
+        y < self.min_y() || y > self.max_y()
+    }
+
+    /// Number of 16 block tall sections covering the height.
+    fn section_count(&self) -> u32 {
+        self.height().div_ceil(16)
+    }
+
+    /// Index of the section containing `y`, counted from the bottom of the level.
+    fn section_index(&self, y: i32) -> usize {
+        ((y >> 4) - (self.min_y() >> 4)) as usize
+    }
+}
+
+/// A level stored as chunks, giving direct access to them.
+///
+/// Optional, levels that aren't split into chunks only implement [`BlockGetter`].
+pub trait ChunkSource {
+    type Chunk: BlockGetter + ?Sized;
+
+    /// The loaded chunk containing `pos`.
+    fn get_chunk_at(&self, pos: BlockPos) -> Option<&Self::Chunk>;
+
+    fn has_chunk_at(&self, pos: BlockPos) -> bool {
+        self.get_chunk_at(pos).is_some()
+    }
+}
+
+pub trait LevelWriter {
+    /// Changes the state at `pos`, returning whether anything changed.
+    fn set_block_state(&mut self, pos: BlockPos, state: BlockState, flags: UpdateFlags) -> bool;
+}
+
+/// A level as seen by block behaviors, able to schedule ticks and propagate updates.
+pub trait LevelAccess: BlockGetter + LevelWriter {
+    /// Schedules [`crate::BlockBehavior::scheduled_tick`] of `block` at `pos` in `delay` ticks.
+    fn schedule_tick(&mut self, pos: BlockPos, block: Block, delay: u32);
+
+    /// Calls [`crate::BlockBehavior::neighbor_changed`] on the six neighbors of `pos`.
+    fn update_neighbors_at(&mut self, pos: BlockPos, source: Block);
+
+    /// The behaviors of the blocks of this level, to query hooks of other blocks.
+    fn behaviors(&self) -> &BlockBehaviors;
+}
+
+/// Fixed vertical bounds.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct LevelHeight {
+    pub min_y: i32,
+    pub height: u32,
+}
+
+impl LevelHeight {
+    pub const OVERWORLD: Self = Self::new(-64, 384);
+    pub const NETHER: Self = Self::new(0, 256);
+
+    pub const fn new(min_y: i32, height: u32) -> Self {
+        Self { min_y, height }
+    }
+}
+
+impl LevelHeightAccessor for LevelHeight {
+    fn min_y(&self) -> i32 {
+        self.min_y
+    }
+
+    fn height(&self) -> u32 {
+        self.height
+    }
+}
+
+/// A level kept in a hash map, for simulating block logic in tests and tools.
+///
+/// Unset positions read as air and writes outside of the build height are rejected.
+#[derive(Debug, Clone)]
+pub struct MemoryLevel {
+    height: LevelHeight,
+    blocks: FxHashMap<BlockPos, BlockState>,
+}
+
+impl MemoryLevel {
+    pub fn new(height: LevelHeight) -> Self {
+        Self {
+            height,
+            blocks: FxHashMap::default(),
+        }
+    }
+
+    /// Sets every position of the box between `from` and `to`, both inclusive, to `state`.
+    pub fn fill(&mut self, from: BlockPos, to: BlockPos, state: BlockState) {
+        for x in from.x.min(to.x)..=from.x.max(to.x) {
+            for y in from.y.min(to.y)..=from.y.max(to.y) {
+                for z in from.z.min(to.z)..=from.z.max(to.z) {
+                    self.set_block_state(BlockPos::new(x, y, z), state, UpdateFlags::NONE);
+                }
+            }
+        }
+    }
+
+    /// Every non-air block.
+    pub fn iter(&self) -> impl Iterator<Item = (BlockPos, BlockState)> + '_ {
+        self.blocks.iter().map(|(pos, state)| (*pos, *state))
+    }
+
+    pub fn len(&self) -> usize {
+        self.blocks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.blocks.is_empty()
+    }
+}
+
+impl Default for MemoryLevel {
+    fn default() -> Self {
+        Self::new(LevelHeight::OVERWORLD)
+    }
+}
+
+impl BlockGetter for MemoryLevel {
+    fn get_block_state(&self, pos: BlockPos) -> BlockState {
+        self.blocks
+            .get(&pos)
+            .copied()
+            .unwrap_or(Block::AIR.default_state())
+    }
+}
+
+impl LevelWriter for MemoryLevel {
+    fn set_block_state(&mut self, pos: BlockPos, state: BlockState, _flags: UpdateFlags) -> bool {
+        if self.is_outside_build_height(pos.y) {
+            return false;
+        }
+
+        let old = if state == Block::AIR.default_state() {
+            self.blocks.remove(&pos)
+        } else {
+            self.blocks.insert(pos, state)
+        };
+        old.unwrap_or(Block::AIR.default_state()) != state
+    }
+}
+
+impl LevelHeightAccessor for MemoryLevel {
+    fn min_y(&self) -> i32 {
+        self.height.min_y
+    }
+
+    fn height(&self) -> u32 {
+        self.height.height
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        Block, BlockGetter, BlockPos, LevelHeight, LevelHeightAccessor, LevelWriter, MemoryLevel,
+        UpdateFlags,
+    };
+
+    #[test]
+    fn test_height_bounds() {
+        let height = LevelHeight::OVERWORLD;
+        assert_eq!(height.max_y(), 319);
+        assert_eq!(height.section_count(), 24);
+        assert_eq!(height.section_index(-64), 0);
+        assert_eq!(height.section_index(-1), 3);
+        assert_eq!(height.section_index(319), 23);
+        assert!(height.is_outside_build_height(-65));
+        assert!(height.is_outside_build_height(320));
+        assert!(!height.is_outside_build_height(0));
+    }
+
+    #[test]
+    fn test_memory_level() {
+        let mut level = MemoryLevel::default();
+        let stone = Block::STONE.default_state();
+        let pos = BlockPos::new(3, -64, -7);
+
+        assert!(level.get_block_state(pos).is_air());
+        assert!(level.set_block_state(pos, stone, UpdateFlags::ALL));
+        assert!(!level.set_block_state(pos, stone, UpdateFlags::ALL));
+        assert_eq!(level.get_block_state(pos), stone);
+        assert!(!level.set_block_state(pos.below(), stone, UpdateFlags::ALL));
+        assert!(level.get_block_state(pos.below()).is_air());
+
+        level.fill(BlockPos::new(1, 0, 1), BlockPos::new(-1, 2, -1), stone);
+        assert_eq!(level.len(), 28);
+
+        assert!(level.set_block_state(pos, Block::AIR.default_state(), UpdateFlags::ALL));
+        assert_eq!(level.len(), 27);
+    }
+}
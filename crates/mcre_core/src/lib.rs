@@ -1,15 +1,103 @@
-#![no_std]
+#![cfg_attr(not(test), no_std)]
 
 extern crate alloc;
 
 mod axis;
+mod behavior;
 mod blockpos;
 mod data;
+mod item;
+mod level;
+mod random;
 mod random_offset;
+mod registry;
+mod resource_location;
+mod state_props;
 mod vec;
+mod version;
 
 pub use axis::{Axis, Direction, SignedAxis};
+pub use behavior::*;
 pub use blockpos::BlockPos;
 pub use data::*;
+pub use item::{ItemStack, ItemTags};
+pub use level::{
+    BlockGetter, ChunkSource, LevelAccess, LevelHeight, LevelHeightAccessor, LevelWriter,
+    MemoryLevel, UpdateFlags,
+};
+pub use random::*;
 pub use random_offset::OffsetType;
+pub use registry::*;
+pub use resource_location::{ResourceLocation, ResourceLocationError};
 pub use vec::*;
+pub use version::DataVersion;
+
+use hashbrown::HashMap;
+use rustc_hash::FxBuildHasher;
+
+pub(crate) type FxHashMap<K, V> = HashMap<K, V, FxBuildHasher>;
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        Block, BlockState, BlockStateIter, Direction, FieldKey, FieldVal, PropKey, PropVal,
+    };
+
+    // evaluated at compile time, a wrong value fails the build
+    const _: () = {
+        assert!(Block::STONE.default_state().solid_render());
+        assert!(Block::AIR.default_state().is_air());
+        assert!(!Block::GLASS.default_state().solid_render());
+        assert!(Block::GLOWSTONE.default_state().light_emission() == 15);
+        assert!(Block::WHEAT.default_state().age() == 0);
+        assert!(Block::WHEAT.max_state().age() == 7);
+        assert!(Block::WHEAT.max_state().block().id() == Block::WHEAT.id());
+        assert!(Block::STONE.min_state().id() == Block::STONE.max_state().id());
+        assert!(Block::LEVER.is_field_present(FieldKey::IsPowered));
+        assert!(!Block::STONE.is_field_present(FieldKey::IsPowered));
+        assert!(matches!(
+            Block::OAK_STAIRS.default_state().facing(),
+            Direction::North
+        ));
+        assert!(matches!(
+            Block::LEVER.default_state().get_field(FieldKey::IsPowered),
+            Some(FieldVal::IsPowered(false))
+        ));
+        assert!(matches!(PropVal::Age(3).key(), PropKey::Age));
+        assert!(Block::OBSIDIAN.default_state().destroy_speed() == 50.0);
+    };
+
+    /// Lookup table built at compile time from the generated accessors.
+    const LIGHT: [u8; 16] = {
+        let mut table = [0; 16];
+        let mut id = 0;
+        while id < table.len() {
+            table[id] = BlockState::from_id(id as u16).light_emission();
+            id += 1;
+        }
+        table
+    };
+
+    #[test]
+    fn test_const_accessors() {
+        const NAME: &str = Block::GRASS_BLOCK.name();
+        assert_eq!(NAME, "grass_block");
+
+        for (id, light) in LIGHT.iter().enumerate() {
+            assert_eq!(BlockState::from(id as u16).light_emission(), *light);
+        }
+    }
+
+    #[test]
+    fn test_state_iter() {
+        let wheat = BlockStateIter::new(Block::WHEAT.min_state(), Block::WHEAT.max_state());
+        assert_eq!(wheat.size_hint(), (8, Some(8)));
+        assert_eq!(wheat.len(), wheat.count());
+
+        let mut all = BlockState::all();
+        assert_eq!(all.len(), u16::from(BlockState::MAX) as usize + 1);
+        all.by_ref().for_each(drop);
+        assert_eq!(all.size_hint(), (0, Some(0)));
+        assert_eq!(all.next(), None);
+    }
+}
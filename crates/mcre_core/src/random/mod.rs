@@ -0,0 +1,292 @@
+mod md5;
+
+use crate::BlockPos;
+
+const GOLDEN_RATIO_64: i64 = 0x9E37_79B9_7F4A_7C15_u64 as i64;
+const SILVER_RATIO_64: i64 = 0x6A09_E667_F3BC_C909;
+
+const DOUBLE_UNIT: f64 = 1.0 / (1u64 << 53) as f64;
+const FLOAT_UNIT: f32 = 1.0 / (1u32 << 24) as f32;
+
+/// A source of pseudo random numbers, matching vanilla's `RandomSource` bit for bit.
+pub trait RandomSource {
+    fn next_long(&mut self) -> i64;
+
+    fn next_int(&mut self) -> i32;
+
+    /// Returns a value in `0..bound`.
+    fn next_int_bounded(&mut self, bound: i32) -> i32;
+
+    fn next_float(&mut self) -> f32;
+
+    fn next_double(&mut self) -> f64;
+
+    fn next_bool(&mut self) -> bool;
+
+    /// Returns a value in `min..=max`.
+    fn next_int_between_inclusive(&mut self, min: i32, max: i32) -> i32 {
+        min + self.next_int_bounded(max - min + 1)
+    }
+}
+
+/// Derives independent random sources from positions or names.
+pub trait PositionalRandomFactory {
+    type Random: RandomSource;
+
+    fn at(&self, pos: BlockPos) -> Self::Random;
+
+    fn with_hash_of(&self, name: &str) -> Self::Random;
+}
+
+/// The Xoroshiro128++ generator used by modern world generation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct XoroshiroRandom {
+    lo: i64,
+    hi: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct XoroshiroPositionalFactory {
+    lo: i64,
+    hi: i64,
+}
+
+/// The linear congruential generator of `java.util.Random`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LegacyRandom {
+    seed: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LegacyPositionalFactory {
+    seed: i64,
+}
+
+fn mix_stafford_13(mut z: i64) -> i64 {
+    z = (z ^ ((z as u64) >> 30) as i64).wrapping_mul(0xBF58_476D_1CE4_E5B9_u64 as i64);
+    z = (z ^ ((z as u64) >> 27) as i64).wrapping_mul(0x94D0_49BB_1331_11EB_u64 as i64);
+    z ^ ((z as u64) >> 31) as i64
+}
+
+impl XoroshiroRandom {
+    pub fn new(seed: i64) -> Self {
+        let lo = seed ^ SILVER_RATIO_64;
+        let hi = lo.wrapping_add(GOLDEN_RATIO_64);
+        Self::from_seed_128(mix_stafford_13(lo), mix_stafford_13(hi))
+    }
+
+    pub fn from_seed_128(lo: i64, hi: i64) -> Self {
+        if lo == 0 && hi == 0 {
+            Self {
+                lo: GOLDEN_RATIO_64,
+                hi: SILVER_RATIO_64,
+            }
+        } else {
+            Self { lo, hi }
+        }
+    }
+
+    pub fn fork(&mut self) -> Self {
+        let lo = self.next_long();
+        let hi = self.next_long();
+        Self::from_seed_128(lo, hi)
+    }
+
+    pub fn fork_positional(&mut self) -> XoroshiroPositionalFactory {
+        let lo = self.next_long();
+        let hi = self.next_long();
+        XoroshiroPositionalFactory { lo, hi }
+    }
+
+    fn next_bits(&mut self, bits: u32) -> i64 {
+        ((self.next_long() as u64) >> (64 - bits)) as i64
+    }
+}
+
+impl RandomSource for XoroshiroRandom {
+    fn next_long(&mut self) -> i64 {
+        let lo = self.lo;
+        let mut hi = self.hi;
+        let result = lo.wrapping_add(hi).rotate_left(17).wrapping_add(lo);
+        hi ^= lo;
+        self.lo = lo.rotate_left(49) ^ hi ^ (hi << 21);
+        self.hi = hi.rotate_left(28);
+        result
+    }
+
+    fn next_int(&mut self) -> i32 {
+        self.next_long() as i32
+    }
+
+    fn next_int_bounded(&mut self, bound: i32) -> i32 {
+        assert!(bound > 0, "bound must be positive");
+
+        // Lemire's nearly divisionless method, as implemented by vanilla
+        let bound = bound as u64;
+        let mut product = (self.next_int() as u32 as u64) * bound;
+        let mut low = product & 0xFFFF_FFFF;
+        if low < bound {
+            let threshold = (bound.wrapping_neg() & 0xFFFF_FFFF) % bound;
+            while low < threshold {
+                product = (self.next_int() as u32 as u64) * bound;
+                low = product & 0xFFFF_FFFF;
+            }
+        }
+        (product >> 32) as i32
+    }
+
+    fn next_float(&mut self) -> f32 {
+        self.next_bits(24) as f32 * FLOAT_UNIT
+    }
+
+    fn next_double(&mut self) -> f64 {
+        self.next_bits(53) as f64 * DOUBLE_UNIT
+    }
+
+    fn next_bool(&mut self) -> bool {
+        self.next_long() & 1 != 0
+    }
+}
+
+impl XoroshiroPositionalFactory {
+    pub fn with_seed(&self, seed: i64) -> XoroshiroRandom {
+        XoroshiroRandom::from_seed_128(seed ^ self.lo, seed ^ self.hi)
+    }
+}
+
+impl PositionalRandomFactory for XoroshiroPositionalFactory {
+    type Random = XoroshiroRandom;
+
+    fn at(&self, pos: BlockPos) -> XoroshiroRandom {
+        XoroshiroRandom::from_seed_128(pos.seed() ^ self.lo, self.hi)
+    }
+
+    fn with_hash_of(&self, name: &str) -> XoroshiroRandom {
+        let hash = md5::digest(name.as_bytes());
+        let lo = i64::from_be_bytes(hash[..8].try_into().unwrap());
+        let hi = i64::from_be_bytes(hash[8..].try_into().unwrap());
+        XoroshiroRandom::from_seed_128(lo ^ self.lo, hi ^ self.hi)
+    }
+}
+
+impl LegacyRandom {
+    const MULTIPLIER: i64 = 0x5_DEEC_E66D;
+    const INCREMENT: i64 = 0xB;
+    const MASK: i64 = (1 << 48) - 1;
+
+    pub fn new(seed: i64) -> Self {
+        Self {
+            seed: (seed ^ Self::MULTIPLIER) & Self::MASK,
+        }
+    }
+
+    pub fn fork(&mut self) -> Self {
+        Self::new(self.next_long())
+    }
+
+    pub fn fork_positional(&mut self) -> LegacyPositionalFactory {
+        LegacyPositionalFactory {
+            seed: self.next_long(),
+        }
+    }
+
+    fn next(&mut self, bits: u32) -> i32 {
+        self.seed = self
+            .seed
+            .wrapping_mul(Self::MULTIPLIER)
+            .wrapping_add(Self::INCREMENT)
+            & Self::MASK;
+        (self.seed >> (48 - bits)) as i32
+    }
+}
+
+impl RandomSource for LegacyRandom {
+    fn next_long(&mut self) -> i64 {
+        let hi = self.next(32) as i64;
+        let lo = self.next(32) as i64;
+        (hi << 32).wrapping_add(lo)
+    }
+
+    fn next_int(&mut self) -> i32 {
+        self.next(32)
+    }
+
+    fn next_int_bounded(&mut self, bound: i32) -> i32 {
+        assert!(bound > 0, "bound must be positive");
+
+        if bound & bound.wrapping_neg() == bound {
+            return ((bound as i64 * self.next(31) as i64) >> 31) as i32;
+        }
+
+        loop {
+            let bits = self.next(31);
+            let value = bits % bound;
+            if bits.wrapping_sub(value).wrapping_add(bound - 1) >= 0 {
+                return value;
+            }
+        }
+    }
+
+    fn next_float(&mut self) -> f32 {
+        self.next(24) as f32 * FLOAT_UNIT
+    }
+
+    fn next_double(&mut self) -> f64 {
+        let hi = self.next(26) as i64;
+        let lo = self.next(27) as i64;
+        ((hi << 27) + lo) as f64 * DOUBLE_UNIT
+    }
+
+    fn next_bool(&mut self) -> bool {
+        self.next(1) != 0
+    }
+}
+
+impl PositionalRandomFactory for LegacyPositionalFactory {
+    type Random = LegacyRandom;
+
+    fn at(&self, pos: BlockPos) -> LegacyRandom {
+        LegacyRandom::new(pos.seed() ^ self.seed)
+    }
+
+    fn with_hash_of(&self, name: &str) -> LegacyRandom {
+        // java.lang.String#hashCode
+        let hash = name
+            .encode_utf16()
+            .fold(0i32, |hash, c| hash.wrapping_mul(31).wrapping_add(c as i32));
+        LegacyRandom::new(hash as i64 ^ self.seed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{LegacyRandom, RandomSource, XoroshiroRandom};
+
+    #[test]
+    fn test_legacy_random_matches_java() {
+        // new java.util.Random(0).nextInt() / nextLong()
+        assert_eq!(LegacyRandom::new(0).next_int(), -1155484576);
+        assert_eq!(LegacyRandom::new(0).next_long(), -4962768465676381896);
+
+        let mut random = LegacyRandom::new(42);
+        assert_eq!(random.next_int_bounded(10), 0);
+        assert_eq!(random.next_int_bounded(10), 3);
+    }
+
+    #[test]
+    fn test_xoroshiro_is_deterministic() {
+        let mut a = XoroshiroRandom::new(1234);
+        let mut b = XoroshiroRandom::new(1234);
+
+        for _ in 0..100 {
+            assert_eq!(a.next_long(), b.next_long());
+        }
+
+        for _ in 0..1000 {
+            let value = a.next_int_bounded(7);
+            assert!((0..7).contains(&value));
+            let value = a.next_double();
+            assert!((0.0..1.0).contains(&value));
+        }
+    }
+}
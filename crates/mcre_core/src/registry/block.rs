@@ -0,0 +1,366 @@
+use alloc::{boxed::Box, string::String, vec::Vec};
+use serde::{Deserialize, Serialize};
+
+use crate::{Block, BlockState, Registry, RegistryError, ResourceLocation, TagEntry};
+
+/// Id of a block inside a [`BlockRegistry`].
+///
+/// In [`BlockRegistry::vanilla`], vanilla blocks keep the ids of [`Block`] and registered content
+/// is appended after them.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct BlockId(u32);
+
+/// Id of a block state inside a [`BlockRegistry`].
+///
+/// Like [`BlockState`], but also able to address states of blocks registered at runtime.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct StateId(u32);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockProperty {
+    pub name: String,
+    pub values: Box<[String]>,
+}
+
+/// Describes a block registered at runtime.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BlockDefinition {
+    pub properties: Vec<BlockProperty>,
+    /// Offset of the default state from the first state of the block.
+    pub default_state: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockEntry {
+    pub first_state: StateId,
+    pub state_count: u32,
+    pub default_state: StateId,
+    /// Properties of runtime registered blocks, empty for vanilla blocks which expose their
+    /// properties through [`BlockState::get_prop`].
+    pub properties: Box<[BlockProperty]>,
+    pub vanilla: Option<Block>,
+}
+
+/// The block registry, owning both blocks and their states.
+#[derive(Debug, Clone)]
+pub struct BlockRegistry {
+    blocks: Registry<BlockEntry>,
+    state_owners: Vec<BlockId>,
+}
+
+impl BlockId {
+    pub const fn new(id: u32) -> Self {
+        Self(id)
+    }
+
+    pub const fn id(self) -> u32 {
+        self.0
+    }
+}
+
+impl From<Block> for BlockId {
+    fn from(block: Block) -> Self {
+        Self(u16::from(block) as u32)
+    }
+}
+
+impl StateId {
+    pub const fn new(id: u32) -> Self {
+        Self(id)
+    }
+
+    pub const fn id(self) -> u32 {
+        self.0
+    }
+}
+
+impl From<BlockState> for StateId {
+    fn from(state: BlockState) -> Self {
+        Self(u16::from(state) as u32)
+    }
+}
+
+impl BlockEntry {
+    pub fn states(&self) -> impl Iterator<Item = StateId> + use<> {
+        let first = self.first_state.0;
+        (first..first + self.state_count).map(StateId)
+    }
+
+    pub fn contains(&self, state: StateId) -> bool {
+        state.0 >= self.first_state.0 && state.0 < self.first_state.0 + self.state_count
+    }
+}
+
+impl BlockRegistry {
+    pub fn new() -> Self {
+        Self {
+            blocks: Registry::new(ResourceLocation::minecraft("block")),
+            state_owners: Vec::new(),
+        }
+    }
+
+    /// Creates a registry pre-populated with every vanilla block from the generated tables.
+    pub fn vanilla() -> Self {
+        let mut registry = Self::new();
+
+        for block in Block::all() {
+            let first_state = StateId::from(block.min_state());
+            let state_count = u16::from(block.max_state()) as u32 - first_state.0 + 1;
+            let entry = BlockEntry {
+                first_state,
+                state_count,
+                default_state: block.default_state().into(),
+                properties: Box::new([]),
+                vanilla: Some(block),
+            };
+
+            registry
+                .insert(ResourceLocation::minecraft(block.name()), entry)
+                .expect("vanilla block names are unique");
+        }
+
+        registry
+    }
+
+    pub fn register(
+        &mut self,
+        key: ResourceLocation,
+        definition: BlockDefinition,
+    ) -> Result<BlockId, RegistryError> {
+        if let Some(property) = definition
+            .properties
+            .iter()
+            .find(|property| property.values.is_empty())
+        {
+            return Err(RegistryError::EmptyProperty {
+                block: key,
+                property: property.name.clone(),
+            });
+        }
+        let state_count = definition
+            .properties
+            .iter()
+            .map(|property| property.values.len() as u32)
+            .product::<u32>();
+        let first_state = StateId(self.state_owners.len() as u32);
+
+        let entry = BlockEntry {
+            first_state,
+            state_count,
+            default_state: StateId(first_state.0 + definition.default_state.min(state_count - 1)),
+            properties: definition.properties.into_boxed_slice(),
+            vanilla: None,
+        };
+
+        self.insert(key, entry)
+    }
+
+    fn insert(
+        &mut self,
+        key: ResourceLocation,
+        entry: BlockEntry,
+    ) -> Result<BlockId, RegistryError> {
+        let state_count = entry.state_count;
+        let id = BlockId(self.blocks.register(key, entry)?);
+        self.state_owners.extend((0..state_count).map(|_| id));
+        Ok(id)
+    }
+
+    pub fn freeze(&mut self) {
+        self.blocks.freeze();
+    }
+
+    pub fn bind_tags<I>(&mut self, tags: I) -> Result<(), RegistryError>
+    where
+        I: IntoIterator<Item = (ResourceLocation, Vec<TagEntry>)>,
+    {
+        self.blocks.bind_tags(tags)
+    }
+
+    /// The underlying block registry, for key and tag lookups.
+    pub fn blocks(&self) -> &Registry<BlockEntry> {
+        &self.blocks
+    }
+
+    pub fn get(&self, block: BlockId) -> Option<&BlockEntry> {
+        self.blocks.get(block.0)
+    }
+
+    pub fn get_by_key(&self, key: &ResourceLocation) -> Option<(BlockId, &BlockEntry)> {
+        let id = self.blocks.id_of(key)?;
+        Some((BlockId(id), self.blocks.get(id)?))
+    }
+
+    /// The vanilla block of an entry added by [`Self::vanilla`], `None` for runtime blocks.
+    pub fn vanilla_block(&self, block: BlockId) -> Option<Block> {
+        self.get(block)?.vanilla
+    }
+
+    /// The vanilla state of a block added by [`Self::vanilla`], `None` for runtime states.
+    pub fn vanilla_state(&self, state: StateId) -> Option<BlockState> {
+        let entry = self.get(self.block_of(state)?)?;
+        let offset = (state.0 - entry.first_state.0) as u16;
+        Some(BlockState::from(
+            u16::from(entry.vanilla?.min_state()) + offset,
+        ))
+    }
+
+    pub fn key_of(&self, block: BlockId) -> Option<&ResourceLocation> {
+        self.blocks.key_of(block.0)
+    }
+
+    pub fn block_of(&self, state: StateId) -> Option<BlockId> {
+        self.state_owners.get(state.0 as usize).copied()
+    }
+
+    pub fn state_count(&self) -> usize {
+        self.state_owners.len()
+    }
+
+    pub fn is_in_tag(&self, block: BlockId, tag: &ResourceLocation) -> bool {
+        self.blocks.is_in_tag(block.0, tag)
+    }
+
+    /// Value of a property of a runtime registered block state.
+    pub fn property_value(&self, state: StateId, name: &str) -> Option<&str> {
+        let entry = self.get(self.block_of(state)?)?;
+        let mut index = (state.0 - entry.first_state.0) as usize;
+
+        // the last property varies fastest, matching vanilla state ordering
+        for property in entry.properties.iter().rev() {
+            let len = property.values.len();
+            if property.name == name {
+                return Some(&property.values[index % len]);
+            }
+            index /= len;
+        }
+
+        None
+    }
+}
+
+impl Default for BlockRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{boxed::Box, string::ToString, vec};
+
+    use crate::{
+        Block, BlockDefinition, BlockId, BlockProperty, BlockRegistry, BlockState, RegistryError,
+        ResourceLocation, StateId,
+    };
+
+    #[test]
+    fn test_vanilla_blocks() {
+        let registry = BlockRegistry::vanilla();
+
+        assert_eq!(registry.blocks().len(), u16::from(Block::MAX) as usize + 1);
+        assert_eq!(
+            registry.state_count(),
+            u16::from(BlockState::MAX) as usize + 1
+        );
+
+        let (id, entry) = registry
+            .get_by_key(&ResourceLocation::minecraft("grass_block"))
+            .unwrap();
+        assert_eq!(registry.vanilla_block(id), Some(Block::GRASS_BLOCK));
+        assert_eq!(
+            registry.vanilla_state(entry.default_state),
+            Some(Block::GRASS_BLOCK.default_state())
+        );
+
+        for state in BlockState::all() {
+            assert_eq!(
+                registry.block_of(state.into()),
+                Some(BlockId::from(state.block()))
+            );
+        }
+    }
+
+    #[test]
+    fn test_dynamic_blocks() {
+        let mut registry = BlockRegistry::vanilla();
+        let first_free_state = registry.state_count() as u32;
+
+        let id = registry
+            .register(
+                "mymod:ruby_lamp".parse().unwrap(),
+                BlockDefinition {
+                    properties: vec![
+                        BlockProperty {
+                            name: "lit".to_string(),
+                            values: Box::new(["true".to_string(), "false".to_string()]),
+                        },
+                        BlockProperty {
+                            name: "color".to_string(),
+                            values: Box::new([
+                                "red".to_string(),
+                                "green".to_string(),
+                                "blue".to_string(),
+                            ]),
+                        },
+                    ],
+                    default_state: 3,
+                },
+            )
+            .unwrap();
+
+        assert_eq!(registry.vanilla_block(id), None);
+
+        let entry = registry.get(id).unwrap();
+        assert_eq!(entry.state_count, 6);
+        assert_eq!(entry.first_state, StateId::new(first_free_state));
+        assert_eq!(registry.vanilla_state(entry.default_state), None);
+
+        let default_state = entry.default_state;
+        assert_eq!(registry.block_of(default_state), Some(id));
+        assert_eq!(registry.property_value(default_state, "lit"), Some("false"));
+        assert_eq!(registry.property_value(default_state, "color"), Some("red"));
+        assert_eq!(registry.property_value(default_state, "missing"), None);
+    }
+
+    #[test]
+    fn test_empty_registry() {
+        let mut registry = BlockRegistry::new();
+        let id = registry
+            .register("mymod:ruby".parse().unwrap(), BlockDefinition::default())
+            .unwrap();
+
+        // the first ids of an empty registry are not the vanilla ones
+        assert_eq!(id, BlockId::from(Block::AIR));
+        assert_eq!(registry.vanilla_block(id), None);
+        assert_eq!(registry.vanilla_state(StateId::new(0)), None);
+    }
+
+    #[test]
+    fn test_empty_property() {
+        let mut registry = BlockRegistry::vanilla();
+        let state_count = registry.state_count();
+        let key: ResourceLocation = "mymod:empty".parse().unwrap();
+
+        let result = registry.register(
+            key.clone(),
+            BlockDefinition {
+                properties: vec![BlockProperty {
+                    name: "color".to_string(),
+                    values: Box::new([]),
+                }],
+                default_state: 0,
+            },
+        );
+
+        assert_eq!(
+            result,
+            Err(RegistryError::EmptyProperty {
+                block: key.clone(),
+                property: "color".to_string(),
+            })
+        );
+        assert!(registry.get_by_key(&key).is_none());
+        assert_eq!(registry.state_count(), state_count);
+    }
+}
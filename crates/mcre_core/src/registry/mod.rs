@@ -0,0 +1,327 @@
+mod block;
+mod tag;
+
+pub use block::*;
+pub use tag::*;
+
+use core::fmt::{self, Display};
+
+use alloc::{boxed::Box, string::String, vec::Vec};
+
+use crate::{FxHashMap, ResourceLocation};
+
+/// An ordered, id-addressable collection of values keyed by [`ResourceLocation`].
+///
+/// Entries receive sequential ids in registration order. Once [`Registry::freeze`] is called no
+/// further entries can be added, but tags can still be (re)bound, mirroring how vanilla reloads
+/// data packs on top of the built-in registries.
+#[derive(Debug, Clone)]
+pub struct Registry<T> {
+    key: ResourceLocation,
+    keys: Vec<ResourceLocation>,
+    values: Vec<T>,
+    ids: FxHashMap<ResourceLocation, u32>,
+    tags: FxHashMap<ResourceLocation, Box<[u32]>>,
+    frozen: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RegistryError {
+    Frozen(ResourceLocation),
+    DuplicateKey(ResourceLocation),
+    UnknownTagEntry {
+        tag: ResourceLocation,
+        entry: ResourceLocation,
+    },
+    UnknownTag {
+        tag: ResourceLocation,
+        reference: ResourceLocation,
+    },
+    CyclicTag(ResourceLocation),
+    /// A block property without any value, so the block would have no states.
+    EmptyProperty {
+        block: ResourceLocation,
+        property: String,
+    },
+}
+
+impl<T> Registry<T> {
+    pub fn new(key: ResourceLocation) -> Self {
+        Self {
+            key,
+            keys: Vec::new(),
+            values: Vec::new(),
+            ids: FxHashMap::default(),
+            tags: FxHashMap::default(),
+            frozen: false,
+        }
+    }
+
+    /// The key of the registry itself, e.g. `minecraft:block`.
+    pub fn key(&self) -> &ResourceLocation {
+        &self.key
+    }
+
+    pub fn register(&mut self, key: ResourceLocation, value: T) -> Result<u32, RegistryError> {
+        if self.frozen {
+            return Err(RegistryError::Frozen(key));
+        }
+
+        if self.ids.contains_key(&key) {
+            return Err(RegistryError::DuplicateKey(key));
+        }
+
+        let id = self.values.len() as u32;
+        self.ids.insert(key.clone(), id);
+        self.keys.push(key);
+        self.values.push(value);
+
+        Ok(id)
+    }
+
+    pub fn freeze(&mut self) {
+        self.frozen = true;
+    }
+
+    pub fn is_frozen(&self) -> bool {
+        self.frozen
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn get(&self, id: u32) -> Option<&T> {
+        self.values.get(id as usize)
+    }
+
+    pub fn get_mut(&mut self, id: u32) -> Option<&mut T> {
+        self.values.get_mut(id as usize)
+    }
+
+    pub fn get_by_key(&self, key: &ResourceLocation) -> Option<&T> {
+        self.id_of(key).and_then(|id| self.get(id))
+    }
+
+    pub fn id_of(&self, key: &ResourceLocation) -> Option<u32> {
+        self.ids.get(key).copied()
+    }
+
+    pub fn key_of(&self, id: u32) -> Option<&ResourceLocation> {
+        self.keys.get(id as usize)
+    }
+
+    /// Reverse lookup from a value to its id.
+    ///
+    /// This is a linear scan, so prefer keeping the id around where possible.
+    pub fn id_of_value(&self, value: &T) -> Option<u32>
+    where
+        T: PartialEq,
+    {
+        self.values
+            .iter()
+            .position(|candidate| candidate == value)
+            .map(|id| id as u32)
+    }
+
+    pub fn contains_key(&self, key: &ResourceLocation) -> bool {
+        self.ids.contains_key(key)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (u32, &ResourceLocation, &T)> {
+        self.keys
+            .iter()
+            .zip(&self.values)
+            .enumerate()
+            .map(|(id, (key, value))| (id as u32, key, value))
+    }
+
+    /// Replaces all tags of this registry, resolving nested tag references.
+    ///
+    /// Binding is all-or-nothing: on error the previously bound tags are kept.
+    pub fn bind_tags<I>(&mut self, tags: I) -> Result<(), RegistryError>
+    where
+        I: IntoIterator<Item = (ResourceLocation, Vec<TagEntry>)>,
+    {
+        let definitions: FxHashMap<ResourceLocation, Vec<TagEntry>> = tags.into_iter().collect();
+        let mut resolved = FxHashMap::default();
+
+        for tag in definitions.keys() {
+            let mut stack = Vec::new();
+            resolve_tag(tag, &definitions, &self.ids, &mut resolved, &mut stack)?;
+        }
+
+        self.tags = resolved;
+        Ok(())
+    }
+
+    pub fn tag(&self, tag: &ResourceLocation) -> Option<&[u32]> {
+        self.tags.get(tag).map(|ids| &**ids)
+    }
+
+    pub fn is_in_tag(&self, id: u32, tag: &ResourceLocation) -> bool {
+        self.tag(tag).is_some_and(|ids| ids.contains(&id))
+    }
+
+    pub fn tags(&self) -> impl Iterator<Item = (&ResourceLocation, &[u32])> {
+        self.tags.iter().map(|(tag, ids)| (tag, &**ids))
+    }
+}
+
+fn resolve_tag(
+    tag: &ResourceLocation,
+    definitions: &FxHashMap<ResourceLocation, Vec<TagEntry>>,
+    ids: &FxHashMap<ResourceLocation, u32>,
+    resolved: &mut FxHashMap<ResourceLocation, Box<[u32]>>,
+    stack: &mut Vec<ResourceLocation>,
+) -> Result<(), RegistryError> {
+    if resolved.contains_key(tag) {
+        return Ok(());
+    }
+
+    if stack.contains(tag) {
+        return Err(RegistryError::CyclicTag(tag.clone()));
+    }
+
+    stack.push(tag.clone());
+
+    let mut values = Vec::new();
+
+    for entry in &definitions[tag] {
+        match entry {
+            TagEntry::Element { id, required } => match ids.get(id) {
+                Some(id) => {
+                    if !values.contains(id) {
+                        values.push(*id);
+                    }
+                }
+                None if *required => {
+                    return Err(RegistryError::UnknownTagEntry {
+                        tag: tag.clone(),
+                        entry: id.clone(),
+                    });
+                }
+                None => {}
+            },
+            TagEntry::Tag { id, required } => {
+                if !definitions.contains_key(id) {
+                    if *required {
+                        return Err(RegistryError::UnknownTag {
+                            tag: tag.clone(),
+                            reference: id.clone(),
+                        });
+                    }
+                    continue;
+                }
+
+                resolve_tag(id, definitions, ids, resolved, stack)?;
+
+                for id in resolved[id].iter() {
+                    if !values.contains(id) {
+                        values.push(*id);
+                    }
+                }
+            }
+        }
+    }
+
+    stack.pop();
+    resolved.insert(tag.clone(), values.into_boxed_slice());
+
+    Ok(())
+}
+
+impl Display for RegistryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Frozen(key) => write!(f, "cannot register {key}: registry is frozen"),
+            Self::DuplicateKey(key) => write!(f, "{key} is already registered"),
+            Self::UnknownTagEntry { tag, entry } => {
+                write!(f, "tag #{tag} references unknown entry {entry}")
+            }
+            Self::UnknownTag { tag, reference } => {
+                write!(f, "tag #{tag} references unknown tag #{reference}")
+            }
+            Self::CyclicTag(tag) => write!(f, "tag #{tag} references itself"),
+            Self::EmptyProperty { block, property } => {
+                write!(f, "property {property} of block {block} has no values")
+            }
+        }
+    }
+}
+
+impl core::error::Error for RegistryError {}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use crate::{Registry, RegistryError, ResourceLocation, TagEntry};
+
+    fn loc(s: &str) -> ResourceLocation {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_register_and_lookup() {
+        let mut registry = Registry::new(loc("test"));
+
+        assert_eq!(registry.register(loc("a"), 'a'), Ok(0));
+        assert_eq!(registry.register(loc("mymod:b"), 'b'), Ok(1));
+        assert_eq!(
+            registry.register(loc("a"), 'c'),
+            Err(RegistryError::DuplicateKey(loc("a")))
+        );
+
+        assert_eq!(registry.get(1), Some(&'b'));
+        assert_eq!(registry.id_of(&loc("mymod:b")), Some(1));
+        assert_eq!(registry.key_of(0), Some(&loc("minecraft:a")));
+        assert_eq!(registry.id_of_value(&'b'), Some(1));
+
+        registry.freeze();
+        assert_eq!(
+            registry.register(loc("c"), 'c'),
+            Err(RegistryError::Frozen(loc("c")))
+        );
+    }
+
+    #[test]
+    fn test_bind_tags() {
+        let mut registry = Registry::new(loc("test"));
+        for key in ["a", "b", "c"] {
+            registry.register(loc(key), ()).unwrap();
+        }
+        registry.freeze();
+
+        registry
+            .bind_tags([
+                (loc("first"), vec![TagEntry::element(loc("a"))]),
+                (
+                    loc("all"),
+                    vec![
+                        TagEntry::tag(loc("first")),
+                        TagEntry::element(loc("b")),
+                        TagEntry::element(loc("c")),
+                        TagEntry::optional_element(loc("missing")),
+                    ],
+                ),
+            ])
+            .unwrap();
+
+        assert_eq!(registry.tag(&loc("all")), Some(&[0, 1, 2][..]));
+        assert!(registry.is_in_tag(0, &loc("first")));
+        assert!(!registry.is_in_tag(1, &loc("first")));
+
+        let result = registry.bind_tags([
+            (loc("x"), vec![TagEntry::tag(loc("y"))]),
+            (loc("y"), vec![TagEntry::tag(loc("x"))]),
+        ]);
+        assert!(matches!(result, Err(RegistryError::CyclicTag(_))));
+        // failed binding keeps the previous tags
+        assert!(registry.tag(&loc("all")).is_some());
+    }
+}
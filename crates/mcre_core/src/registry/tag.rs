@@ -0,0 +1,136 @@
+use core::{
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+use alloc::string::{String, ToString};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{ResourceLocation, ResourceLocationError};
+
+/// A single entry of a tag definition, as found in `data/<namespace>/tags/<registry>/*.json`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum TagEntry {
+    Element {
+        id: ResourceLocation,
+        required: bool,
+    },
+    Tag {
+        id: ResourceLocation,
+        required: bool,
+    },
+}
+
+impl TagEntry {
+    pub fn element(id: ResourceLocation) -> Self {
+        Self::Element { id, required: true }
+    }
+
+    pub fn optional_element(id: ResourceLocation) -> Self {
+        Self::Element {
+            id,
+            required: false,
+        }
+    }
+
+    pub fn tag(id: ResourceLocation) -> Self {
+        Self::Tag { id, required: true }
+    }
+
+    pub fn optional_tag(id: ResourceLocation) -> Self {
+        Self::Tag {
+            id,
+            required: false,
+        }
+    }
+
+    pub fn id(&self) -> &ResourceLocation {
+        match self {
+            Self::Element { id, .. } | Self::Tag { id, .. } => id,
+        }
+    }
+
+    pub fn is_required(&self) -> bool {
+        match self {
+            Self::Element { required, .. } | Self::Tag { required, .. } => *required,
+        }
+    }
+}
+
+impl FromStr for TagEntry {
+    type Err = ResourceLocationError;
+
+    /// Parses the short form of an entry, e.g. `minecraft:stone` or `#minecraft:logs`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.strip_prefix('#') {
+            Some(tag) => Ok(Self::tag(tag.parse()?)),
+            None => Ok(Self::element(s.parse()?)),
+        }
+    }
+}
+
+impl Display for TagEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Element { id, .. } => write!(f, "{id}"),
+            Self::Tag { id, .. } => write!(f, "#{id}"),
+        }
+    }
+}
+
+impl Serialize for TagEntry {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if self.is_required() {
+            serializer.serialize_str(&self.to_string())
+        } else {
+            #[derive(Serialize)]
+            struct Helper {
+                id: String,
+                required: bool,
+            }
+
+            Helper {
+                id: self.to_string(),
+                required: false,
+            }
+            .serialize(serializer)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for TagEntry {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        fn default_required() -> bool {
+            true
+        }
+
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Helper {
+            Short(String),
+            Full {
+                id: String,
+                #[serde(default = "default_required")]
+                required: bool,
+            },
+        }
+
+        let (id, required) = match Helper::deserialize(deserializer)? {
+            Helper::Short(id) => (id, true),
+            Helper::Full { id, required } => (id, required),
+        };
+
+        let entry: TagEntry = id.parse().map_err(serde::de::Error::custom)?;
+
+        Ok(match entry {
+            Self::Element { id, .. } => Self::Element { id, required },
+            Self::Tag { id, .. } => Self::Tag { id, required },
+        })
+    }
+}
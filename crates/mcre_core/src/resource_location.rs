@@ -0,0 +1,150 @@
+use core::{
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+use alloc::string::{String, ToString};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// A namespaced identifier such as `minecraft:stone` or `mymod:ruby_ore`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ResourceLocation {
+    namespace: String,
+    path: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResourceLocationError {
+    InvalidNamespace,
+    InvalidPath,
+}
+
+impl ResourceLocation {
+    pub const DEFAULT_NAMESPACE: &'static str = "minecraft";
+
+    pub fn new(
+        namespace: impl Into<String>,
+        path: impl Into<String>,
+    ) -> Result<Self, ResourceLocationError> {
+        let namespace = namespace.into();
+        let path = path.into();
+
+        if namespace.is_empty() || !namespace.bytes().all(is_valid_namespace_char) {
+            return Err(ResourceLocationError::InvalidNamespace);
+        }
+
+        if path.is_empty() || !path.bytes().all(is_valid_path_char) {
+            return Err(ResourceLocationError::InvalidPath);
+        }
+
+        Ok(Self { namespace, path })
+    }
+
+    /// Shorthand for a location in the `minecraft` namespace.
+    ///
+    /// # Panics
+    /// Panics if `path` is not a valid path.
+    pub fn minecraft(path: impl Into<String>) -> Self {
+        Self::new(Self::DEFAULT_NAMESPACE, path).expect("invalid resource location path")
+    }
+
+    pub fn namespace(&self) -> &str {
+        &self.namespace
+    }
+
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Returns a new location in the same namespace with `prefix` prepended to the path.
+    pub fn with_prefix(&self, prefix: &str) -> Self {
+        let mut path = String::with_capacity(prefix.len() + self.path.len());
+        path.push_str(prefix);
+        path.push_str(&self.path);
+
+        Self {
+            namespace: self.namespace.clone(),
+            path,
+        }
+    }
+}
+
+fn is_valid_namespace_char(c: u8) -> bool {
+    matches!(c, b'a'..=b'z' | b'0'..=b'9' | b'_' | b'-' | b'.')
+}
+
+fn is_valid_path_char(c: u8) -> bool {
+    is_valid_namespace_char(c) || c == b'/'
+}
+
+impl FromStr for ResourceLocation {
+    type Err = ResourceLocationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(':') {
+            Some((namespace, path)) => Self::new(namespace, path),
+            None => Self::new(Self::DEFAULT_NAMESPACE, s),
+        }
+    }
+}
+
+impl Display for ResourceLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.namespace, self.path)
+    }
+}
+
+impl Display for ResourceLocationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidNamespace => f.write_str("invalid resource location namespace"),
+            Self::InvalidPath => f.write_str("invalid resource location path"),
+        }
+    }
+}
+
+impl core::error::Error for ResourceLocationError {}
+
+impl Serialize for ResourceLocation {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for ResourceLocation {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ResourceLocation, ResourceLocationError};
+
+    #[test]
+    fn test_parse() {
+        let loc: ResourceLocation = "stone".parse().unwrap();
+        assert_eq!(loc.namespace(), "minecraft");
+        assert_eq!(loc.path(), "stone");
+
+        let loc: ResourceLocation = "mymod:block/ruby_ore".parse().unwrap();
+        assert_eq!(loc.namespace(), "mymod");
+        assert_eq!(loc.path(), "block/ruby_ore");
+
+        assert_eq!(
+            "My:stone".parse::<ResourceLocation>(),
+            Err(ResourceLocationError::InvalidNamespace)
+        );
+        assert_eq!(
+            "minecraft:".parse::<ResourceLocation>(),
+            Err(ResourceLocationError::InvalidPath)
+        );
+    }
+}
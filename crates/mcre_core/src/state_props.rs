@@ -0,0 +1,112 @@
+use crate::{BlockState, PropKey, PropVal};
+
+impl BlockState {
+    /// Returns the state of the same block with `val` replacing the current value of its property,
+    /// keeping every other property unchanged.
+    ///
+    /// Returns `None` if the block doesn't have the property or doesn't allow the value.
+    pub fn with_prop(self, val: PropVal) -> Option<Self> {
+        let layout = PropLayout::of(self, val.key())?;
+        (0..layout.count)
+            .map(|digit| layout.state(digit))
+            .find(|state| state.get_prop(val.key()) == Some(val))
+    }
+
+    /// Returns the state with the next value of `key`, wrapping around after the last one, like
+    /// vanilla's `BlockState::cycle`.
+    pub fn cycle_prop(self, key: PropKey) -> Option<Self> {
+        let layout = PropLayout::of(self, key)?;
+        Some(layout.state((layout.digit + 1) % layout.count))
+    }
+}
+
+/// Where the values of one property of a state's block live.
+///
+/// States are the cartesian product of all properties, so each property cycles through its
+/// values with a fixed stride.
+struct PropLayout {
+    /// Id of the state with this property at its first value and every other property unchanged.
+    base: u16,
+    stride: u16,
+    count: u16,
+    digit: u16,
+}
+
+impl PropLayout {
+    fn of(state: BlockState, key: PropKey) -> Option<Self> {
+        state.get_prop(key)?;
+
+        let block = state.block();
+        let min = u16::from(block.min_state());
+        let len = u16::from(block.max_state()) - min + 1;
+        let prop_at = |offset: u16| BlockState::from(min + offset).get_prop(key);
+
+        let first = prop_at(0);
+        let (stride, count) = match (1..len).find(|&offset| prop_at(offset) != first) {
+            Some(stride) => {
+                let mut count = 1;
+                while count * stride < len && prop_at(count * stride) != first {
+                    count += 1;
+                }
+                (stride, count)
+            }
+            None => (1, 1),
+        };
+
+        let offset = u16::from(state) - min;
+        let digit = offset / stride % count;
+
+        Some(Self {
+            base: min + offset - digit * stride,
+            stride,
+            count,
+            digit,
+        })
+    }
+
+    fn state(&self, digit: u16) -> BlockState {
+        BlockState::from(self.base + digit * self.stride)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Block, Direction, PropKey, PropVal};
+
+    #[test]
+    fn test_with_prop() {
+        let wheat = Block::WHEAT.default_state();
+        assert_eq!(wheat.age(), 0);
+
+        let grown = wheat.with_prop(PropVal::Age(7)).unwrap();
+        assert_eq!(grown.block(), Block::WHEAT);
+        assert_eq!(grown.age(), 7);
+        assert_eq!(wheat.with_prop(PropVal::Age(8)), None);
+        assert_eq!(wheat.with_prop(PropVal::Facing(Direction::North)), None);
+
+        let stairs = Block::OAK_STAIRS
+            .default_state()
+            .with_prop(PropVal::Waterlogged(true))
+            .unwrap();
+        let turned = stairs.with_prop(PropVal::Facing(Direction::East)).unwrap();
+        assert_eq!(turned.facing(), Direction::East);
+        assert!(turned.is_waterlogged());
+        assert_eq!(
+            turned.get_prop(PropKey::Half),
+            stairs.get_prop(PropKey::Half)
+        );
+        assert_eq!(
+            turned.get_prop(PropKey::Shape),
+            stairs.get_prop(PropKey::Shape)
+        );
+    }
+
+    #[test]
+    fn test_cycle_prop() {
+        let lever = Block::LEVER.default_state();
+        let on = lever.cycle_prop(PropKey::Powered).unwrap();
+        assert!(on.is_powered());
+        assert_eq!(on.facing(), lever.facing());
+        assert_eq!(on.cycle_prop(PropKey::Powered), Some(lever));
+    }
+}
@@ -0,0 +1,8 @@
+/// The game version the block tables were generated from.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct DataVersion {
+    pub id: &'static str,
+    /// Data version stored in saves.
+    pub world_version: u32,
+    pub protocol_version: u32,
+}
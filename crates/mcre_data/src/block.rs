@@ -1,8 +1,9 @@
 use serde::{Deserialize, Serialize};
 use std::io;
-use std::path::PathBuf;
 use tokio::fs;
 
+use crate::data_dir;
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Block {
     pub id: u16,
@@ -67,16 +68,14 @@ impl BlockStateFieldValues {
 }
 
 impl Block {
-    pub fn all_sync() -> io::Result<Box<[Self]>> {
-        let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
-        let block_data_path = root.join("blocks.json");
+    pub fn all_sync(version: &str) -> io::Result<Box<[Self]>> {
+        let block_data_path = data_dir(version).join("blocks.json");
         let block_data_json = std::fs::read_to_string(block_data_path)?;
         Ok(serde_json::from_str(&block_data_json)?)
     }
 
-    pub async fn all() -> io::Result<Vec<Self>> {
-        let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
-        let block_data_path = root.join("blocks.json");
+    pub async fn all(version: &str) -> io::Result<Vec<Self>> {
+        let block_data_path = data_dir(version).join("blocks.json");
         let block_data_json = fs::read_to_string(block_data_path).await?;
         let block_data: Vec<Self> = serde_json::from_str(&block_data_json)?;
 
@@ -90,7 +89,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_block_data_load() {
-        let blocks = Block::all().await.unwrap();
+        let blocks = Block::all("1.21.11").await.unwrap();
         assert!(!blocks.is_empty());
     }
 }
@@ -1,2 +1,10 @@
+use std::path::PathBuf;
+
 pub mod block;
 pub mod state;
+pub mod version;
+
+/// Directory holding the data generated for the game version `version`.
+pub fn data_dir(version: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(version)
+}
@@ -2,9 +2,10 @@ use indexmap::IndexMap;
 use mcre_core::{BlockPos, OffsetType};
 use serde::{Deserialize, Serialize};
 use std::io;
-use std::path::PathBuf;
 use tokio::fs;
 
+use crate::data_dir;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BlockState {
     pub id: u16,
@@ -58,9 +59,8 @@ impl BlockState {
 }
 
 impl BlockState {
-    pub async fn all() -> io::Result<Vec<Self>> {
-        let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
-        let block_state_data_path = root.join("block_states.json");
+    pub async fn all(version: &str) -> io::Result<Vec<Self>> {
+        let block_state_data_path = data_dir(version).join("block_states.json");
         let block_state_data_json = fs::read_to_string(block_state_data_path).await?;
         let block_data: Vec<Self> = serde_json::from_str(&block_state_data_json)?;
 
@@ -70,86 +70,84 @@ impl BlockState {
 
 #[cfg(test)]
 mod tests {
-    use jni::{JNIEnv, objects::JValueGen};
+    use jni::JNIEnv;
     use mcre_core::BlockPos;
 
     use crate::state::{BlockState, OffsetType};
 
+    mod java {
+        use jni::objects::JObject;
+
+        #[mcje::class("net/minecraft/core/IdMapper")]
+        pub trait IdMapper {
+            fn byId(&self, id: i32) -> JObject;
+        }
+
+        #[mcje::class("net/minecraft/world/level/block/Block")]
+        pub trait Block {
+            #[mcje(field)]
+            fn BLOCK_STATE_REGISTRY() -> IdMapper;
+        }
+
+        #[mcje::class("net/minecraft/world/level/block/state/BlockState")]
+        pub trait BlockState {
+            #[mcje(field)]
+            fn offsetFunction(&self) -> OffsetFunction;
+        }
+
+        #[mcje::class("net/minecraft/world/level/block/state/BlockBehaviour$OffsetFunction")]
+        pub trait OffsetFunction {
+            fn evaluate(&self, state: BlockState, pos: BlockPos) -> Vec3;
+        }
+
+        #[mcje::class("net/minecraft/core/BlockPos")]
+        pub trait BlockPos {
+            fn new(x: i32, y: i32, z: i32);
+        }
+
+        #[mcje::class("net/minecraft/world/phys/Vec3")]
+        pub trait Vec3 {
+            #[mcje(field)]
+            fn x(&self) -> f64;
+            #[mcje(field)]
+            fn y(&self) -> f64;
+            #[mcje(field)]
+            fn z(&self) -> f64;
+        }
+    }
+
     #[tokio::test]
     async fn test_block_state_data_load() {
-        let block_states = BlockState::all().await.unwrap();
+        let block_states = BlockState::all("1.21.11").await.unwrap();
         assert!(!block_states.is_empty());
     }
 
     #[mcje::test]
     async fn test_random_offset(env: &mut JNIEnv<'_>) {
-        let block_states = BlockState::all().await.unwrap();
-
-        let block_state_registry = env
-            .get_static_field(
-                "net/minecraft/world/level/block/Block",
-                "BLOCK_STATE_REGISTRY",
-                "Lnet/minecraft/core/IdMapper;",
-            )
-            .unwrap()
-            .l()
-            .unwrap();
-
-        let mut block_state = env
-            .call_method(
-                &block_state_registry,
-                "byId",
-                "(I)Ljava/lang/Object;",
-                &[JValueGen::Int(0)],
-            )
-            .unwrap()
-            .l()
-            .unwrap();
+        let block_states = BlockState::all(mcje::VERSION).await.unwrap();
+
+        let block_state_registry = java::Block::BLOCK_STATE_REGISTRY(env).unwrap();
 
         let mut block_state_id = 0u16;
+        let mut block_state = java::BlockState::from(block_state_registry.byId(env, 0).unwrap());
 
         while !block_state.is_null() {
-            let offset_function = env
-                .get_field(
-                    &block_state,
-                    "offsetFunction",
-                    "Lnet/minecraft/world/level/block/state/BlockBehaviour$OffsetFunction;",
-                )
-                .unwrap()
-                .l()
-                .unwrap();
+            let offset_function = block_state.offsetFunction(env).unwrap();
             if offset_function.is_null() {
                 assert_eq!(
                     block_states[block_state_id as usize].offset_type,
                     OffsetType::None
                 );
             } else {
-                let block_pos_class = "net/minecraft/core/BlockPos";
-
                 for i in 0..10 {
-                    // BlockPos(i, i, i)
-                    let pos_obj = env
-                        .new_object(
-                            block_pos_class,
-                            "(III)V",
-                            &[JValueGen::Int(i), JValueGen::Int(i), JValueGen::Int(i)],
-                        )
-                        .unwrap();
-
-                    // Call offsetFunction.evaluate(state, pos)
-                    // Signature: (LBlockState;LBlockPos;)LVec3;
-                    let vec3_obj = env.call_method(
-                        &offset_function,
-                        "evaluate",
-                        "(Lnet/minecraft/world/level/block/state/BlockState;Lnet/minecraft/core/BlockPos;)Lnet/minecraft/world/phys/Vec3;",
-                        &[JValueGen::Object(&block_state), JValueGen::Object(&pos_obj)]
-                    ).unwrap().l().unwrap();
-
-                    let java_x = env.get_field(&vec3_obj, "x", "D").unwrap().d().unwrap();
-                    let java_y = env.get_field(&vec3_obj, "y", "D").unwrap().d().unwrap();
-                    let java_z = env.get_field(&vec3_obj, "z", "D").unwrap().d().unwrap();
-
-                    let java_value = (java_x, java_y, java_z);
+                    let pos = java::BlockPos::new(env, i, i, i).unwrap();
+                    let vec3 = offset_function.evaluate(env, &block_state, &pos).unwrap();
+
+                    let java_value = (
+                        vec3.x(env).unwrap(),
+                        vec3.y(env).unwrap(),
+                        vec3.z(env).unwrap(),
+                    );
 
                     let block_state = &block_states[block_state_id as usize];
 
@@ -193,16 +191,10 @@ mod tests {
                 }
             }
             block_state_id += 1;
-            block_state = env
-                .call_method(
-                    &block_state_registry,
-                    "byId",
-                    "(I)Ljava/lang/Object;",
-                    &[JValueGen::Int(block_state_id.into())],
-                )
+            block_state = block_state_registry
+                .byId(env, block_state_id.into())
                 .unwrap()
-                .l()
-                .unwrap();
+                .into();
         }
     }
 }
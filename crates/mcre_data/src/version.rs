@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+use std::io;
+use tokio::fs;
+
+use crate::data_dir;
+
+/// Identifies the game version some data was generated from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VersionInfo {
+    pub id: String,
+    /// Data version stored in saves, `SharedConstants.WORLD_VERSION`.
+    pub world_version: u32,
+    pub protocol_version: u32,
+}
+
+impl VersionInfo {
+    pub async fn load(version: &str) -> io::Result<Self> {
+        let path = data_dir(version).join("version.json");
+        let json = fs::read_to_string(path).await?;
+        Ok(serde_json::from_str(&json)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::version::VersionInfo;
+
+    #[tokio::test]
+    async fn test_version_info_load() {
+        let info = VersionInfo::load("1.21.11").await.unwrap();
+        assert_eq!(info.id, "1.21.11");
+    }
+}
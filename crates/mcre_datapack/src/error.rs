@@ -0,0 +1,79 @@
+use std::{
+    fmt::{self, Display},
+    io,
+    path::PathBuf,
+};
+
+use mcre_core::RegistryError;
+use mcre_recipe::RecipeError;
+use zip::result::ZipError;
+
+#[derive(Debug)]
+pub enum DataPackError {
+    Io {
+        path: PathBuf,
+        error: io::Error,
+    },
+    Zip {
+        path: PathBuf,
+        error: ZipError,
+    },
+    /// A pack without a valid `pack.mcmeta`.
+    InvalidMetadata {
+        path: PathBuf,
+        error: Option<serde_json::Error>,
+    },
+    Json {
+        pack: String,
+        file: String,
+        error: serde_json::Error,
+    },
+    Recipe(RecipeError),
+    Registry(RegistryError),
+}
+
+impl Display for DataPackError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io { path, error } => write!(f, "failed to read {}: {error}", path.display()),
+            Self::Zip { path, error } => write!(f, "failed to read {}: {error}", path.display()),
+            Self::InvalidMetadata { path, error: None } => {
+                write!(f, "{} has no pack.mcmeta", path.display())
+            }
+            Self::InvalidMetadata {
+                path,
+                error: Some(error),
+            } => write!(f, "{} has an invalid pack.mcmeta: {error}", path.display()),
+            Self::Json { pack, file, error } => {
+                write!(f, "failed to parse {file} in {pack}: {error}")
+            }
+            Self::Recipe(error) => write!(f, "{error}"),
+            Self::Registry(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl std::error::Error for DataPackError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io { error, .. } => Some(error),
+            Self::Zip { error, .. } => Some(error),
+            Self::InvalidMetadata { error, .. } => error.as_ref().map(|error| error as _),
+            Self::Json { error, .. } => Some(error),
+            Self::Recipe(error) => Some(error),
+            Self::Registry(error) => Some(error),
+        }
+    }
+}
+
+impl From<RecipeError> for DataPackError {
+    fn from(error: RecipeError) -> Self {
+        Self::Recipe(error)
+    }
+}
+
+impl From<RegistryError> for DataPackError {
+    fn from(error: RegistryError) -> Self {
+        Self::Registry(error)
+    }
+}
@@ -0,0 +1,9 @@
+//! Data packs read from directories and zip files, layered on top of each other.
+
+mod error;
+mod pack;
+mod packs;
+
+pub use error::DataPackError;
+pub use pack::{DataPack, PackMetadata};
+pub use packs::DataPacks;
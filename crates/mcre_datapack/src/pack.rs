@@ -0,0 +1,317 @@
+use std::{
+    collections::BTreeSet,
+    fs::{self, File},
+    io::{self, BufReader, Read},
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use mcre_core::ResourceLocation;
+use serde::{Deserialize, Deserializer};
+use zip::{ZipArchive, result::ZipError};
+
+use crate::DataPackError;
+
+const METADATA: &str = "pack.mcmeta";
+
+/// A data pack, either a directory or a zip file containing `pack.mcmeta` and `data/`.
+#[derive(Debug)]
+pub struct DataPack {
+    name: String,
+    path: PathBuf,
+    metadata: PackMetadata,
+    /// Paths of every file under `data/`, relative to the pack root.
+    files: BTreeSet<String>,
+    source: Source,
+}
+
+#[derive(Debug)]
+enum Source {
+    Directory,
+    Zip(Mutex<ZipArchive<BufReader<File>>>),
+}
+
+/// The `pack` section of `pack.mcmeta`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackMetadata {
+    /// The plain text of the description.
+    pub description: String,
+    /// Absent from packs that only declare `min_format` and `max_format`.
+    pub pack_format: Option<u32>,
+}
+
+impl DataPack {
+    /// Opens the directory or zip file at `path`, listing its files.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, DataPackError> {
+        let path = path.as_ref();
+        let io_error = |error| DataPackError::Io {
+            path: path.to_owned(),
+            error,
+        };
+
+        let name = path
+            .file_name()
+            .map_or_else(String::new, |name| name.to_string_lossy().into_owned());
+
+        let (files, source) = if fs::metadata(path).map_err(io_error)?.is_dir() {
+            let mut files = BTreeSet::new();
+            list_dir(path, "data", &mut files).map_err(io_error)?;
+            (files, Source::Directory)
+        } else {
+            let file = File::open(path).map_err(io_error)?;
+            let archive =
+                ZipArchive::new(BufReader::new(file)).map_err(|error| DataPackError::Zip {
+                    path: path.to_owned(),
+                    error,
+                })?;
+            let files = archive
+                .file_names()
+                .filter(|name| name.starts_with("data/") && !name.ends_with('/'))
+                .map(str::to_owned)
+                .collect();
+            (files, Source::Zip(Mutex::new(archive)))
+        };
+
+        let mut pack = Self {
+            name,
+            path: path.to_owned(),
+            metadata: PackMetadata {
+                description: String::new(),
+                pack_format: None,
+            },
+            files,
+            source,
+        };
+
+        let metadata = pack.read_file(METADATA).map_err(|error| match error {
+            DataPackError::Io { error, .. } if error.kind() == io::ErrorKind::NotFound => {
+                DataPackError::InvalidMetadata {
+                    path: path.to_owned(),
+                    error: None,
+                }
+            }
+            DataPackError::Zip {
+                error: ZipError::FileNotFound,
+                ..
+            } => DataPackError::InvalidMetadata {
+                path: path.to_owned(),
+                error: None,
+            },
+            error => error,
+        })?;
+        pack.metadata = serde_json::from_slice::<McMeta>(&metadata)
+            .map_err(|error| DataPackError::InvalidMetadata {
+                path: path.to_owned(),
+                error: Some(error),
+            })?
+            .pack;
+
+        Ok(pack)
+    }
+
+    /// The file or directory name of the pack.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn metadata(&self) -> &PackMetadata {
+        &self.metadata
+    }
+
+    /// Paths of every file under `data/`, relative to the pack root.
+    pub fn files(&self) -> impl Iterator<Item = &str> {
+        self.files.iter().map(String::as_str)
+    }
+
+    /// The JSON resources of a kind such as `recipe` or `tags/item`, keyed by id.
+    ///
+    /// Files whose path isn't a valid resource location are skipped.
+    pub fn resources<'a>(
+        &'a self,
+        kind: &'a str,
+    ) -> impl Iterator<Item = (ResourceLocation, &'a str)> + 'a {
+        self.files().filter_map(move |file| {
+            let (namespace, rest) = file.strip_prefix("data/")?.split_once('/')?;
+            let path = rest
+                .strip_prefix(kind)?
+                .strip_prefix('/')?
+                .strip_suffix(".json")?;
+            Some((ResourceLocation::new(namespace, path).ok()?, file))
+        })
+    }
+
+    /// The path of a resource file, whether it exists or not.
+    pub fn resource_path(kind: &str, id: &ResourceLocation) -> String {
+        format!("data/{}/{kind}/{}.json", id.namespace(), id.path())
+    }
+
+    pub fn contains(&self, file: &str) -> bool {
+        self.files.contains(file)
+    }
+
+    /// Reads a file, given relative to the pack root.
+    pub fn read_file(&self, file: &str) -> Result<Vec<u8>, DataPackError> {
+        match &self.source {
+            Source::Directory => {
+                let path = self.path.join(file);
+                fs::read(&path).map_err(|error| DataPackError::Io { path, error })
+            }
+            Source::Zip(archive) => {
+                let zip_error = |error| DataPackError::Zip {
+                    path: self.path.clone(),
+                    error,
+                };
+
+                let mut archive = archive.lock().unwrap_or_else(|err| err.into_inner());
+                let mut entry = archive.by_name(file).map_err(zip_error)?;
+                let mut bytes = Vec::with_capacity(entry.size() as usize);
+                entry
+                    .read_to_end(&mut bytes)
+                    .map_err(|error| DataPackError::Io {
+                        path: self.path.join(file),
+                        error,
+                    })?;
+                Ok(bytes)
+            }
+        }
+    }
+
+    /// Reads and parses a JSON file, given relative to the pack root.
+    pub fn read_json<T>(&self, file: &str) -> Result<T, DataPackError>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        let bytes = self.read_file(file)?;
+        serde_json::from_slice(&bytes).map_err(|error| DataPackError::Json {
+            pack: self.name.clone(),
+            file: file.to_owned(),
+            error,
+        })
+    }
+}
+
+fn list_dir(root: &Path, dir: &str, files: &mut BTreeSet<String>) -> io::Result<()> {
+    let entries = match fs::read_dir(root.join(dir)) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(err) => return Err(err),
+    };
+
+    for entry in entries {
+        let entry = entry?;
+        let name = entry.file_name();
+        let path = format!("{dir}/{}", name.to_string_lossy());
+        if entry.file_type()?.is_dir() {
+            list_dir(root, &path, files)?;
+        } else {
+            files.insert(path);
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(Deserialize)]
+struct McMeta {
+    pack: PackMetadata,
+}
+
+impl<'de> Deserialize<'de> for PackMetadata {
+    /// The description is a text component, of which only the text is kept.
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        struct Helper {
+            #[serde(default)]
+            description: serde_json::Value,
+            #[serde(default)]
+            pack_format: Option<u32>,
+        }
+
+        fn text(component: &serde_json::Value, out: &mut String) {
+            match component {
+                serde_json::Value::String(s) => out.push_str(s),
+                serde_json::Value::Array(components) => {
+                    components.iter().for_each(|component| text(component, out))
+                }
+                serde_json::Value::Object(component) => {
+                    if let Some(s) = component.get("text").and_then(|s| s.as_str()) {
+                        out.push_str(s);
+                    }
+                    if let Some(extra) = component.get("extra") {
+                        text(extra, out);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let helper = Helper::deserialize(deserializer)?;
+        let mut description = String::new();
+        text(&helper.description, &mut description);
+
+        Ok(Self {
+            description,
+            pack_format: helper.pack_format,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use mcre_core::ResourceLocation;
+
+    use crate::{DataPack, DataPackError};
+
+    fn fixture(name: &str) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("fixtures")
+            .join(name)
+    }
+
+    #[test]
+    fn test_open_directory() {
+        let pack = DataPack::open(fixture("custom")).unwrap();
+
+        assert_eq!(pack.name(), "custom");
+        assert_eq!(pack.metadata().description, "Custom content for mcre");
+        assert_eq!(pack.metadata().pack_format, Some(94));
+
+        let recipes = pack.resources("recipe").collect::<Vec<_>>();
+        assert_eq!(
+            recipes,
+            [
+                (
+                    "mcre:ruby_planks".parse::<ResourceLocation>().unwrap(),
+                    "data/mcre/recipe/ruby_planks.json"
+                ),
+                (
+                    ResourceLocation::minecraft("stick"),
+                    "data/minecraft/recipe/stick.json"
+                ),
+            ]
+        );
+        assert_eq!(pack.resources("tags/item").count(), 2);
+        assert_eq!(pack.resources("tag").count(), 0);
+    }
+
+    #[test]
+    fn test_missing_metadata() {
+        assert!(matches!(
+            DataPack::open(fixture("no_metadata")),
+            Err(DataPackError::InvalidMetadata { error: None, .. })
+        ));
+        assert!(matches!(
+            DataPack::open(fixture("missing")),
+            Err(DataPackError::Io { .. })
+        ));
+    }
+}
@@ -0,0 +1,303 @@
+use std::collections::BTreeMap;
+
+use mcre_core::{Registry, ResourceLocation, TagEntry};
+use mcre_loot::LootTables;
+use mcre_recipe::RecipeManager;
+use mcre_worldgen::{Biome, DimensionType, noise::NoiseParameters};
+use serde::Deserialize;
+
+use crate::{DataPack, DataPackError};
+
+/// Data packs in priority order, each pack overriding the resources of the packs before it.
+#[derive(Debug, Default)]
+pub struct DataPacks {
+    packs: Vec<DataPack>,
+}
+
+/// A tag file, as found in `data/<namespace>/tags/<registry>/*.json`.
+#[derive(Deserialize)]
+struct TagFile {
+    #[serde(default)]
+    replace: bool,
+    values: Vec<TagEntry>,
+}
+
+impl DataPacks {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a pack on top of the others.
+    pub fn push(&mut self, pack: DataPack) {
+        self.packs.push(pack);
+    }
+
+    /// The packs, lowest priority first.
+    pub fn packs(&self) -> &[DataPack] {
+        &self.packs
+    }
+
+    /// The ids of every resource of a kind, with the pack that provides it.
+    pub fn resources(&self, kind: &str) -> BTreeMap<ResourceLocation, &DataPack> {
+        let mut resources = BTreeMap::new();
+        for pack in &self.packs {
+            for (id, _) in pack.resources(kind) {
+                resources.insert(id, pack);
+            }
+        }
+        resources
+    }
+
+    /// Parses a resource from the pack with the highest priority that has it.
+    pub fn get<T>(&self, kind: &str, id: &ResourceLocation) -> Result<Option<T>, DataPackError>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        let file = DataPack::resource_path(kind, id);
+        self.packs
+            .iter()
+            .rev()
+            .find(|pack| pack.contains(&file))
+            .map(|pack| pack.read_json(&file))
+            .transpose()
+    }
+
+    /// Parses every resource of a kind, such as `worldgen/biome`.
+    pub fn load<T>(&self, kind: &str) -> Result<BTreeMap<ResourceLocation, T>, DataPackError>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        self.resources(kind)
+            .into_iter()
+            .map(|(id, pack)| {
+                let value = pack.read_json(&DataPack::resource_path(kind, &id))?;
+                Ok((id, value))
+            })
+            .collect()
+    }
+
+    /// Merges the tags of a registry such as `item` or `worldgen/biome`.
+    ///
+    /// Entries of the same tag from several packs are appended in pack order, unless a pack sets
+    /// `replace` in which case the entries of the packs before it are dropped.
+    pub fn tags(
+        &self,
+        registry: &str,
+    ) -> Result<BTreeMap<ResourceLocation, Vec<TagEntry>>, DataPackError> {
+        let kind = format!("tags/{registry}");
+        let mut tags = BTreeMap::<_, Vec<_>>::new();
+
+        for pack in &self.packs {
+            for (id, file) in pack.resources(&kind) {
+                let tag: TagFile = pack.read_json(file)?;
+                let entries = tags.entry(id).or_default();
+                if tag.replace {
+                    entries.clear();
+                }
+                entries.extend(tag.values);
+            }
+        }
+
+        Ok(tags)
+    }
+
+    /// Binds the merged tags of the registry, found from its key.
+    pub fn bind_tags<T>(&self, registry: &mut Registry<T>) -> Result<(), DataPackError> {
+        let key = registry.key();
+        let name = if key.namespace() == ResourceLocation::DEFAULT_NAMESPACE {
+            key.path().to_owned()
+        } else {
+            format!("{}/{}", key.namespace(), key.path())
+        };
+
+        let tags = self.tags(&name)?;
+        registry.bind_tags(tags)?;
+        Ok(())
+    }
+
+    pub fn recipes(&self) -> Result<RecipeManager, DataPackError> {
+        let mut recipes = RecipeManager::new();
+        for (id, recipe) in self.load("recipe")? {
+            recipes.insert(id, recipe)?;
+        }
+        Ok(recipes)
+    }
+
+    pub fn loot_tables(&self) -> Result<LootTables, DataPackError> {
+        let mut tables = LootTables::new();
+        for (id, table) in self.load("loot_table")? {
+            tables.insert(id, table);
+        }
+        Ok(tables)
+    }
+
+    pub fn biomes(&self) -> Result<BTreeMap<ResourceLocation, Biome>, DataPackError> {
+        self.load("worldgen/biome")
+    }
+
+    pub fn noise_parameters(
+        &self,
+    ) -> Result<BTreeMap<ResourceLocation, NoiseParameters>, DataPackError> {
+        self.load("worldgen/noise")
+    }
+
+    pub fn dimension_types(
+        &self,
+    ) -> Result<BTreeMap<ResourceLocation, DimensionType>, DataPackError> {
+        self.load("dimension_type")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs::{self, File},
+        io::Write,
+        path::{Path, PathBuf},
+    };
+
+    use mcre_core::{ItemStack, Registry, ResourceLocation, TagEntry};
+    use mcre_recipe::CraftingGrid;
+    use zip::{ZipWriter, write::SimpleFileOptions};
+
+    use crate::{DataPack, DataPacks};
+
+    fn fixture(name: &str) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("fixtures")
+            .join(name)
+    }
+
+    fn loc(s: &str) -> ResourceLocation {
+        s.parse().unwrap()
+    }
+
+    /// Zips a fixture pack into the temporary directory.
+    fn zip_fixture(name: &str) -> PathBuf {
+        fn add(zip: &mut ZipWriter<File>, root: &Path, dir: &Path) {
+            for entry in fs::read_dir(dir).unwrap() {
+                let path = entry.unwrap().path();
+                if path.is_dir() {
+                    add(zip, root, &path);
+                } else {
+                    let name = path.strip_prefix(root).unwrap().to_str().unwrap();
+                    zip.start_file(name.replace('\\', "/"), SimpleFileOptions::default())
+                        .unwrap();
+                    zip.write_all(&fs::read(&path).unwrap()).unwrap();
+                }
+            }
+        }
+
+        let path =
+            std::env::temp_dir().join(format!("mcre_datapack_{name}_{}.zip", std::process::id()));
+        let mut zip = ZipWriter::new(File::create(&path).unwrap());
+        add(&mut zip, &fixture(name), &fixture(name));
+        zip.finish().unwrap();
+        path
+    }
+
+    fn packs(custom: PathBuf) -> DataPacks {
+        let mut packs = DataPacks::new();
+        packs.push(DataPack::open(fixture("vanilla")).unwrap());
+        packs.push(DataPack::open(custom).unwrap());
+        packs
+    }
+
+    fn check_layering(packs: &DataPacks) {
+        let stick = packs
+            .get::<mcre_recipe::Recipe>("recipe", &loc("stick"))
+            .unwrap()
+            .unwrap();
+        assert!(
+            matches!(stick, mcre_recipe::Recipe::Shaped(ref recipe) if recipe.result.count == 8)
+        );
+        assert!(
+            packs
+                .get::<mcre_recipe::Recipe>("recipe", &loc("missing"))
+                .unwrap()
+                .is_none()
+        );
+
+        let recipes = packs.recipes().unwrap();
+        assert_eq!(recipes.len(), 3);
+        assert!(recipes.get(&loc("mcre:ruby_planks")).is_some());
+
+        let tags = packs.tags("item").unwrap();
+        assert_eq!(
+            tags[&loc("planks")],
+            [
+                TagEntry::element(loc("oak_planks")),
+                TagEntry::element(loc("spruce_planks")),
+                TagEntry::element(loc("mcre:ruby_planks")),
+            ]
+        );
+        assert_eq!(
+            tags[&loc("logs")],
+            [TagEntry::element(loc("mcre:ruby_log"))]
+        );
+        assert_eq!(
+            packs.tags("block").unwrap()[&loc("mineable/pickaxe")],
+            [TagEntry::element(loc("stone"))]
+        );
+
+        let overworld = &packs.dimension_types().unwrap()[&loc("overworld")];
+        assert_eq!((overworld.min_y, overworld.height), (-128, 512));
+        assert_eq!(packs.biomes().unwrap().len(), 1);
+        assert_eq!(
+            packs.noise_parameters().unwrap()[&loc("temperature")].first_octave,
+            -10
+        );
+        assert!(
+            packs
+                .loot_tables()
+                .unwrap()
+                .get(&loc("blocks/stone"))
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn test_directory_packs() {
+        check_layering(&packs(fixture("custom")));
+    }
+
+    #[test]
+    fn test_zip_packs() {
+        let zip = zip_fixture("custom");
+        let packs = packs(zip.clone());
+        check_layering(&packs);
+        fs::remove_file(zip).unwrap();
+    }
+
+    #[test]
+    fn test_bind_tags() {
+        let packs = packs(fixture("custom"));
+
+        let mut items = Registry::new(loc("item"));
+        for item in [
+            "oak_planks",
+            "spruce_planks",
+            "oak_log",
+            "spruce_log",
+            "stick",
+            "mcre:ruby_planks",
+            "mcre:ruby_log",
+        ] {
+            items.register(loc(item), ()).unwrap();
+        }
+        packs.bind_tags(&mut items).unwrap();
+
+        let recipes = packs.recipes().unwrap();
+        let grid =
+            CraftingGrid::from_rows(&[[Some("mcre:ruby_planks")], [Some("mcre:ruby_planks")]]);
+        assert_eq!(
+            recipes
+                .craft(&grid, &items)
+                .map(|(_, result)| result.clone()),
+            Some(ItemStack::new(loc("stick"), 8))
+        );
+
+        let mut blocks = Registry::<()>::new(loc("block"));
+        assert!(packs.bind_tags(&mut blocks).is_err());
+    }
+}
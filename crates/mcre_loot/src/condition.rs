@@ -0,0 +1,376 @@
+use alloc::{
+    boxed::Box,
+    collections::BTreeMap,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use mcre_core::{PropKey, PropVal, RandomSource, ResourceLocation, TagEntry};
+use serde::{Deserialize, Deserializer};
+
+use crate::{LootContext, NumberProvider};
+
+/// A predicate deciding whether a pool, entry or function applies.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "condition")]
+pub enum LootCondition {
+    /// Passes with a chance of `1 / radius` when the block was destroyed by an explosion.
+    #[serde(rename = "minecraft:survives_explosion", alias = "survives_explosion")]
+    SurvivesExplosion,
+    #[serde(rename = "minecraft:match_tool", alias = "match_tool")]
+    MatchTool { predicate: ItemPredicate },
+    /// Passes with the chance at the index of the enchantment level.
+    #[serde(rename = "minecraft:table_bonus", alias = "table_bonus")]
+    TableBonus {
+        enchantment: ResourceLocation,
+        chances: Vec<f32>,
+    },
+    #[serde(
+        rename = "minecraft:block_state_property",
+        alias = "block_state_property"
+    )]
+    BlockStateProperty {
+        block: ResourceLocation,
+        #[serde(default)]
+        properties: BTreeMap<String, PropertyMatcher>,
+    },
+    #[serde(rename = "minecraft:random_chance", alias = "random_chance")]
+    RandomChance { chance: NumberProvider },
+    #[serde(rename = "minecraft:inverted", alias = "inverted")]
+    Inverted { term: Box<LootCondition> },
+    #[serde(
+        rename = "minecraft:any_of",
+        alias = "any_of",
+        alias = "minecraft:alternative",
+        alias = "alternative"
+    )]
+    AnyOf { terms: Vec<LootCondition> },
+    #[serde(rename = "minecraft:all_of", alias = "all_of")]
+    AllOf { terms: Vec<LootCondition> },
+    /// Conditions on entities, weather, etc. which never pass when rolling block drops.
+    #[serde(other)]
+    Unsupported,
+}
+
+/// Matches the tool of a [`LootContext`].
+#[derive(Debug, Clone, PartialEq, Default, Deserialize)]
+pub struct ItemPredicate {
+    #[serde(default, deserialize_with = "one_or_many")]
+    pub items: Vec<TagEntry>,
+    #[serde(default)]
+    pub predicates: ItemSubPredicates,
+    /// The location of enchantment predicates before 1.20.5.
+    #[serde(default)]
+    pub enchantments: Vec<EnchantmentPredicate>,
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Deserialize)]
+pub struct ItemSubPredicates {
+    #[serde(rename = "minecraft:enchantments", default)]
+    pub enchantments: Vec<EnchantmentPredicate>,
+}
+
+/// Requires one of `enchantments`, or any enchantment if empty, with a level in `levels`.
+#[derive(Debug, Clone, PartialEq, Default, Deserialize)]
+pub struct EnchantmentPredicate {
+    #[serde(default, alias = "enchantment", deserialize_with = "one_or_many")]
+    pub enchantments: Vec<TagEntry>,
+    #[serde(default)]
+    pub levels: LevelRange,
+}
+
+/// An inclusive range of integers, either bound being optional.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct LevelRange {
+    pub min: Option<i32>,
+    pub max: Option<i32>,
+}
+
+/// The value a block state property must have, or the range of integers it must be in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PropertyMatcher {
+    Exact(String),
+    Range {
+        min: Option<String>,
+        max: Option<String>,
+    },
+}
+
+impl LootCondition {
+    pub fn test(&self, ctx: &mut LootContext) -> bool {
+        match self {
+            Self::SurvivesExplosion => ctx
+                .explosion_radius
+                .is_none_or(|radius| ctx.random.next_float() <= 1.0 / radius),
+            Self::MatchTool { predicate } => predicate.test(ctx),
+            Self::TableBonus {
+                enchantment,
+                chances,
+            } => {
+                let level = ctx.enchantment_level(enchantment) as usize;
+                let chance = chances
+                    .get(level.min(chances.len().saturating_sub(1)))
+                    .copied()
+                    .unwrap_or(0.0);
+                ctx.random.next_float() < chance
+            }
+            Self::BlockStateProperty { block, properties } => {
+                let Some(state) = ctx.state else {
+                    return false;
+                };
+                block.namespace() == "minecraft"
+                    && block.path() == state.block().name()
+                    && properties.iter().all(|(key, matcher)| {
+                        key.parse::<PropKey>().is_ok_and(|key| {
+                            state
+                                .get_prop(key)
+                                .is_some_and(|val| matcher.test(key, val))
+                        })
+                    })
+            }
+            Self::RandomChance { chance } => {
+                let chance = chance.get_float(&mut ctx.random);
+                ctx.random.next_float() < chance
+            }
+            Self::Inverted { term } => !term.test(ctx),
+            Self::AnyOf { terms } => terms.iter().any(|term| term.test(ctx)),
+            Self::AllOf { terms } => terms.iter().all(|term| term.test(ctx)),
+            Self::Unsupported => false,
+        }
+    }
+
+    /// Whether every condition passes, stopping at the first failing one.
+    pub fn all(conditions: &[Self], ctx: &mut LootContext) -> bool {
+        conditions.iter().all(|condition| condition.test(ctx))
+    }
+}
+
+impl ItemPredicate {
+    pub fn test(&self, ctx: &LootContext) -> bool {
+        let Some(tool) = &ctx.tool else {
+            return false;
+        };
+
+        let item_matches = self.items.is_empty()
+            || self.items.iter().any(|entry| match entry {
+                TagEntry::Element { id, .. } => *id == tool.item,
+                TagEntry::Tag { id, .. } => ctx.item_tags.has_tag(&tool.item, id),
+            });
+
+        item_matches
+            && self
+                .enchantments
+                .iter()
+                .chain(&self.predicates.enchantments)
+                .all(|predicate| {
+                    tool.enchantments
+                        .iter()
+                        .any(|(id, &level)| predicate.test(id, level))
+                })
+    }
+}
+
+impl EnchantmentPredicate {
+    /// Enchantment tags can't be resolved here and never match.
+    pub fn test(&self, enchantment: &ResourceLocation, level: u32) -> bool {
+        let enchantment_matches = self.enchantments.is_empty()
+            || self
+                .enchantments
+                .iter()
+                .any(|entry| matches!(entry, TagEntry::Element { id, .. } if id == enchantment));
+
+        enchantment_matches
+            && level >= 1
+            && self
+                .levels
+                .contains(i32::try_from(level).unwrap_or(i32::MAX))
+    }
+}
+
+impl LevelRange {
+    pub fn contains(&self, value: i32) -> bool {
+        self.min.is_none_or(|min| value >= min) && self.max.is_none_or(|max| value <= max)
+    }
+}
+
+impl PropertyMatcher {
+    pub fn test(&self, key: PropKey, val: PropVal) -> bool {
+        match self {
+            Self::Exact(value) => PropVal::parse_with_key(key, value) == Some(val),
+            Self::Range { min, max } => {
+                let bound = |bound: &Option<String>, default| match bound {
+                    Some(bound) => bound.parse::<u8>().ok(),
+                    None => Some(default),
+                };
+                let (Some(min), Some(max)) = (bound(min, u8::MIN), bound(max, u8::MAX)) else {
+                    return false;
+                };
+                (min..=max)
+                    .any(|value| PropVal::parse_with_key(key, &value.to_string()) == Some(val))
+            }
+        }
+    }
+}
+
+fn one_or_many<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Helper<T> {
+        One(T),
+        Many(Vec<T>),
+    }
+
+    Ok(match Helper::deserialize(deserializer)? {
+        Helper::One(value) => vec![value],
+        Helper::Many(values) => values,
+    })
+}
+
+impl<'de> Deserialize<'de> for LevelRange {
+    /// Accepts an exact level or `{"min": ..., "max": ...}`.
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Helper {
+            Exact(i32),
+            Range {
+                #[serde(default)]
+                min: Option<i32>,
+                #[serde(default)]
+                max: Option<i32>,
+            },
+        }
+
+        Ok(match Helper::deserialize(deserializer)? {
+            Helper::Exact(level) => Self {
+                min: Some(level),
+                max: Some(level),
+            },
+            Helper::Range { min, max } => Self { min, max },
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for PropertyMatcher {
+    /// Values may be given as strings, numbers or booleans.
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Scalar {
+            String(String),
+            Int(u8),
+            Bool(bool),
+        }
+
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Helper {
+            Exact(Scalar),
+            Range {
+                #[serde(default)]
+                min: Option<Scalar>,
+                #[serde(default)]
+                max: Option<Scalar>,
+            },
+        }
+
+        let to_string = |scalar: Scalar| match scalar {
+            Scalar::String(s) => s,
+            Scalar::Int(value) => value.to_string(),
+            Scalar::Bool(value) => String::from(if value { "true" } else { "false" }),
+        };
+
+        Ok(match Helper::deserialize(deserializer)? {
+            Helper::Exact(value) => Self::Exact(to_string(value)),
+            Helper::Range { min, max } => Self::Range {
+                min: min.map(to_string),
+                max: max.map(to_string),
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mcre_core::{Block, PropVal, ResourceLocation};
+
+    use crate::{LootCondition, LootContext, Tool};
+
+    fn parse(json: &str) -> LootCondition {
+        serde_json::from_str(json).unwrap()
+    }
+
+    fn loc(s: &str) -> ResourceLocation {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_block_state_property() {
+        let exact = parse(
+            r#"{"condition": "minecraft:block_state_property", "block": "minecraft:wheat", "properties": {"age": "7"}}"#,
+        );
+        let range = parse(
+            r#"{"condition": "block_state_property", "block": "wheat", "properties": {"age": {"min": 5}}}"#,
+        );
+
+        let age = |age| {
+            Block::WHEAT
+                .default_state()
+                .with_prop(PropVal::Age(age))
+                .unwrap()
+        };
+        let test = |condition: &LootCondition, state| {
+            condition.test(&mut LootContext::new(0).with_state(state))
+        };
+
+        assert!(test(&exact, age(7)));
+        assert!(!test(&exact, age(6)));
+        assert!(test(&range, age(5)));
+        assert!(!test(&range, age(4)));
+        assert!(!test(&exact, Block::CARROTS.default_state()));
+        assert!(!exact.test(&mut LootContext::new(0)));
+    }
+
+    #[test]
+    fn test_match_tool() {
+        let silk_touch = parse(
+            r#"{"condition": "minecraft:match_tool", "predicate": {"predicates": {"minecraft:enchantments": [{"enchantments": "minecraft:silk_touch", "levels": {"min": 1}}]}}}"#,
+        );
+        let legacy = parse(
+            r#"{"condition": "match_tool", "predicate": {"enchantments": [{"enchantment": "minecraft:silk_touch", "levels": 1}]}}"#,
+        );
+        let shears = parse(
+            r#"{"condition": "minecraft:inverted", "term": {"condition": "minecraft:match_tool", "predicate": {"items": "minecraft:shears"}}}"#,
+        );
+
+        let pickaxe = Tool::new(loc("diamond_pickaxe"));
+        let silk_pickaxe = pickaxe.clone().with_enchantment(loc("silk_touch"), 1);
+        let test = |condition: &LootCondition, tool: &Tool| {
+            condition.test(&mut LootContext::new(0).with_tool(tool.clone()))
+        };
+
+        assert!(test(&silk_touch, &silk_pickaxe));
+        assert!(test(&legacy, &silk_pickaxe));
+        assert!(!test(&silk_touch, &pickaxe));
+        assert!(!silk_touch.test(&mut LootContext::new(0)));
+        assert!(test(&shears, &pickaxe));
+        assert!(!test(&shears, &Tool::new(loc("shears"))));
+    }
+
+    #[test]
+    fn test_unsupported() {
+        let condition = parse(r#"{"condition": "minecraft:killed_by_player"}"#);
+        assert_eq!(condition, LootCondition::Unsupported);
+        assert!(!condition.test(&mut LootContext::new(0)));
+    }
+}
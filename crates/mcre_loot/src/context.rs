@@ -0,0 +1,99 @@
+use alloc::{collections::BTreeMap, vec::Vec};
+use mcre_core::{BlockState, ItemTags, ResourceLocation, XoroshiroRandom};
+
+use crate::LootTables;
+
+/// The item used to break a block, with its enchantments.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tool {
+    pub item: ResourceLocation,
+    pub enchantments: BTreeMap<ResourceLocation, u32>,
+}
+
+impl Tool {
+    pub fn new(item: ResourceLocation) -> Self {
+        Self {
+            item,
+            enchantments: BTreeMap::new(),
+        }
+    }
+
+    pub fn with_enchantment(mut self, enchantment: ResourceLocation, level: u32) -> Self {
+        self.enchantments.insert(enchantment, level);
+        self
+    }
+
+    /// The level of an enchantment, 0 if the tool doesn't have it.
+    pub fn level(&self, enchantment: &ResourceLocation) -> u32 {
+        self.enchantments.get(enchantment).copied().unwrap_or(0)
+    }
+}
+
+/// Everything a loot table can look at while being rolled.
+pub struct LootContext<'a> {
+    pub(crate) random: XoroshiroRandom,
+    pub(crate) state: Option<BlockState>,
+    pub(crate) tool: Option<Tool>,
+    pub(crate) explosion_radius: Option<f32>,
+    pub(crate) luck: f32,
+    pub(crate) item_tags: &'a dyn ItemTags,
+    pub(crate) tables: Option<&'a LootTables>,
+    /// Tables being rolled, to stop tables that reference themselves.
+    pub(crate) visiting: Vec<ResourceLocation>,
+}
+
+impl<'a> LootContext<'a> {
+    pub fn new(seed: i64) -> Self {
+        Self {
+            random: XoroshiroRandom::new(seed),
+            state: None,
+            tool: None,
+            explosion_radius: None,
+            luck: 0.0,
+            item_tags: &(),
+            tables: None,
+            visiting: Vec::new(),
+        }
+    }
+
+    pub fn with_state(mut self, state: BlockState) -> Self {
+        self.state = Some(state);
+        self
+    }
+
+    pub fn with_tool(mut self, tool: Tool) -> Self {
+        self.tool = Some(tool);
+        self
+    }
+
+    /// Marks the block as destroyed by an explosion of the given radius.
+    pub fn with_explosion_radius(mut self, radius: f32) -> Self {
+        self.explosion_radius = Some(radius);
+        self
+    }
+
+    pub fn with_luck(mut self, luck: f32) -> Self {
+        self.luck = luck;
+        self
+    }
+
+    pub fn with_item_tags(mut self, item_tags: &'a dyn ItemTags) -> Self {
+        self.item_tags = item_tags;
+        self
+    }
+
+    /// Tables that `minecraft:loot_table` entries refer to.
+    pub fn with_tables(mut self, tables: &'a LootTables) -> Self {
+        self.tables = Some(tables);
+        self
+    }
+
+    pub fn random(&mut self) -> &mut XoroshiroRandom {
+        &mut self.random
+    }
+
+    /// The level of an enchantment on the tool, 0 without a tool.
+    pub(crate) fn enchantment_level(&self, enchantment: &ResourceLocation) -> u32 {
+        self.tool.as_ref().map_or(0, |tool| tool.level(enchantment))
+    }
+}
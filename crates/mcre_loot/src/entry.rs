@@ -0,0 +1,204 @@
+use alloc::{vec, vec::Vec};
+use mcre_core::{ItemStack, ResourceLocation};
+use serde::Deserialize;
+
+use crate::{LootCondition, LootContext, LootFunction, number::floor};
+
+/// An entry of a loot pool.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "type")]
+pub enum LootEntry {
+    #[serde(rename = "minecraft:item", alias = "item")]
+    Item(ItemEntry),
+    /// Rolls another loot table.
+    #[serde(rename = "minecraft:loot_table", alias = "loot_table")]
+    LootTable(TableEntry),
+    #[serde(rename = "minecraft:empty", alias = "empty")]
+    Empty(SingletonEntry),
+    /// Uses the first child whose conditions pass.
+    #[serde(rename = "minecraft:alternatives", alias = "alternatives")]
+    Alternatives(CompositeEntry),
+    /// Uses every child whose conditions pass.
+    #[serde(rename = "minecraft:group", alias = "group")]
+    Group(CompositeEntry),
+    /// Uses children until the conditions of one fail.
+    #[serde(rename = "minecraft:sequence", alias = "sequence")]
+    Sequence(CompositeEntry),
+    /// Every item of an item tag, or one of them when expanded.
+    #[serde(rename = "minecraft:tag", alias = "tag")]
+    Tag(ItemTagEntry),
+    /// Dynamic entries, which need data this crate doesn't have.
+    #[serde(other)]
+    Unsupported,
+}
+
+/// The weight, conditions and functions shared by entries producing items.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct SingletonEntry {
+    #[serde(default = "default_weight")]
+    pub weight: i32,
+    #[serde(default)]
+    pub quality: i32,
+    #[serde(default)]
+    pub conditions: Vec<LootCondition>,
+    #[serde(default)]
+    pub functions: Vec<LootFunction>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ItemEntry {
+    pub name: ResourceLocation,
+    #[serde(flatten)]
+    pub entry: SingletonEntry,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct TableEntry {
+    /// The table id, called `name` before 1.20.5.
+    #[serde(alias = "name")]
+    pub value: ResourceLocation,
+    #[serde(flatten)]
+    pub entry: SingletonEntry,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ItemTagEntry {
+    /// The item tag id.
+    pub name: ResourceLocation,
+    /// Whether each item of the tag is a separate choice with the weight of the entry, instead of
+    /// the entry producing all of them.
+    pub expand: bool,
+    #[serde(flatten)]
+    pub entry: SingletonEntry,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct CompositeEntry {
+    pub children: Vec<LootEntry>,
+    #[serde(default)]
+    pub conditions: Vec<LootCondition>,
+}
+
+fn default_weight() -> i32 {
+    1
+}
+
+/// An entry picked from by weight, after composite entries were expanded.
+#[derive(Debug, Clone)]
+pub(crate) struct Candidate<'t> {
+    entry: &'t LootEntry,
+    /// The item of an expanded tag entry.
+    item: Option<ResourceLocation>,
+}
+
+impl LootEntry {
+    /// Adds the entries producing items this entry expands to, returning whether its
+    /// conditions passed.
+    pub(crate) fn expand<'t>(
+        &'t self,
+        ctx: &mut LootContext,
+        out: &mut Vec<Candidate<'t>>,
+    ) -> bool {
+        match self {
+            Self::Item(ItemEntry { entry, .. })
+            | Self::LootTable(TableEntry { entry, .. })
+            | Self::Empty(entry) => {
+                let passed = LootCondition::all(&entry.conditions, ctx);
+                if passed {
+                    out.push(Candidate::new(self, None));
+                }
+                passed
+            }
+            Self::Tag(ItemTagEntry {
+                name,
+                expand,
+                entry,
+            }) => {
+                let passed = LootCondition::all(&entry.conditions, ctx);
+                if passed && *expand {
+                    let items = ctx.item_tags.tag_items(name);
+                    out.extend(
+                        items
+                            .into_iter()
+                            .map(|item| Candidate::new(self, Some(item))),
+                    );
+                } else if passed {
+                    out.push(Candidate::new(self, None));
+                }
+                passed
+            }
+            Self::Alternatives(composite) => {
+                LootCondition::all(&composite.conditions, ctx)
+                    && composite
+                        .children
+                        .iter()
+                        .any(|child| child.expand(ctx, out))
+            }
+            Self::Group(composite) => {
+                let passed = LootCondition::all(&composite.conditions, ctx);
+                if passed {
+                    for child in &composite.children {
+                        child.expand(ctx, out);
+                    }
+                }
+                passed
+            }
+            Self::Sequence(composite) => {
+                LootCondition::all(&composite.conditions, ctx)
+                    && composite
+                        .children
+                        .iter()
+                        .all(|child| child.expand(ctx, out))
+            }
+            Self::Unsupported => false,
+        }
+    }
+}
+
+impl<'t> Candidate<'t> {
+    fn new(entry: &'t LootEntry, item: Option<ResourceLocation>) -> Self {
+        Self { entry, item }
+    }
+
+    /// The weight of the entry, adjusted by luck.
+    pub(crate) fn weight(&self, luck: f32) -> i32 {
+        match self.entry {
+            LootEntry::Item(ItemEntry { entry, .. })
+            | LootEntry::LootTable(TableEntry { entry, .. })
+            | LootEntry::Tag(ItemTagEntry { entry, .. })
+            | LootEntry::Empty(entry) => {
+                floor(entry.weight as f32 + entry.quality as f32 * luck).max(0)
+            }
+            _ => 0,
+        }
+    }
+
+    /// Produces the items of the entry.
+    pub(crate) fn create(&self, ctx: &mut LootContext, out: &mut Vec<ItemStack>) {
+        match self.entry {
+            LootEntry::Item(ItemEntry { name, entry }) => {
+                let stack = ItemStack::new(name.clone(), 1);
+                out.push(LootFunction::apply_all(&entry.functions, stack, ctx));
+            }
+            LootEntry::LootTable(TableEntry { value, entry }) => {
+                let Some(tables) = ctx.tables else {
+                    return;
+                };
+                for stack in tables.roll(value, ctx) {
+                    out.push(LootFunction::apply_all(&entry.functions, stack, ctx));
+                }
+            }
+            LootEntry::Tag(ItemTagEntry { name, entry, .. }) => {
+                let items = match &self.item {
+                    Some(item) => vec![item.clone()],
+                    None => ctx.item_tags.tag_items(name),
+                };
+                for item in items {
+                    let stack = ItemStack::new(item, 1);
+                    out.push(LootFunction::apply_all(&entry.functions, stack, ctx));
+                }
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,250 @@
+use alloc::vec::Vec;
+use mcre_core::{ItemStack, RandomSource, ResourceLocation};
+use serde::Deserialize;
+
+use crate::{LootCondition, LootContext, NumberProvider};
+
+/// A function modifying the items produced by an entry, pool or table.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct LootFunction {
+    #[serde(flatten)]
+    pub kind: LootFunctionKind,
+    #[serde(default)]
+    pub conditions: Vec<LootCondition>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "function")]
+pub enum LootFunctionKind {
+    #[serde(rename = "minecraft:set_count", alias = "set_count")]
+    SetCount {
+        count: NumberProvider,
+        #[serde(default)]
+        add: bool,
+    },
+    #[serde(rename = "minecraft:apply_bonus", alias = "apply_bonus")]
+    ApplyBonus {
+        enchantment: ResourceLocation,
+        #[serde(flatten)]
+        formula: BonusFormula,
+    },
+    /// Keeps each item with a chance of `1 / radius` when the block was destroyed by an
+    /// explosion.
+    #[serde(rename = "minecraft:explosion_decay", alias = "explosion_decay")]
+    ExplosionDecay,
+    #[serde(rename = "minecraft:limit_count", alias = "limit_count")]
+    LimitCount { limit: CountLimit },
+    /// Functions on item components, which don't change the item or its count.
+    #[serde(other)]
+    Unsupported,
+}
+
+/// How [`LootFunctionKind::ApplyBonus`] changes the count from the enchantment level.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "formula", content = "parameters")]
+pub enum BonusFormula {
+    /// Multiplies the count by a random value in `1..=level + 1`, skewed towards 1.
+    #[serde(rename = "minecraft:ore_drops", alias = "ore_drops")]
+    OreDrops,
+    /// Adds a random value in `0..=multiplier * level`.
+    #[serde(
+        rename = "minecraft:uniform_bonus_count",
+        alias = "uniform_bonus_count"
+    )]
+    UniformBonusCount {
+        #[serde(rename = "bonusMultiplier")]
+        bonus_multiplier: i32,
+    },
+    /// Adds one for each of `level + extra` trials passing with `probability`.
+    #[serde(
+        rename = "minecraft:binomial_with_bonus_count",
+        alias = "binomial_with_bonus_count"
+    )]
+    BinomialWithBonusCount { extra: i32, probability: f32 },
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct CountLimit {
+    #[serde(default)]
+    pub min: Option<NumberProvider>,
+    #[serde(default)]
+    pub max: Option<NumberProvider>,
+}
+
+impl LootFunction {
+    pub fn apply(&self, mut stack: ItemStack, ctx: &mut LootContext) -> ItemStack {
+        if LootCondition::all(&self.conditions, ctx) {
+            stack.count = self.kind.count(stack.count, ctx);
+        }
+        stack
+    }
+
+    /// Applies every function in order.
+    pub fn apply_all(functions: &[Self], stack: ItemStack, ctx: &mut LootContext) -> ItemStack {
+        functions
+            .iter()
+            .fold(stack, |stack, function| function.apply(stack, ctx))
+    }
+}
+
+impl LootFunctionKind {
+    fn count(&self, count: u32, ctx: &mut LootContext) -> u32 {
+        let random = &mut ctx.random;
+        match self {
+            Self::SetCount { count: n, add } => {
+                let n = n.get_int(random);
+                let count = if *add { count as i32 + n } else { n };
+                count.max(0) as u32
+            }
+            Self::ApplyBonus {
+                enchantment,
+                formula,
+            } => {
+                let level = ctx.enchantment_level(enchantment) as i32;
+                formula.count(count as i32, level, &mut ctx.random).max(0) as u32
+            }
+            Self::ExplosionDecay => match ctx.explosion_radius {
+                Some(radius) => (0..count)
+                    .filter(|_| random.next_float() <= 1.0 / radius)
+                    .count() as u32,
+                None => count,
+            },
+            Self::LimitCount { limit } => {
+                let mut count = count as i32;
+                if let Some(min) = &limit.min {
+                    count = count.max(min.get_int(random));
+                }
+                if let Some(max) = &limit.max {
+                    count = count.min(max.get_int(random));
+                }
+                count.max(0) as u32
+            }
+            Self::Unsupported => count,
+        }
+    }
+}
+
+impl BonusFormula {
+    pub fn count(&self, count: i32, level: i32, random: &mut impl RandomSource) -> i32 {
+        match *self {
+            Self::OreDrops if level > 0 => {
+                let bonus = (random.next_int_bounded(level + 2) - 1).max(0);
+                count * (bonus + 1)
+            }
+            Self::OreDrops => count,
+            Self::UniformBonusCount { bonus_multiplier } => {
+                // data packs may give a negative multiplier, which never adds anything
+                let bound = bonus_multiplier.saturating_mul(level).saturating_add(1);
+                count + random.next_int_bounded(bound.max(1))
+            }
+            Self::BinomialWithBonusCount { extra, probability } => {
+                count
+                    + (0..level + extra)
+                        .filter(|_| random.next_float() < probability)
+                        .count() as i32
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mcre_core::{ItemStack, ResourceLocation};
+
+    use crate::{BonusFormula, LootContext, LootFunction, LootFunctionKind, Tool};
+
+    fn parse(json: &str) -> LootFunction {
+        serde_json::from_str(json).unwrap()
+    }
+
+    fn stack(count: u32) -> ItemStack {
+        ItemStack::new(ResourceLocation::minecraft("diamond"), count)
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let bonus = parse(
+            r#"{"function": "minecraft:apply_bonus", "enchantment": "minecraft:fortune", "formula": "minecraft:binomial_with_bonus_count", "parameters": {"extra": 3, "probability": 0.5714286}}"#,
+        );
+        assert_eq!(
+            bonus.kind,
+            LootFunctionKind::ApplyBonus {
+                enchantment: ResourceLocation::minecraft("fortune"),
+                formula: BonusFormula::BinomialWithBonusCount {
+                    extra: 3,
+                    probability: 0.5714286
+                },
+            }
+        );
+
+        let ore_drops = parse(
+            r#"{"function": "apply_bonus", "enchantment": "minecraft:fortune", "formula": "minecraft:ore_drops"}"#,
+        );
+        assert!(matches!(
+            ore_drops.kind,
+            LootFunctionKind::ApplyBonus {
+                formula: BonusFormula::OreDrops,
+                ..
+            }
+        ));
+
+        let unsupported = parse(
+            r#"{"function": "minecraft:copy_components", "source": "block_entity", "conditions": [{"condition": "minecraft:survives_explosion"}]}"#,
+        );
+        assert_eq!(unsupported.kind, LootFunctionKind::Unsupported);
+        assert_eq!(unsupported.conditions.len(), 1);
+    }
+
+    #[test]
+    fn test_count() {
+        let mut ctx = LootContext::new(0);
+
+        let set = parse(r#"{"function": "minecraft:set_count", "count": 3}"#);
+        let add = parse(r#"{"function": "minecraft:set_count", "count": -5, "add": true}"#);
+        let limit = parse(r#"{"function": "minecraft:limit_count", "limit": {"max": 4.0}}"#);
+        let decay = parse(r#"{"function": "minecraft:explosion_decay"}"#);
+
+        assert_eq!(set.apply(stack(1), &mut ctx).count, 3);
+        assert_eq!(add.apply(stack(2), &mut ctx).count, 0);
+        assert_eq!(limit.apply(stack(9), &mut ctx).count, 4);
+        assert_eq!(decay.apply(stack(9), &mut ctx).count, 9);
+
+        let mut ctx = LootContext::new(0).with_explosion_radius(4.0);
+        let kept = decay.apply(stack(1000), &mut ctx).count;
+        assert!((150..350).contains(&kept), "{kept}");
+    }
+
+    #[test]
+    fn test_bonus() {
+        let fortune = |level| {
+            LootContext::new(1).with_tool(
+                Tool::new(ResourceLocation::minecraft("diamond_pickaxe"))
+                    .with_enchantment(ResourceLocation::minecraft("fortune"), level),
+            )
+        };
+        let ore_drops = parse(
+            r#"{"function": "apply_bonus", "enchantment": "minecraft:fortune", "formula": "minecraft:ore_drops"}"#,
+        );
+
+        assert_eq!(ore_drops.apply(stack(1), &mut fortune(0)).count, 1);
+        let mut ctx = fortune(3);
+        let counts = (0..100)
+            .map(|_| ore_drops.apply(stack(1), &mut ctx).count)
+            .collect::<Vec<_>>();
+        assert!(counts.iter().all(|count| (1..=4).contains(count)));
+        assert!(counts.contains(&4));
+
+        let uniform = |multiplier: i32| {
+            parse(&format!(
+                r#"{{"function": "apply_bonus", "enchantment": "minecraft:fortune", "formula": "minecraft:uniform_bonus_count", "parameters": {{"bonusMultiplier": {multiplier}}}}}"#
+            ))
+        };
+        let mut ctx = fortune(3);
+        let counts = (0..100)
+            .map(|_| uniform(1).apply(stack(1), &mut ctx).count)
+            .collect::<Vec<_>>();
+        assert!(counts.iter().all(|count| (1..=4).contains(count)));
+        assert!(counts.contains(&1) && counts.contains(&4));
+        assert!((0..100).all(|_| uniform(-2).apply(stack(1), &mut ctx).count == 1));
+    }
+}
@@ -0,0 +1,20 @@
+#![cfg_attr(not(test), no_std)]
+
+extern crate alloc;
+
+mod condition;
+mod context;
+mod entry;
+mod function;
+mod number;
+mod table;
+
+pub use condition::{
+    EnchantmentPredicate, ItemPredicate, ItemSubPredicates, LevelRange, LootCondition,
+    PropertyMatcher,
+};
+pub use context::{LootContext, Tool};
+pub use entry::{CompositeEntry, ItemEntry, ItemTagEntry, LootEntry, SingletonEntry, TableEntry};
+pub use function::{BonusFormula, CountLimit, LootFunction, LootFunctionKind};
+pub use number::NumberProvider;
+pub use table::{LootPool, LootTable, LootTables};
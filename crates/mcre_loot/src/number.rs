@@ -0,0 +1,168 @@
+use alloc::boxed::Box;
+use mcre_core::RandomSource;
+use serde::{Deserialize, Deserializer};
+
+/// A number rolled when a loot table is used, such as the count of an item.
+#[derive(Debug, Clone, PartialEq)]
+pub enum NumberProvider {
+    Constant(f32),
+    Uniform {
+        min: Box<NumberProvider>,
+        max: Box<NumberProvider>,
+    },
+    Binomial {
+        n: Box<NumberProvider>,
+        p: Box<NumberProvider>,
+    },
+}
+
+impl NumberProvider {
+    pub fn get_float(&self, random: &mut impl RandomSource) -> f32 {
+        match self {
+            Self::Constant(value) => *value,
+            Self::Uniform { min, max } => {
+                let min = min.get_float(random);
+                let max = max.get_float(random);
+                min + random.next_float() * (max - min)
+            }
+            Self::Binomial { .. } => self.get_int(random) as f32,
+        }
+    }
+
+    pub fn get_int(&self, random: &mut impl RandomSource) -> i32 {
+        match self {
+            Self::Constant(value) => round(*value),
+            Self::Uniform { min, max } => {
+                let min = min.get_int(random);
+                let max = max.get_int(random);
+                if min >= max {
+                    min
+                } else {
+                    random.next_int_between_inclusive(min, max)
+                }
+            }
+            Self::Binomial { n, p } => {
+                let n = n.get_int(random);
+                let p = p.get_float(random);
+                (0..n).filter(|_| random.next_float() < p).count() as i32
+            }
+        }
+    }
+}
+
+impl Default for NumberProvider {
+    fn default() -> Self {
+        Self::Constant(0.0)
+    }
+}
+
+/// `Mth.floor`, as `f32::floor` isn't available without `std`.
+pub(crate) fn floor(value: f32) -> i32 {
+    let truncated = value as i32;
+    if (truncated as f32) > value {
+        truncated - 1
+    } else {
+        truncated
+    }
+}
+
+/// `Math.round`, rounding halves up.
+fn round(value: f32) -> i32 {
+    floor(value + 0.5)
+}
+
+impl<'de> Deserialize<'de> for NumberProvider {
+    /// Accepts plain numbers, typed objects and the untyped `{"min": ..., "max": ...}` form of
+    /// uniform ranges.
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(tag = "type")]
+        enum Typed {
+            #[serde(rename = "minecraft:constant", alias = "constant")]
+            Constant { value: f32 },
+            #[serde(rename = "minecraft:uniform", alias = "uniform")]
+            Uniform {
+                min: NumberProvider,
+                max: NumberProvider,
+            },
+            #[serde(rename = "minecraft:binomial", alias = "binomial")]
+            Binomial {
+                n: NumberProvider,
+                p: NumberProvider,
+            },
+        }
+
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Helper {
+            Constant(f32),
+            Typed(Typed),
+            Range {
+                min: NumberProvider,
+                max: NumberProvider,
+            },
+        }
+
+        Ok(match Helper::deserialize(deserializer)? {
+            Helper::Constant(value) | Helper::Typed(Typed::Constant { value }) => {
+                Self::Constant(value)
+            }
+            Helper::Typed(Typed::Uniform { min, max }) | Helper::Range { min, max } => {
+                Self::Uniform {
+                    min: Box::new(min),
+                    max: Box::new(max),
+                }
+            }
+            Helper::Typed(Typed::Binomial { n, p }) => Self::Binomial {
+                n: Box::new(n),
+                p: Box::new(p),
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mcre_core::XoroshiroRandom;
+
+    use crate::NumberProvider;
+
+    fn parse(json: &str) -> NumberProvider {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_deserialize() {
+        assert_eq!(parse("2.0"), NumberProvider::Constant(2.0));
+        assert_eq!(
+            parse(r#"{"type": "minecraft:constant", "value": 3}"#),
+            NumberProvider::Constant(3.0)
+        );
+
+        let uniform = parse(r#"{"type": "minecraft:uniform", "min": 2.0, "max": 4.0}"#);
+        assert_eq!(parse(r#"{"min": 2, "max": 4}"#), uniform);
+        assert!(matches!(
+            parse(r#"{"type": "binomial", "n": 3, "p": 0.5}"#),
+            NumberProvider::Binomial { .. }
+        ));
+    }
+
+    #[test]
+    fn test_rolls() {
+        let mut random = XoroshiroRandom::new(0);
+
+        assert_eq!(NumberProvider::Constant(2.5).get_int(&mut random), 3);
+        assert_eq!(NumberProvider::Constant(-2.5).get_int(&mut random), -2);
+
+        let uniform = parse(r#"{"min": 2, "max": 4}"#);
+        let binomial = parse(r#"{"type": "binomial", "n": 3, "p": 0.5}"#);
+        for _ in 0..100 {
+            assert!((2..=4).contains(&uniform.get_int(&mut random)));
+            assert!((2.0..=4.0).contains(&uniform.get_float(&mut random)));
+            assert!((0..=3).contains(&binomial.get_int(&mut random)));
+        }
+    }
+}
@@ -0,0 +1,435 @@
+use alloc::{collections::BTreeMap, format, vec::Vec};
+use mcre_core::{BlockState, ItemStack, RandomSource, ResourceLocation};
+use serde::Deserialize;
+
+use crate::{LootCondition, LootContext, LootEntry, LootFunction, NumberProvider, number::floor};
+
+/// A loot table as found in `data/<namespace>/loot_table/**/*.json`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct LootTable {
+    /// The context the table is meant for, like `minecraft:block`.
+    #[serde(rename = "type", default)]
+    pub kind: Option<ResourceLocation>,
+    #[serde(default)]
+    pub pools: Vec<LootPool>,
+    #[serde(default)]
+    pub functions: Vec<LootFunction>,
+    #[serde(default)]
+    pub random_sequence: Option<ResourceLocation>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct LootPool {
+    pub rolls: NumberProvider,
+    /// Extra rolls per point of luck.
+    #[serde(default)]
+    pub bonus_rolls: NumberProvider,
+    pub entries: Vec<LootEntry>,
+    #[serde(default)]
+    pub conditions: Vec<LootCondition>,
+    #[serde(default)]
+    pub functions: Vec<LootFunction>,
+}
+
+/// Loot tables by id.
+#[derive(Debug, Clone, Default)]
+pub struct LootTables {
+    tables: BTreeMap<ResourceLocation, LootTable>,
+}
+
+impl LootTable {
+    pub fn roll(&self, ctx: &mut LootContext) -> Vec<ItemStack> {
+        let mut stacks = Vec::new();
+        for pool in &self.pools {
+            pool.roll(ctx, &mut stacks);
+        }
+
+        stacks
+            .into_iter()
+            .map(|stack| LootFunction::apply_all(&self.functions, stack, ctx))
+            .filter(|stack| stack.count > 0)
+            .collect()
+    }
+}
+
+impl LootPool {
+    pub fn roll(&self, ctx: &mut LootContext, out: &mut Vec<ItemStack>) {
+        if !LootCondition::all(&self.conditions, ctx) {
+            return;
+        }
+
+        let rolls = self.rolls.get_int(&mut ctx.random)
+            + floor(self.bonus_rolls.get_float(&mut ctx.random) * ctx.luck);
+        for _ in 0..rolls {
+            let mut rolled = Vec::new();
+            self.roll_once(ctx, &mut rolled);
+            out.extend(
+                rolled
+                    .into_iter()
+                    .map(|stack| LootFunction::apply_all(&self.functions, stack, ctx)),
+            );
+        }
+    }
+
+    /// Picks one of the expanded entries by weight.
+    fn roll_once(&self, ctx: &mut LootContext, out: &mut Vec<ItemStack>) {
+        let mut candidates = Vec::new();
+        for entry in &self.entries {
+            entry.expand(ctx, &mut candidates);
+        }
+        candidates.retain(|entry| entry.weight(ctx.luck) > 0);
+
+        let chosen = match candidates.as_slice() {
+            [] => return,
+            [entry] => entry,
+            _ => {
+                let total = candidates.iter().map(|entry| entry.weight(ctx.luck)).sum();
+                let mut remaining = ctx.random.next_int_bounded(total);
+                let mut chosen = &candidates[0];
+                for entry in &candidates {
+                    remaining -= entry.weight(ctx.luck);
+                    if remaining < 0 {
+                        chosen = entry;
+                        break;
+                    }
+                }
+                chosen
+            }
+        };
+
+        chosen.create(ctx, out);
+    }
+}
+
+impl LootTables {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, id: ResourceLocation, table: LootTable) -> Option<LootTable> {
+        self.tables.insert(id, table)
+    }
+
+    pub fn get(&self, id: &ResourceLocation) -> Option<&LootTable> {
+        self.tables.get(id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.tables.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tables.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&ResourceLocation, &LootTable)> {
+        self.tables.iter()
+    }
+
+    /// Rolls a table, producing nothing if it doesn't exist or is already being rolled.
+    pub fn roll(&self, id: &ResourceLocation, ctx: &mut LootContext) -> Vec<ItemStack> {
+        let Some(table) = self.get(id) else {
+            return Vec::new();
+        };
+        if ctx.visiting.contains(id) {
+            return Vec::new();
+        }
+
+        ctx.visiting.push(id.clone());
+        let stacks = table.roll(ctx);
+        ctx.visiting.pop();
+        stacks
+    }
+
+    /// Rolls the drops of a block from `blocks/<name>`, the state being set on the context.
+    pub fn block_drops(&self, state: BlockState, ctx: &mut LootContext) -> Vec<ItemStack> {
+        ctx.state = Some(state);
+        let id = ResourceLocation::minecraft(format!("blocks/{}", state.block().name()));
+        self.roll(&id, ctx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mcre_core::{Block, BlockState, ItemStack, PropVal, Registry, ResourceLocation, TagEntry};
+
+    use crate::{LootContext, LootTable, LootTables, Tool};
+
+    const FIXTURES: &[(&str, &str)] = &[
+        ("diamond_ore", include_str!("../fixtures/diamond_ore.json")),
+        ("glowstone", include_str!("../fixtures/glowstone.json")),
+        ("gravel", include_str!("../fixtures/gravel.json")),
+        ("short_grass", include_str!("../fixtures/short_grass.json")),
+        ("stone", include_str!("../fixtures/stone.json")),
+        ("wheat", include_str!("../fixtures/wheat.json")),
+    ];
+
+    fn tables() -> LootTables {
+        let mut tables = LootTables::new();
+        for (name, json) in FIXTURES {
+            let table = serde_json::from_str(json).unwrap();
+            tables.insert(ResourceLocation::minecraft(format!("blocks/{name}")), table);
+        }
+        tables
+    }
+
+    fn loc(s: &str) -> ResourceLocation {
+        s.parse().unwrap()
+    }
+
+    fn stack(id: &str, count: u32) -> ItemStack {
+        ItemStack::new(loc(id), count)
+    }
+
+    fn pickaxe() -> Tool {
+        Tool::new(loc("diamond_pickaxe"))
+    }
+
+    fn fortune(level: u32) -> Tool {
+        pickaxe().with_enchantment(loc("fortune"), level)
+    }
+
+    fn silk_touch() -> Tool {
+        pickaxe().with_enchantment(loc("silk_touch"), 1)
+    }
+
+    /// Rolls the drops of a block with many seeds.
+    fn drops(
+        tables: &LootTables,
+        state: BlockState,
+        ctx: impl Fn(LootContext) -> LootContext,
+    ) -> impl Iterator<Item = Vec<ItemStack>> {
+        (0..200).map(move |seed| tables.block_drops(state, &mut ctx(LootContext::new(seed))))
+    }
+
+    #[test]
+    fn test_silk_touch() {
+        let tables = tables();
+        let stone = Block::STONE.default_state();
+
+        assert!(
+            drops(&tables, stone, |ctx| ctx.with_tool(pickaxe()))
+                .all(|drops| drops == [stack("cobblestone", 1)])
+        );
+        assert!(
+            drops(&tables, stone, |ctx| ctx.with_tool(silk_touch()))
+                .all(|drops| drops == [stack("stone", 1)])
+        );
+
+        let exploded = drops(&tables, stone, |ctx| ctx.with_explosion_radius(4.0))
+            .filter(Vec::is_empty)
+            .count();
+        assert!((100..200).contains(&exploded), "{exploded}");
+    }
+
+    #[test]
+    fn test_ore_drops() {
+        let tables = tables();
+        let ore = Block::DIAMOND_ORE.default_state();
+
+        assert!(
+            drops(&tables, ore, |ctx| ctx.with_tool(pickaxe()))
+                .all(|drops| drops == [stack("diamond", 1)])
+        );
+
+        let counts = drops(&tables, ore, |ctx| ctx.with_tool(fortune(3)))
+            .map(|drops| drops[0].count)
+            .collect::<Vec<_>>();
+        assert!(counts.iter().all(|count| (1..=4).contains(count)));
+        assert!(counts.contains(&1) && counts.contains(&4));
+    }
+
+    #[test]
+    fn test_crop() {
+        let tables = tables();
+        let age = |age| {
+            Block::WHEAT
+                .default_state()
+                .with_prop(PropVal::Age(age))
+                .unwrap()
+        };
+
+        assert!(drops(&tables, age(3), |ctx| ctx).all(|drops| drops == [stack("wheat_seeds", 1)]));
+
+        for drops in drops(&tables, age(7), |ctx| ctx.with_tool(fortune(2))) {
+            assert_eq!(drops[0], stack("wheat", 1));
+            assert!(
+                drops[1..]
+                    .iter()
+                    .all(|seeds| seeds.id == loc("wheat_seeds"))
+            );
+            assert!(
+                drops[1..]
+                    .iter()
+                    .all(|seeds| (1..=6).contains(&seeds.count))
+            );
+        }
+    }
+
+    #[test]
+    fn test_count_functions() {
+        let tables = tables();
+        let glowstone = Block::GLOWSTONE.default_state();
+
+        let counts = drops(&tables, glowstone, |ctx| ctx)
+            .map(|drops| drops[0].count)
+            .collect::<Vec<_>>();
+        assert!(counts.iter().all(|count| (2..=4).contains(count)));
+        assert!(counts.contains(&2) && counts.contains(&4));
+
+        assert!(
+            drops(&tables, glowstone, |ctx| ctx.with_tool(fortune(3)))
+                .all(|drops| drops[0].count <= 4)
+        );
+    }
+
+    #[test]
+    fn test_chances() {
+        let tables = tables();
+        let gravel = Block::GRAVEL.default_state();
+
+        assert!(
+            drops(&tables, gravel, |ctx| ctx.with_tool(fortune(3)))
+                .all(|drops| drops == [stack("flint", 1)])
+        );
+        let flint = drops(&tables, gravel, |ctx| ctx)
+            .filter(|drops| drops == &[stack("flint", 1)])
+            .count();
+        assert!((5..50).contains(&flint), "{flint}");
+
+        let grass = Block::SHORT_GRASS.default_state();
+        assert!(
+            drops(&tables, grass, |ctx| ctx
+                .with_tool(Tool::new(loc("shears"))))
+            .all(|drops| drops == [stack("short_grass", 1)])
+        );
+        let seeds = drops(&tables, grass, |ctx| ctx)
+            .filter(|drops| !drops.is_empty())
+            .count();
+        assert!((5..60).contains(&seeds), "{seeds}");
+    }
+
+    #[test]
+    fn test_weights_and_references() {
+        let table: LootTable = serde_json::from_str(
+            r#"{
+                "pools": [{
+                    "rolls": {"type": "minecraft:uniform", "min": 1, "max": 3},
+                    "entries": [
+                        {"type": "minecraft:item", "name": "minecraft:apple", "weight": 3},
+                        {"type": "minecraft:empty", "weight": 1, "quality": -1},
+                        {"type": "minecraft:loot_table", "value": "minecraft:chests/nested"},
+                        {"type": "minecraft:tag", "name": "minecraft:music_discs", "expand": true}
+                    ]
+                }]
+            }"#,
+        )
+        .unwrap();
+        let nested: LootTable = serde_json::from_str(
+            r#"{
+                "pools": [{
+                    "rolls": 1,
+                    "entries": [
+                        {"type": "minecraft:loot_table", "value": "minecraft:chests/nested"},
+                        {"type": "minecraft:item", "name": "minecraft:stick", "weight": 0}
+                    ]
+                }]
+            }"#,
+        )
+        .unwrap();
+
+        let mut tables = LootTables::new();
+        tables.insert(loc("chests/nested"), nested);
+        let mut items = Registry::new(loc("item"));
+        for item in ["apple", "music_disc_cat", "music_disc_13"] {
+            items.register(loc(item), ()).unwrap();
+        }
+        let discs = [
+            TagEntry::element(loc("music_disc_cat")),
+            TagEntry::element(loc("music_disc_13")),
+        ];
+        items
+            .bind_tags([(loc("music_discs"), discs.to_vec())])
+            .unwrap();
+
+        // apples weigh 3 and each disc 1
+        let (mut apples, mut discs) = (0, 0);
+        for seed in 0..200 {
+            let mut ctx = LootContext::new(seed)
+                .with_tables(&tables)
+                .with_luck(1.0)
+                .with_item_tags(&items);
+            let drops = table.roll(&mut ctx);
+            assert!(drops.len() <= 3);
+            for drop in drops {
+                match drop.id.path() {
+                    "apple" => apples += 1,
+                    "music_disc_cat" | "music_disc_13" => discs += 1,
+                    _ => panic!("unexpected drop {drop:?}"),
+                }
+                assert_eq!(drop.count, 1);
+            }
+        }
+        assert!(
+            apples > 150 && discs > 50 && apples > discs,
+            "{apples} {discs}"
+        );
+    }
+
+    #[test]
+    fn test_tag_entries() {
+        let mut items = Registry::new(loc("item"));
+        for item in ["oak_planks", "birch_planks", "stone"] {
+            items.register(loc(item), ()).unwrap();
+        }
+        let planks = [
+            TagEntry::element(loc("oak_planks")),
+            TagEntry::element(loc("birch_planks")),
+        ];
+        items.bind_tags([(loc("planks"), planks.to_vec())]).unwrap();
+        let table = |expand: bool| -> LootTable {
+            serde_json::from_str(&format!(
+                r#"{{
+                    "pools": [{{
+                        "rolls": 1,
+                        "entries": [{{
+                            "type": "minecraft:tag",
+                            "name": "minecraft:planks",
+                            "expand": {expand},
+                            "functions": [{{"function": "minecraft:set_count", "count": 2}}]
+                        }}]
+                    }}]
+                }}"#
+            ))
+            .unwrap()
+        };
+
+        // every item of the tag at once
+        let all = table(false);
+        for seed in 0..20 {
+            let mut ctx = LootContext::new(seed).with_item_tags(&items);
+            assert_eq!(
+                all.roll(&mut ctx),
+                [stack("oak_planks", 2), stack("birch_planks", 2)]
+            );
+        }
+
+        // one item of the tag per roll, each of them picked
+        let one = table(true);
+        let mut picked = Vec::new();
+        for seed in 0..50 {
+            let mut ctx = LootContext::new(seed).with_item_tags(&items);
+            let drops = one.roll(&mut ctx);
+            assert_eq!(drops.len(), 1);
+            assert!(drops[0] == stack("oak_planks", 2) || drops[0] == stack("birch_planks", 2));
+            if !picked.contains(&drops[0]) {
+                picked.push(drops[0].clone());
+            }
+        }
+        assert_eq!(picked.len(), 2);
+
+        // unknown tags produce nothing
+        assert!(one.roll(&mut LootContext::new(0)).is_empty());
+        assert!(all.roll(&mut LootContext::new(0)).is_empty());
+    }
+}
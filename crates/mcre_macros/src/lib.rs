@@ -0,0 +1,210 @@
+use mcre_core::{Block, BlockState, DATA_VERSION, PropKey, PropVal};
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::{
+    Ident, LitInt, Token, braced,
+    ext::IdentExt,
+    parse::{Parse, ParseStream},
+    parse_macro_input,
+    punctuated::Punctuated,
+};
+
+/// Expands to the constant [`BlockState`] of a block with the given properties, the others
+/// keeping their default value.
+///
+/// ```
+/// use mcre_core::{Block, BlockState, Direction};
+/// use mcre_macros::block_state;
+///
+/// const STAIRS: BlockState = block_state!(oak_stairs { facing: east, half: top, waterlogged: true });
+/// const STONE: BlockState = block_state!(stone);
+///
+/// assert_eq!(STAIRS.facing(), Direction::East);
+/// assert_eq!(STONE, Block::STONE.default_state());
+/// ```
+///
+/// Block names, property names and values are checked against the generated data, errors
+/// pointing at the offending token:
+///
+/// ```compile_fail
+/// let state = mcre_macros::block_state!(oak_stairs { facing: up });
+/// ```
+#[proc_macro]
+pub fn block_state(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as BlockStateInput);
+
+    match input.resolve() {
+        Ok(state) => expand(state).into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn expand(state: BlockState) -> proc_macro2::TokenStream {
+    let id = state.id();
+    let world_version = DATA_VERSION.world_version;
+    let version = DATA_VERSION.id;
+    let message = format!("block_state! was checked against the data of {version}");
+
+    quote! {{
+        const _: () = assert!(::mcre_core::DATA_VERSION.world_version == #world_version, #message);
+        ::mcre_core::BlockState::from_id(#id)
+    }}
+}
+
+struct BlockStateInput {
+    block: Ident,
+    props: Punctuated<Prop, Token![,]>,
+}
+
+struct Prop {
+    key: Ident,
+    value: (String, Span),
+}
+
+impl Parse for BlockStateInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let block = Ident::parse_any(input)?;
+        let props = if input.is_empty() {
+            Punctuated::new()
+        } else {
+            let content;
+            braced!(content in input);
+            Punctuated::parse_terminated(&content)?
+        };
+
+        Ok(Self { block, props })
+    }
+}
+
+impl Parse for Prop {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let key = Ident::parse_any(input)?;
+        input.parse::<Token![:]>()?;
+        let value = if input.peek(LitInt) {
+            let lit = input.parse::<LitInt>()?;
+            (lit.base10_digits().to_string(), lit.span())
+        } else {
+            let ident = Ident::parse_any(input)?;
+            (ident.unraw().to_string(), ident.span())
+        };
+
+        Ok(Self { key, value })
+    }
+}
+
+impl BlockStateInput {
+    fn resolve(&self) -> syn::Result<BlockState> {
+        let name = self.block.unraw().to_string();
+        let block = Block::all()
+            .find(|block| block.name() == name)
+            .ok_or_else(|| syn::Error::new(self.block.span(), format!("unknown block `{name}`")))?;
+
+        let mut state = block.default_state();
+        let mut seen = Vec::new();
+
+        for prop in &self.props {
+            let key_name = prop.key.unraw().to_string();
+            let key = key_name
+                .parse::<PropKey>()
+                .ok()
+                .filter(|key| state.get_prop(*key).is_some())
+                .ok_or_else(|| {
+                    syn::Error::new(
+                        prop.key.span(),
+                        format!("block `{name}` has no property `{key_name}`"),
+                    )
+                })?;
+
+            if seen.contains(&key) {
+                return Err(syn::Error::new(
+                    prop.key.span(),
+                    format!("property `{key_name}` is set more than once"),
+                ));
+            }
+            seen.push(key);
+
+            let (value, span) = &prop.value;
+            state = PropVal::parse_with_key(key, value)
+                .and_then(|val| state.with_prop(val))
+                .ok_or_else(|| {
+                    syn::Error::new(
+                        *span,
+                        format!("invalid value `{value}` for property `{key_name}` of `{name}`"),
+                    )
+                })?;
+        }
+
+        Ok(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mcre_core::{Block, BlockState, Direction, Half, PropKey, PropVal};
+
+    use crate::BlockStateInput;
+
+    fn resolve(input: &str) -> Result<BlockState, String> {
+        syn::parse_str::<BlockStateInput>(input)
+            .and_then(|input| input.resolve())
+            .map_err(|err| err.to_string())
+    }
+
+    #[test]
+    fn test_resolve() {
+        let stairs = resolve("oak_stairs { facing: east, half: top, waterlogged: true }").unwrap();
+        assert_eq!(stairs.block(), Block::OAK_STAIRS);
+        assert_eq!(stairs.facing(), Direction::East);
+        assert_eq!(stairs.half(), Half::Top);
+        assert!(stairs.is_waterlogged());
+        assert_eq!(
+            stairs.get_prop(PropKey::Shape),
+            Block::OAK_STAIRS.default_state().get_prop(PropKey::Shape)
+        );
+
+        assert_eq!(resolve("stone"), Ok(Block::STONE.default_state()));
+        assert_eq!(
+            resolve("wheat { age: 7, }"),
+            Ok(Block::WHEAT
+                .default_state()
+                .with_prop(PropVal::Age(7))
+                .unwrap())
+        );
+        assert_eq!(
+            resolve("oak_slab { type: top }"),
+            Block::OAK_SLAB
+                .default_state()
+                .with_prop(PropVal::parse_with_key(PropKey::Type, "top").unwrap())
+                .ok_or_else(String::new)
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            resolve("oak_stair"),
+            Err("unknown block `oak_stair`".into())
+        );
+        assert_eq!(
+            resolve("oak_stairs { facin: east }"),
+            Err("block `oak_stairs` has no property `facin`".into())
+        );
+        assert_eq!(
+            resolve("stone { age: 1 }"),
+            Err("block `stone` has no property `age`".into())
+        );
+        assert_eq!(
+            resolve("oak_stairs { facing: up }"),
+            Err("invalid value `up` for property `facing` of `oak_stairs`".into())
+        );
+        assert_eq!(
+            resolve("wheat { age: 8 }"),
+            Err("invalid value `8` for property `age` of `wheat`".into())
+        );
+        assert_eq!(
+            resolve("wheat { age: 1, age: 2 }"),
+            Err("property `age` is set more than once".into())
+        );
+    }
+}
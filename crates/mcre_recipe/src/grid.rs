@@ -0,0 +1,79 @@
+use alloc::{vec, vec::Vec};
+use mcre_core::ResourceLocation;
+
+/// The items placed in a crafting grid, 2x2 for the inventory or 3x3 for a crafting table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CraftingGrid {
+    width: usize,
+    height: usize,
+    items: Vec<Option<ResourceLocation>>,
+}
+
+impl CraftingGrid {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            items: vec![None; width * height],
+        }
+    }
+
+    /// Builds a grid from its rows, `None` being an empty slot.
+    ///
+    /// # Panics
+    /// Panics if an item isn't a valid id.
+    pub fn from_rows<const W: usize>(rows: &[[Option<&str>; W]]) -> Self {
+        let mut grid = Self::new(W, rows.len());
+        for (y, row) in rows.iter().enumerate() {
+            for (x, item) in row.iter().enumerate() {
+                grid.set(
+                    x,
+                    y,
+                    item.map(|item| item.parse().expect("invalid item id")),
+                );
+            }
+        }
+        grid
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<&ResourceLocation> {
+        self.items[y * self.width + x].as_ref()
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, item: Option<ResourceLocation>) {
+        self.items[y * self.width + x] = item;
+    }
+
+    /// The occupied items, in row order.
+    pub fn items(&self) -> impl Iterator<Item = &ResourceLocation> {
+        self.items.iter().flatten()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items().next().is_none()
+    }
+
+    /// The smallest rectangle containing every item, as `(x, y, width, height)`.
+    pub(crate) fn bounds(&self) -> Option<(usize, usize, usize, usize)> {
+        let occupied = || {
+            (0..self.height)
+                .flat_map(move |y| (0..self.width).map(move |x| (x, y)))
+                .filter(|&(x, y)| self.get(x, y).is_some())
+        };
+
+        let min_x = occupied().map(|(x, _)| x).min()?;
+        let max_x = occupied().map(|(x, _)| x).max()?;
+        let min_y = occupied().map(|(_, y)| y).min()?;
+        let max_y = occupied().map(|(_, y)| y).max()?;
+
+        Some((min_x, min_y, max_x - min_x + 1, max_y - min_y + 1))
+    }
+}
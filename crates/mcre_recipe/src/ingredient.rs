@@ -0,0 +1,168 @@
+use core::{
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+use alloc::{
+    boxed::Box,
+    string::{String, ToString},
+    vec::Vec,
+};
+use mcre_core::{ItemTags, ResourceLocation, ResourceLocationError};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum IngredientEntry {
+    Item(ResourceLocation),
+    Tag(ResourceLocation),
+}
+
+/// The items accepted by one slot of a recipe, any of the entries matching.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Ingredient(Box<[IngredientEntry]>);
+
+impl IngredientEntry {
+    pub fn test(&self, item: &ResourceLocation, tags: &impl ItemTags) -> bool {
+        match self {
+            Self::Item(id) => id == item,
+            Self::Tag(tag) => tags.has_tag(item, tag),
+        }
+    }
+}
+
+impl Ingredient {
+    pub fn new(entries: impl Into<Box<[IngredientEntry]>>) -> Self {
+        Self(entries.into())
+    }
+
+    pub fn item(item: ResourceLocation) -> Self {
+        Self::new([IngredientEntry::Item(item)])
+    }
+
+    pub fn tag(tag: ResourceLocation) -> Self {
+        Self::new([IngredientEntry::Tag(tag)])
+    }
+
+    pub fn entries(&self) -> &[IngredientEntry] {
+        &self.0
+    }
+
+    pub fn test(&self, item: &ResourceLocation, tags: &impl ItemTags) -> bool {
+        self.0.iter().any(|entry| entry.test(item, tags))
+    }
+}
+
+impl FromStr for IngredientEntry {
+    type Err = ResourceLocationError;
+
+    /// Parses `minecraft:stick` or `#minecraft:planks`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.strip_prefix('#') {
+            Some(tag) => Ok(Self::Tag(tag.parse()?)),
+            None => Ok(Self::Item(s.parse()?)),
+        }
+    }
+}
+
+impl Display for IngredientEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Item(id) => write!(f, "{id}"),
+            Self::Tag(tag) => write!(f, "#{tag}"),
+        }
+    }
+}
+
+impl Serialize for Ingredient {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match &*self.0 {
+            [entry] => serializer.collect_str(entry),
+            entries => serializer.collect_seq(entries.iter().map(|entry| entry.to_string())),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Ingredient {
+    /// Accepts the current string form (`"minecraft:stick"`, `"#minecraft:planks"` or a list of
+    /// them) as well as the object form used before 1.21.2 (`{"item": ...}` or `{"tag": ...}`).
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Entry {
+            Short(String),
+            Item { item: ResourceLocation },
+            Tag { tag: ResourceLocation },
+        }
+
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Helper {
+            Single(Entry),
+            List(Vec<Entry>),
+        }
+
+        let entries = match Helper::deserialize(deserializer)? {
+            Helper::Single(entry) => alloc::vec![entry],
+            Helper::List(entries) => entries,
+        };
+
+        if entries.is_empty() {
+            return Err(serde::de::Error::custom("ingredient without any item"));
+        }
+
+        entries
+            .into_iter()
+            .map(|entry| match entry {
+                Entry::Short(s) => s.parse().map_err(serde::de::Error::custom),
+                Entry::Item { item } => Ok(IngredientEntry::Item(item)),
+                Entry::Tag { tag } => Ok(IngredientEntry::Tag(tag)),
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map(Self::new)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mcre_core::ResourceLocation;
+
+    use crate::{Ingredient, IngredientEntry};
+
+    fn loc(s: &str) -> ResourceLocation {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let parse = |json: &str| serde_json::from_str::<Ingredient>(json).unwrap();
+
+        assert_eq!(
+            parse(r#""minecraft:stick""#),
+            Ingredient::item(loc("stick"))
+        );
+        assert_eq!(
+            parse(r##""#minecraft:planks""##),
+            Ingredient::tag(loc("planks"))
+        );
+        assert_eq!(
+            parse(r#"[{"item": "minecraft:coal"}, {"tag": "minecraft:logs"}]"#),
+            Ingredient::new([
+                IngredientEntry::Item(loc("coal")),
+                IngredientEntry::Tag(loc("logs")),
+            ])
+        );
+        assert!(serde_json::from_str::<Ingredient>("[]").is_err());
+
+        let ingredient = parse(r##"["minecraft:coal", "#minecraft:coals"]"##);
+        assert_eq!(
+            serde_json::to_string(&ingredient).unwrap(),
+            r##"["minecraft:coal","#minecraft:coals"]"##
+        );
+    }
+}
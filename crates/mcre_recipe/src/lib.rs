@@ -0,0 +1,13 @@
+#![cfg_attr(not(test), no_std)]
+
+extern crate alloc;
+
+mod grid;
+mod ingredient;
+mod manager;
+mod recipe;
+
+pub use grid::CraftingGrid;
+pub use ingredient::{Ingredient, IngredientEntry};
+pub use manager::{RecipeError, RecipeManager};
+pub use recipe::*;
@@ -0,0 +1,617 @@
+use core::fmt::{self, Display};
+
+use alloc::{collections::BTreeMap, string::String, vec::Vec};
+use mcre_core::{ItemStack, ItemTags, ResourceLocation};
+
+use crate::{
+    CookingKind, CookingRecipe, CraftingGrid, Ingredient, Recipe, ShapedRecipe, ShapelessRecipe,
+};
+
+/// Largest width and height of a crafting pattern, and so the most ingredients of a recipe.
+const MAX_SIZE: usize = 3;
+
+/// Every loaded recipe, by id.
+#[derive(Debug, Clone, Default)]
+pub struct RecipeManager {
+    recipes: BTreeMap<ResourceLocation, Recipe>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RecipeError {
+    /// A pattern that is empty, has rows of different lengths or is larger than 3x3.
+    InvalidPattern(ResourceLocation),
+    UndefinedKey {
+        recipe: ResourceLocation,
+        key: char,
+    },
+    UnusedKey {
+        recipe: ResourceLocation,
+        key: char,
+    },
+    /// A shapeless recipe without ingredients or with more than fit in the grid.
+    InvalidIngredientCount(ResourceLocation),
+}
+
+impl RecipeManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a recipe, replacing any previous recipe with the same id.
+    pub fn insert(&mut self, id: ResourceLocation, recipe: Recipe) -> Result<(), RecipeError> {
+        match &recipe {
+            Recipe::Shaped(recipe) => validate_shaped(&id, recipe)?,
+            Recipe::Shapeless(recipe) => {
+                if recipe.ingredients.is_empty() || recipe.ingredients.len() > MAX_SIZE * MAX_SIZE {
+                    return Err(RecipeError::InvalidIngredientCount(id));
+                }
+            }
+            _ => {}
+        }
+
+        self.recipes.insert(id, recipe);
+        Ok(())
+    }
+
+    pub fn get(&self, id: &ResourceLocation) -> Option<&Recipe> {
+        self.recipes.get(id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.recipes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.recipes.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&ResourceLocation, &Recipe)> {
+        self.recipes.iter()
+    }
+
+    /// Finds the crafting recipe matching the items of `grid`, shaped recipes also matching
+    /// when mirrored horizontally.
+    pub fn craft(
+        &self,
+        grid: &CraftingGrid,
+        tags: &impl ItemTags,
+    ) -> Option<(&ResourceLocation, &ItemStack)> {
+        if grid.is_empty() {
+            return None;
+        }
+
+        self.recipes.iter().find_map(|(id, recipe)| {
+            let result = match recipe {
+                Recipe::Shaped(recipe) if matches_shaped(recipe, grid, tags) => &recipe.result,
+                Recipe::Shapeless(recipe) if matches_shapeless(recipe, grid, tags) => {
+                    &recipe.result
+                }
+                _ => return None,
+            };
+            Some((id, result))
+        })
+    }
+
+    /// Finds the recipe cooking `item` in the given appliance.
+    pub fn cook(
+        &self,
+        kind: CookingKind,
+        item: &ResourceLocation,
+        tags: &impl ItemTags,
+    ) -> Option<(&ResourceLocation, &CookingRecipe)> {
+        self.recipes.iter().find_map(|(id, recipe)| {
+            recipe
+                .cooking()
+                .filter(|(recipe_kind, recipe)| {
+                    *recipe_kind == kind && recipe.ingredient.test(item, tags)
+                })
+                .map(|(_, recipe)| (id, recipe))
+        })
+    }
+
+    /// Every result the stonecutter offers for `item`.
+    pub fn stonecut<'a>(
+        &'a self,
+        item: &'a ResourceLocation,
+        tags: &'a impl ItemTags,
+    ) -> impl Iterator<Item = (&'a ResourceLocation, &'a ItemStack)> {
+        self.recipes
+            .iter()
+            .filter_map(move |(id, recipe)| match recipe {
+                Recipe::Stonecutting(recipe) if recipe.ingredient.test(item, tags) => {
+                    Some((id, &recipe.result))
+                }
+                _ => None,
+            })
+    }
+
+    /// Finds the smithing recipe matching the three inputs of a smithing table.
+    pub fn smith(
+        &self,
+        template: &ResourceLocation,
+        base: &ResourceLocation,
+        addition: &ResourceLocation,
+        tags: &impl ItemTags,
+    ) -> Option<(&ResourceLocation, ItemStack)> {
+        let matches = |template_ingredient: &Ingredient,
+                       base_ingredient: &Ingredient,
+                       addition_ingredient: &Ingredient| {
+            template_ingredient.test(template, tags)
+                && base_ingredient.test(base, tags)
+                && addition_ingredient.test(addition, tags)
+        };
+
+        self.recipes.iter().find_map(|(id, recipe)| match recipe {
+            Recipe::SmithingTransform(recipe)
+                if matches(&recipe.template, &recipe.base, &recipe.addition) =>
+            {
+                Some((id, recipe.result.clone()))
+            }
+            Recipe::SmithingTrim(recipe)
+                if matches(&recipe.template, &recipe.base, &recipe.addition) =>
+            {
+                Some((id, ItemStack::new(base.clone(), 1)))
+            }
+            _ => None,
+        })
+    }
+}
+
+/// The rows of a pattern with the empty rows and columns around it removed, `None` if it only
+/// has empty slots.
+fn trimmed_pattern(pattern: &[String]) -> Option<Vec<Vec<char>>> {
+    let rows = pattern
+        .iter()
+        .map(|row| row.chars().collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    let row_occupied = |row: &Vec<char>| row.iter().any(|c| *c != ' ');
+    let column_occupied = |x: &usize| rows.iter().any(|row| row[*x] != ' ');
+
+    let first_row = rows.iter().position(row_occupied)?;
+    let last_row = rows.iter().rposition(row_occupied)?;
+    let width = rows[0].len();
+    let first_column = (0..width).find(column_occupied)?;
+    let last_column = (0..width).rfind(column_occupied)?;
+
+    Some(
+        rows[first_row..=last_row]
+            .iter()
+            .map(|row| row[first_column..=last_column].to_vec())
+            .collect(),
+    )
+}
+
+fn validate_shaped(id: &ResourceLocation, recipe: &ShapedRecipe) -> Result<(), RecipeError> {
+    let width = recipe.pattern.first().map_or(0, |row| row.chars().count());
+    if width == 0
+        || width > MAX_SIZE
+        || recipe.pattern.len() > MAX_SIZE
+        || recipe
+            .pattern
+            .iter()
+            .any(|row| row.chars().count() != width)
+        || trimmed_pattern(&recipe.pattern).is_none()
+    {
+        return Err(RecipeError::InvalidPattern(id.clone()));
+    }
+
+    for key in recipe.pattern.iter().flat_map(|row| row.chars()) {
+        if key != ' ' && !recipe.key.contains_key(&key) {
+            return Err(RecipeError::UndefinedKey {
+                recipe: id.clone(),
+                key,
+            });
+        }
+    }
+
+    for key in recipe.key.keys() {
+        if *key == ' ' || !recipe.pattern.iter().any(|row| row.contains(*key)) {
+            return Err(RecipeError::UnusedKey {
+                recipe: id.clone(),
+                key: *key,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+fn matches_shaped(recipe: &ShapedRecipe, grid: &CraftingGrid, tags: &impl ItemTags) -> bool {
+    let Some((left, top, width, height)) = grid.bounds() else {
+        return false;
+    };
+
+    let Some(pattern) = trimmed_pattern(&recipe.pattern) else {
+        return false;
+    };
+    if pattern.len() != height || pattern[0].len() != width {
+        return false;
+    }
+
+    let matches = |mirrored: bool| {
+        (0..height).all(|y| {
+            (0..width).all(|x| {
+                let column = if mirrored { width - 1 - x } else { x };
+                let item = grid.get(left + x, top + y);
+                match (pattern[y][column], item) {
+                    (' ', item) => item.is_none(),
+                    (_, None) => false,
+                    (key, Some(item)) => recipe.key[&key].test(item, tags),
+                }
+            })
+        })
+    };
+
+    matches(false) || matches(true)
+}
+
+fn matches_shapeless(recipe: &ShapelessRecipe, grid: &CraftingGrid, tags: &impl ItemTags) -> bool {
+    let items = grid.items().collect::<Vec<_>>();
+    if items.len() != recipe.ingredients.len() {
+        return false;
+    }
+
+    // every item has to be used by a different ingredient
+    fn assign(
+        ingredients: &[Ingredient],
+        items: &[&ResourceLocation],
+        used: &mut [bool],
+        tags: &impl ItemTags,
+    ) -> bool {
+        let Some((ingredient, rest)) = ingredients.split_first() else {
+            return true;
+        };
+
+        for (i, item) in items.iter().enumerate() {
+            if !used[i] && ingredient.test(item, tags) {
+                used[i] = true;
+                if assign(rest, items, used, tags) {
+                    return true;
+                }
+                used[i] = false;
+            }
+        }
+
+        false
+    }
+
+    let mut used = [false; MAX_SIZE * MAX_SIZE];
+    assign(&recipe.ingredients, &items, &mut used, tags)
+}
+
+impl Display for RecipeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidPattern(recipe) => write!(f, "recipe {recipe} has an invalid pattern"),
+            Self::UndefinedKey { recipe, key } => {
+                write!(f, "recipe {recipe} uses undefined key '{key}'")
+            }
+            Self::UnusedKey { recipe, key } => {
+                write!(f, "recipe {recipe} defines unused key '{key}'")
+            }
+            Self::InvalidIngredientCount(recipe) => {
+                write!(f, "recipe {recipe} has no or too many ingredients")
+            }
+        }
+    }
+}
+
+impl core::error::Error for RecipeError {}
+
+#[cfg(test)]
+mod tests {
+    use mcre_core::{ItemStack, Registry, ResourceLocation, TagEntry};
+
+    use crate::{CookingKind, CraftingGrid, Recipe, RecipeError, RecipeManager};
+
+    const FIXTURES: [(&str, &str); 14] = [
+        ("armor_dye", include_str!("../fixtures/armor_dye.json")),
+        (
+            "baked_potato_from_campfire_cooking",
+            include_str!("../fixtures/baked_potato_from_campfire_cooking.json"),
+        ),
+        ("bow", include_str!("../fixtures/bow.json")),
+        (
+            "coast_armor_trim_smithing_template_smithing_trim",
+            include_str!("../fixtures/coast_armor_trim_smithing_template_smithing_trim.json"),
+        ),
+        (
+            "crafting_table",
+            include_str!("../fixtures/crafting_table.json"),
+        ),
+        ("fire_charge", include_str!("../fixtures/fire_charge.json")),
+        (
+            "iron_ingot_from_blasting_iron_ore",
+            include_str!("../fixtures/iron_ingot_from_blasting_iron_ore.json"),
+        ),
+        (
+            "iron_ingot_from_smelting_iron_ore",
+            include_str!("../fixtures/iron_ingot_from_smelting_iron_ore.json"),
+        ),
+        (
+            "netherite_sword_smithing",
+            include_str!("../fixtures/netherite_sword_smithing.json"),
+        ),
+        ("oak_planks", include_str!("../fixtures/oak_planks.json")),
+        ("stick", include_str!("../fixtures/stick.json")),
+        (
+            "stone_bricks_from_stone_stonecutting",
+            include_str!("../fixtures/stone_bricks_from_stone_stonecutting.json"),
+        ),
+        (
+            "stone_slab_from_stone_stonecutting",
+            include_str!("../fixtures/stone_slab_from_stone_stonecutting.json"),
+        ),
+        ("torch", include_str!("../fixtures/torch.json")),
+    ];
+
+    fn loc(s: &str) -> ResourceLocation {
+        s.parse().unwrap()
+    }
+
+    fn manager() -> RecipeManager {
+        let mut manager = RecipeManager::new();
+        for (id, json) in FIXTURES {
+            let recipe: Recipe = serde_json::from_str(json).unwrap();
+            manager.insert(loc(id), recipe).unwrap();
+        }
+        manager
+    }
+
+    fn items() -> Registry<()> {
+        let mut items = Registry::new(loc("item"));
+        for item in [
+            "oak_planks",
+            "birch_planks",
+            "oak_log",
+            "netherite_ingot",
+            "iron_ingot",
+            "iron_chestplate",
+        ] {
+            items.register(loc(item), ()).unwrap();
+        }
+        items.freeze();
+
+        let tag = |entries: &[&str]| {
+            entries
+                .iter()
+                .map(|entry| entry.parse::<TagEntry>().unwrap())
+                .collect::<Vec<_>>()
+        };
+        items
+            .bind_tags([
+                (loc("planks"), tag(&["oak_planks", "birch_planks"])),
+                (loc("oak_logs"), tag(&["oak_log"])),
+                (loc("netherite_tool_materials"), tag(&["netherite_ingot"])),
+                (loc("trim_materials"), tag(&["iron_ingot"])),
+                (loc("trimmable_armor"), tag(&["iron_chestplate"])),
+            ])
+            .unwrap();
+        items
+    }
+
+    fn result(id: &str, count: u32) -> ItemStack {
+        ItemStack::new(loc(id), count)
+    }
+
+    #[test]
+    fn test_parse_fixtures() {
+        let manager = manager();
+        assert_eq!(manager.len(), FIXTURES.len());
+        assert_eq!(manager.get(&loc("armor_dye")), Some(&Recipe::Special));
+
+        let (kind, smelting) = manager
+            .get(&loc("iron_ingot_from_smelting_iron_ore"))
+            .and_then(Recipe::cooking)
+            .unwrap();
+        assert_eq!(kind, CookingKind::Smelting);
+        assert_eq!(smelting.experience, 0.7);
+        assert_eq!(smelting.cooking_time(kind), 200);
+        assert_eq!(smelting.group.as_deref(), Some("iron_ingot"));
+    }
+
+    #[test]
+    fn test_craft_shaped() {
+        let manager = manager();
+        let items = items();
+
+        let table = CraftingGrid::from_rows(&[
+            [Some("oak_planks"), Some("birch_planks")],
+            [Some("birch_planks"), Some("oak_planks")],
+        ]);
+        assert_eq!(
+            manager.craft(&table, &items),
+            Some((&loc("crafting_table"), &result("crafting_table", 1)))
+        );
+        // tags can't be resolved without the item registry
+        assert_eq!(manager.craft(&table, &()), None);
+
+        // a stick anywhere in a 3x3 grid
+        let sticks = CraftingGrid::from_rows(&[
+            [None, None, None],
+            [None, None, Some("oak_planks")],
+            [None, None, Some("oak_planks")],
+        ]);
+        assert_eq!(
+            manager.craft(&sticks, &items).map(|(_, result)| result),
+            Some(&result("stick", 4))
+        );
+
+        let torch = CraftingGrid::from_rows(&[[Some("charcoal")], [Some("stick")]]);
+        assert_eq!(
+            manager.craft(&torch, &items).map(|(_, result)| result),
+            Some(&result("torch", 4))
+        );
+        let upside_down = CraftingGrid::from_rows(&[[Some("stick")], [Some("charcoal")]]);
+        assert_eq!(manager.craft(&upside_down, &items), None);
+    }
+
+    #[test]
+    fn test_craft_mirrored() {
+        let manager = manager();
+
+        let bow = CraftingGrid::from_rows(&[
+            [None, Some("stick"), Some("string")],
+            [Some("stick"), None, Some("string")],
+            [None, Some("stick"), Some("string")],
+        ]);
+        let mirrored = CraftingGrid::from_rows(&[
+            [Some("string"), Some("stick"), None],
+            [Some("string"), None, Some("stick")],
+            [Some("string"), Some("stick"), None],
+        ]);
+        let broken = CraftingGrid::from_rows(&[
+            [Some("string"), Some("stick"), None],
+            [Some("string"), None, Some("stick")],
+            [None, Some("stick"), Some("string")],
+        ]);
+
+        for grid in [&bow, &mirrored] {
+            assert_eq!(
+                manager.craft(grid, &()),
+                Some((&loc("bow"), &result("bow", 1)))
+            );
+        }
+        assert_eq!(manager.craft(&broken, &()), None);
+    }
+
+    #[test]
+    fn test_craft_shapeless() {
+        let manager = manager();
+        let items = items();
+
+        let planks = CraftingGrid::from_rows(&[[None, None], [None, Some("oak_log")]]);
+        assert_eq!(
+            manager.craft(&planks, &items).map(|(_, result)| result),
+            Some(&result("oak_planks", 4))
+        );
+
+        let fire_charge = CraftingGrid::from_rows(&[
+            [Some("coal"), None, None],
+            [None, Some("blaze_powder"), None],
+            [None, None, Some("gunpowder")],
+        ]);
+        assert_eq!(
+            manager.craft(&fire_charge, &()).map(|(_, result)| result),
+            Some(&result("fire_charge", 3))
+        );
+
+        let extra = CraftingGrid::from_rows(&[
+            [Some("coal"), Some("coal"), None],
+            [None, Some("blaze_powder"), None],
+            [None, None, Some("gunpowder")],
+        ]);
+        assert_eq!(manager.craft(&extra, &()), None);
+        assert_eq!(manager.craft(&CraftingGrid::new(3, 3), &()), None);
+    }
+
+    #[test]
+    fn test_cook_stonecut_smith() {
+        let manager = manager();
+        let items = items();
+
+        let (_, smelting) = manager
+            .cook(CookingKind::Smelting, &loc("iron_ore"), &items)
+            .unwrap();
+        assert_eq!(smelting.result, result("iron_ingot", 1));
+        let (id, blasting) = manager
+            .cook(CookingKind::Blasting, &loc("iron_ore"), &items)
+            .unwrap();
+        assert_eq!(id, &loc("iron_ingot_from_blasting_iron_ore"));
+        assert_eq!(blasting.cooking_time(CookingKind::Blasting), 100);
+        assert!(
+            manager
+                .cook(CookingKind::Smoking, &loc("iron_ore"), &items)
+                .is_none()
+        );
+        assert!(
+            manager
+                .cook(CookingKind::CampfireCooking, &loc("potato"), &items)
+                .is_some()
+        );
+
+        let results = manager
+            .stonecut(&loc("stone"), &items)
+            .map(|(_, result)| result.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            results,
+            [result("stone_bricks", 1), result("stone_slab", 2)]
+        );
+
+        assert_eq!(
+            manager.smith(
+                &loc("netherite_upgrade_smithing_template"),
+                &loc("diamond_sword"),
+                &loc("netherite_ingot"),
+                &items
+            ),
+            Some((
+                &loc("netherite_sword_smithing"),
+                result("netherite_sword", 1)
+            ))
+        );
+        assert_eq!(
+            manager
+                .smith(
+                    &loc("coast_armor_trim_smithing_template"),
+                    &loc("iron_chestplate"),
+                    &loc("iron_ingot"),
+                    &items
+                )
+                .map(|(_, result)| result),
+            Some(result("iron_chestplate", 1))
+        );
+        assert_eq!(
+            manager.smith(
+                &loc("coast_armor_trim_smithing_template"),
+                &loc("diamond_sword"),
+                &loc("iron_ingot"),
+                &items
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_invalid_recipes() {
+        let mut manager = RecipeManager::new();
+        let mut insert =
+            |json: &str| manager.insert(loc("test"), serde_json::from_str(json).unwrap());
+
+        assert_eq!(
+            insert(
+                r###"{"type": "minecraft:crafting_shaped", "key": {"#": "minecraft:stick"},
+                    "pattern": ["#X"], "result": {"id": "minecraft:bow"}}"###
+            ),
+            Err(RecipeError::UndefinedKey {
+                recipe: loc("test"),
+                key: 'X'
+            })
+        );
+        assert_eq!(
+            insert(
+                r###"{"type": "minecraft:crafting_shaped", "key": {"#": "minecraft:stick", "X": "minecraft:string"},
+                    "pattern": ["##"], "result": {"id": "minecraft:bow"}}"###
+            ),
+            Err(RecipeError::UnusedKey {
+                recipe: loc("test"),
+                key: 'X'
+            })
+        );
+        assert_eq!(
+            insert(
+                r###"{"type": "minecraft:crafting_shaped", "key": {"#": "minecraft:stick"},
+                    "pattern": ["##", "#"], "result": {"id": "minecraft:bow"}}"###
+            ),
+            Err(RecipeError::InvalidPattern(loc("test")))
+        );
+        assert_eq!(
+            insert(
+                r#"{"type": "minecraft:crafting_shapeless", "ingredients": [],
+                    "result": {"id": "minecraft:bow"}}"#
+            ),
+            Err(RecipeError::InvalidIngredientCount(loc("test")))
+        );
+    }
+}
@@ -0,0 +1,130 @@
+use alloc::{collections::BTreeMap, string::String, vec::Vec};
+use mcre_core::{ItemStack, ResourceLocation};
+use serde::{Deserialize, Serialize};
+
+use crate::Ingredient;
+
+/// A recipe as found in `data/<namespace>/recipe/*.json`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum Recipe {
+    #[serde(rename = "minecraft:crafting_shaped", alias = "crafting_shaped")]
+    Shaped(ShapedRecipe),
+    #[serde(rename = "minecraft:crafting_shapeless", alias = "crafting_shapeless")]
+    Shapeless(ShapelessRecipe),
+    #[serde(rename = "minecraft:smelting", alias = "smelting")]
+    Smelting(CookingRecipe),
+    #[serde(rename = "minecraft:blasting", alias = "blasting")]
+    Blasting(CookingRecipe),
+    #[serde(rename = "minecraft:smoking", alias = "smoking")]
+    Smoking(CookingRecipe),
+    #[serde(rename = "minecraft:campfire_cooking", alias = "campfire_cooking")]
+    CampfireCooking(CookingRecipe),
+    #[serde(rename = "minecraft:stonecutting", alias = "stonecutting")]
+    Stonecutting(StonecuttingRecipe),
+    #[serde(rename = "minecraft:smithing_transform", alias = "smithing_transform")]
+    SmithingTransform(SmithingTransformRecipe),
+    #[serde(rename = "minecraft:smithing_trim", alias = "smithing_trim")]
+    SmithingTrim(SmithingTrimRecipe),
+    /// Recipes implemented in code, like dyeing armor or cloning books.
+    #[serde(other)]
+    Special,
+}
+
+/// The appliance a [`CookingRecipe`] is made in.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum CookingKind {
+    Smelting,
+    Blasting,
+    Smoking,
+    CampfireCooking,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ShapedRecipe {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    pub key: BTreeMap<char, Ingredient>,
+    pub pattern: Vec<String>,
+    pub result: ItemStack,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ShapelessRecipe {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    pub ingredients: Vec<Ingredient>,
+    pub result: ItemStack,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CookingRecipe {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    pub ingredient: Ingredient,
+    pub result: ItemStack,
+    #[serde(default)]
+    pub experience: f32,
+    /// Cooking time in ticks, defaulting to the one of the appliance.
+    #[serde(
+        default,
+        rename = "cookingtime",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub cooking_time: Option<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StonecuttingRecipe {
+    pub ingredient: Ingredient,
+    pub result: ItemStack,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SmithingTransformRecipe {
+    pub template: Ingredient,
+    pub base: Ingredient,
+    pub addition: Ingredient,
+    pub result: ItemStack,
+}
+
+/// Applies an armor trim, the result being the base item itself.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SmithingTrimRecipe {
+    pub template: Ingredient,
+    pub base: Ingredient,
+    pub addition: Ingredient,
+    /// The trim pattern, implied by the template before 1.21.5.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<ResourceLocation>,
+}
+
+impl Recipe {
+    pub fn cooking(&self) -> Option<(CookingKind, &CookingRecipe)> {
+        match self {
+            Self::Smelting(recipe) => Some((CookingKind::Smelting, recipe)),
+            Self::Blasting(recipe) => Some((CookingKind::Blasting, recipe)),
+            Self::Smoking(recipe) => Some((CookingKind::Smoking, recipe)),
+            Self::CampfireCooking(recipe) => Some((CookingKind::CampfireCooking, recipe)),
+            _ => None,
+        }
+    }
+}
+
+impl CookingKind {
+    /// Cooking time of recipes that don't specify one, in ticks.
+    pub const fn default_cooking_time(self) -> u32 {
+        match self {
+            Self::Smelting => 200,
+            Self::Blasting | Self::Smoking => 100,
+            Self::CampfireCooking => 600,
+        }
+    }
+}
+
+impl CookingRecipe {
+    pub fn cooking_time(&self, kind: CookingKind) -> u32 {
+        self.cooking_time
+            .unwrap_or_else(|| kind.default_cooking_time())
+    }
+}
@@ -0,0 +1,245 @@
+use mcre_core::{Registry, RegistryError, ResourceLocation};
+use serde::{Deserialize, Serialize};
+
+/// Temperature below which precipitation falls as snow.
+const SNOW_TEMPERATURE: f32 = 0.15;
+
+/// A biome definition, as found in `worldgen/biome/*.json`.
+///
+/// Only the climate and visual parts are modelled, features, carvers and spawns are not.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Biome {
+    pub has_precipitation: bool,
+    pub temperature: f32,
+    #[serde(default)]
+    pub temperature_modifier: TemperatureModifier,
+    pub downfall: f32,
+    pub effects: BiomeEffects,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BiomeEffects {
+    pub fog_color: u32,
+    pub water_color: u32,
+    pub water_fog_color: u32,
+    pub sky_color: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub foliage_color: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub grass_color: Option<u32>,
+    #[serde(default)]
+    pub grass_color_modifier: GrassColorModifier,
+}
+
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TemperatureModifier {
+    #[default]
+    None,
+    Frozen,
+}
+
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GrassColorModifier {
+    #[default]
+    None,
+    DarkForest,
+    Swamp,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Precipitation {
+    None,
+    Rain,
+    Snow,
+}
+
+impl Biome {
+    pub fn precipitation(&self) -> Precipitation {
+        if !self.has_precipitation {
+            Precipitation::None
+        } else if self.temperature < SNOW_TEMPERATURE {
+            Precipitation::Snow
+        } else {
+            Precipitation::Rain
+        }
+    }
+
+    /// Index into the 256x256 `grass.png` / `foliage.png` colormaps.
+    pub fn colormap_index(&self) -> usize {
+        let temperature = self.temperature.clamp(0.0, 1.0);
+        let downfall = self.downfall.clamp(0.0, 1.0) * temperature;
+
+        let x = ((1.0 - temperature) * 255.0) as usize;
+        let y = ((1.0 - downfall) * 255.0) as usize;
+        (y << 8) | x
+    }
+
+    /// The grass tint, looking the base color up in `colormap` unless the biome overrides it.
+    ///
+    /// Swamps use their warm color everywhere, as the per-position noise vanilla blends in is not
+    /// sampled here.
+    pub fn grass_color(&self, colormap: &[u32]) -> u32 {
+        let color = self
+            .effects
+            .grass_color
+            .unwrap_or_else(|| colormap[self.colormap_index()]);
+
+        match self.effects.grass_color_modifier {
+            GrassColorModifier::None => color,
+            GrassColorModifier::DarkForest => ((color & 0xFEFEFE) + 0x28340A) >> 1,
+            GrassColorModifier::Swamp => 0x6A7039,
+        }
+    }
+
+    pub fn foliage_color(&self, colormap: &[u32]) -> u32 {
+        self.effects
+            .foliage_color
+            .unwrap_or_else(|| colormap[self.colormap_index()])
+    }
+}
+
+fn biome(has_precipitation: bool, temperature: f32, downfall: f32, effects: BiomeEffects) -> Biome {
+    Biome {
+        has_precipitation,
+        temperature,
+        temperature_modifier: TemperatureModifier::None,
+        downfall,
+        effects,
+    }
+}
+
+fn nether_effects(fog_color: u32) -> BiomeEffects {
+    BiomeEffects {
+        fog_color,
+        water_color: 0x3F76E4,
+        water_fog_color: 0x050533,
+        sky_color: 0x6EB1FF,
+        foliage_color: None,
+        grass_color: None,
+        grass_color_modifier: GrassColorModifier::None,
+    }
+}
+
+/// The biomes needed by the built-in biome sources, registered under their vanilla keys.
+///
+/// The full vanilla set is expected to come from data packs.
+pub fn builtin_biomes() -> Result<Registry<Biome>, RegistryError> {
+    let mut registry = Registry::new(ResourceLocation::minecraft("worldgen/biome"));
+
+    let biomes = [
+        (
+            "the_void",
+            biome(
+                false,
+                0.5,
+                0.5,
+                BiomeEffects {
+                    fog_color: 0xC0D8FF,
+                    water_color: 0x3F76E4,
+                    water_fog_color: 0x050533,
+                    sky_color: 0x7BA4FF,
+                    foliage_color: None,
+                    grass_color: None,
+                    grass_color_modifier: GrassColorModifier::None,
+                },
+            ),
+        ),
+        (
+            "plains",
+            biome(
+                true,
+                0.8,
+                0.4,
+                BiomeEffects {
+                    fog_color: 0xC0D8FF,
+                    water_color: 0x3F76E4,
+                    water_fog_color: 0x050533,
+                    sky_color: 0x78A7FF,
+                    foliage_color: None,
+                    grass_color: None,
+                    grass_color_modifier: GrassColorModifier::None,
+                },
+            ),
+        ),
+        (
+            "nether_wastes",
+            biome(false, 2.0, 0.0, nether_effects(0x330808)),
+        ),
+        (
+            "soul_sand_valley",
+            biome(false, 2.0, 0.0, nether_effects(0x1B4745)),
+        ),
+        (
+            "crimson_forest",
+            biome(false, 2.0, 0.0, nether_effects(0x330303)),
+        ),
+        (
+            "warped_forest",
+            biome(false, 2.0, 0.0, nether_effects(0x1A051A)),
+        ),
+        (
+            "basalt_deltas",
+            biome(false, 2.0, 0.0, nether_effects(0x685F70)),
+        ),
+    ];
+
+    for (name, biome) in biomes {
+        registry.register(ResourceLocation::minecraft(name), biome)?;
+    }
+
+    Ok(registry)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use mcre_core::ResourceLocation;
+
+    use crate::{Biome, GrassColorModifier, Precipitation, builtin_biomes};
+
+    #[test]
+    fn test_deserialize_biome() {
+        let biome: Biome = serde_json::from_str(
+            r#"{
+                "has_precipitation": true,
+                "temperature": 0.7,
+                "downfall": 0.8,
+                "effects": {
+                    "fog_color": 12638463,
+                    "water_color": 4159204,
+                    "water_fog_color": 329011,
+                    "sky_color": 7972607,
+                    "grass_color_modifier": "dark_forest"
+                }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(biome.precipitation(), Precipitation::Rain);
+        assert_eq!(
+            biome.effects.grass_color_modifier,
+            GrassColorModifier::DarkForest
+        );
+        assert_eq!(biome.colormap_index(), (112 << 8) | 76);
+
+        let colormap = vec![0x204060; 256 * 256];
+        assert_eq!(
+            biome.grass_color(&colormap),
+            ((0x204060 & 0xFEFEFE) + 0x28340A) >> 1
+        );
+    }
+
+    #[test]
+    fn test_builtin_biomes() {
+        let registry = builtin_biomes().unwrap();
+        let wastes = registry
+            .get_by_key(&ResourceLocation::minecraft("nether_wastes"))
+            .unwrap();
+
+        assert_eq!(wastes.precipitation(), Precipitation::None);
+        assert_eq!(wastes.effects.fog_color, 0x330808);
+    }
+}
@@ -0,0 +1,307 @@
+use alloc::vec::Vec;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+const QUANTIZATION_FACTOR: f32 = 10000.0;
+
+pub fn quantize(value: f32) -> i64 {
+    (value * QUANTIZATION_FACTOR) as i64
+}
+
+pub fn unquantize(value: i64) -> f32 {
+    value as f32 / QUANTIZATION_FACTOR
+}
+
+/// A quantized range of a single climate dimension.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Parameter {
+    pub min: i64,
+    pub max: i64,
+}
+
+/// The climate sampled at a position, in quantized units.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub struct TargetPoint {
+    pub temperature: i64,
+    pub humidity: i64,
+    pub continentalness: i64,
+    pub erosion: i64,
+    pub depth: i64,
+    pub weirdness: i64,
+}
+
+/// The climate a biome is placed at, as found in the `biomes` list of a multi noise biome source.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ParameterPoint {
+    pub temperature: Parameter,
+    pub humidity: Parameter,
+    pub continentalness: Parameter,
+    pub erosion: Parameter,
+    pub depth: Parameter,
+    pub weirdness: Parameter,
+    #[serde(with = "quantized")]
+    pub offset: i64,
+}
+
+/// Samples the climate at quart (4x4x4 block) positions.
+pub trait ClimateSampler {
+    fn sample(&self, quart_x: i32, quart_y: i32, quart_z: i32) -> TargetPoint;
+}
+
+/// The biomes of a multi noise source together with the climate each of them prefers.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParameterList<T> {
+    values: Vec<(ParameterPoint, T)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParameterListEntry<T> {
+    pub biome: T,
+    pub parameters: ParameterPoint,
+}
+
+impl Parameter {
+    pub fn point(value: f32) -> Self {
+        Self::span(value, value)
+    }
+
+    pub fn span(min: f32, max: f32) -> Self {
+        Self {
+            min: quantize(min),
+            max: quantize(max),
+        }
+    }
+
+    /// Distance of `target` to this range, zero if it lies inside.
+    pub fn distance(&self, target: i64) -> i64 {
+        let above = target - self.max;
+        let below = self.min - target;
+        if above > 0 { above } else { below.max(0) }
+    }
+}
+
+impl TargetPoint {
+    pub fn new(
+        temperature: f32,
+        humidity: f32,
+        continentalness: f32,
+        erosion: f32,
+        depth: f32,
+        weirdness: f32,
+    ) -> Self {
+        Self {
+            temperature: quantize(temperature),
+            humidity: quantize(humidity),
+            continentalness: quantize(continentalness),
+            erosion: quantize(erosion),
+            depth: quantize(depth),
+            weirdness: quantize(weirdness),
+        }
+    }
+}
+
+impl ParameterPoint {
+    pub fn new(
+        temperature: Parameter,
+        humidity: Parameter,
+        continentalness: Parameter,
+        erosion: Parameter,
+        depth: Parameter,
+        weirdness: Parameter,
+        offset: f32,
+    ) -> Self {
+        Self {
+            temperature,
+            humidity,
+            continentalness,
+            erosion,
+            depth,
+            weirdness,
+            offset: quantize(offset),
+        }
+    }
+
+    /// Squared distance to `target`, lower is a better fit.
+    pub fn fitness(&self, target: &TargetPoint) -> i64 {
+        let square = |value: i64| value * value;
+
+        square(self.temperature.distance(target.temperature))
+            + square(self.humidity.distance(target.humidity))
+            + square(self.continentalness.distance(target.continentalness))
+            + square(self.erosion.distance(target.erosion))
+            + square(self.depth.distance(target.depth))
+            + square(self.weirdness.distance(target.weirdness))
+            + square(self.offset)
+    }
+}
+
+impl<T> ParameterList<T> {
+    pub fn new(values: Vec<(ParameterPoint, T)>) -> Self {
+        Self { values }
+    }
+
+    pub fn values(&self) -> &[(ParameterPoint, T)] {
+        &self.values
+    }
+
+    /// The value whose parameters fit `target` best, the first one winning ties.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the list is empty.
+    pub fn find_value(&self, target: &TargetPoint) -> &T {
+        let (_, value) = self
+            .values
+            .iter()
+            .min_by_key(|(point, _)| point.fitness(target))
+            .expect("parameter list must not be empty");
+        value
+    }
+
+    pub fn map<U>(self, mut f: impl FnMut(T) -> U) -> ParameterList<U> {
+        ParameterList {
+            values: self
+                .values
+                .into_iter()
+                .map(|(point, value)| (point, f(value)))
+                .collect(),
+        }
+    }
+
+    /// Like [`ParameterList::map`], failing on the first value `f` cannot convert.
+    pub fn try_map<U, E>(
+        self,
+        mut f: impl FnMut(T) -> Result<U, E>,
+    ) -> Result<ParameterList<U>, E> {
+        let values = self
+            .values
+            .into_iter()
+            .map(|(point, value)| Ok((point, f(value)?)))
+            .collect::<Result<_, E>>()?;
+        Ok(ParameterList { values })
+    }
+}
+
+impl<T> FromIterator<ParameterListEntry<T>> for ParameterList<T> {
+    fn from_iter<I: IntoIterator<Item = ParameterListEntry<T>>>(iter: I) -> Self {
+        Self::new(
+            iter.into_iter()
+                .map(|entry| (entry.parameters, entry.biome))
+                .collect(),
+        )
+    }
+}
+
+impl Serialize for Parameter {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if self.min == self.max {
+            unquantize(self.min).serialize(serializer)
+        } else {
+            [unquantize(self.min), unquantize(self.max)].serialize(serializer)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Parameter {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Helper {
+            Point(f32),
+            Span([f32; 2]),
+        }
+
+        Ok(match Helper::deserialize(deserializer)? {
+            Helper::Point(value) => Self::point(value),
+            Helper::Span([min, max]) => Self::span(min, max),
+        })
+    }
+}
+
+mod quantized {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::{quantize, unquantize};
+
+    pub fn serialize<S: Serializer>(value: &i64, serializer: S) -> Result<S::Ok, S::Error> {
+        unquantize(*value).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<i64, D::Error> {
+        f32::deserialize(deserializer).map(quantize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use crate::{Parameter, ParameterList, ParameterListEntry, ParameterPoint, TargetPoint};
+
+    #[test]
+    fn test_parameter_distance() {
+        let parameter = Parameter::span(-0.5, 0.5);
+        assert_eq!(parameter.distance(0), 0);
+        assert_eq!(parameter.distance(6000), 1000);
+        assert_eq!(parameter.distance(-7000), 2000);
+    }
+
+    #[test]
+    fn test_find_value() {
+        let point = |temperature: f32, offset: f32| {
+            let zero = Parameter::point(0.0);
+            ParameterPoint::new(
+                Parameter::point(temperature),
+                zero,
+                zero,
+                zero,
+                zero,
+                zero,
+                offset,
+            )
+        };
+
+        let list = ParameterList::new(vec![
+            (point(0.0, 0.0), "a"),
+            (point(1.0, 0.0), "b"),
+            (point(0.9, 0.5), "c"),
+        ]);
+
+        assert_eq!(
+            *list.find_value(&TargetPoint::new(0.2, 0.0, 0.0, 0.0, 0.0, 0.0)),
+            "a"
+        );
+        assert_eq!(
+            *list.find_value(&TargetPoint::new(0.9, 0.0, 0.0, 0.0, 0.0, 0.0)),
+            "b"
+        );
+    }
+
+    #[test]
+    fn test_deserialize_entry() {
+        let entry: ParameterListEntry<&str> = serde_json::from_str(
+            r#"{
+                "biome": "minecraft:plains",
+                "parameters": {
+                    "temperature": [-0.45, -0.15],
+                    "humidity": -1.0,
+                    "continentalness": [-0.11, 0.03],
+                    "erosion": [0.05, 0.45],
+                    "depth": 0.0,
+                    "weirdness": [-1.0, -0.9333],
+                    "offset": 0.0
+                }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(entry.biome, "minecraft:plains");
+        assert_eq!(entry.parameters.temperature, Parameter::span(-0.45, -0.15));
+        assert_eq!(entry.parameters.humidity, Parameter::point(-1.0));
+    }
+}
@@ -0,0 +1,87 @@
+use serde::{Deserialize, Serialize};
+
+/// The vertical extent and lighting of a dimension, as found in `dimension_type/*.json`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DimensionType {
+    /// The lowest y blocks can be placed at, a multiple of 16.
+    pub min_y: i32,
+    /// The number of block layers, a multiple of 16.
+    pub height: u32,
+    /// The height portals and chorus fruit can teleport within.
+    pub logical_height: u32,
+    #[serde(default = "default_coordinate_scale")]
+    pub coordinate_scale: f64,
+    #[serde(default)]
+    pub has_skylight: bool,
+    #[serde(default)]
+    pub has_ceiling: bool,
+    #[serde(default)]
+    pub ambient_light: f32,
+}
+
+fn default_coordinate_scale() -> f64 {
+    1.0
+}
+
+impl DimensionType {
+    /// The vanilla overworld, from y -64 to 319.
+    pub fn overworld() -> Self {
+        Self {
+            min_y: -64,
+            height: 384,
+            logical_height: 384,
+            coordinate_scale: 1.0,
+            has_skylight: true,
+            has_ceiling: false,
+            ambient_light: 0.0,
+        }
+    }
+
+    /// One above the highest y blocks can be placed at.
+    pub fn max_y(&self) -> i32 {
+        self.min_y + self.height as i32
+    }
+
+    pub fn contains_y(&self, y: i32) -> bool {
+        (self.min_y..self.max_y()).contains(&y)
+    }
+
+    /// The number of 16 block tall sections in a chunk column.
+    pub fn section_count(&self) -> u32 {
+        self.height / 16
+    }
+
+    /// The section index of the lowest section, i.e. `min_y / 16`.
+    pub fn min_section(&self) -> i32 {
+        self.min_y >> 4
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::DimensionType;
+
+    #[test]
+    fn test_deserialize() {
+        let dimension: DimensionType = serde_json::from_str(
+            r#"{
+                "ambient_light": 0.0,
+                "coordinate_scale": 1.0,
+                "has_ceiling": false,
+                "has_skylight": true,
+                "height": 384,
+                "logical_height": 384,
+                "min_y": -64,
+                "monster_spawn_light_level": {"type": "minecraft:uniform", "max_inclusive": 7, "min_inclusive": 0}
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(dimension, DimensionType::overworld());
+        assert_eq!(dimension.max_y(), 320);
+        assert_eq!(dimension.section_count(), 24);
+        assert_eq!(dimension.min_section(), -4);
+        assert!(dimension.contains_y(-64) && dimension.contains_y(319));
+        assert!(!dimension.contains_y(320) && !dimension.contains_y(-65));
+    }
+}
@@ -0,0 +1,21 @@
+#![cfg_attr(not(test), no_std)]
+
+extern crate alloc;
+
+mod biome;
+mod climate;
+mod dimension;
+pub mod noise;
+mod sampler;
+mod section;
+mod source;
+
+pub use biome::*;
+pub use climate::{
+    ClimateSampler, Parameter, ParameterList, ParameterListEntry, ParameterPoint, TargetPoint,
+    quantize, unquantize,
+};
+pub use dimension::DimensionType;
+pub use sampler::NoiseSampler;
+pub use section::SectionBiomes;
+pub use source::{MultiNoiseBiomeSource, nether_preset};
@@ -0,0 +1,110 @@
+use mcre_core::RandomSource;
+
+use crate::noise::{floor, lerp3, smoothstep};
+
+const GRADIENT: [[f64; 3]; 16] = [
+    [1.0, 1.0, 0.0],
+    [-1.0, 1.0, 0.0],
+    [1.0, -1.0, 0.0],
+    [-1.0, -1.0, 0.0],
+    [1.0, 0.0, 1.0],
+    [-1.0, 0.0, 1.0],
+    [1.0, 0.0, -1.0],
+    [-1.0, 0.0, -1.0],
+    [0.0, 1.0, 1.0],
+    [0.0, -1.0, 1.0],
+    [0.0, 1.0, -1.0],
+    [0.0, -1.0, -1.0],
+    [1.0, 1.0, 0.0],
+    [0.0, -1.0, 1.0],
+    [-1.0, 1.0, 0.0],
+    [0.0, -1.0, -1.0],
+];
+
+/// A single octave of Perlin noise, vanilla's `ImprovedNoise`.
+#[derive(Debug, Clone)]
+pub struct ImprovedNoise {
+    permutation: [u8; 256],
+    pub xo: f64,
+    pub yo: f64,
+    pub zo: f64,
+}
+
+impl ImprovedNoise {
+    pub fn new(random: &mut impl RandomSource) -> Self {
+        let xo = random.next_double() * 256.0;
+        let yo = random.next_double() * 256.0;
+        let zo = random.next_double() * 256.0;
+
+        let mut permutation = [0u8; 256];
+        for (i, value) in permutation.iter_mut().enumerate() {
+            *value = i as u8;
+        }
+
+        for i in 0..256 {
+            let j = random.next_int_bounded(256 - i as i32) as usize;
+            permutation.swap(i, i + j);
+        }
+
+        Self {
+            permutation,
+            xo,
+            yo,
+            zo,
+        }
+    }
+
+    /// Advances `random` exactly as far as [`ImprovedNoise::new`] would in the common case.
+    pub(crate) fn skip(random: &mut impl RandomSource) {
+        for _ in 0..262 {
+            random.next_int();
+        }
+    }
+
+    pub fn noise(&self, x: f64, y: f64, z: f64) -> f64 {
+        let x = x + self.xo;
+        let y = y + self.yo;
+        let z = z + self.zo;
+
+        let (grid_x, grid_y, grid_z) = (floor(x), floor(y), floor(z));
+        let delta_x = x - grid_x as f64;
+        let delta_y = y - grid_y as f64;
+        let delta_z = z - grid_z as f64;
+
+        self.sample_and_lerp(grid_x, grid_y, grid_z, delta_x, delta_y, delta_z)
+    }
+
+    fn p(&self, index: i32) -> i32 {
+        self.permutation[(index & 0xFF) as usize] as i32
+    }
+
+    fn sample_and_lerp(&self, x: i32, y: i32, z: i32, dx: f64, dy: f64, dz: f64) -> f64 {
+        let i = self.p(x);
+        let j = self.p(x + 1);
+        let k = self.p(i + y);
+        let l = self.p(i + y + 1);
+        let m = self.p(j + y);
+        let n = self.p(j + y + 1);
+
+        let d = grad_dot(self.p(k + z), dx, dy, dz);
+        let e = grad_dot(self.p(m + z), dx - 1.0, dy, dz);
+        let f = grad_dot(self.p(l + z), dx, dy - 1.0, dz);
+        let g = grad_dot(self.p(n + z), dx - 1.0, dy - 1.0, dz);
+        let h = grad_dot(self.p(k + z + 1), dx, dy, dz - 1.0);
+        let o = grad_dot(self.p(m + z + 1), dx - 1.0, dy, dz - 1.0);
+        let q = grad_dot(self.p(l + z + 1), dx, dy - 1.0, dz - 1.0);
+        let r = grad_dot(self.p(n + z + 1), dx - 1.0, dy - 1.0, dz - 1.0);
+
+        lerp3(
+            smoothstep(dx),
+            smoothstep(dy),
+            smoothstep(dz),
+            [d, e, f, g, h, o, q, r],
+        )
+    }
+}
+
+fn grad_dot(hash: i32, x: f64, y: f64, z: f64) -> f64 {
+    let [gx, gy, gz] = GRADIENT[(hash & 15) as usize];
+    gx * x + gy * y + gz * z
+}
@@ -0,0 +1,155 @@
+mod improved;
+mod perlin;
+
+pub use improved::ImprovedNoise;
+pub use perlin::PerlinNoise;
+
+use alloc::vec::Vec;
+use mcre_core::{RandomSource, XoroshiroRandom};
+use serde::{Deserialize, Serialize};
+
+const INPUT_FACTOR: f64 = 1.018_126_888_217_522_7;
+
+/// Octave configuration of a [`NormalNoise`], as found in `worldgen/noise/*.json`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NoiseParameters {
+    #[serde(rename = "firstOctave")]
+    pub first_octave: i32,
+    pub amplitudes: Vec<f64>,
+}
+
+/// Two offset [`PerlinNoise`]s added together to approximate a normal distribution.
+#[derive(Debug, Clone)]
+pub struct NormalNoise {
+    first: PerlinNoise,
+    second: PerlinNoise,
+    value_factor: f64,
+    max_value: f64,
+}
+
+impl NoiseParameters {
+    pub fn new(first_octave: i32, amplitudes: &[f64]) -> Self {
+        Self {
+            first_octave,
+            amplitudes: amplitudes.into(),
+        }
+    }
+}
+
+impl NormalNoise {
+    pub fn new(random: &mut XoroshiroRandom, parameters: &NoiseParameters) -> Self {
+        let first = PerlinNoise::new(random, parameters.first_octave, &parameters.amplitudes);
+        let second = PerlinNoise::new(random, parameters.first_octave, &parameters.amplitudes);
+        Self::from_octaves(first, second, &parameters.amplitudes)
+    }
+
+    /// The legacy flavour still used by the nether biome source, which keeps a legacy random source.
+    pub fn legacy_nether_biome(
+        random: &mut impl RandomSource,
+        parameters: &NoiseParameters,
+    ) -> Self {
+        let first = PerlinNoise::legacy(random, parameters.first_octave, &parameters.amplitudes);
+        let second = PerlinNoise::legacy(random, parameters.first_octave, &parameters.amplitudes);
+        Self::from_octaves(first, second, &parameters.amplitudes)
+    }
+
+    fn from_octaves(first: PerlinNoise, second: PerlinNoise, amplitudes: &[f64]) -> Self {
+        let lowest = amplitudes.iter().position(|&a| a != 0.0);
+        let highest = amplitudes.iter().rposition(|&a| a != 0.0);
+        let span = match (lowest, highest) {
+            (Some(lowest), Some(highest)) => highest - lowest,
+            _ => 0,
+        };
+
+        let expected_deviation = 0.1 * (1.0 + 1.0 / (span as f64 + 1.0));
+        let value_factor = (1.0 / 6.0) / expected_deviation;
+        let max_value = (first.max_value() + second.max_value()) * value_factor;
+
+        Self {
+            first,
+            second,
+            value_factor,
+            max_value,
+        }
+    }
+
+    pub fn get_value(&self, x: f64, y: f64, z: f64) -> f64 {
+        let first = self.first.get_value(x, y, z);
+        let second = self
+            .second
+            .get_value(x * INPUT_FACTOR, y * INPUT_FACTOR, z * INPUT_FACTOR);
+        (first + second) * self.value_factor
+    }
+
+    pub fn max_value(&self) -> f64 {
+        self.max_value
+    }
+}
+
+pub(crate) fn lfloor(value: f64) -> i64 {
+    let truncated = value as i64;
+    if value < truncated as f64 {
+        truncated - 1
+    } else {
+        truncated
+    }
+}
+
+pub(crate) fn floor(value: f64) -> i32 {
+    let truncated = value as i32;
+    if value < truncated as f64 {
+        truncated - 1
+    } else {
+        truncated
+    }
+}
+
+/// `2^exponent`, exact for every exponent a noise can reasonably use.
+pub(crate) fn pow2(exponent: i32) -> f64 {
+    f64::from_bits(((1023 + exponent) as u64) << 52)
+}
+
+pub(crate) fn smoothstep(x: f64) -> f64 {
+    x * x * x * (x * (x * 6.0 - 15.0) + 10.0)
+}
+
+fn lerp(delta: f64, start: f64, end: f64) -> f64 {
+    start + delta * (end - start)
+}
+
+pub(crate) fn lerp3(dx: f64, dy: f64, dz: f64, corners: [f64; 8]) -> f64 {
+    let [a, b, c, d, e, f, g, h] = corners;
+    let bottom = lerp(dy, lerp(dx, a, b), lerp(dx, c, d));
+    let top = lerp(dy, lerp(dx, e, f), lerp(dx, g, h));
+    lerp(dz, bottom, top)
+}
+
+#[cfg(test)]
+mod tests {
+    use mcre_core::{LegacyRandom, XoroshiroRandom};
+
+    use crate::noise::{NoiseParameters, NormalNoise, lfloor, pow2};
+
+    #[test]
+    fn test_helpers() {
+        assert_eq!(pow2(-7), 1.0 / 128.0);
+        assert_eq!(pow2(3), 8.0);
+        assert_eq!(lfloor(-0.5), -1);
+        assert_eq!(lfloor(2.0), 2);
+    }
+
+    #[test]
+    fn test_normal_noise_is_bounded() {
+        let parameters = NoiseParameters::new(-7, &[1.0, 2.0, 1.0]);
+        let modern = NormalNoise::new(&mut XoroshiroRandom::new(42), &parameters);
+        let legacy = NormalNoise::legacy_nether_biome(&mut LegacyRandom::new(42), &parameters);
+
+        for noise in [modern, legacy] {
+            let max = noise.max_value();
+            for i in 0..1000 {
+                let value = noise.get_value(i as f64 * 13.7, i as f64 * -3.1, i as f64 * 0.9);
+                assert!(value.abs() <= max, "{value} exceeds {max}");
+            }
+        }
+    }
+}
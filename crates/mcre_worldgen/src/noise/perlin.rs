@@ -0,0 +1,123 @@
+use alloc::{boxed::Box, format, vec::Vec};
+use mcre_core::{PositionalRandomFactory, RandomSource, XoroshiroRandom};
+
+use crate::noise::{ImprovedNoise, lfloor, pow2};
+
+const ROUND_OFF: f64 = 33_554_432.0;
+
+/// Several octaves of [`ImprovedNoise`] summed together, vanilla's `PerlinNoise`.
+#[derive(Debug, Clone)]
+pub struct PerlinNoise {
+    levels: Box<[Option<ImprovedNoise>]>,
+    amplitudes: Box<[f64]>,
+    lowest_freq_input_factor: f64,
+    lowest_freq_value_factor: f64,
+}
+
+impl PerlinNoise {
+    /// Creates the noise the way modern world generation does, seeding every octave by name.
+    pub fn new(random: &mut XoroshiroRandom, first_octave: i32, amplitudes: &[f64]) -> Self {
+        let factory = random.fork_positional();
+
+        let levels = amplitudes
+            .iter()
+            .enumerate()
+            .map(|(i, &amplitude)| {
+                (amplitude != 0.0).then(|| {
+                    let octave = first_octave + i as i32;
+                    ImprovedNoise::new(&mut factory.with_hash_of(&format!("octave_{octave}")))
+                })
+            })
+            .collect();
+
+        Self::from_levels(levels, first_octave, amplitudes)
+    }
+
+    /// Creates the noise the way legacy (pre 1.18) generators do, drawing octaves in sequence from
+    /// highest to lowest frequency.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any octave has a positive frequency exponent, which vanilla rejects as well.
+    pub fn legacy(random: &mut impl RandomSource, first_octave: i32, amplitudes: &[f64]) -> Self {
+        let zero_octave = -first_octave;
+        let count = amplitudes.len() as i32;
+        assert!(
+            zero_octave >= count - 1,
+            "positive octaves are not supported by legacy noise"
+        );
+
+        let mut levels: Vec<Option<ImprovedNoise>> = (0..count).map(|_| None).collect();
+
+        let noise = ImprovedNoise::new(random);
+        if zero_octave < count && amplitudes[zero_octave as usize] != 0.0 {
+            levels[zero_octave as usize] = Some(noise);
+        }
+
+        for octave in (0..zero_octave).rev() {
+            if octave < count && amplitudes[octave as usize] != 0.0 {
+                levels[octave as usize] = Some(ImprovedNoise::new(random));
+            } else {
+                ImprovedNoise::skip(random);
+            }
+        }
+
+        Self::from_levels(levels.into_boxed_slice(), first_octave, amplitudes)
+    }
+
+    fn from_levels(
+        levels: Box<[Option<ImprovedNoise>]>,
+        first_octave: i32,
+        amplitudes: &[f64],
+    ) -> Self {
+        let count = amplitudes.len() as i32;
+
+        Self {
+            levels,
+            amplitudes: amplitudes.into(),
+            lowest_freq_input_factor: pow2(first_octave),
+            lowest_freq_value_factor: pow2(count - 1) / (pow2(count) - 1.0),
+        }
+    }
+
+    pub fn get_value(&self, x: f64, y: f64, z: f64) -> f64 {
+        let mut value = 0.0;
+        let mut input_factor = self.lowest_freq_input_factor;
+        let mut value_factor = self.lowest_freq_value_factor;
+
+        for (level, amplitude) in self.levels.iter().zip(&self.amplitudes) {
+            if let Some(noise) = level {
+                let sample = noise.noise(
+                    wrap(x * input_factor),
+                    wrap(y * input_factor),
+                    wrap(z * input_factor),
+                );
+                value += amplitude * sample * value_factor;
+            }
+
+            input_factor *= 2.0;
+            value_factor /= 2.0;
+        }
+
+        value
+    }
+
+    pub fn max_value(&self) -> f64 {
+        let mut value = 0.0;
+        let mut value_factor = self.lowest_freq_value_factor;
+
+        for (level, amplitude) in self.levels.iter().zip(&self.amplitudes) {
+            if level.is_some() {
+                value += amplitude * 2.0 * value_factor;
+            }
+            value_factor /= 2.0;
+        }
+
+        value
+    }
+}
+
+/// Keeps coordinates in a range where `f64` still has sub-block precision.
+fn wrap(value: f64) -> f64 {
+    value - lfloor(value / ROUND_OFF + 0.5) as f64 * ROUND_OFF
+}
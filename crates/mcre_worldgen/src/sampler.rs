@@ -0,0 +1,126 @@
+use mcre_core::{LegacyRandom, PositionalRandomFactory, XoroshiroRandom};
+
+use crate::{
+    ClimateSampler, TargetPoint,
+    climate::quantize,
+    noise::{NoiseParameters, NormalNoise},
+};
+
+const XZ_SCALE: f64 = 0.25;
+
+/// Base of vanilla's overworld terrain offset, before its continentalness/erosion spline is added.
+const OVERWORLD_BASE_OFFSET: f64 = -0.503_75;
+
+/// Samples the climate from the noises of a dimension's noise router.
+#[derive(Debug, Clone)]
+pub struct NoiseSampler {
+    shift: Option<NormalNoise>,
+    temperature: NormalNoise,
+    humidity: NormalNoise,
+    terrain: Option<TerrainNoises>,
+}
+
+#[derive(Debug, Clone)]
+struct TerrainNoises {
+    continentalness: NormalNoise,
+    erosion: NormalNoise,
+    weirdness: NormalNoise,
+}
+
+impl NoiseSampler {
+    /// The overworld climate for `seed`.
+    ///
+    /// Depth only follows the vertical gradient around the base terrain offset, the terrain
+    /// shaping splines that move it with continentalness and erosion are not implemented yet.
+    pub fn overworld(seed: i64) -> Self {
+        let factory = XoroshiroRandom::new(seed).fork_positional();
+        let noise = |name: &str, first_octave: i32, amplitudes: &[f64]| {
+            let mut random = factory.with_hash_of(&alloc::format!("minecraft:{name}"));
+            NormalNoise::new(&mut random, &NoiseParameters::new(first_octave, amplitudes))
+        };
+
+        Self {
+            shift: Some(noise("offset", -3, &[1.0, 1.0, 1.0, 0.0])),
+            temperature: noise("temperature", -10, &[1.5, 0.0, 1.0, 0.0, 0.0, 0.0]),
+            humidity: noise("vegetation", -8, &[1.0, 1.0, 0.0, 0.0, 0.0, 0.0]),
+            terrain: Some(TerrainNoises {
+                continentalness: noise(
+                    "continentalness",
+                    -9,
+                    &[1.0, 1.0, 2.0, 2.0, 2.0, 1.0, 1.0, 1.0, 1.0],
+                ),
+                erosion: noise("erosion", -9, &[1.0, 1.0, 0.0, 1.0, 1.0]),
+                weirdness: noise("ridge", -7, &[1.0, 2.0, 1.0, 0.0, 0.0, 0.0]),
+            }),
+        }
+    }
+
+    /// The nether climate for `seed`, which still uses the legacy random source and only varies
+    /// temperature and humidity.
+    pub fn nether(seed: i64) -> Self {
+        let parameters = NoiseParameters::new(-7, &[1.0, 1.0]);
+
+        Self {
+            // the legacy offset noise has no octaves, so coordinates are never shifted
+            shift: None,
+            temperature: NormalNoise::legacy_nether_biome(
+                &mut LegacyRandom::new(seed),
+                &parameters,
+            ),
+            humidity: NormalNoise::legacy_nether_biome(
+                &mut LegacyRandom::new(seed.wrapping_add(1)),
+                &parameters,
+            ),
+            terrain: None,
+        }
+    }
+
+    fn shifted(&self, noise: &NormalNoise, x: i32, z: i32, shift: (f64, f64)) -> f64 {
+        let (shift_x, shift_z) = shift;
+        noise.get_value(
+            x as f64 * XZ_SCALE + shift_x,
+            0.0,
+            z as f64 * XZ_SCALE + shift_z,
+        )
+    }
+}
+
+impl ClimateSampler for NoiseSampler {
+    fn sample(&self, quart_x: i32, quart_y: i32, quart_z: i32) -> TargetPoint {
+        let (x, y, z) = (quart_x << 2, quart_y << 2, quart_z << 2);
+
+        let shift = match &self.shift {
+            Some(noise) => {
+                let (x, z) = (x as f64 * 0.25, z as f64 * 0.25);
+                (
+                    noise.get_value(x, 0.0, z) * 4.0,
+                    noise.get_value(z, x, 0.0) * 4.0,
+                )
+            }
+            None => (0.0, 0.0),
+        };
+
+        let temperature = self.shifted(&self.temperature, x, z, shift);
+        let humidity = self.shifted(&self.humidity, x, z, shift);
+
+        let Some(terrain) = &self.terrain else {
+            return TargetPoint {
+                temperature: quantize(temperature as f32),
+                humidity: quantize(humidity as f32),
+                ..TargetPoint::default()
+            };
+        };
+
+        let gradient = 1.5 - (y as f64 + 64.0) / 384.0 * 3.0;
+        let depth = gradient.clamp(-1.5, 1.5) + OVERWORLD_BASE_OFFSET;
+
+        TargetPoint {
+            temperature: quantize(temperature as f32),
+            humidity: quantize(humidity as f32),
+            continentalness: quantize(self.shifted(&terrain.continentalness, x, z, shift) as f32),
+            erosion: quantize(self.shifted(&terrain.erosion, x, z, shift) as f32),
+            depth: quantize(depth as f32),
+            weirdness: quantize(self.shifted(&terrain.weirdness, x, z, shift) as f32),
+        }
+    }
+}
@@ -0,0 +1,50 @@
+use mcre_core::BlockPos;
+
+/// Biome ids of a 16x16x16 chunk section at quart (4x4x4 block) resolution.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SectionBiomes {
+    biomes: [u32; 64],
+}
+
+impl SectionBiomes {
+    /// A section filled with a single biome.
+    pub fn uniform(biome: u32) -> Self {
+        Self {
+            biomes: [biome; 64],
+        }
+    }
+
+    /// Same `y, z, x` ordering as vanilla's biome containers.
+    fn index(x: usize, y: usize, z: usize) -> usize {
+        debug_assert!(x < 4 && y < 4 && z < 4);
+        (y << 4) | (z << 2) | x
+    }
+
+    pub fn get(&self, x: usize, y: usize, z: usize) -> u32 {
+        self.biomes[Self::index(x, y, z)]
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, z: usize, biome: u32) {
+        self.biomes[Self::index(x, y, z)] = biome;
+    }
+
+    /// The biome covering the block at `pos`, which may be in any section.
+    pub fn get_at_block(&self, pos: BlockPos) -> u32 {
+        let quart = |value: i32| ((value & 15) >> 2) as usize;
+        self.get(quart(pos.x), quart(pos.y), quart(pos.z))
+    }
+
+    pub fn is_uniform(&self) -> bool {
+        self.biomes.iter().all(|&biome| biome == self.biomes[0])
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = u32> + '_ {
+        self.biomes.iter().copied()
+    }
+}
+
+impl Default for SectionBiomes {
+    fn default() -> Self {
+        Self::uniform(0)
+    }
+}
@@ -0,0 +1,309 @@
+//! Biome sources picking biomes from the sampled climate.
+//!
+//! Only the nether preset is built in so far. Vanilla's overworld parameter list is deferred until
+//! overworld depth follows the terrain shaping splines, see
+//! [`NoiseSampler::overworld`](crate::NoiseSampler::overworld).
+
+use alloc::vec;
+use mcre_core::{BlockPos, ResourceLocation};
+
+use crate::{ClimateSampler, Parameter, ParameterList, ParameterPoint, SectionBiomes};
+
+/// Picks biomes by finding the closest climate parameter point to the sampled climate.
+#[derive(Debug, Clone)]
+pub struct MultiNoiseBiomeSource<T> {
+    parameters: ParameterList<T>,
+}
+
+impl<T> MultiNoiseBiomeSource<T> {
+    pub fn new(parameters: ParameterList<T>) -> Self {
+        Self { parameters }
+    }
+
+    pub fn parameters(&self) -> &ParameterList<T> {
+        &self.parameters
+    }
+
+    pub fn possible_biomes(&self) -> impl Iterator<Item = &T> {
+        self.parameters.values().iter().map(|(_, biome)| biome)
+    }
+
+    pub fn get_noise_biome(
+        &self,
+        quart_x: i32,
+        quart_y: i32,
+        quart_z: i32,
+        sampler: &impl ClimateSampler,
+    ) -> &T {
+        self.parameters
+            .find_value(&sampler.sample(quart_x, quart_y, quart_z))
+    }
+}
+
+impl MultiNoiseBiomeSource<u32> {
+    /// Samples the biomes of the section whose minimum corner is `origin`.
+    pub fn fill_section(&self, origin: BlockPos, sampler: &impl ClimateSampler) -> SectionBiomes {
+        let (base_x, base_y, base_z) = (origin.x >> 2, origin.y >> 2, origin.z >> 2);
+        let mut biomes = SectionBiomes::default();
+
+        for y in 0..4 {
+            for z in 0..4 {
+                for x in 0..4 {
+                    let biome = self.get_noise_biome(
+                        base_x + x as i32,
+                        base_y + y as i32,
+                        base_z + z as i32,
+                        sampler,
+                    );
+                    biomes.set(x, y, z, *biome);
+                }
+            }
+        }
+
+        biomes
+    }
+}
+
+/// Vanilla's `minecraft:nether` multi noise preset.
+pub fn nether_preset() -> ParameterList<ResourceLocation> {
+    let point = |temperature: f32, humidity: f32, offset: f32| {
+        let zero = Parameter::point(0.0);
+        ParameterPoint::new(
+            Parameter::point(temperature),
+            Parameter::point(humidity),
+            zero,
+            zero,
+            zero,
+            zero,
+            offset,
+        )
+    };
+
+    ParameterList::new(vec![
+        (
+            point(0.0, 0.0, 0.0),
+            ResourceLocation::minecraft("nether_wastes"),
+        ),
+        (
+            point(0.0, -0.5, 0.0),
+            ResourceLocation::minecraft("soul_sand_valley"),
+        ),
+        (
+            point(0.4, 0.0, 0.0),
+            ResourceLocation::minecraft("crimson_forest"),
+        ),
+        (
+            point(0.0, 0.5, 0.375),
+            ResourceLocation::minecraft("warped_forest"),
+        ),
+        (
+            point(-0.5, 0.0, 0.175),
+            ResourceLocation::minecraft("basalt_deltas"),
+        ),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{string::String, vec::Vec};
+    use jni::{JNIEnv, objects::JObject};
+    use mcre_core::{BlockPos, ResourceLocation};
+
+    use crate::{
+        ClimateSampler, MultiNoiseBiomeSource, NoiseSampler, TargetPoint, builtin_biomes,
+        nether_preset,
+    };
+
+    mod java {
+        use jni::objects::JString;
+
+        #[mcje::class("net/minecraft/data/registries/VanillaRegistries")]
+        pub trait VanillaRegistries {
+            fn createLookup() -> HolderLookupProvider;
+        }
+
+        #[mcje::class("net/minecraft/core/HolderLookup$Provider")]
+        pub trait HolderLookupProvider {}
+
+        #[mcje::class("net/minecraft/core/HolderGetter$Provider")]
+        pub trait HolderGetterProvider {
+            fn lookupOrThrow(&self, registry: ResourceKey) -> HolderGetter;
+        }
+
+        #[mcje::class("net/minecraft/core/HolderGetter")]
+        pub trait HolderGetter {
+            fn getOrThrow(&self, key: ResourceKey) -> HolderReference;
+        }
+
+        #[mcje::class("net/minecraft/core/Holder")]
+        pub trait Holder {
+            fn getRegisteredName(&self) -> JString;
+        }
+
+        #[mcje::class("net/minecraft/core/Holder$Reference")]
+        pub trait HolderReference {}
+
+        #[mcje::class("net/minecraft/resources/ResourceKey")]
+        pub trait ResourceKey {}
+
+        #[mcje::class("net/minecraft/core/registries/Registries")]
+        pub trait Registries {
+            #[mcje(field)]
+            fn MULTI_NOISE_BIOME_SOURCE_PARAMETER_LIST() -> ResourceKey;
+        }
+
+        #[mcje::class("net/minecraft/world/level/biome/MultiNoiseBiomeSourceParameterLists")]
+        pub trait MultiNoiseBiomeSourceParameterLists {
+            #[mcje(field)]
+            fn NETHER() -> ResourceKey;
+        }
+
+        #[mcje::class("net/minecraft/world/level/biome/MultiNoiseBiomeSource")]
+        pub trait MultiNoiseBiomeSource {
+            fn createFromPreset(preset: Holder) -> Self;
+            fn getNoiseBiome(&self, x: i32, y: i32, z: i32, sampler: ClimateSampler) -> Holder;
+        }
+
+        #[mcje::class("net/minecraft/world/level/levelgen/NoiseGeneratorSettings")]
+        pub trait NoiseGeneratorSettings {
+            #[mcje(field)]
+            fn NETHER() -> ResourceKey;
+        }
+
+        #[mcje::class("net/minecraft/world/level/levelgen/RandomState")]
+        pub trait RandomState {
+            fn create(registries: HolderGetterProvider, settings: ResourceKey, seed: i64) -> Self;
+            fn sampler(&self) -> ClimateSampler;
+        }
+
+        #[mcje::class("net/minecraft/world/level/biome/Climate$Sampler")]
+        pub trait ClimateSampler {}
+    }
+
+    fn nether_source() -> MultiNoiseBiomeSource<u32> {
+        let registry = builtin_biomes().unwrap();
+        let parameters = nether_preset()
+            .try_map(|key| registry.id_of(&key).ok_or(key))
+            .unwrap();
+        MultiNoiseBiomeSource::new(parameters)
+    }
+
+    fn key(id: u32) -> ResourceLocation {
+        builtin_biomes().unwrap().key_of(id).unwrap().clone()
+    }
+
+    #[test]
+    fn test_nether_preset_lookup() {
+        let source = MultiNoiseBiomeSource::new(nether_preset());
+        let lookup = |temperature, humidity| {
+            source
+                .parameters()
+                .find_value(&TargetPoint::new(temperature, humidity, 0.0, 0.0, 0.0, 0.0))
+                .path()
+        };
+
+        assert_eq!(lookup(0.0, 0.0), "nether_wastes");
+        assert_eq!(lookup(0.0, -0.6), "soul_sand_valley");
+        assert_eq!(lookup(0.5, 0.0), "crimson_forest");
+        assert_eq!(lookup(0.0, 0.9), "warped_forest");
+        assert_eq!(lookup(-0.7, 0.0), "basalt_deltas");
+    }
+
+    #[mcje::test]
+    async fn test_fixed_seed_biomes(env: &mut JNIEnv<'_>) {
+        let lookup = java::HolderGetterProvider::from(JObject::from(
+            java::VanillaRegistries::createLookup(env).unwrap(),
+        ));
+        let registry = java::Registries::MULTI_NOISE_BIOME_SOURCE_PARAMETER_LIST(env).unwrap();
+        let preset_key = java::MultiNoiseBiomeSourceParameterLists::NETHER(env).unwrap();
+        let presets = lookup.lookupOrThrow(env, &registry).unwrap();
+        let preset = presets.getOrThrow(env, &preset_key).unwrap();
+        let vanilla_source =
+            java::MultiNoiseBiomeSource::createFromPreset(env, &JObject::from(preset).into())
+                .unwrap();
+        let settings = java::NoiseGeneratorSettings::NETHER(env).unwrap();
+
+        let positions = [
+            (0, 0),
+            (25, -40),
+            (-150, 75),
+            (300, 300),
+            (-512, -64),
+            (1000, -777),
+            (64, 640),
+            (-900, 900),
+        ];
+        let source = nether_source();
+
+        for seed in [0, 1, -4_172_144_997_902_289_642] {
+            let climate = java::RandomState::create(env, &lookup, &settings, seed)
+                .unwrap()
+                .sampler(env)
+                .unwrap();
+            let sampler = NoiseSampler::nether(seed);
+            for (x, z) in positions {
+                let name = vanilla_source
+                    .getNoiseBiome(env, x, 0, z, &climate)
+                    .unwrap()
+                    .getRegisteredName(env)
+                    .unwrap();
+                let expected = String::from(env.get_string(&name).unwrap());
+                let found = key(*source.get_noise_biome(x, 0, z, &sampler));
+                assert_eq!(found.to_string(), expected, "seed {seed} at ({x}, {z})");
+            }
+        }
+    }
+
+    #[test]
+    fn test_overworld_seeds() {
+        let a = NoiseSampler::overworld(42);
+        let b = NoiseSampler::overworld(43);
+        let differs = (0..64).any(|i| a.sample(i * 16, 16, 0) != b.sample(i * 16, 16, 0));
+        assert!(differs, "different seeds must produce different climates");
+    }
+
+    #[test]
+    fn test_nether_biome_variety() {
+        let source = nether_source();
+        let sampler = NoiseSampler::nether(0);
+
+        let mut found = Vec::new();
+        for x in -64..64 {
+            for z in -64..64 {
+                let biome = key(*source.get_noise_biome(x * 16, 0, z * 16, &sampler));
+                if !found.contains(&biome) {
+                    found.push(biome);
+                }
+            }
+        }
+
+        assert_eq!(found.len(), 5, "found only {found:?}");
+    }
+
+    #[test]
+    fn test_fill_section() {
+        let source = nether_source();
+        let sampler = NoiseSampler::nether(1234);
+        let origin = BlockPos::new(-32, 64, 48);
+        let section = source.fill_section(origin, &sampler);
+
+        for y in 0..4 {
+            for z in 0..4 {
+                for x in 0..4 {
+                    let expected = source.get_noise_biome(
+                        (origin.x >> 2) + x as i32,
+                        (origin.y >> 2) + y as i32,
+                        (origin.z >> 2) + z as i32,
+                        &sampler,
+                    );
+                    assert_eq!(section.get(x, y, z), *expected);
+                }
+            }
+        }
+
+        assert_eq!(
+            section.get_at_block(BlockPos::new(-17, 70, 63)),
+            section.get(3, 1, 3)
+        );
+    }
+}
@@ -2,7 +2,7 @@ use crate::{
     analyzer::{Analysis, FieldSchema},
     generators::{
         Scope, ScopeGen, Unit, UnitGen,
-        common::{MultiByteGen, StringGen},
+        common::{CompactGen, MultiByteGen, StringGen, ValueKind},
     },
 };
 
@@ -44,9 +44,11 @@ impl<'a> ScopeGen<'a> for BlockDataScope<'a> {
                     list: self.blocks,
                     mapping_fn: Box::new(|block, _analysis: &Analysis<'_>| block.max_state_id),
                 }),
-                Box::new(MultiByteGen {
+                Box::new(CompactGen {
                     name: "fields_present".to_string(),
+                    kind: ValueKind::U128,
                     list: self.blocks,
+                    by_block: None,
                     mapping_fn: Box::new(|block, analysis: &Analysis<'_>| {
                         let mut fields_present = 0;
 
@@ -116,6 +118,7 @@ impl UnitGen for BlockDataRootUnit {
             name: "mod".to_string(),
             code,
             data: None,
+            report: None,
         }
     }
 }
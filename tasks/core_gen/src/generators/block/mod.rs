@@ -42,7 +42,7 @@ impl UnitGen for BlockRootUnit<'_> {
             mod data;
             mod consts;
 
-            use crate::{BlockState, FieldKey};
+            use super::{BlockState, FieldKey};
             use serde::{Serialize, Deserialize};
 
             #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -69,27 +69,35 @@ impl UnitGen for BlockRootUnit<'_> {
             impl Block {
                 pub const MAX: Self = Self(#max);
 
-                pub fn name(self) -> &'static str {
+                pub const fn from_id(id: u16) -> Self {
+                    Self(id)
+                }
+
+                pub const fn id(self) -> u16 {
+                    self.0
+                }
+
+                pub const fn name(self) -> &'static str {
                     data::name::get(self.0)
                 }
 
-                pub fn display_name(self) -> &'static str {
+                pub const fn display_name(self) -> &'static str {
                     data::display_name::get(self.0)
                 }
 
-                pub fn default_state(self) -> BlockState {
-                    data::default_state::get(self.0).into()
+                pub const fn default_state(self) -> BlockState {
+                    BlockState::from_id(data::default_state::get(self.0))
                 }
 
-                pub fn min_state(self) -> BlockState {
-                    data::min_state::get(self.0).into()
+                pub const fn min_state(self) -> BlockState {
+                    BlockState::from_id(data::min_state::get(self.0))
                 }
 
-                pub fn max_state(self) -> BlockState {
-                    data::max_state::get(self.0).into()
+                pub const fn max_state(self) -> BlockState {
+                    BlockState::from_id(data::max_state::get(self.0))
                 }
 
-                pub fn is_field_present(self, field: FieldKey) -> bool {
+                pub const fn is_field_present(self, field: FieldKey) -> bool {
                     let fields_present = data::fields_present::get(self.0);
                     ((fields_present >> (field as u8)) & 1) == 1
                 }
@@ -144,6 +152,7 @@ impl UnitGen for BlockRootUnit<'_> {
             name: "mod".to_string(),
             code,
             data: None,
+            report: None,
         }
     }
 }
@@ -173,6 +182,7 @@ impl UnitGen for BlockConstsUnit<'_> {
             name: "consts".to_string(),
             code,
             data: None,
+            report: None,
         }
     }
 }
@@ -0,0 +1,360 @@
+use std::fmt;
+
+use proc_macro2::TokenStream;
+use quote::quote;
+
+use crate::{
+    analyzer::Analysis,
+    generators::{Unit, UnitGen, common::sub_byte::SubByteArray},
+};
+
+type MappingFn<'a, T> = Box<dyn Fn(&'a T, &Analysis) -> u128>;
+
+#[derive(Debug, Clone, Copy)]
+pub enum ValueKind {
+    Bool,
+    // inclusive range
+    Int {
+        min: u8,
+        max: u8,
+    },
+    /// Stored once in a palette, with the table holding indices into it.
+    F32,
+    /// Stored once in a palette, with the table holding indices into it.
+    U128,
+}
+
+/// How a table indexed by state can be grouped by block.
+pub struct ByBlock<T> {
+    /// Module with the `get` accessor mapping a state to its block.
+    pub table: TokenStream,
+    pub block_of: fn(&T) -> u16,
+}
+
+/// A table stored in the smallest of these layouts:
+///
+/// - `Flat`: one packed value per entry.
+/// - `PerBlock`: one packed value per block, when every block has a single value.
+/// - `Mixed`: one packed value per block, plus per-state values for the blocks whose states
+///   don't agree, found through a bitset and a rank table.
+///
+/// Multi-byte values are deduplicated into a palette first, so the tables only hold indices.
+pub struct CompactGen<'a, T> {
+    pub name: String,
+    pub kind: ValueKind,
+    pub list: &'a [T],
+    pub by_block: Option<ByBlock<T>>,
+    pub mapping_fn: MappingFn<'a, T>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Layout {
+    Flat,
+    PerBlock,
+    Mixed,
+}
+
+/// Size of a table before and after compaction, in bytes.
+#[derive(Debug, Clone, Copy)]
+pub struct TableReport {
+    pub layout: Layout,
+    pub before: usize,
+    pub after: usize,
+}
+
+impl fmt::Display for TableReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:>7} -> {:>7} bytes ({:?})",
+            self.before, self.after, self.layout
+        )
+    }
+}
+
+struct Palette {
+    values: Vec<u128>,
+    indices: Vec<u8>,
+}
+
+impl Palette {
+    fn new(raw: &[u128]) -> Self {
+        let mut values = Vec::new();
+        let indices = raw
+            .iter()
+            .map(|value| {
+                let index = values.iter().position(|v| v == value).unwrap_or_else(|| {
+                    values.push(*value);
+                    values.len() - 1
+                });
+                u8::try_from(index).expect("more than 256 distinct values")
+            })
+            .collect();
+
+        Self { values, indices }
+    }
+}
+
+impl<T> UnitGen for CompactGen<'_, T> {
+    fn generate(&self, analysis: &Analysis) -> Unit {
+        let raw = self
+            .list
+            .iter()
+            .map(|el| (self.mapping_fn)(el, analysis))
+            .collect::<Vec<_>>();
+
+        let (palette, values, min, max) = match self.kind {
+            ValueKind::Bool => (None, raw.iter().map(|v| *v as u8).collect(), 0, 1),
+            ValueKind::Int { min, max } => (None, raw.iter().map(|v| *v as u8).collect(), min, max),
+            ValueKind::F32 | ValueKind::U128 => {
+                let palette = Palette::new(&raw);
+                let max = (palette.values.len() - 1) as u8;
+                (Some(palette.values), palette.indices, 0, max)
+            }
+        };
+
+        let before = match self.kind {
+            ValueKind::Bool | ValueKind::Int { .. } => {
+                SubByteArray::new(min, max, values.len()).byte_len()
+            }
+            ValueKind::F32 => 4 * values.len(),
+            ValueKind::U128 => 16 * values.len(),
+        };
+
+        let tables = Tables::new(&values, min, max, self.by_block.as_ref(), self.list);
+        let palette_len = palette.as_ref().map_or(0, |palette| {
+            palette.len()
+                * if matches!(self.kind, ValueKind::F32) {
+                    4
+                } else {
+                    16
+                }
+        });
+        let report = TableReport {
+            layout: tables.layout,
+            before,
+            after: tables.data.len() + palette_len,
+        };
+
+        let (ret, convert) = match self.kind {
+            ValueKind::Bool => (quote! { bool }, Some(quote! { value == 1 })),
+            ValueKind::Int { .. } => (quote! { u8 }, None),
+            ValueKind::F32 | ValueKind::U128 => {
+                let ty = if matches!(self.kind, ValueKind::F32) {
+                    quote! { f32 }
+                } else {
+                    quote! { u128 }
+                };
+                (ty, Some(quote! { PALETTE[value as usize] }))
+            }
+        };
+        let palette = palette.map(|palette| {
+            let len = palette.len();
+            let values = palette.iter().map(|value| match self.kind {
+                ValueKind::F32 => {
+                    let bits = *value as u32;
+                    quote! { f32::from_bits(#bits) }
+                }
+                _ => quote! { #value },
+            });
+            quote! {
+                static PALETTE: [#ret; #len] = [#( #values ),*];
+            }
+        });
+
+        let data_len = tables.data.len();
+        let data_path = format!("./{}.bin", self.name);
+        let read = tables.read_fn();
+        let (prelude, value) = tables.lookup(self.by_block.as_ref());
+        let body = match convert {
+            Some(convert) => quote! {
+                #prelude
+                let value = #value;
+                #convert
+            },
+            None => quote! {
+                #prelude
+                #value
+            },
+        };
+
+        let code = quote! {
+            static DATA: [u8; #data_len] = *include_bytes!(#data_path);
+
+            #palette
+
+            #read
+
+            pub(crate) const fn get(idx: u16) -> #ret {
+                #body
+            }
+        };
+
+        Unit {
+            name: self.name.clone(),
+            code,
+            data: Some(tables.data.into_boxed_slice()),
+            report: Some(report),
+        }
+    }
+}
+
+struct Tables {
+    layout: Layout,
+    data: Vec<u8>,
+    min: u8,
+    bits: u8,
+    /// Offsets of the bitset, rank table, bases and per-state values of the `Mixed` layout.
+    offsets: [usize; 4],
+}
+
+impl Tables {
+    fn new<T>(values: &[u8], min: u8, max: u8, by_block: Option<&ByBlock<T>>, list: &[T]) -> Self {
+        let pack = |values: &mut dyn Iterator<Item = u8>, len: usize| {
+            let mut array = SubByteArray::new(min, max, len);
+            for (i, value) in values.enumerate() {
+                array.set(i, value);
+            }
+            array
+        };
+
+        let flat = pack(&mut values.iter().copied(), values.len());
+        let (min, bits) = (flat.min(), flat.bits());
+        let flat = Self {
+            layout: Layout::Flat,
+            data: flat.into_values().into_vec(),
+            min,
+            bits,
+            offsets: [0; 4],
+        };
+
+        let Some(by_block) = by_block else {
+            return flat;
+        };
+
+        // states of a block are contiguous
+        let mut blocks = Vec::<(usize, usize)>::new();
+        for (i, el) in list.iter().enumerate() {
+            let block = (by_block.block_of)(el) as usize;
+            if block == blocks.len() {
+                blocks.push((i, i + 1));
+            } else {
+                blocks[block].1 = i + 1;
+            }
+        }
+
+        let varying = blocks
+            .iter()
+            .map(|&(start, end)| values[start..end].iter().any(|v| *v != values[start]))
+            .collect::<Vec<_>>();
+        let per_block = pack(
+            &mut blocks.iter().map(|&(start, _)| values[start]),
+            blocks.len(),
+        )
+        .into_values();
+
+        if !varying.contains(&true) {
+            return Self {
+                layout: Layout::PerBlock,
+                data: per_block.into_vec(),
+                ..flat
+            };
+        }
+
+        let mut bitset = vec![0u8; blocks.len().div_ceil(8)];
+        let mut ranks = Vec::new();
+        let mut bases = Vec::new();
+        let mut states = Vec::new();
+        for (block, &(start, end)) in blocks.iter().enumerate() {
+            if block % 8 == 0 {
+                ranks.extend_from_slice(&(bases.len() as u16 / 2).to_le_bytes());
+            }
+            if varying[block] {
+                bitset[block / 8] |= 1 << (block % 8);
+                let base = (states.len() as u16).wrapping_sub(start as u16);
+                bases.extend_from_slice(&base.to_le_bytes());
+                states.extend_from_slice(&values[start..end]);
+            }
+        }
+        let states = pack(&mut states.iter().copied(), states.len()).into_values();
+
+        let mut data = per_block.into_vec();
+        let mut offsets = [0; 4];
+        for (offset, section) in offsets
+            .iter_mut()
+            .zip([&bitset, &ranks, &bases, &states[..]])
+        {
+            *offset = data.len();
+            data.extend_from_slice(section);
+        }
+
+        if data.len() >= flat.data.len() {
+            return flat;
+        }
+
+        Self {
+            layout: Layout::Mixed,
+            data,
+            offsets,
+            ..flat
+        }
+    }
+
+    fn read_fn(&self) -> TokenStream {
+        let bits = self.bits as usize;
+        let values_per_byte = 8 / bits;
+        let mask = ((1u16 << bits) - 1) as u8;
+
+        let value = match bits {
+            8 => quote! { DATA[offset + idx] },
+            1 => quote! { (DATA[offset + idx / 8] >> (idx % 8)) & 1 },
+            _ => quote! {
+                (DATA[offset + idx / #values_per_byte] >> (idx % #values_per_byte * #bits)) & #mask
+            },
+        };
+        let value = match (self.min, bits) {
+            (0, _) => value,
+            (min, 8) => quote! { #value + #min },
+            (min, _) => quote! { (#value) + #min },
+        };
+
+        quote! {
+            /// Reads the `idx`th packed value of the table starting at `offset`.
+            const fn read(offset: usize, idx: usize) -> u8 {
+                #value
+            }
+        }
+    }
+
+    /// The statements finding the packed value of state `idx`, ending with the expression reading it.
+    fn lookup<T>(&self, by_block: Option<&ByBlock<T>>) -> (Option<TokenStream>, TokenStream) {
+        let block = by_block.map(|by_block| {
+            let table = &by_block.table;
+            quote! { let block = #table::get(idx) as usize; }
+        });
+
+        match self.layout {
+            Layout::Flat => (None, quote! { read(0, idx as usize) }),
+            Layout::PerBlock => (block, quote! { read(0, block) }),
+            Layout::Mixed => {
+                let [bitset, ranks, bases, states] = self.offsets;
+                let block = quote! {
+                    #block
+                    let varying = DATA[#bitset + block / 8];
+                    let bit = block % 8;
+                };
+                let value = quote! {
+                    if (varying >> bit) & 1 == 0 {
+                        read(0, block)
+                    } else {
+                        let rank = u16::from_le_bytes([DATA[#ranks + block / 8 * 2], DATA[#ranks + block / 8 * 2 + 1]]) as usize
+                            + (varying & ((1 << bit) - 1)).count_ones() as usize;
+                        let base = u16::from_le_bytes([DATA[#bases + rank * 2], DATA[#bases + rank * 2 + 1]]);
+                        read(#states, base.wrapping_add(idx) as usize)
+                    }
+                };
+                (Some(block), value)
+            }
+        }
+    }
+}
@@ -1,7 +1,8 @@
+mod compact;
 mod multi_byte;
 mod string;
 mod sub_byte;
 
+pub use compact::{ByBlock, CompactGen, TableReport, ValueKind};
 pub use multi_byte::MultiByteGen;
 pub use string::StringGen;
-pub use sub_byte::SubByteGen;
@@ -33,7 +33,7 @@ impl<'a, T, U> UnitGen for MultiByteGen<'a, T, U> {
             static VALUES: [#type_name; #len] =
                 unsafe { core::mem::transmute(*include_bytes!(#data_path)) };
 
-            pub(crate) fn get(idx: u16) -> #type_name {
+            pub(crate) const fn get(idx: u16) -> #type_name {
                 VALUES[idx as usize]
             }
         };
@@ -42,6 +42,7 @@ impl<'a, T, U> UnitGen for MultiByteGen<'a, T, U> {
             name: self.name.clone(),
             code,
             data: Some(data),
+            report: None,
         }
     }
 }
@@ -19,7 +19,7 @@ impl<'a, T> UnitGen for StringGen<'a, T> {
         let code = quote! {
             static VALUES: [&str; #len] = [#( #data, )*];
 
-            pub(crate) fn get(idx: u16) -> &'static str {
+            pub(crate) const fn get(idx: u16) -> &'static str {
                 VALUES[idx as usize]
             }
         };
@@ -28,6 +28,7 @@ impl<'a, T> UnitGen for StringGen<'a, T> {
             name: self.name.clone(),
             code,
             data: None,
+            report: None,
         }
     }
 }
@@ -87,6 +87,7 @@ impl UnitGen for FieldsUnit {
             name: "fields".to_string(),
             code,
             data: None,
+            report: None,
         }
     }
 }
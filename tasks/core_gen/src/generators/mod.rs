@@ -3,7 +3,7 @@ use std::path::{Path, PathBuf};
 use proc_macro2::TokenStream;
 use tokio::{fs, io};
 
-use crate::analyzer::Analysis;
+use crate::{analyzer::Analysis, generators::common::TableReport};
 
 mod block;
 pub mod common;
@@ -12,12 +12,14 @@ mod props;
 mod root;
 mod state;
 
-pub use root::RootScope;
+pub use root::{RootScope, VersionsUnit};
 
 pub struct Unit {
     pub name: String,
     pub code: TokenStream,
     pub data: Option<Box<[u8]>>,
+    /// Size of the table before and after compaction, for tables that are compacted.
+    pub report: Option<TableReport>,
 }
 
 pub struct Scope<'a> {
@@ -58,6 +60,11 @@ impl<'a> Factory<'a> {
         self.scopes.push(Box::new(scope));
     }
 
+    /// Writes a unit that doesn't depend on the analysis of a version.
+    pub async fn write_unit(&self, unit: Unit) {
+        Self::serialize_unit(&self.root, None, unit).await;
+    }
+
     pub async fn generate(self, analysis: &Analysis<'_>) {
         let mut serialization_units = Vec::new();
 
@@ -86,11 +93,33 @@ impl<'a> Factory<'a> {
             }
         }
 
+        Self::print_report(&serialization_units);
+
         for (scope, unit) in serialization_units {
             Self::serialize_unit(&self.root, scope, unit).await;
         }
     }
 
+    fn print_report(units: &[(Option<PathBuf>, Unit)]) {
+        let (mut before, mut after) = (0, 0);
+
+        for (scope, unit) in units {
+            let Some(report) = unit.report else {
+                continue;
+            };
+            let path = scope.as_deref().unwrap_or(Path::new("")).join(&unit.name);
+            println!("{:<56} {report}", path.display());
+            before += report.before;
+            after += report.after;
+        }
+
+        println!(
+            "{:<56} {before:>7} -> {after:>7} bytes ({:.1}%)",
+            "total",
+            after as f64 * 100.0 / before.max(1) as f64
+        );
+    }
+
     async fn serialize_unit(root_path: &Path, scope_path: Option<PathBuf>, unit: Unit) {
         let mut path = if let Some(scope_path) = scope_path {
             scope_path.join(unit.name)
@@ -93,7 +93,7 @@ impl UnitGen for PropsUnit {
                         }
 
                         impl #superenum_name {
-                            pub fn as_str(self) -> &'static str {
+                            pub const fn as_str(self) -> &'static str {
                                 match self {
                                     #( Self::#variants_idents => #variants_literals, )*
                                 }
@@ -145,7 +145,7 @@ impl UnitGen for PropsUnit {
             }
 
             impl PropKey {
-                pub fn as_str(self) -> &'static str {
+                pub const fn as_str(self) -> &'static str {
                     match self {
                         #( Self::#props_idents => #props_literals, )*
                     }
@@ -153,7 +153,7 @@ impl UnitGen for PropsUnit {
             }
 
             impl PropVal {
-                pub fn key(self) -> PropKey {
+                pub const fn key(self) -> PropKey {
                     match self {
                         #( Self::#props_idents(_) => PropKey::#props_idents, )*
                     }
@@ -167,7 +167,7 @@ impl UnitGen for PropsUnit {
             }
 
             impl PropFilter {
-                pub fn key(&self) -> PropKey {
+                pub const fn key(&self) -> PropKey {
                     match self {
                         #( Self::#props_idents(_) => PropKey::#props_idents, )*
                     }
@@ -228,6 +228,7 @@ impl UnitGen for PropsUnit {
             name: "props".to_string(),
             code,
             data: None,
+            report: None,
         }
     }
 }
@@ -6,10 +6,13 @@ use crate::{
     },
 };
 
-use mcre_data::{block::Block, state::BlockState};
-use quote::quote;
+use mcre_data::{block::Block, state::BlockState, version::VersionInfo};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
 
 pub struct RootScope<'a> {
+    pub module: &'a str,
+    pub version: &'a VersionInfo,
     pub blocks: &'a [Block],
     pub states: &'a [BlockState],
 }
@@ -17,9 +20,11 @@ pub struct RootScope<'a> {
 impl<'a> ScopeGen<'a> for RootScope<'a> {
     fn generate(&self, _analysis: &Analysis) -> Scope<'a> {
         Scope {
-            name: String::new(),
+            name: self.module.to_string(),
             units: Box::new([
-                Box::new(RootUnit),
+                Box::new(RootUnit {
+                    version: self.version,
+                }),
                 Box::new(PropsUnit),
                 Box::new(FieldsUnit),
             ]),
@@ -35,10 +40,18 @@ impl<'a> ScopeGen<'a> for RootScope<'a> {
     }
 }
 
-pub struct RootUnit;
+pub struct RootUnit<'a> {
+    version: &'a VersionInfo,
+}
 
-impl UnitGen for RootUnit {
+impl UnitGen for RootUnit<'_> {
     fn generate(&self, _analysis: &Analysis) -> Unit {
+        let VersionInfo {
+            id,
+            world_version,
+            protocol_version,
+        } = self.version;
+
         let code = quote! {
             mod block;
             mod state;
@@ -49,12 +62,94 @@ impl UnitGen for RootUnit {
             pub use state::*;
             pub use props::*;
             pub use fields::*;
+
+            use crate::DataVersion;
+
+            pub const DATA_VERSION: DataVersion = DataVersion {
+                id: #id,
+                world_version: #world_version,
+                protocol_version: #protocol_version,
+            };
+        };
+
+        Unit {
+            name: "mod".to_string(),
+            code,
+            data: None,
+            report: None,
+        }
+    }
+}
+
+/// Declares the module of every generated version, re-exporting the newest enabled one.
+///
+/// The newest generated version is used when no version feature is enabled.
+pub struct VersionsUnit<'a> {
+    pub versions: &'a [(&'a str, &'a str)],
+}
+
+impl VersionsUnit<'_> {
+    pub fn generate(&self) -> Unit {
+        // every module is gated by its feature, the newest one is also the default when no
+        // version is selected, and a single version needs no gate
+        let gate = |condition: &TokenStream| {
+            if self.versions.len() > 1 {
+                quote! { #[cfg(#condition)] }
+            } else {
+                TokenStream::new()
+            }
+        };
+        let enabled = self
+            .versions
+            .iter()
+            .enumerate()
+            .map(|(i, (_, module))| {
+                if i > 0 {
+                    return quote! { feature = #module };
+                }
+                let others = self.versions[1..]
+                    .iter()
+                    .map(|(_, other)| quote! { feature = #other });
+                quote! { any(feature = #module, not(any(#( #others ),*))) }
+            })
+            .collect::<Vec<_>>();
+
+        let modules = self
+            .versions
+            .iter()
+            .zip(&enabled)
+            .map(|((_, module), enabled)| {
+                let ident = format_ident!("{}", module);
+                let gate = gate(enabled);
+                quote! {
+                    #gate
+                    pub mod #ident;
+                }
+            });
+        // the newest enabled version is the one used
+        let reexports = self.versions.iter().enumerate().map(|(i, (_, module))| {
+            let ident = format_ident!("{}", module);
+            let current = &enabled[i];
+            let cfg = match &enabled[..i] {
+                [] => gate(current),
+                newer => gate(&quote! { all(#current, not(any(#( #newer ),*))) }),
+            };
+            quote! {
+                #cfg
+                pub use #ident::*;
+            }
+        });
+
+        let code = quote! {
+            #( #modules )*
+            #( #reexports )*
         };
 
         Unit {
             name: "mod".to_string(),
             code,
             data: None,
+            report: None,
         }
     }
 }
@@ -3,7 +3,10 @@ use quote::{format_ident, quote};
 
 use crate::{
     analyzer::{Analysis, FieldSchema},
-    generators::{Scope, ScopeGen, Unit, UnitGen, common::SubByteGen},
+    generators::{
+        Scope, ScopeGen, Unit, UnitGen,
+        common::{ByBlock, CompactGen, ValueKind},
+    },
 };
 
 pub struct StateFieldsDataScope<'a> {
@@ -18,25 +21,26 @@ impl<'a> ScopeGen<'a> for StateFieldsDataScope<'a> {
             .map(|(field_name, field_schema)| {
                 let field_name = field_name.clone();
                 let unit = match field_schema {
-                    FieldSchema::Bool => SubByteGen {
+                    FieldSchema::Bool => CompactGen {
                         name: field_name.clone(),
-                        is_bool: true,
-                        min: 0,
-                        max: 1,
+                        kind: ValueKind::Bool,
                         list: self.states,
+                        by_block: by_block(),
                         mapping_fn: Box::new(move |state, _analysis: &Analysis<'_>| {
                             state
                                 .state_values
                                 .get(field_name.strip_prefix("is_").unwrap())
-                                .map_or(0, |val| matches!(val, StateValue::Bool(true)) as u8)
+                                .map_or(0, |val| matches!(val, StateValue::Bool(true)) as u128)
                         }),
                     },
-                    FieldSchema::Int(min, max) => SubByteGen {
+                    FieldSchema::Int(min, max) => CompactGen {
                         name: field_name.clone(),
-                        is_bool: false,
-                        min: *min,
-                        max: *max,
+                        kind: ValueKind::Int {
+                            min: *min,
+                            max: *max,
+                        },
                         list: self.states,
+                        by_block: by_block(),
                         mapping_fn: {
                             let min = *min;
                             Box::new(move |state, _analysis: &Analysis<'_>| {
@@ -47,16 +51,18 @@ impl<'a> ScopeGen<'a> for StateFieldsDataScope<'a> {
                                         StateValue::Int(val) => Some(*val),
                                         _ => None,
                                     })
-                                    .unwrap_or(min)
+                                    .unwrap_or(min) as u128
                             })
                         },
                     },
-                    FieldSchema::Enum(enum_name) => SubByteGen {
+                    FieldSchema::Enum(enum_name) => CompactGen {
                         name: field_name.clone(),
-                        is_bool: false,
-                        min: 0,
-                        max: (analysis.enums.get(enum_name).unwrap().len() - 1) as u8,
+                        kind: ValueKind::Int {
+                            min: 0,
+                            max: (analysis.enums.get(enum_name).unwrap().len() - 1) as u8,
+                        },
                         list: self.states,
+                        by_block: by_block(),
                         mapping_fn: {
                             let enum_name = enum_name.to_string();
                             Box::new(move |state, analysis: &Analysis<'_>| {
@@ -86,8 +92,7 @@ impl<'a> ScopeGen<'a> for StateFieldsDataScope<'a> {
                                                     .iter()
                                                     .position(|variant| variant == val)
                                                     .unwrap()
-                                                    .try_into()
-                                                    .unwrap(),
+                                                    as u128,
                                             )
                                         } else {
                                             None
@@ -113,6 +118,13 @@ impl<'a> ScopeGen<'a> for StateFieldsDataScope<'a> {
     }
 }
 
+fn by_block() -> Option<ByBlock<BlockState>> {
+    Some(ByBlock {
+        table: quote! { super::super::block },
+        block_of: |state| state.block_id,
+    })
+}
+
 pub struct StateFieldsDataRootUnit;
 
 impl UnitGen for StateFieldsDataRootUnit {
@@ -128,6 +140,7 @@ impl UnitGen for StateFieldsDataRootUnit {
             name: "mod".to_string(),
             code,
             data: None,
+            report: None,
         }
     }
 }
@@ -7,7 +7,7 @@ use crate::{
     analyzer::Analysis,
     generators::{
         Scope, ScopeGen, Unit, UnitGen,
-        common::{MultiByteGen, SubByteGen},
+        common::{ByBlock, CompactGen, MultiByteGen, ValueKind},
         state::data::fields::StateFieldsDataScope,
     },
 };
@@ -27,141 +27,146 @@ impl<'a> ScopeGen<'a> for StateDataScope<'a> {
                     list: self.states,
                     mapping_fn: Box::new(|state, _analysis: &Analysis<'_>| state.block_id),
                 }),
-                Box::new(SubByteGen {
+                Box::new(CompactGen {
                     name: "light_emission".to_string(),
-                    is_bool: false,
-                    min: 0,
-                    max: 15,
+                    kind: ValueKind::Int { min: 0, max: 15 },
                     list: self.states,
-                    mapping_fn: Box::new(|state, _analysis: &Analysis<'_>| state.light_emission),
+                    by_block: by_block(),
+                    mapping_fn: Box::new(|state, _analysis: &Analysis<'_>| {
+                        state.light_emission as u128
+                    }),
                 }),
-                Box::new(SubByteGen {
+                Box::new(CompactGen {
                     name: "use_shape_for_light_occlusion".to_string(),
-                    is_bool: true,
-                    min: 0,
-                    max: 1,
+                    kind: ValueKind::Bool,
                     list: self.states,
+                    by_block: by_block(),
                     mapping_fn: Box::new(|state, _analysis: &Analysis<'_>| {
-                        state.use_shape_for_light_occlusion as u8
+                        state.use_shape_for_light_occlusion as u128
                     }),
                 }),
-                Box::new(SubByteGen {
+                Box::new(CompactGen {
                     name: "propagates_skylight_down".to_string(),
-                    is_bool: true,
-                    min: 0,
-                    max: 1,
+                    kind: ValueKind::Bool,
                     list: self.states,
+                    by_block: by_block(),
                     mapping_fn: Box::new(|state, _analysis: &Analysis<'_>| {
-                        state.propagates_skylight_down as u8
+                        state.propagates_skylight_down as u128
                     }),
                 }),
-                Box::new(SubByteGen {
+                Box::new(CompactGen {
                     name: "light_block".to_string(),
-                    is_bool: false,
-                    min: 0,
-                    max: 15,
+                    kind: ValueKind::Int { min: 0, max: 15 },
                     list: self.states,
-                    mapping_fn: Box::new(|state, _analysis: &Analysis<'_>| state.light_block),
+                    by_block: by_block(),
+                    mapping_fn: Box::new(|state, _analysis: &Analysis<'_>| {
+                        state.light_block as u128
+                    }),
                 }),
-                Box::new(SubByteGen {
+                Box::new(CompactGen {
                     name: "solid_render".to_string(),
-                    is_bool: true,
-                    min: 0,
-                    max: 1,
+                    kind: ValueKind::Bool,
                     list: self.states,
+                    by_block: by_block(),
                     mapping_fn: Box::new(|state, _analysis: &Analysis<'_>| {
-                        state.solid_render as u8
+                        state.solid_render as u128
                     }),
                 }),
-                Box::new(SubByteGen {
+                Box::new(CompactGen {
                     name: "is_air".to_string(),
-                    is_bool: true,
-                    min: 0,
-                    max: 1,
+                    kind: ValueKind::Bool,
                     list: self.states,
-                    mapping_fn: Box::new(|state, _analysis: &Analysis<'_>| state.is_air as u8),
+                    by_block: by_block(),
+                    mapping_fn: Box::new(|state, _analysis: &Analysis<'_>| state.is_air as u128),
                 }),
-                Box::new(SubByteGen {
+                Box::new(CompactGen {
                     name: "ignited_by_lava".to_string(),
-                    is_bool: true,
-                    min: 0,
-                    max: 1,
+                    kind: ValueKind::Bool,
                     list: self.states,
+                    by_block: by_block(),
                     mapping_fn: Box::new(|state, _analysis: &Analysis<'_>| {
-                        state.ignited_by_lava as u8
+                        state.ignited_by_lava as u128
                     }),
                 }),
-                Box::new(SubByteGen {
+                Box::new(CompactGen {
                     name: "can_occlude".to_string(),
-                    is_bool: true,
-                    min: 0,
-                    max: 1,
+                    kind: ValueKind::Bool,
                     list: self.states,
-                    mapping_fn: Box::new(|state, _analysis: &Analysis<'_>| state.can_occlude as u8),
+                    by_block: by_block(),
+                    mapping_fn: Box::new(|state, _analysis: &Analysis<'_>| {
+                        state.can_occlude as u128
+                    }),
                 }),
-                Box::new(SubByteGen {
+                Box::new(CompactGen {
                     name: "is_randomly_ticking".to_string(),
-                    is_bool: true,
-                    min: 0,
-                    max: 1,
+                    kind: ValueKind::Bool,
                     list: self.states,
+                    by_block: by_block(),
                     mapping_fn: Box::new(|state, _analysis: &Analysis<'_>| {
-                        state.is_randomly_ticking as u8
+                        state.is_randomly_ticking as u128
                     }),
                 }),
-                Box::new(SubByteGen {
+                Box::new(CompactGen {
                     name: "replaceable".to_string(),
-                    is_bool: true,
-                    min: 0,
-                    max: 1,
+                    kind: ValueKind::Bool,
                     list: self.states,
-                    mapping_fn: Box::new(|state, _analysis: &Analysis<'_>| state.replaceable as u8),
+                    by_block: by_block(),
+                    mapping_fn: Box::new(|state, _analysis: &Analysis<'_>| {
+                        state.replaceable as u128
+                    }),
                 }),
-                Box::new(SubByteGen {
+                Box::new(CompactGen {
                     name: "spawn_terrain_particles".to_string(),
-                    is_bool: true,
-                    min: 0,
-                    max: 1,
+                    kind: ValueKind::Bool,
                     list: self.states,
+                    by_block: by_block(),
                     mapping_fn: Box::new(|state, _analysis: &Analysis<'_>| {
-                        state.spawn_terrain_particles as u8
+                        state.spawn_terrain_particles as u128
                     }),
                 }),
-                Box::new(SubByteGen {
+                Box::new(CompactGen {
                     name: "requires_correct_tool_for_drops".to_string(),
-                    is_bool: true,
-                    min: 0,
-                    max: 1,
+                    kind: ValueKind::Bool,
                     list: self.states,
+                    by_block: by_block(),
                     mapping_fn: Box::new(|state, _analysis: &Analysis<'_>| {
-                        state.requires_correct_tool_for_drops as u8
+                        state.requires_correct_tool_for_drops as u128
                     }),
                 }),
-                Box::new(MultiByteGen {
+                Box::new(CompactGen {
                     name: "destroy_speed".to_string(),
+                    kind: ValueKind::F32,
                     list: self.states,
-                    mapping_fn: Box::new(|state, _analysis: &Analysis<'_>| state.destroy_speed),
+                    by_block: by_block(),
+                    mapping_fn: Box::new(|state, _analysis: &Analysis<'_>| {
+                        state.destroy_speed.to_bits() as u128
+                    }),
                 }),
-                Box::new(SubByteGen {
+                Box::new(CompactGen {
                     name: "offset_type".to_string(),
-                    is_bool: false,
-                    min: 0,
-                    max: 2,
+                    kind: ValueKind::Int { min: 0, max: 2 },
                     list: self.states,
-                    mapping_fn: Box::new(|state, _analysis: &Analysis<'_>| state.offset_type as u8),
+                    by_block: by_block(),
+                    mapping_fn: Box::new(|state, _analysis: &Analysis<'_>| {
+                        state.offset_type as u128
+                    }),
                 }),
-                Box::new(MultiByteGen {
+                Box::new(CompactGen {
                     name: "max_horizontal_offset".to_string(),
+                    kind: ValueKind::F32,
                     list: self.states,
+                    by_block: by_block(),
                     mapping_fn: Box::new(|state, _analysis: &Analysis<'_>| {
-                        state.max_horizontal_offset
+                        state.max_horizontal_offset.to_bits() as u128
                     }),
                 }),
-                Box::new(MultiByteGen {
+                Box::new(CompactGen {
                     name: "max_vertical_offset".to_string(),
+                    kind: ValueKind::F32,
                     list: self.states,
+                    by_block: by_block(),
                     mapping_fn: Box::new(|state, _analysis: &Analysis<'_>| {
-                        state.max_vertical_offset
+                        state.max_vertical_offset.to_bits() as u128
                     }),
                 }),
             ]),
@@ -172,6 +177,14 @@ impl<'a> ScopeGen<'a> for StateDataScope<'a> {
     }
 }
 
+/// Groups the tables of the `data` scope by the block of each state.
+fn by_block() -> Option<ByBlock<BlockState>> {
+    Some(ByBlock {
+        table: quote! { super::block },
+        block_of: |state| state.block_id,
+    })
+}
+
 pub struct StateDataRootUnit;
 
 impl UnitGen for StateDataRootUnit {
@@ -202,6 +215,7 @@ impl UnitGen for StateDataRootUnit {
             name: "mod".to_string(),
             code,
             data: None,
+            report: None,
         }
     }
 }
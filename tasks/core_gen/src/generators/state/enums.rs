@@ -31,7 +31,7 @@ impl UnitGen for EnumsGenerator {
                     }
 
                     impl #enum_name {
-                        pub fn as_str(self) -> &'static str {
+                        pub const fn as_str(self) -> &'static str {
                             match self {
                                 #( Self::#variants_idents => #variants, )*
                             }
@@ -67,6 +67,7 @@ impl UnitGen for EnumsGenerator {
             name: "enums".to_string(),
             code,
             data: None,
+            report: None,
         }
     }
 }
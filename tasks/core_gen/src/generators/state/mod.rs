@@ -44,19 +44,19 @@ impl UnitGen for StateRootUnit<'_> {
             let field_name = format_ident!("{}", field_name);
             match schema {
                 FieldSchema::Bool => quote! {
-                    pub fn #field_name(self) -> bool {
+                    pub const fn #field_name(self) -> bool {
                         data::fields::#field_name::get(self.0)
                     }
                 },
                 FieldSchema::Int(_, _) => quote! {
-                    pub fn #field_name(self) -> u8 {
+                    pub const fn #field_name(self) -> u8 {
                         data::fields::#field_name::get(self.0)
                     }
                 },
                 FieldSchema::Enum(enum_name) => {
                     let enum_name = format_ident!("{}", enum_name);
                     quote! {
-                        pub fn #field_name(self) -> #enum_name {
+                        pub const fn #field_name(self) -> #enum_name {
                             unsafe {
                                 core::mem::transmute::<u8, #enum_name>(data::fields::#field_name::get(self.0))
                             }
@@ -135,7 +135,8 @@ impl UnitGen for StateRootUnit<'_> {
             mod data;
             mod enums;
 
-            use crate::{Block, OffsetType, FieldKey, FieldVal, PropKey, PropVal};
+            use super::{Block, FieldKey, FieldVal, PropKey, PropVal};
+            use crate::OffsetType;
             use serde::{Serialize, Deserialize};
             pub use enums::*;
 
@@ -157,75 +158,83 @@ impl UnitGen for StateRootUnit<'_> {
             impl BlockState {
                 pub const MAX: Self = Self(#max);
 
-                pub fn block(self) -> Block {
-                    data::block::get(self.0).into()
+                pub const fn from_id(id: u16) -> Self {
+                    Self(id)
+                }
+
+                pub const fn id(self) -> u16 {
+                    self.0
                 }
 
-                pub fn light_emission(self) -> u8 {
+                pub const fn block(self) -> Block {
+                    Block::from_id(data::block::get(self.0))
+                }
+
+                pub const fn light_emission(self) -> u8 {
                     data::light_emission::get(self.0)
                 }
 
-                pub fn use_shape_for_light_occlusion(self) -> bool {
+                pub const fn use_shape_for_light_occlusion(self) -> bool {
                     data::use_shape_for_light_occlusion::get(self.0)
                 }
 
-                pub fn propagates_skylight_down(self) -> bool {
+                pub const fn propagates_skylight_down(self) -> bool {
                     data::propagates_skylight_down::get(self.0)
                 }
 
-                pub fn light_block(self) -> u8 {
+                pub const fn light_block(self) -> u8 {
                     data::light_block::get(self.0)
                 }
 
-                pub fn solid_render(self) -> bool {
+                pub const fn solid_render(self) -> bool {
                     data::solid_render::get(self.0)
                 }
 
-                pub fn is_air(self) -> bool {
+                pub const fn is_air(self) -> bool {
                     data::is_air::get(self.0)
                 }
 
-                pub fn ignited_by_lava(self) -> bool {
+                pub const fn ignited_by_lava(self) -> bool {
                     data::ignited_by_lava::get(self.0)
                 }
 
-                pub fn can_occlude(self) -> bool {
+                pub const fn can_occlude(self) -> bool {
                     data::can_occlude::get(self.0)
                 }
 
-                pub fn is_randomly_ticking(self) -> bool {
+                pub const fn is_randomly_ticking(self) -> bool {
                     data::is_randomly_ticking::get(self.0)
                 }
 
-                pub fn replaceable(self) -> bool {
+                pub const fn replaceable(self) -> bool {
                     data::replaceable::get(self.0)
                 }
 
-                pub fn spawn_terrain_particles(self) -> bool {
+                pub const fn spawn_terrain_particles(self) -> bool {
                     data::spawn_terrain_particles::get(self.0)
                 }
 
-                pub fn requires_correct_tool_for_drops(self) -> bool {
+                pub const fn requires_correct_tool_for_drops(self) -> bool {
                     data::requires_correct_tool_for_drops::get(self.0)
                 }
 
-                pub fn destroy_speed(self) -> f32 {
+                pub const fn destroy_speed(self) -> f32 {
                     data::destroy_speed::get(self.0)
                 }
 
-                pub fn offset_type(self) -> OffsetType {
+                pub const fn offset_type(self) -> OffsetType {
                     unsafe { core::mem::transmute::<u8, OffsetType>(data::offset_type::get(self.0)) }
                 }
 
-                pub fn max_horizontal_offset(self) -> f32 {
+                pub const fn max_horizontal_offset(self) -> f32 {
                     data::max_horizontal_offset::get(self.0)
                 }
 
-                pub fn max_vertical_offset(self) -> f32 {
+                pub const fn max_vertical_offset(self) -> f32 {
                     data::max_vertical_offset::get(self.0)
                 }
 
-                pub fn get_field(self, field: FieldKey) -> Option<FieldVal> {
+                pub const fn get_field(self, field: FieldKey) -> Option<FieldVal> {
                     if !self.block().is_field_present(field) {
                         return None;
                     }
@@ -240,7 +249,7 @@ impl UnitGen for StateRootUnit<'_> {
                     }
                 }
 
-                pub fn all() -> impl Iterator<Item = Self> {
+                pub fn all() -> impl ExactSizeIterator<Item = Self> {
                     BlockStateIter::new(BlockState(0), Self::MAX)
                 }
 
@@ -276,16 +285,23 @@ impl UnitGen for StateRootUnit<'_> {
                 }
 
                 fn size_hint(&self) -> (usize, Option<usize>) {
-                    let remaining = (self.end - self.current) as usize;
+                    let remaining = if self.current > self.end {
+                        0
+                    } else {
+                        (self.end - self.current + 1) as usize
+                    };
                     (remaining, Some(remaining))
                 }
             }
+
+            impl ExactSizeIterator for BlockStateIter {}
         };
 
         Unit {
             name: "mod".to_string(),
             code,
             data: None,
+            report: None,
         }
     }
 }
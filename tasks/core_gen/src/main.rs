@@ -4,36 +4,150 @@ mod generators;
 use std::path::PathBuf;
 
 use indexmap::IndexMap;
-use mcre_data::{block::Block, state::BlockState};
+use mcre_data::{block::Block, state::BlockState, version::VersionInfo};
 
 use crate::{
     analyzer::analyze,
-    generators::{Factory, RootScope},
+    generators::{Factory, RootScope, VersionsUnit},
 };
 
+/// Every version the tables can be generated for, newest first, as `(version id, module)`.
+///
+/// Each module is gated behind the cargo feature of the same name in `mcre_core`.
+const VERSIONS: [(&str, &str); 1] = [("1.21.11", "v1_21_11")];
+
 #[tokio::main]
 async fn main() {
-    let blocks = Block::all().await.unwrap();
-    let block_states = BlockState::all().await.unwrap();
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../crates/mcre_core/src/data");
+
+    for (id, module) in VERSIONS {
+        let info = VersionInfo::load(id)
+            .await
+            .unwrap_or_else(|err| panic!("no data for {id}, run data_gen for it first: {err}"));
+        let blocks = Block::all(id).await.unwrap();
+        let block_states = BlockState::all(id).await.unwrap();
+
+        let mut foreign_enums: IndexMap<&str, Box<[&str]>> = IndexMap::new();
+
+        foreign_enums.insert(
+            "Direction",
+            Box::new(["down", "up", "north", "south", "west", "east"]),
+        );
+        foreign_enums.insert("Axis", Box::new(["x", "y", "z"]));
+
+        let analysis = analyze(&blocks, foreign_enums);
+
+        let mut factory = Factory::new(root.clone());
+
+        factory.add_scope(RootScope {
+            module,
+            version: &info,
+            blocks: &blocks,
+            states: &block_states,
+        });
+
+        factory.generate(&analysis).await;
+    }
+
+    Factory::new(root)
+        .write_unit(
+            VersionsUnit {
+                versions: &VERSIONS,
+            }
+            .generate(),
+        )
+        .await;
+}
 
-    let mut foreign_enums: IndexMap<&str, Box<[&str]>> = IndexMap::new();
+#[cfg(test)]
+mod tests {
+    use mcre_core::{BlockState, DATA_VERSION, PropKey, PropVal};
+    use mcre_data::{block::Block, state::StateValue};
 
-    foreign_enums.insert(
-        "Direction",
-        Box::new(["down", "up", "north", "south", "west", "east"]),
-    );
-    foreign_enums.insert("Axis", Box::new(["x", "y", "z"]));
+    /// Compares every accessor of the compiled tables against the data they were generated from.
+    #[tokio::test]
+    async fn test_tables_match_data() {
+        let blocks = Block::all(DATA_VERSION.id).await.unwrap();
+        let states = mcre_data::state::BlockState::all(DATA_VERSION.id)
+            .await
+            .unwrap();
 
-    let analysis = analyze(&blocks, foreign_enums);
+        for block in &blocks {
+            let compiled = mcre_core::Block::from_id(block.id);
+            assert_eq!(compiled.name(), block.name);
+            assert_eq!(compiled.display_name(), block.display_name);
+            assert_eq!(compiled.default_state().id(), block.default_state_id);
+            assert_eq!(compiled.min_state().id(), block.min_state_id);
+            assert_eq!(compiled.max_state().id(), block.max_state_id);
+        }
 
-    let mut factory = Factory::new(
-        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../crates/mcre_core/src/data"),
-    );
+        for state in &states {
+            let compiled = BlockState::from_id(state.id);
+            let name = &state.block_name;
 
-    factory.add_scope(RootScope {
-        blocks: &blocks,
-        states: &block_states,
-    });
+            assert_eq!(compiled.block().id(), state.block_id, "{name}");
+            assert_eq!(compiled.light_emission(), state.light_emission, "{name}");
+            assert_eq!(
+                compiled.use_shape_for_light_occlusion(),
+                state.use_shape_for_light_occlusion,
+                "{name}"
+            );
+            assert_eq!(
+                compiled.propagates_skylight_down(),
+                state.propagates_skylight_down,
+                "{name}"
+            );
+            assert_eq!(compiled.light_block(), state.light_block, "{name}");
+            assert_eq!(compiled.solid_render(), state.solid_render, "{name}");
+            assert_eq!(compiled.is_air(), state.is_air, "{name}");
+            assert_eq!(compiled.ignited_by_lava(), state.ignited_by_lava, "{name}");
+            assert_eq!(compiled.can_occlude(), state.can_occlude, "{name}");
+            assert_eq!(
+                compiled.is_randomly_ticking(),
+                state.is_randomly_ticking,
+                "{name}"
+            );
+            assert_eq!(compiled.replaceable(), state.replaceable, "{name}");
+            assert_eq!(
+                compiled.spawn_terrain_particles(),
+                state.spawn_terrain_particles,
+                "{name}"
+            );
+            assert_eq!(
+                compiled.requires_correct_tool_for_drops(),
+                state.requires_correct_tool_for_drops,
+                "{name}"
+            );
+            assert_eq!(
+                compiled.destroy_speed().to_bits(),
+                state.destroy_speed.to_bits(),
+                "{name}"
+            );
+            assert_eq!(compiled.offset_type(), state.offset_type, "{name}");
+            assert_eq!(
+                compiled.max_horizontal_offset().to_bits(),
+                state.max_horizontal_offset.to_bits(),
+                "{name}"
+            );
+            assert_eq!(
+                compiled.max_vertical_offset().to_bits(),
+                state.max_vertical_offset.to_bits(),
+                "{name}"
+            );
 
-    factory.generate(&analysis).await;
+            for (key, value) in &state.state_values {
+                let key = key.parse::<PropKey>().unwrap();
+                let value = match value {
+                    StateValue::Bool(value) => value.to_string(),
+                    StateValue::Int(value) => value.to_string(),
+                    StateValue::String(value) => value.clone(),
+                };
+                assert_eq!(
+                    compiled.get_prop(key),
+                    PropVal::parse_with_key(key, &value),
+                    "{name}[{key:?}={value}]"
+                );
+            }
+        }
+    }
 }
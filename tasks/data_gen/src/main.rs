@@ -1,4 +1,4 @@
-use std::{fs, path::PathBuf};
+use std::fs;
 
 use indexmap::IndexMap;
 use jni::{
@@ -9,18 +9,50 @@ use mcje::{get_registry, iterate};
 use mcre_core::OffsetType;
 use mcre_data::{
     block::{Block, BlockStateField, BlockStateFieldValues},
+    data_dir,
     state::{BlockState, StateValue},
+    version::VersionInfo,
 };
 
-const BLOCK_DATA_PATH: &str = "crates/mcre_data/blocks.json";
-const BLOCK_STATE_DATA_PATH: &str = "crates/mcre_data/block_states.json";
-
 #[mcje::main]
 async fn main(env: &mut JNIEnv<'_>) {
+    fs::create_dir_all(data_dir(mcje::VERSION)).unwrap();
+
+    generate_version_info(env);
     generate_block_data(env);
     generate_block_state_data(env);
 }
 
+fn generate_version_info(env: &mut JNIEnv) {
+    let world_version = env
+        .get_static_field("net/minecraft/SharedConstants", "WORLD_VERSION", "I")
+        .unwrap()
+        .i()
+        .unwrap();
+    let protocol_version = env
+        .call_static_method(
+            "net/minecraft/SharedConstants",
+            "getProtocolVersion",
+            "()I",
+            &[],
+        )
+        .unwrap()
+        .i()
+        .unwrap();
+
+    let info = VersionInfo {
+        id: mcje::VERSION.to_string(),
+        world_version: world_version as u32,
+        protocol_version: protocol_version as u32,
+    };
+
+    let json_string = serde_json::to_string_pretty(&info).unwrap();
+    let data_path = data_dir(mcje::VERSION).join("version.json");
+    fs::write(&data_path, json_string).unwrap();
+
+    println!("[DEBUG] Version info saved to `{}`", data_path.display());
+}
+
 fn generate_block_data(env: &mut JNIEnv) {
     println!("[DEBUG] Generating block data");
     let block_registry = get_registry(env, "BLOCK", "DefaultedRegistry");
@@ -108,12 +140,10 @@ fn generate_block_data(env: &mut JNIEnv) {
     println!("[DEBUG] Done!");
 
     let json_string = serde_json::to_string_pretty(&blocks).unwrap();
-    let root = env!("CARGO_MANIFEST_DIR");
-    let root = PathBuf::from(root);
-    let data_path = root.join("../../").join(BLOCK_DATA_PATH);
+    let data_path = data_dir(mcje::VERSION).join("blocks.json");
     fs::write(&data_path, json_string).unwrap();
 
-    println!("[DEBUG] Block data saved to `{}`", BLOCK_DATA_PATH);
+    println!("[DEBUG] Block data saved to `{}`", data_path.display());
 }
 
 fn generate_block_state_data(env: &mut JNIEnv) {
@@ -164,14 +194,12 @@ fn generate_block_state_data(env: &mut JNIEnv) {
     println!("[DEBUG] Done!");
 
     let json_string = serde_json::to_string_pretty(&states).unwrap();
-    let root = env!("CARGO_MANIFEST_DIR");
-    let root = PathBuf::from(root);
-    let data_path = root.join("../../").join(BLOCK_STATE_DATA_PATH);
+    let data_path = data_dir(mcje::VERSION).join("block_states.json");
     fs::write(&data_path, json_string).unwrap();
 
     println!(
         "[DEBUG] Block state data saved to `{}`",
-        BLOCK_STATE_DATA_PATH
+        data_path.display()
     );
 }
 